@@ -0,0 +1,187 @@
+/*!
+Benchmarks for the performance sensitive operations of the plugin manager; loading, looking up,
+iterating over, both as a vector and through a read guard, and unloading plugins, each with 10,
+1,000, and 10,000 plugins registered. The plugins are provided by a memory source, so that the
+cost measured is that of the plugin manager rather than of opening dynamic libraries.
+
+Run with `cargo bench --bench manager`; criterion records the results of each run, and reports
+changes from the previous run, so that regressions can be tracked.
+*/
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use dygpi::error::Result;
+use dygpi::manager::PluginManager;
+use dygpi::plugin::Plugin;
+use dygpi::source::PluginSource;
+use dygpi::testing::MemorySource;
+use std::sync::Arc;
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct BenchPlugin(String);
+
+const PLUGIN_COUNTS: &[usize] = &[10, 1_000, 10_000];
+
+const PLUGINS_PER_LOCATION: usize = 10;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Plugin for BenchPlugin {
+    fn plugin_id(&self) -> &str {
+        &self.0
+    }
+    fn on_load(&self) -> Result<()> {
+        Ok(())
+    }
+    fn on_unload(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn plugin_id(index: usize) -> String {
+    format!(
+        "bench::location_{}::Plugin{}",
+        index / PLUGINS_PER_LOCATION,
+        index
+    )
+}
+
+fn make_source(plugin_count: usize) -> Arc<dyn PluginSource<BenchPlugin>> {
+    let memory_source = MemorySource::default();
+    for index in 0..plugin_count {
+        memory_source.add_plugin(
+            format!("location_{}", index / PLUGINS_PER_LOCATION),
+            BenchPlugin(plugin_id(index)),
+        );
+    }
+    Arc::new(memory_source)
+}
+
+fn make_manager(source: &Arc<dyn PluginSource<BenchPlugin>>) -> PluginManager<BenchPlugin> {
+    let plugin_manager = PluginManager::default();
+    plugin_manager.load_all_plugins_from_source(source).unwrap();
+    plugin_manager
+}
+
+fn bench_load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+    for plugin_count in PLUGIN_COUNTS {
+        let source = make_source(*plugin_count);
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(plugin_count),
+            plugin_count,
+            |b, _| {
+                b.iter_batched(
+                    PluginManager::default,
+                    |plugin_manager| {
+                        plugin_manager
+                            .load_all_plugins_from_source(&source)
+                            .unwrap();
+                        plugin_manager
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get");
+    for plugin_count in PLUGIN_COUNTS {
+        let source = make_source(*plugin_count);
+        let plugin_manager = make_manager(&source);
+        let plugin_ids: Vec<String> = (0..*plugin_count).map(plugin_id).collect();
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(plugin_count),
+            plugin_count,
+            |b, _| {
+                let mut index = 0;
+                b.iter(|| {
+                    index = (index + 1) % plugin_ids.len();
+                    black_box(plugin_manager.get(&plugin_ids[index]))
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterate");
+    for plugin_count in PLUGIN_COUNTS {
+        let source = make_source(*plugin_count);
+        let plugin_manager = make_manager(&source);
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(plugin_count),
+            plugin_count,
+            |b, _| b.iter(|| black_box(plugin_manager.plugins())),
+        );
+    }
+    group.finish();
+}
+
+fn bench_iterate_read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterate_read");
+    for plugin_count in PLUGIN_COUNTS {
+        let source = make_source(*plugin_count);
+        let plugin_manager = make_manager(&source);
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(plugin_count),
+            plugin_count,
+            |b, _| {
+                b.iter(|| {
+                    let plugins = plugin_manager.read();
+                    plugins.iter().for_each(|plugin| {
+                        let _ = black_box(plugin);
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_unload(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unload");
+    group.sample_size(10);
+    for plugin_count in PLUGIN_COUNTS {
+        let source = make_source(*plugin_count);
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(plugin_count),
+            plugin_count,
+            |b, _| {
+                b.iter_batched(
+                    || make_manager(&source),
+                    |plugin_manager| {
+                        plugin_manager.unload_all().unwrap();
+                        plugin_manager
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_load,
+    bench_get,
+    bench_iterate,
+    bench_iterate_read,
+    bench_unload
+);
+criterion_main!(benches);
@@ -0,0 +1,137 @@
+/*!
+Benchmarks for the plugin manager's registry with large numbers of plugins. Loading, and
+unloading, a single small library into a manager that already holds thousands of plugins measures
+the cost of an update, which copies only the registry shards changed. Looking up plugins from
+several threads at once measures the cost of concurrent reads.
+*/
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dygpi::error::Result;
+use dygpi::manager::PluginManager;
+use dygpi::plugin::Plugin;
+use dygpi::source::PluginSource;
+use dygpi::testing::MemorySource;
+use std::sync::Arc;
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct BenchPlugin(String);
+
+const PLUGIN_COUNTS: &[usize] = &[100, 1_000, 10_000];
+
+const PLUGINS_PER_LOCATION: usize = 10;
+
+const READER_THREADS: usize = 8;
+
+const READS_PER_THREAD: usize = 1_000;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Plugin for BenchPlugin {
+    fn plugin_id(&self) -> &str {
+        &self.0
+    }
+    fn on_load(&self) -> Result<()> {
+        Ok(())
+    }
+    fn on_unload(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn plugin_id(index: usize) -> String {
+    format!(
+        "bench::location_{}::Plugin{}",
+        index / PLUGINS_PER_LOCATION,
+        index
+    )
+}
+
+fn make_source(plugin_count: usize) -> Arc<dyn PluginSource<BenchPlugin>> {
+    let memory_source = MemorySource::default();
+    for index in 0..plugin_count {
+        memory_source.add_plugin(
+            format!("location_{}", index / PLUGINS_PER_LOCATION),
+            BenchPlugin(plugin_id(index)),
+        );
+    }
+    memory_source.add_plugin("extra", BenchPlugin("bench::extra::Plugin".to_string()));
+    Arc::new(memory_source)
+}
+
+fn make_manager(
+    source: &Arc<dyn PluginSource<BenchPlugin>>,
+    plugin_count: usize,
+) -> PluginManager<BenchPlugin> {
+    let plugin_manager = PluginManager::default();
+    for location in 0..(plugin_count / PLUGINS_PER_LOCATION) {
+        plugin_manager
+            .load_plugins_from_source(source, format!("location_{}", location))
+            .unwrap();
+    }
+    plugin_manager
+}
+
+fn bench_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("registry_update");
+    for plugin_count in PLUGIN_COUNTS {
+        let source = make_source(*plugin_count);
+        let plugin_manager = make_manager(&source, *plugin_count);
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(plugin_count),
+            plugin_count,
+            |b, _| {
+                b.iter(|| {
+                    plugin_manager
+                        .load_plugins_from_source(&source, "extra")
+                        .unwrap();
+                    plugin_manager.unload_library("extra").unwrap();
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_concurrent_get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("registry_concurrent_get");
+    for plugin_count in PLUGIN_COUNTS {
+        let source = make_source(*plugin_count);
+        let plugin_manager = make_manager(&source, *plugin_count);
+        let plugin_ids: Vec<String> = (0..*plugin_count).map(plugin_id).collect();
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(plugin_count),
+            plugin_count,
+            |b, _| {
+                b.iter(|| {
+                    std::thread::scope(|scope| {
+                        for thread in 0..READER_THREADS {
+                            let plugin_manager = &plugin_manager;
+                            let plugin_ids = &plugin_ids;
+                            let _ = scope.spawn(move || {
+                                for read in 0..READS_PER_THREAD {
+                                    let index =
+                                        (thread * READS_PER_THREAD + read) % plugin_ids.len();
+                                    let _ = black_box(plugin_manager.get(&plugin_ids[index]));
+                                }
+                            });
+                        }
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_update, bench_concurrent_get);
+criterion_main!(benches);
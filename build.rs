@@ -1,4 +1,3 @@
 fn main() {
-    let version = rustc_version::version().unwrap();
-    println!("cargo:rustc-env=RUSTC_VERSION={}", version);
+    dygpi_build::emit_compatibility_env();
 }
@@ -0,0 +1,99 @@
+/*!
+Build script helpers for crates that use `dygpi`, either as a plugin _host_ or a plugin _provider_.
+
+The `dygpi` compatibility check compares values, such as the compiler version, that are captured
+when a crate is built. This crate computes those values the same way `dygpi` does, and emits them
+as environment variables for the crate being built, so that plugin provider crates do not have to
+replicate `dygpi`'s own build script.
+
+# Example
+
+In the provider's `Cargo.toml`:
+
+```toml
+[build-dependencies]
+dygpi-build = "0.1"
+```
+
+In the provider's `build.rs`:
+
+```rust,ignore
+fn main() {
+    dygpi_build::emit_compatibility_env();
+}
+```
+
+The values are then available to the provider with the `env!` macro.
+
+```rust,ignore
+const RUSTC_VERSION: &str = env!("RUSTC_VERSION");
+```
+
+*/
+
+#![warn(
+    // ---------- Stylistic
+    future_incompatible,
+    nonstandard_style,
+    rust_2018_idioms,
+    trivial_casts,
+    trivial_numeric_casts,
+    // ---------- Public
+    missing_debug_implementations,
+    missing_docs,
+    unreachable_pub,
+    // ---------- Unsafe
+    unsafe_code,
+    // ---------- Unused
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_results,
+)]
+
+// ------------------------------------------------------------------------------------------------
+// Public Values
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The name of the environment variable containing the version of the compiler.
+///
+pub const RUSTC_VERSION_ENV: &str = "RUSTC_VERSION";
+
+///
+/// The name of the environment variable containing the target triple being built for.
+///
+pub const BUILD_TARGET_ENV: &str = "DYGPI_BUILD_TARGET";
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Return the version of the compiler used for this build, this will panic if the compiler version
+/// cannot be determined, which should only happen outside of a build script.
+///
+pub fn rustc_version() -> String {
+    rustc_version::version()
+        .expect("Could not determine the rustc version")
+        .to_string()
+}
+
+///
+/// Return the target triple for this build, if running within a build script.
+///
+pub fn build_target() -> Option<String> {
+    std::env::var("TARGET").ok()
+}
+
+///
+/// Emit the environment variables used as compatibility inputs so that they are available to the
+/// crate being built via the `env!` macro. This must be called from a build script.
+///
+pub fn emit_compatibility_env() {
+    println!("cargo:rustc-env={}={}", RUSTC_VERSION_ENV, rustc_version());
+    if let Some(target) = build_target() {
+        println!("cargo:rustc-env={}={}", BUILD_TARGET_ENV, target);
+    }
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
@@ -0,0 +1,210 @@
+/*!
+Provides the [`BundleManifest`](struct.BundleManifest.html) type, read from a `plugin.toml` file
+placed alongside the plugin libraries in a directory (a _bundle_). The manifest describes each
+plugin, the plugin type it implements, the library that provides it, the registration function
+to call, and the platforms it supports; this allows a plugin manager to select libraries without
+opening every candidate file, see
+[`PluginManager::load_bundle`](../manager/struct.PluginManager.html#method.load_bundle).
+
+# Example
+
+```toml
+[[plugin]]
+id = "sound_one::sound_one::DelayEffect"
+type = "sound_api::SoundEffectPlugin"
+library = "sound_one"
+
+[[plugin]]
+id = "sound_one::sound_one::MoodLighting"
+type = "sound_api::LightEffectPlugin"
+library = "sound_one"
+entry = "register_light_plugins"
+platforms = ["linux", "macos"]
+```
+
+The `library` value is the library name without any platform prefix or extension, see
+[`make_platform_dylib_name`](../manager/fn.make_platform_dylib_name.html). The `entry` value
+defaults to `register_plugins`, and an empty or missing `platforms` list means the plugin is
+supported on all platforms; platform names are those displayed by
+[`Platform`](../platform/enum.Platform.html).
+
+*/
+
+use crate::error::{Error, Result};
+use crate::platform::Platform;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The contents of a bundle's `plugin.toml` file.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BundleManifest {
+    #[serde(default, rename = "plugin")]
+    plugins: Vec<BundleEntry>,
+}
+
+///
+/// The description of a single plugin within a [`BundleManifest`](struct.BundleManifest.html).
+///
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BundleEntry {
+    id: String,
+    #[serde(rename = "type")]
+    plugin_type: String,
+    library: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    platforms: Vec<String>,
+}
+
+///
+/// The name of the manifest file within a bundle directory.
+///
+pub const BUNDLE_MANIFEST_FILE_NAME: &str = "plugin.toml";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl BundleManifest {
+    ///
+    /// Read the manifest file from the bundle directory.
+    ///
+    pub fn read_from(directory: &Path) -> Result<Self> {
+        let file_name = directory.join(BUNDLE_MANIFEST_FILE_NAME);
+        trace!("BundleManifest::read_from({:?})", file_name);
+        let manifest_failed =
+            |e: Box<dyn std::error::Error>| Error::bundle_manifest_failed(&file_name, e);
+        let text = std::fs::read_to_string(&file_name).map_err(|e| manifest_failed(Box::new(e)))?;
+        toml::from_str(&text).map_err(|e| manifest_failed(Box::new(e)))
+    }
+
+    ///
+    /// Return an iterator over all plugin entries in the manifest.
+    ///
+    pub fn plugins(&self) -> impl Iterator<Item = &BundleEntry> {
+        self.plugins.iter()
+    }
+
+    ///
+    /// Return an iterator over the plugin entries for the plugin type, identified by its type
+    /// name (see `std::any::type_name`), that are supported on the current platform.
+    ///
+    pub fn plugins_for<'a>(
+        &'a self,
+        plugin_type: &'a str,
+    ) -> impl Iterator<Item = &'a BundleEntry> {
+        let platform = Platform::current();
+        self.plugins
+            .iter()
+            .filter(move |p| p.plugin_type == plugin_type && p.supports_platform(platform))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl BundleEntry {
+    ///
+    /// The plugin identifier.
+    ///
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    ///
+    /// The type name of the plugin type this plugin implements.
+    ///
+    pub fn plugin_type(&self) -> &String {
+        &self.plugin_type
+    }
+
+    ///
+    /// The name of the library, without platform prefix or extension, providing this plugin.
+    ///
+    pub fn library(&self) -> &String {
+        &self.library
+    }
+
+    ///
+    /// The name of the registration function to call, if not the default.
+    ///
+    pub fn entry(&self) -> Option<&String> {
+        self.entry.as_ref()
+    }
+
+    ///
+    /// The platforms this plugin supports; if empty all platforms are supported.
+    ///
+    pub fn platforms(&self) -> impl Iterator<Item = &String> {
+        self.platforms.iter()
+    }
+
+    ///
+    /// Returns `true` if this plugin is supported on the platform.
+    ///
+    pub fn supports_platform(&self, platform: Platform) -> bool {
+        let platform = platform.to_string();
+        self.platforms.is_empty() || self.platforms.contains(&platform)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_bundle_manifest() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(
+            directory.path().join(BUNDLE_MANIFEST_FILE_NAME),
+            r##"
+[[plugin]]
+id = "one"
+type = "api::Effect"
+library = "effects"
+
+[[plugin]]
+id = "two"
+type = "api::Effect"
+library = "effects"
+entry = "register_other_plugins"
+platforms = ["windows"]
+
+[[plugin]]
+id = "three"
+type = "api::Source"
+library = "sources"
+"##,
+        )
+        .unwrap();
+
+        let manifest = BundleManifest::read_from(directory.path()).unwrap();
+        assert_eq!(manifest.plugins().count(), 3);
+
+        let effects: Vec<&String> = manifest
+            .plugins_for("api::Effect")
+            .map(|p| p.id())
+            .collect();
+        if Platform::current() == Platform::Windows {
+            assert_eq!(effects, vec!["one", "two"]);
+        } else {
+            assert_eq!(effects, vec!["one"]);
+        }
+    }
+
+    #[test]
+    fn test_missing_bundle_manifest() {
+        let directory = tempfile::tempdir().unwrap();
+        assert!(BundleManifest::read_from(directory.path()).is_err());
+    }
+}
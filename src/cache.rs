@@ -0,0 +1,367 @@
+/*!
+Provides the [`LibraryCache`](struct.LibraryCache.html) type, shared between plugin managers so
+that a library loaded by more than one manager is opened, and checked for compatibility, once.
+
+Libraries are keyed by their canonical path, and the cache holds only weak references; a library
+is closed when the last plugin, in any manager, that was registered from it is unloaded. A library
+being reloaded is never taken from the cache, it is always opened again, so that a reload does not
+register plugins from the library it replaces while that library is still referenced. By
+default all plugin managers share the process-wide cache returned by
+[`LibraryCache::global`](struct.LibraryCache.html#method.global), a manager may be given its own
+cache with
+[`PluginManager::set_library_cache`](../manager/struct.PluginManager.html#method.set_library_cache).
+
+# Example
+
+```rust,no_run
+use dygpi::cache::LibraryCache;
+use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+# #[derive(Debug)]
+# struct SoundSourcePlugin;
+# impl Plugin for SoundSourcePlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+use std::sync::Arc;
+
+let cache = Arc::new(LibraryCache::default());
+
+let mut effects: PluginManager<SoundEffectPlugin> = PluginManager::default();
+effects.set_library_cache(cache.clone());
+effects.load_plugins_from("libsound_one.dylib").unwrap();
+
+let mut sources: PluginManager<SoundSourcePlugin> = PluginManager::default();
+sources.set_library_cache(cache.clone());
+sources.set_registration_fn_name(dygpi::symbol!("register_sources"));
+sources.load_plugins_from("libsound_one.dylib").unwrap();
+
+assert_eq!(cache.len(), 1);
+```
+
+*/
+
+use crate::error::{Error, Result};
+use crate::source::SourceLocation;
+use libloading::{Library, Symbol};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Duration;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A cache of open libraries, keyed by canonical path.
+///
+#[derive(Debug, Default)]
+pub struct LibraryCache {
+    libraries: Mutex<HashMap<PathBuf, Weak<LoadedLibrary>>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An open library. If the library was opened from a shadow copy the copy is declared, and so
+/// dropped, after the library so that it is only removed once the library is closed.
+///
+#[derive(Debug)]
+pub(crate) struct LoadedLibrary {
+    pub(crate) file_name: PathBuf,
+    pub(crate) canonical_name: PathBuf,
+    pub(crate) library: LibraryKind,
+    pub(crate) shadow_copy: Option<ShadowCopy>,
+}
+
+///
+/// A dynamic library, or a location loaded from a [`PluginSource`](../source/trait.PluginSource.html).
+///
+#[derive(Debug)]
+pub(crate) enum LibraryKind {
+    Dynamic(Library),
+    Source(SourceLocation),
+}
+
+///
+/// A copy of a library file, in its own directory, which is removed when dropped.
+///
+#[derive(Debug)]
+pub(crate) struct ShadowCopy {
+    path: PathBuf,
+}
+
+///
+/// A library whose plugins were still referenced when its plugin manager was dropped, and which
+/// is closed by the reaper once `is_referenced` returns `false`.
+///
+struct OrphanedLibrary {
+    library: Arc<LoadedLibrary>,
+    is_referenced: Box<dyn Fn() -> bool + Send>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+/// How often the reaper checks whether orphaned libraries are still referenced.
+const REAPER_INTERVAL: Duration = Duration::from_millis(50);
+
+fn orphaned_libraries() -> &'static Mutex<Vec<OrphanedLibrary>> {
+    static ORPHANED: OnceLock<Mutex<Vec<OrphanedLibrary>>> = OnceLock::new();
+    ORPHANED.get_or_init(Default::default)
+}
+
+///
+/// Hand `library` to the process-wide reaper, which closes it once `is_referenced` returns
+/// `false`; this is used for libraries whose plugins the host still references when their
+/// plugin manager is dropped, so that they are closed rather than leaked. The reaper runs on
+/// its own thread, which exits once no orphaned libraries remain.
+///
+pub(crate) fn close_when_released<F>(library: Arc<LoadedLibrary>, is_referenced: F)
+where
+    F: Fn() -> bool + Send + 'static,
+{
+    let mut orphaned = orphaned_libraries().lock().unwrap();
+    orphaned.push(OrphanedLibrary {
+        library,
+        is_referenced: Box::new(is_referenced),
+    });
+    if orphaned.len() == 1 {
+        if let Err(e) = std::thread::Builder::new()
+            .name("dygpi-reaper".to_string())
+            .spawn(reap_orphaned_libraries)
+        {
+            warn!(
+                "Could not start the library reaper, orphaned libraries will not be closed; {}",
+                e
+            );
+        }
+    }
+}
+
+///
+/// Returns `true` if the library with the canonical path `canonical_name` is waiting for the
+/// reaper to close it, else `false`.
+///
+pub(crate) fn is_orphaned(canonical_name: &Path) -> bool {
+    orphaned_libraries()
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|orphaned| orphaned.library.canonical_name == canonical_name)
+}
+
+fn reap_orphaned_libraries() {
+    loop {
+        std::thread::sleep(REAPER_INTERVAL);
+        let (released, done) = {
+            let mut orphaned = orphaned_libraries().lock().unwrap();
+            let (released, remaining): (Vec<_>, Vec<_>) = orphaned
+                .drain(..)
+                .partition(|orphaned| !(orphaned.is_referenced)());
+            *orphaned = remaining;
+            (released, orphaned.is_empty())
+        };
+        for orphaned in released {
+            trace!(
+                "reap_orphaned_libraries() > releasing {:?}",
+                orphaned.library.file_name
+            );
+            if let Some(library) = Arc::into_inner(orphaned.library) {
+                if let Err(e) = library.close() {
+                    warn!("Error closing orphaned library; {}", e);
+                }
+            }
+        }
+        if done {
+            return;
+        }
+    }
+}
+
+///
+/// Return the canonical form of the path, or the path itself if it cannot be canonicalized, for
+/// example because the file does not exist.
+///
+pub(crate) fn canonical_path(file_name: &Path) -> PathBuf {
+    std::fs::canonicalize(file_name).unwrap_or_else(|_| file_name.to_path_buf())
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl LibraryCache {
+    ///
+    /// Return the process-wide library cache used by default by all plugin managers.
+    ///
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<LibraryCache>> = OnceLock::new();
+        GLOBAL.get_or_init(Default::default).clone()
+    }
+
+    /// Returns `true` if the cache contains no open libraries, else `false`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of open libraries in the cache.
+    pub fn len(&self) -> usize {
+        self.libraries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|library| library.strong_count() > 0)
+            .count()
+    }
+
+    /// Returns `true` if the library with this path is open, else `false`.
+    pub fn contains(&self, file_name: &Path) -> bool {
+        self.libraries
+            .lock()
+            .unwrap()
+            .get(&canonical_path(file_name))
+            .is_some_and(|library| library.strong_count() > 0)
+    }
+
+    ///
+    /// Return the open library with the same canonical path as `file_name`, or call `open` to
+    /// open, and check, the library and add it to the cache.
+    ///
+    pub(crate) fn get_or_open<F>(&self, file_name: &Path, open: F) -> Result<Arc<LoadedLibrary>>
+    where
+        F: FnOnce(PathBuf, PathBuf) -> Result<LoadedLibrary>,
+    {
+        let canonical_name = canonical_path(file_name);
+        let mut libraries = self.libraries.lock().unwrap();
+        if let Some(library) = libraries.get(&canonical_name).and_then(Weak::upgrade) {
+            trace!(
+                "LibraryCache::get_or_open() > sharing open library {:?}",
+                canonical_name
+            );
+            return Ok(library);
+        }
+        libraries.retain(|_, library| library.strong_count() > 0);
+
+        let library = Arc::new(open(file_name.to_path_buf(), canonical_name.clone())?);
+        let _ = libraries.insert(canonical_name, Arc::downgrade(&library));
+        Ok(library)
+    }
+
+    ///
+    /// Remove the library with the same canonical path as `file_name` so that the next call to
+    /// `get_or_open` opens it again, rather than sharing a library that is still open because a
+    /// plugin from it is referenced; the removed library is closed as usual once it is released.
+    ///
+    pub(crate) fn evict(&self, file_name: &Path) {
+        let _ = self
+            .libraries
+            .lock()
+            .unwrap()
+            .remove(&canonical_path(file_name));
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl LoadedLibrary {
+    ///
+    /// Look up the symbol `name`; this fails for a location loaded from a source.
+    ///
+    #[allow(unsafe_code)]
+    pub(crate) unsafe fn get<S>(&self, name: &CStr) -> Result<Symbol<'_, S>> {
+        let symbol = name.to_string_lossy();
+        match &self.library {
+            LibraryKind::Dynamic(library) => library
+                .get(name.to_bytes_with_nul())
+                .map_err(|e| Error::symbol_not_found(&self.file_name, &symbol, e)),
+            LibraryKind::Source(_) => Err(Error::symbol_not_found(
+                &self.file_name,
+                &symbol,
+                "the library was loaded from a plugin source",
+            )),
+        }
+    }
+
+    /// The file handed to the platform loader, the shadow copy if there is one.
+    #[cfg(feature = "verify")]
+    pub(crate) fn opened_name(&self) -> &Path {
+        self.shadow_copy
+            .as_ref()
+            .map_or(self.file_name.as_path(), |shadow_copy| shadow_copy.path())
+    }
+
+    /// Returns `true` if this is a location loaded from a source, else `false`.
+    pub(crate) fn is_source(&self) -> bool {
+        matches!(self.library, LibraryKind::Source(_))
+    }
+
+    ///
+    /// Close the dynamic library, or have the source unload the location.
+    ///
+    pub(crate) fn close(self) -> Result<()> {
+        let file_name = self.file_name;
+        let _shadow_copy = self.shadow_copy;
+        match self.library {
+            LibraryKind::Dynamic(library) => library
+                .close()
+                .map_err(|e| Error::from(e).with_library(&file_name)),
+            LibraryKind::Source(location) => location.close(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ShadowCopy {
+    ///
+    /// Copy `file_name` into a new, uniquely named, directory within `directory`; the copy keeps
+    /// the original file name.
+    ///
+    pub(crate) fn create(directory: &Path, file_name: &Path) -> std::io::Result<Self> {
+        static NEXT_COPY: AtomicU64 = AtomicU64::new(1);
+        let copy_directory = directory.join(format!(
+            "{}-{}",
+            std::process::id(),
+            NEXT_COPY.fetch_add(1, Ordering::Relaxed)
+        ));
+        let path = copy_directory.join(file_name.file_name().unwrap_or(file_name.as_os_str()));
+        trace!("ShadowCopy::create({:?}, {:?})", file_name, path);
+        std::fs::create_dir_all(&copy_directory)?;
+        if let Err(e) = std::fs::copy(file_name, &path) {
+            let _ = std::fs::remove_dir_all(&copy_directory);
+            return Err(e);
+        }
+        Ok(Self { path })
+    }
+
+    /// The path of the copy.
+    pub(crate) fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
+
+impl Drop for ShadowCopy {
+    fn drop(&mut self) {
+        trace!("ShadowCopy::drop({:?})", self.path);
+        if let Some(copy_directory) = self.path.parent() {
+            if let Err(e) = std::fs::remove_dir_all(copy_directory) {
+                warn!("Error removing shadow copy {:?}; {}", self.path, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,422 @@
+/*!
+Provides the [`CheckCache`](struct.CheckCache.html) type, a cache of the results of checking
+library files, so that repeated scans of a large plugin directory skip work for libraries that
+have not changed.
+
+Results are keyed by the canonical path of the library and are only used while the size and
+modification time of the file are unchanged. Two results are cached:
+
+1. the compatibility hash reported by the library when it is opened by a plugin manager, see
+   [`PluginManager::set_check_cache`](../manager/struct.PluginManager.html#method.set_check_cache);
+   a library known to be incompatible is rejected without being opened again.
+1. the plugin manifest read from the library file, see
+   [`read_manifest`](struct.CheckCache.html#method.read_manifest) and
+   [`validate_library`](struct.CheckCache.html#method.validate_library).
+
+The cache may be persisted to disk with [`save`](struct.CheckCache.html#method.save) and read
+back at the next startup with [`load`](struct.CheckCache.html#method.load).
+
+# Example
+
+```rust,no_run
+use dygpi::check_cache::CheckCache;
+use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+use std::path::Path;
+use std::sync::Arc;
+
+let cache_file = Path::new("plugins.cache");
+let cache = Arc::new(CheckCache::load(cache_file).unwrap());
+
+let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.set_check_cache(cache.clone());
+plugin_manager.load_plugins_from_dir("plugins").unwrap();
+
+cache.save(cache_file).unwrap();
+```
+
+*/
+
+use crate::cache::canonical_path;
+use crate::error::{Error, Result};
+use crate::inspect::{check_manifest, read_manifest};
+use crate::manifest::PluginManifest;
+use crate::plugin::HostInfo;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A cache of library check results, keyed by canonical path, size, and modification time.
+///
+#[derive(Debug, Default)]
+pub struct CheckCache {
+    entries: Mutex<HashMap<PathBuf, CheckEntry>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Fingerprint {
+    size: u64,
+    modified: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CheckEntry {
+    fingerprint: Fingerprint,
+    compatibility_hash: Option<u64>,
+    manifest: Option<Option<PluginManifest>>,
+}
+
+const CHECK_CACHE_HEADER: &str = "dygpi-check-cache 2";
+
+///
+/// The header of caches written before compatibility hashes were stable; their entries are
+/// discarded as the hashes they contain can no longer be compared.
+///
+const UNSTABLE_HASH_CHECK_CACHE_HEADER: &str = "dygpi-check-cache 1";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl CheckCache {
+    ///
+    /// Read a cache previously written with [`save`](#method.save); if the file does not exist
+    /// an empty cache is returned.
+    ///
+    pub fn load(file_name: &Path) -> Result<Self> {
+        trace!("CheckCache::load({:?})", file_name);
+        let cache_failed = |e: Box<dyn std::error::Error>| Error::check_cache_failed(file_name, e);
+        let text = match std::fs::read_to_string(file_name) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Default::default()),
+            Err(e) => return Err(cache_failed(Box::new(e))),
+        };
+        let entries = parse_entries(&text).map_err(|e| cache_failed(Box::from(e)))?;
+        Ok(Self {
+            entries: Mutex::new(entries),
+        })
+    }
+
+    ///
+    /// Write the cache to `file_name`, replacing any existing file.
+    ///
+    pub fn save(&self, file_name: &Path) -> Result<()> {
+        trace!("CheckCache::save({:?})", file_name);
+        let mut text = String::new();
+        let _ = writeln!(text, "{}", CHECK_CACHE_HEADER);
+        for (library, entry) in self.entries.lock().unwrap().iter() {
+            let _ = writeln!(
+                text,
+                "library {} {}.{:09} {}",
+                entry.fingerprint.size,
+                entry.fingerprint.modified.as_secs(),
+                entry.fingerprint.modified.subsec_nanos(),
+                library.to_string_lossy()
+            );
+            if let Some(hash) = entry.compatibility_hash {
+                let _ = writeln!(text, "compatibility {:#x}", hash);
+            }
+            match &entry.manifest {
+                None => {}
+                Some(None) => {
+                    let _ = writeln!(text, "no_manifest");
+                }
+                Some(Some(manifest)) => {
+                    for line in manifest.to_string().lines() {
+                        let _ = writeln!(text, "manifest {}", line);
+                    }
+                }
+            }
+        }
+        std::fs::write(file_name, text).map_err(|e| Error::check_cache_failed(file_name, e))
+    }
+
+    /// Returns `true` if the cache contains no results, else `false`.
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().unwrap().is_empty()
+    }
+
+    /// Returns the number of libraries with cached results.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Remove all cached results.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear()
+    }
+
+    ///
+    /// As [`inspect::read_manifest`](../inspect/fn.read_manifest.html), returning the cached
+    /// manifest if the library file is unchanged since it was last read.
+    ///
+    pub fn read_manifest(&self, file_name: &Path) -> Result<Option<PluginManifest>> {
+        let canonical_name = canonical_path(file_name);
+        if let Some(manifest) = self.lookup(&canonical_name, |entry| entry.manifest.clone()) {
+            trace!("CheckCache::read_manifest() > using cached manifest");
+            return Ok(manifest);
+        }
+        let manifest = read_manifest(file_name)?;
+        self.update(&canonical_name, |entry| {
+            entry.manifest = Some(manifest.clone())
+        });
+        Ok(manifest)
+    }
+
+    ///
+    /// As [`inspect::validate_library`](../inspect/fn.validate_library.html), using the cached
+    /// manifest if the library file is unchanged since it was last read.
+    ///
+    pub fn validate_library(
+        &self,
+        file_name: &Path,
+        host_info: &HostInfo,
+    ) -> Result<PluginManifest> {
+        check_manifest(file_name, self.read_manifest(file_name)?, host_info)
+    }
+
+    ///
+    /// The compatibility hash previously reported by the library, if the library file is
+    /// unchanged since it was opened.
+    ///
+    pub(crate) fn compatibility_hash(&self, canonical_name: &Path) -> Option<u64> {
+        self.lookup(canonical_name, |entry| entry.compatibility_hash)
+    }
+
+    pub(crate) fn set_compatibility_hash(&self, canonical_name: &Path, hash: u64) {
+        self.update(canonical_name, |entry| {
+            entry.compatibility_hash = Some(hash)
+        });
+    }
+
+    fn lookup<F, R>(&self, canonical_name: &Path, f: F) -> Option<R>
+    where
+        F: FnOnce(&CheckEntry) -> Option<R>,
+    {
+        let fingerprint = Fingerprint::of(canonical_name)?;
+        self.entries
+            .lock()
+            .unwrap()
+            .get(canonical_name)
+            .filter(|entry| entry.fingerprint == fingerprint)
+            .and_then(f)
+    }
+
+    fn update<F>(&self, canonical_name: &Path, f: F)
+    where
+        F: FnOnce(&mut CheckEntry),
+    {
+        let fingerprint = match Fingerprint::of(canonical_name) {
+            Some(fingerprint) => fingerprint,
+            None => return,
+        };
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries
+            .entry(canonical_name.to_path_buf())
+            .or_insert_with(|| CheckEntry::new(fingerprint));
+        if entry.fingerprint != fingerprint {
+            *entry = CheckEntry::new(fingerprint);
+        }
+        f(entry)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Fingerprint {
+    fn of(file_name: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(file_name).ok()?;
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?,
+        })
+    }
+}
+
+impl CheckEntry {
+    fn new(fingerprint: Fingerprint) -> Self {
+        Self {
+            fingerprint,
+            compatibility_hash: None,
+            manifest: None,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn parse_entries(text: &str) -> std::result::Result<HashMap<PathBuf, CheckEntry>, String> {
+    let mut lines = text.lines();
+    match lines.next() {
+        Some(CHECK_CACHE_HEADER) => {}
+        Some(UNSTABLE_HASH_CHECK_CACHE_HEADER) => return Ok(Default::default()),
+        other => return Err(format!("unexpected cache header {:?}", other)),
+    }
+
+    let mut entries: HashMap<PathBuf, CheckEntry> = Default::default();
+    let mut current: Option<(PathBuf, CheckEntry, Vec<&str>)> = None;
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match (key, current.as_mut()) {
+            ("library", _) => {
+                if let Some(entry) = current.take() {
+                    let (library, entry) = finish_entry(entry)?;
+                    let _ = entries.insert(library, entry);
+                }
+                current = Some(parse_library_line(value)?);
+            }
+            ("compatibility", Some((_, entry, _))) => {
+                let hash = value.trim().trim_start_matches("0x");
+                entry.compatibility_hash = Some(
+                    u64::from_str_radix(hash, 16)
+                        .map_err(|_| format!("invalid compatibility hash {:?}", value))?,
+                );
+            }
+            ("no_manifest", Some((_, entry, _))) => entry.manifest = Some(None),
+            ("manifest", Some((_, _, manifest))) => manifest.push(value),
+            _ => return Err(format!("unexpected cache line {:?}", line)),
+        }
+    }
+    if let Some(entry) = current.take() {
+        let (library, entry) = finish_entry(entry)?;
+        let _ = entries.insert(library, entry);
+    }
+    Ok(entries)
+}
+
+fn parse_library_line(
+    value: &str,
+) -> std::result::Result<(PathBuf, CheckEntry, Vec<&str>), String> {
+    let invalid = || format!("invalid library line {:?}", value);
+    let mut fields = value.splitn(3, ' ');
+    let size = fields.next().and_then(|v| v.parse::<u64>().ok());
+    let modified = fields.next().and_then(|v| {
+        let (secs, nanos) = v.split_once('.')?;
+        Some(Duration::new(secs.parse().ok()?, nanos.parse().ok()?))
+    });
+    match (size, modified, fields.next()) {
+        (Some(size), Some(modified), Some(library)) if !library.is_empty() => Ok((
+            PathBuf::from(library),
+            CheckEntry::new(Fingerprint { size, modified }),
+            Vec::new(),
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+fn finish_entry(
+    (library, mut entry, manifest): (PathBuf, CheckEntry, Vec<&str>),
+) -> std::result::Result<(PathBuf, CheckEntry), String> {
+    if !manifest.is_empty() {
+        let manifest: PluginManifest = manifest
+            .join("\n")
+            .parse()
+            .map_err(|e: Error| e.to_string())?;
+        entry.manifest = Some(Some(manifest));
+    }
+    Ok((library, entry))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::PluginDescriptor;
+
+    #[test]
+    fn test_entries_invalidated_when_file_changes() {
+        let directory = tempfile::tempdir().unwrap();
+        let library = directory.path().join("libeffects.so");
+        std::fs::write(&library, "not a library").unwrap();
+        let library = canonical_path(&library);
+
+        let cache = CheckCache::default();
+        cache.set_compatibility_hash(&library, 42);
+        assert_eq!(cache.compatibility_hash(&library), Some(42));
+
+        std::fs::write(&library, "a different library").unwrap();
+        assert_eq!(cache.compatibility_hash(&library), None);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let directory = tempfile::tempdir().unwrap();
+        let library = canonical_path(&directory.path().join("lib effects.so"));
+        std::fs::write(&library, "not a library").unwrap();
+        let other = canonical_path(&directory.path().join("libsources.so"));
+        std::fs::write(&other, "not a library either").unwrap();
+
+        let cache = CheckCache::default();
+        cache.set_compatibility_hash(&library, 0xdead_beef);
+        cache.update(&library, |entry| {
+            entry.manifest = Some(Some(PluginManifest::new(
+                Some(2..=3),
+                vec![PluginDescriptor::new("effects::Delay", "0.1.0")],
+            )))
+        });
+        cache.update(&other, |entry| entry.manifest = Some(None));
+
+        let cache_file = directory.path().join("plugins.cache");
+        cache.save(&cache_file).unwrap();
+        let loaded = CheckCache::load(&cache_file).unwrap();
+        assert_eq!(
+            *loaded.entries.lock().unwrap(),
+            *cache.entries.lock().unwrap()
+        );
+        assert_eq!(loaded.compatibility_hash(&library), Some(0xdead_beef));
+        assert_eq!(loaded.read_manifest(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = CheckCache::load(&directory.path().join("plugins.cache")).unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_load_invalid_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache_file = directory.path().join("plugins.cache");
+        std::fs::write(&cache_file, "something else\n").unwrap();
+        assert!(CheckCache::load(&cache_file).is_err());
+    }
+
+    #[test]
+    fn test_load_unstable_hash_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache_file = directory.path().join("plugins.cache");
+        std::fs::write(
+            &cache_file,
+            format!(
+                "{}\nlibrary /tmp/libdelay.so\ncompatibility 0x2a\n",
+                UNSTABLE_HASH_CHECK_CACHE_HEADER
+            ),
+        )
+        .unwrap();
+        assert!(CheckCache::load(&cache_file).unwrap().is_empty());
+    }
+}
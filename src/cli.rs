@@ -0,0 +1,295 @@
+/*!
+Provides the [`PluginArgs`](struct.PluginArgs.html) type, which adds the common plugin flags to a
+[clap](https://docs.rs/clap) command line and feeds the parsed values into a plugin manager or a
+plugin manager configuration. This module is only available if the feature `clap` is enabled.
+
+The flags added are:
+
+* `--plugin <LIBRARY>`, may be repeated, a library to load plugins from; relative names are
+  resolved using the plugin manager's search path.
+* `--plugin-dir <DIRECTORY>`, may be repeated, a directory from which to load all libraries, see
+  [`PluginManager::load_plugins_from_dir`](../manager/struct.PluginManager.html#method.load_plugins_from_dir).
+* `--plugin-config <FILE>`, a TOML file containing a
+  [`PluginManagerConfiguration`](../config/struct.PluginManagerConfiguration.html).
+
+# Example
+
+`PluginArgs` implements clap's `Args` and `FromArgMatches` traits, and so it may be flattened into
+a command line defined with clap's derive API, or added to one built with the builder API as shown
+below.
+
+```rust,no_run
+use clap::{Args, Command, FromArgMatches};
+use dygpi::cli::PluginArgs;
+use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+
+let command = PluginArgs::augment_args(Command::new("sound_host"));
+let plugin_args = PluginArgs::from_arg_matches(&command.get_matches()).unwrap();
+
+let plugin_manager: PluginManager<SoundEffectPlugin> = match plugin_args.configuration().unwrap() {
+    Some(config) => config.make_manager_for_type("sound_effects").unwrap(),
+    None => PluginManager::default(),
+};
+plugin_args.load_into(&plugin_manager).unwrap();
+```
+
+*/
+
+use crate::config::PluginManagerConfiguration;
+use crate::error::{Error, Result};
+use crate::manager::PluginManager;
+use crate::plugin::Plugin;
+use clap::builder::{PathBufValueParser, TypedValueParser};
+use clap::{Arg, ArgAction, ArgMatches, Args, Command, FromArgMatches};
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The identifier of the `--plugin` argument.
+///
+pub const PLUGIN_ARG: &str = "plugin";
+
+///
+/// The identifier of the `--plugin-dir` argument.
+///
+pub const PLUGIN_DIR_ARG: &str = "plugin-dir";
+
+///
+/// The identifier of the `--plugin-config` argument.
+///
+pub const PLUGIN_CONFIG_ARG: &str = "plugin-config";
+
+///
+/// The values of the plugin flags, see the [module documentation](index.html).
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginArgs {
+    libraries: Vec<PathBuf>,
+    directories: Vec<PathBuf>,
+    config_file: Option<PathBuf>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A value parser which accepts only the path of an existing directory, used by `--plugin-dir`.
+///
+pub fn directory_value_parser() -> impl TypedValueParser<Value = PathBuf> {
+    PathBufValueParser::new().try_map(|path| {
+        if path.is_dir() {
+            Ok(path)
+        } else {
+            Err(format!("'{}' is not a directory", path.display()))
+        }
+    })
+}
+
+///
+/// A value parser which accepts only the path of an existing file, used by `--plugin-config`.
+///
+pub fn file_value_parser() -> impl TypedValueParser<Value = PathBuf> {
+    PathBufValueParser::new().try_map(|path| {
+        if path.is_file() {
+            Ok(path)
+        } else {
+            Err(format!("'{}' is not a file", path.display()))
+        }
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Args for PluginArgs {
+    fn augment_args(command: Command) -> Command {
+        command
+            .arg(
+                Arg::new(PLUGIN_ARG)
+                    .long(PLUGIN_ARG)
+                    .value_name("LIBRARY")
+                    .help("A library to load plugins from")
+                    .action(ArgAction::Append)
+                    .value_parser(PathBufValueParser::new()),
+            )
+            .arg(
+                Arg::new(PLUGIN_DIR_ARG)
+                    .long(PLUGIN_DIR_ARG)
+                    .value_name("DIRECTORY")
+                    .help("A directory to load all plugin libraries from")
+                    .action(ArgAction::Append)
+                    .value_parser(directory_value_parser()),
+            )
+            .arg(
+                Arg::new(PLUGIN_CONFIG_ARG)
+                    .long(PLUGIN_CONFIG_ARG)
+                    .value_name("FILE")
+                    .help("A TOML file containing the plugin configuration")
+                    .action(ArgAction::Set)
+                    .value_parser(file_value_parser()),
+            )
+    }
+
+    fn augment_args_for_update(command: Command) -> Command {
+        Self::augment_args(command)
+    }
+}
+
+impl FromArgMatches for PluginArgs {
+    fn from_arg_matches(matches: &ArgMatches) -> std::result::Result<Self, clap::Error> {
+        let mut plugin_args = Self::default();
+        plugin_args.update_from_arg_matches(matches)?;
+        Ok(plugin_args)
+    }
+
+    fn update_from_arg_matches(
+        &mut self,
+        matches: &ArgMatches,
+    ) -> std::result::Result<(), clap::Error> {
+        if let Some(libraries) = matches.get_many::<PathBuf>(PLUGIN_ARG) {
+            self.libraries = libraries.cloned().collect();
+        }
+        if let Some(directories) = matches.get_many::<PathBuf>(PLUGIN_DIR_ARG) {
+            self.directories = directories.cloned().collect();
+        }
+        if let Some(config_file) = matches.get_one::<PathBuf>(PLUGIN_CONFIG_ARG) {
+            self.config_file = Some(config_file.clone());
+        }
+        Ok(())
+    }
+}
+
+impl PluginArgs {
+    /// Returns `true` if no plugin flags were provided, else `false`.
+    pub fn is_empty(&self) -> bool {
+        self.libraries.is_empty() && self.directories.is_empty() && self.config_file.is_none()
+    }
+
+    /// The libraries provided with `--plugin`, in command line order.
+    pub fn libraries(&self) -> impl Iterator<Item = &PathBuf> {
+        self.libraries.iter()
+    }
+
+    /// The directories provided with `--plugin-dir`, in command line order.
+    pub fn directories(&self) -> impl Iterator<Item = &PathBuf> {
+        self.directories.iter()
+    }
+
+    /// The configuration file provided with `--plugin-config`, if any.
+    pub fn config_file(&self) -> Option<&PathBuf> {
+        self.config_file.as_ref()
+    }
+
+    ///
+    /// Read the configuration file provided with `--plugin-config`, returning `None` if the flag
+    /// was not provided.
+    ///
+    pub fn configuration(&self) -> Result<Option<PluginManagerConfiguration>> {
+        self.config_file
+            .as_deref()
+            .map(read_configuration)
+            .transpose()
+    }
+
+    ///
+    /// Load the plugins from all libraries provided with `--plugin`, then from all libraries in
+    /// the directories provided with `--plugin-dir`.
+    ///
+    pub fn load_into<T>(&self, plugin_manager: &PluginManager<T>) -> Result<()>
+    where
+        T: Plugin,
+    {
+        info!("PluginArgs::load_into({:?})", self);
+        if !self.libraries.is_empty() {
+            plugin_manager.load_plugins_from_all(&self.libraries)?;
+        }
+        for directory in &self.directories {
+            plugin_manager.load_plugins_from_dir(directory)?;
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn read_configuration(file_name: &Path) -> Result<PluginManagerConfiguration> {
+    trace!("read_configuration({:?})", file_name);
+    let config_failed = |e: Box<dyn std::error::Error>| Error::configuration_failed(file_name, e);
+    let text = std::fs::read_to_string(file_name).map_err(|e| config_failed(Box::new(e)))?;
+    toml::from_str(&text).map_err(|e| config_failed(Box::new(e)))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> std::result::Result<PluginArgs, clap::Error> {
+        let command = PluginArgs::augment_args(Command::new("test"));
+        PluginArgs::from_arg_matches(&command.try_get_matches_from(args)?)
+    }
+
+    #[test]
+    fn test_parse_plugin_args() {
+        let directory = tempfile::tempdir().unwrap();
+        let config_file = directory.path().join("plugins.toml");
+        std::fs::write(&config_file, "[plugins]\nsound = [\"libdelay\"]\n").unwrap();
+
+        let plugin_args = parse(&[
+            "test",
+            "--plugin",
+            "libdelay",
+            "--plugin",
+            "libreverb",
+            "--plugin-dir",
+            directory.path().to_str().unwrap(),
+            "--plugin-config",
+            config_file.to_str().unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            plugin_args.libraries().collect::<Vec<&PathBuf>>(),
+            vec![&PathBuf::from("libdelay"), &PathBuf::from("libreverb")]
+        );
+        assert_eq!(
+            plugin_args.directories().collect::<Vec<&PathBuf>>(),
+            vec![&directory.path().to_path_buf()]
+        );
+        let config = plugin_args.configuration().unwrap().unwrap();
+        assert!(config.contains_plugin_type("sound"));
+
+        assert!(parse(&["test"]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reject_invalid_paths() {
+        let directory = tempfile::tempdir().unwrap();
+        let missing = directory.path().join("missing");
+        assert!(parse(&["test", "--plugin-dir", missing.to_str().unwrap()]).is_err());
+        assert!(parse(&["test", "--plugin-config", missing.to_str().unwrap()]).is_err());
+        assert!(parse(&[
+            "test",
+            "--plugin-config",
+            directory.path().to_str().unwrap()
+        ])
+        .is_err());
+    }
+}
@@ -1,7 +1,8 @@
 /*!
 Provides a configuration type that can be used to map a plugin type identifier to a list of
-library paths. The result is the ability to create plugin manager instances from this configuration
-without having to load all the plugin provider paths programmatically.
+library paths, and the options used to load them. The result is the ability to create plugin
+manager instances from this configuration without having to load all the plugin provider paths
+programmatically.
 
 # Example
 
@@ -16,7 +17,7 @@ use dygpi::plugin::Plugin;
 # #[derive(Debug)]
 # struct SoundEffectPlugin;
 # impl Plugin for SoundEffectPlugin {
-#     fn plugin_id(&self) -> &String {
+#     fn plugin_id(&self) -> &str {
 #         todo!()
 #     }
 #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
@@ -34,6 +35,127 @@ let plugin_manager: PluginManager<SoundEffectPlugin> =
     };
 ```
 
+# Example - Environment
+
+The configuration may instead be read from environment variables, which is convenient for
+container deployments. Each variable named with the prefix
+[`DEFAULT_ENV_PREFIX`](constant.DEFAULT_ENV_PREFIX.html), `DYGPI_PLUGINS_`, provides the libraries
+for one plugin type; the remainder of the name, in lower case, is the plugin type identifier and
+the value is a list of library paths separated by the platform's path separator, `':'`, or `';'`
+on Windows. So the following provides two libraries for the plugin type `sound_effects`.
+
+```bash
+DYGPI_PLUGINS_SOUND_EFFECTS=libdelay:libreverb
+```
+
+```rust
+use dygpi::config::PluginManagerConfiguration;
+
+let config = PluginManagerConfiguration::from_env();
+for library in config
+    .plugin_libraries_for_type("sound_effects")
+    .into_iter()
+    .flatten()
+{
+    println!("{:?}", library);
+}
+```
+
+# Example - Disabled Plugins
+
+A configuration may also list plugins, by plugin identifier, that are disabled when they are
+loaded, see [`PluginManager::disable_plugin`](../manager/struct.PluginManager.html#method.disable_plugin).
+Once a user has enabled or disabled plugins the set may be recorded back into the configuration
+with [`record_disabled_plugins`](struct.PluginManagerConfiguration.html#method.record_disabled_plugins)
+and saved.
+
+```toml
+disabled = ["sound_one::sound_one::ReverbEffect"]
+
+[plugins]
+sound_effects = ["libsound_one.dylib"]
+```
+
+# Example - Platform Library Names
+
+Library file names differ by platform, `libdelay.so` on Linux, `libdelay.dylib` on macOS, and
+`delay.dll` on Windows. If a configuration sets `auto_platform_names` its library names without an
+extension are formatted according to the conventions of the current platform before loading, see
+[`PluginManager::set_auto_platform_names`](../manager/struct.PluginManager.html#method.set_auto_platform_names),
+so that the same configuration file may be used on every platform.
+
+```toml
+auto_platform_names = true
+
+[plugins]
+sound_effects = ["delay", "reverb"]
+```
+
+# Example - Plugin Type Options
+
+A plugin type may be given as a table, rather than a list of libraries, to set the options used
+by [`make_manager_for_type`](struct.PluginManagerConfiguration.html#method.make_manager_for_type)
+when loading them, see [`PluginTypeConfiguration`](struct.PluginTypeConfiguration.html). The two
+forms may be mixed in the same file. The top-level `search_path` is shared by the plugin managers
+of every type, which may be created in one pass with
+[`make_all`](struct.PluginManagerConfiguration.html#method.make_all).
+
+```toml
+search_path = ["/usr/local/lib/sound_host"]
+
+[plugins]
+light_effects = ["libbright.so"]
+
+[plugins.sound_effects]
+libraries = ["libdelay.so", "libreverb.so"]
+search_path = ["/opt/sound_host/plugins"]
+register_fn = "register_effects"
+required = true
+
+[plugins.sound_effects.settings]
+sample_rate = "48000"
+```
+
+# Example - Configuration Files
+
+If the feature `dirs` is enabled the configuration of an application may be read from the
+platform's standard directories, with the file name [`CONFIG_FILE_NAME`](constant.CONFIG_FILE_NAME.html).
+System-wide files are read first, followed by the user's file, and each is merged into the result
+with [`merge_configuration`](struct.PluginManagerConfiguration.html#method.merge_configuration).
+
+| Platform | User file                                               | System files                               |
+|----------|---------------------------------------------------------|--------------------------------------------|
+| Linux    | `$XDG_CONFIG_HOME/<app>/plugins.toml`                   | `$XDG_CONFIG_DIRS/<app>/plugins.toml`      |
+| macOS    | `$HOME/Library/Application Support/<app>/plugins.toml`  | `/Library/Application Support/<app>/plugins.toml` |
+| Windows  | `%APPDATA%\<app>\plugins.toml`                          | `%PROGRAMDATA%\<app>\plugins.toml`         |
+
+```rust,no_run
+# #[cfg(feature = "dirs")]
+# fn main() {
+use dygpi::config::PluginManagerConfiguration;
+# use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+
+let mut config = PluginManagerConfiguration::load_for_application("sound_host").unwrap();
+let plugin_manager: PluginManager<SoundEffectPlugin> =
+    config.make_manager_for_type("sound_effects").unwrap();
+
+// ... the user disables a plugin ...
+
+config.record_disabled_plugins(&plugin_manager);
+let _ = config.save_for_user("sound_host").unwrap();
+# }
+# #[cfg(not(feature = "dirs"))]
+# fn main() {}
+```
+
 # Example - Serde
 
 Given the following simple configuration we can save it in any format supported by Serde.
@@ -42,8 +164,8 @@ Given the following simple configuration we can save it in any format supported
 use dygpi::config::PluginManagerConfiguration;
 
 let mut config = PluginManagerConfiguration::default();
-let _ = config.insert("sound_effects", &["beep".as_ref(), "boop".as_ref()]);
-let _ = config.insert("light_effects", &["bright".as_ref(), "mood".as_ref()]);
+let _ = config.insert("sound_effects", &["beep".as_ref(), "boop".as_ref()]).unwrap();
+let _ = config.insert("light_effects", &["bright".as_ref(), "mood".as_ref()]).unwrap();
 ```
 
 In **TOML**:
@@ -80,19 +202,37 @@ plugins:
 
 */
 
-use crate::error::{Error, ErrorKind, Result};
+use crate::error::{Error, Result};
 use crate::manager::PluginManager;
-use crate::plugin::Plugin;
-use std::collections::{HashMap, HashSet};
+use crate::plugin::{Plugin, PluginType};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::env;
+use std::ffi::OsString;
+use std::iter::FromIterator;
 
 #[cfg(feature = "config_serde")]
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use std::ffi::CString;
 use std::path::{Path, PathBuf};
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
+///
+/// The prefix of the environment variables read by
+/// [`PluginManagerConfiguration::from_env`](struct.PluginManagerConfiguration.html#method.from_env).
+///
+pub const DEFAULT_ENV_PREFIX: &str = "DYGPI_PLUGINS_";
+
+///
+/// The name of the configuration files read by
+/// [`PluginManagerConfiguration::load_for_application`](struct.PluginManagerConfiguration.html#method.load_for_application).
+/// This is only available if the feature `dirs` is enabled.
+///
+#[cfg(feature = "dirs")]
+pub const CONFIG_FILE_NAME: &str = "plugins.toml";
+
 ///
 /// The plugin manager configuration itself. This is logically a map from a _plugin type identifier_
 /// and a list of library paths. The type identifier allows the configuration to partition the list
@@ -115,24 +255,193 @@ use std::path::{Path, PathBuf};
 /// ```
 ///
 #[cfg_attr(feature = "config_serde", derive(Deserialize, Serialize))]
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct PluginManagerConfiguration {
-    plugins: HashMap<String, HashSet<PathBuf>>,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    disabled: Option<BTreeSet<String>>,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(default, skip_serializing_if = "is_false")
+    )]
+    auto_platform_names: bool,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    search_path: Vec<PathBuf>,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(
+            deserialize_with = "deserialize_plugins",
+            serialize_with = "serialize_plugins"
+        )
+    )]
+    plugins: HashMap<String, PluginTypeConfiguration>,
+}
+
+///
+/// The configuration of a single plugin type; the libraries to load, and the options used by
+/// [`PluginManagerConfiguration::make_manager_for_type`](struct.PluginManagerConfiguration.html#method.make_manager_for_type)
+/// to load them. When serialized a plugin type with no options set is written as a list of its
+/// libraries, and either form is accepted when deserialized.
+///
+#[cfg_attr(feature = "config_serde", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginTypeConfiguration {
+    libraries: HashSet<PathBuf>,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    search_path: Vec<PathBuf>,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    register_fn: Option<String>,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(default, skip_serializing_if = "is_false")
+    )]
+    required: bool,
+    #[cfg_attr(
+        feature = "config_serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    settings: BTreeMap<String, String>,
+}
+
+///
+/// The differences between two configurations, returned by
+/// [`PluginManagerConfiguration::diff`](struct.PluginManagerConfiguration.html#method.diff).
+/// Plugin types are listed in name order, and libraries as pairs of plugin type identifier and
+/// library path in plugin type, then path, order. The libraries of an added or removed plugin
+/// type are included in the added or removed libraries.
+///
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    added_plugin_types: Vec<String>,
+    removed_plugin_types: Vec<String>,
+    added_libraries: Vec<(String, PathBuf)>,
+    removed_libraries: Vec<(String, PathBuf)>,
+}
+
+///
+/// A plugin type in a configuration, passed to the closure provided to
+/// [`PluginManagerConfiguration::make_all`](struct.PluginManagerConfiguration.html#method.make_all)
+/// so that it may create the plugin manager for the type.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct ConfiguredPluginType<'a> {
+    config: &'a PluginManagerConfiguration,
+    plugin_type: &'a str,
 }
 
 // ------------------------------------------------------------------------------------------------
-// Implementations
+// Private Types
 // ------------------------------------------------------------------------------------------------
 
-impl Default for PluginManagerConfiguration {
-    fn default() -> Self {
-        Self {
-            plugins: Default::default(),
+///
+/// A plugin type as written in a configuration file, either the original list of libraries or a
+/// table of options.
+///
+#[cfg(feature = "config_serde")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PluginTypeEntry {
+    Libraries(HashSet<PathBuf>),
+    Options(PluginTypeConfiguration),
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Returns `true` if `plugin_type` is a valid plugin type identifier, else `false`. A valid
+/// identifier is not empty, and contains only ASCII letters, digits, `'_'`, and `'-'`, so that it
+/// may be written as a bare key in a TOML configuration file.
+///
+pub const fn is_valid_plugin_type(plugin_type: &str) -> bool {
+    let bytes = plugin_type.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let c = bytes[index];
+        if !(c.is_ascii_alphanumeric() || c == b'_' || c == b'-') {
+            return false;
         }
+        index += 1;
     }
+    !bytes.is_empty()
 }
 
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
 impl PluginManagerConfiguration {
+    /// Construct a configuration from the environment variables named with the prefix
+    /// [`DEFAULT_ENV_PREFIX`](constant.DEFAULT_ENV_PREFIX.html), see the
+    /// [module documentation](index.html#example---environment) for the naming scheme.
+    pub fn from_env() -> Self {
+        Self::from_env_with_prefix(DEFAULT_ENV_PREFIX)
+    }
+
+    /// Construct a configuration from the environment variables named with `prefix`; the
+    /// remainder of each name, in lower case, is the plugin type identifier, and the value is a
+    /// list of library paths separated by the platform's path separator. Variables with an empty
+    /// value, or an empty plugin type, are ignored.
+    pub fn from_env_with_prefix(prefix: &str) -> Self {
+        info!(
+            "PluginManagerConfiguration::from_env_with_prefix({:?})",
+            prefix
+        );
+        Self::from_vars(prefix, env::vars_os())
+    }
+
+    fn from_vars<I>(prefix: &str, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (OsString, OsString)>,
+    {
+        let mut config = Self::default();
+        for (name, value) in vars {
+            let plugin_type = match name.to_str().and_then(|name| name.strip_prefix(prefix)) {
+                Some(plugin_type) if !plugin_type.is_empty() => plugin_type.to_lowercase(),
+                _ => continue,
+            };
+            let libraries: HashSet<PathBuf> = env::split_paths(&value)
+                .filter(|library| !library.as_os_str().is_empty())
+                .collect();
+            if !is_valid_plugin_type(&plugin_type) {
+                warn!(
+                    "Environment variable for plugin type {:?} is not a valid plugin type",
+                    plugin_type
+                );
+            } else if libraries.is_empty() {
+                warn!(
+                    "Environment variable for plugin type {:?} is empty",
+                    plugin_type
+                );
+            } else {
+                trace!(
+                    "PluginManagerConfiguration::from_vars() > plugin type {:?}",
+                    plugin_type
+                );
+                config
+                    .plugins
+                    .entry(plugin_type)
+                    .or_default()
+                    .libraries
+                    .extend(libraries);
+            }
+        }
+        config
+    }
+
     /// Returns `true` if the configuration contains no plugin types, else `false`.
     pub fn is_empty(&self) -> bool {
         self.plugins.is_empty()
@@ -160,45 +469,172 @@ impl PluginManagerConfiguration {
         &self,
         plugin_type: &str,
     ) -> Option<impl Iterator<Item = &PathBuf>> {
-        self.plugins.get(plugin_type).map(|vs| vs.iter())
+        self.plugins.get(plugin_type).map(|vs| vs.libraries.iter())
+    }
+
+    /// Returns the libraries, and options, specified for the provided plugin type identifier, or
+    /// `None` if the configuration has no entry for the plugin type.
+    pub fn plugin_type_configuration(&self, plugin_type: &str) -> Option<&PluginTypeConfiguration> {
+        self.plugins.get(plugin_type)
+    }
+
+    /// Returns a mutable reference to the libraries, and options, specified for the provided
+    /// plugin type identifier, or `None` if the configuration has no entry for the plugin type.
+    pub fn plugin_type_configuration_mut(
+        &mut self,
+        plugin_type: &str,
+    ) -> Option<&mut PluginTypeConfiguration> {
+        self.plugins.get_mut(plugin_type)
     }
 
-    /// Insert a list of libraries for the named plugin type; if there exists an entry for this
-    /// type already it will be replaced. Note that this method will panic if the library list is
-    /// empty.
+    /// Insert a list of libraries, with no options, for the named plugin type; if there exists an
+    /// entry for this type already it will be replaced, and returned. An error is returned if the
+    /// library list is empty, or the plugin type identifier is not valid, see
+    /// [`is_valid_plugin_type`](fn.is_valid_plugin_type.html).
     pub fn insert(
         &mut self,
         plugin_type: &str,
         library_list: &[&Path],
-    ) -> Option<HashSet<PathBuf>> {
-        assert!(!library_list.is_empty());
-        self.plugins.insert(
+    ) -> Result<Option<PluginTypeConfiguration>> {
+        check_library_list(plugin_type, library_list)?;
+        Ok(self.plugins.insert(
             plugin_type.to_string(),
-            library_list.iter().map(|p| p.to_path_buf()).collect(),
-        )
+            PluginTypeConfiguration::new(library_list),
+        ))
     }
 
     /// Merge a list of libraries into the configuration for the plugin type. if there exists an
     /// entry for this type already the values provided will be added to the list, if not then this
-    /// acts exactly as `insert`. Note that this method will panic if the library list is empty.
-    pub fn merge(&mut self, plugin_type: &str, library_list: &[&Path]) {
-        assert!(!library_list.is_empty());
-        if let Some(libraries) = self.plugins.get_mut(plugin_type) {
-            libraries.extend(library_list.iter().map(|p| p.to_path_buf()))
+    /// acts exactly as `insert`. An error is returned if the library list is empty, or the plugin
+    /// type identifier is not valid.
+    pub fn merge(&mut self, plugin_type: &str, library_list: &[&Path]) -> Result<()> {
+        check_library_list(plugin_type, library_list)?;
+        if let Some(config) = self.plugins.get_mut(plugin_type) {
+            config
+                .libraries
+                .extend(library_list.iter().map(|p| p.to_path_buf()))
         } else {
-            let _ = self.insert(plugin_type, library_list);
+            let _ = self.insert(plugin_type, library_list)?;
         }
+        Ok(())
     }
 
-    /// Removes and returns the plugin libraries for the plugin type.
-    pub fn remove(&mut self, plugin_type: &str) -> Option<HashSet<PathBuf>> {
+    /// Removes and returns the plugin libraries, and options, for the plugin type.
+    pub fn remove(&mut self, plugin_type: &str) -> Option<PluginTypeConfiguration> {
         self.plugins.remove(plugin_type)
     }
 
+    /// Merge `other` into this configuration; the libraries and search path for each plugin type
+    /// are added, as for [`merge`](#method.merge), and the options set in `other` replace those in
+    /// this configuration. If `other` lists disabled plugins its list replaces any in this
+    /// configuration. This is used to layer a user's configuration over a system-wide one.
+    pub fn merge_configuration(&mut self, other: Self) {
+        for (plugin_type, config) in other.plugins {
+            self.plugins
+                .entry(plugin_type)
+                .or_default()
+                .merge_configuration(config);
+        }
+        if other.disabled.is_some() {
+            self.disabled = other.disabled;
+        }
+        self.auto_platform_names |= other.auto_platform_names;
+        for path in other.search_path {
+            if !self.search_path.contains(&path) {
+                self.search_path.push(path);
+            }
+        }
+    }
+
+    /// Set whether library names without an extension, such as `"delay"`, are formatted according
+    /// to the platform's conventions by the plugin managers created from this configuration, see
+    /// [`PluginManager::set_auto_platform_names`](../manager/struct.PluginManager.html#method.set_auto_platform_names).
+    /// The default is `false`.
+    pub fn set_auto_platform_names(&mut self, auto_platform_names: bool) {
+        self.auto_platform_names = auto_platform_names
+    }
+
+    /// Returns `true` if library names without an extension are given the platform's prefix and
+    /// extension before loading, else `false`.
+    pub fn auto_platform_names(&self) -> bool {
+        self.auto_platform_names
+    }
+
+    /// Return an iterator over the directories added to the search path of every plugin manager
+    /// created from this configuration, in the order they are searched. These are searched before
+    /// the search path of the individual plugin type.
+    pub fn search_path(&self) -> impl Iterator<Item = &PathBuf> {
+        self.search_path.iter()
+    }
+
+    /// Add a directory to the end of the search path shared by every plugin manager created from
+    /// this configuration, see
+    /// [`PluginManager::add_search_path`](../manager/struct.PluginManager.html#method.add_search_path).
+    pub fn add_search_path(&mut self, path: &Path) {
+        self.search_path.push(path.to_path_buf())
+    }
+
+    /// Return an iterator over the identifiers of the plugins that are disabled when loaded, in
+    /// identifier order.
+    pub fn disabled_plugins(&self) -> impl Iterator<Item = &String> {
+        self.disabled.iter().flatten()
+    }
+
+    /// Returns `true` if the plugin with the provided identifier is disabled when loaded, else
+    /// `false`.
+    pub fn is_plugin_disabled(&self, plugin_id: &str) -> bool {
+        self.disabled
+            .as_ref()
+            .is_some_and(|disabled| disabled.contains(plugin_id))
+    }
+
+    /// Add the plugin with the provided identifier to, or if `disabled` is `false` remove it
+    /// from, the plugins that are disabled when loaded.
+    pub fn set_plugin_disabled(&mut self, plugin_id: &str, disabled: bool) {
+        let disabled_plugins = self.disabled.get_or_insert_with(Default::default);
+        if disabled {
+            let _ = disabled_plugins.insert(plugin_id.to_string());
+        } else {
+            let _ = disabled_plugins.remove(plugin_id);
+        }
+    }
+
+    /// Record whether each plugin registered in `plugin_manager` is disabled, so that the user's
+    /// changes may be saved; plugins not registered in the manager, for example those of other
+    /// plugin types, are unaffected.
+    pub fn record_disabled_plugins<T>(&mut self, plugin_manager: &PluginManager<T>)
+    where
+        T: Plugin,
+    {
+        for info in plugin_manager.plugin_infos() {
+            self.set_plugin_disabled(info.plugin_id(), !info.is_enabled());
+        }
+    }
+
+    /// Return the changes that would turn this configuration into `other`, for example to
+    /// preview the effect of applying a new configuration with
+    /// [`PluginManager::apply_configuration`](../manager/struct.PluginManager.html#method.apply_configuration).
+    /// Library paths are compared as written, they are not resolved or canonicalized.
+    pub fn diff(&self, other: &Self) -> ConfigDiff {
+        let mut diff = ConfigDiff {
+            added_plugin_types: missing_plugin_types(other, self),
+            removed_plugin_types: missing_plugin_types(self, other),
+            added_libraries: missing_libraries(other, self),
+            removed_libraries: missing_libraries(self, other),
+        };
+        diff.added_plugin_types.sort();
+        diff.removed_plugin_types.sort();
+        diff.added_libraries.sort();
+        diff.removed_libraries.sort();
+        diff
+    }
+
     /// Construct and return a new [`PluginManager`](../manager/struct.PluginManager.html) for
-    /// plugins of type `T` using the list of libraries specified for the plugin type identifier
-    /// provided. Note that this method will return an error if there is no configured library
-    /// list for the provided plugin type.
+    /// plugins of type `T` using the list of libraries, and options, specified for the plugin type
+    /// identifier provided; any plugins the configuration lists as disabled are disabled once
+    /// loaded. Note that this method will return an error if there is no configured library list
+    /// for the provided plugin type, or if the plugin type is required and its libraries register
+    /// no plugins.
     ///
     /// # Example
     ///
@@ -213,7 +649,7 @@ impl PluginManagerConfiguration {
     /// #     id: String,
     /// # };
     /// # impl Plugin for SoundEffectPlugin {
-    /// #     fn plugin_id(&self) -> &String {
+    /// #     fn plugin_id(&self) -> &str {
     /// #         &self.id
     /// #     }
     /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
@@ -235,21 +671,631 @@ impl PluginManagerConfiguration {
     where
         T: Plugin,
     {
-        if let Some(library_list) = self.plugins.get(plugin_type) {
+        self.make_manager_for_type_with(plugin_type, |_| {})
+    }
+
+    /// Construct and return a new [`PluginManager`](../manager/struct.PluginManager.html) for
+    /// plugins of type `T`, as for [`make_manager_for_type`](#method.make_manager_for_type), but
+    /// call `customize` with the new manager before the configured libraries are loaded. The
+    /// manager passed to `customize` already has the options from the configuration applied, so
+    /// that it may add to, or override, them; for example to add to the search path, or set the
+    /// registration function name, duplicate load policy, or interceptors.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use dygpi::config::PluginManagerConfiguration;
+    /// use dygpi::manager::{DuplicateLoadPolicy, PluginManager};
+    /// use std::path::Path;
+    /// # use dygpi::plugin::Plugin;
+    /// # #[derive(Debug)]
+    /// # struct SoundEffectPlugin;
+    /// # impl Plugin for SoundEffectPlugin {
+    /// #     fn plugin_id(&self) -> &str { unimplemented!() }
+    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// # }
+    /// # fn load_config_file() -> PluginManagerConfiguration { PluginManagerConfiguration::default() }
+    ///
+    /// let config = load_config_file();
+    /// let manager: PluginManager<SoundEffectPlugin> = config
+    ///     .make_manager_for_type_with("sound_effects", |manager| {
+    ///         manager.add_search_path(Path::new("/opt/sound_host/plugins"));
+    ///         manager.set_duplicate_load_policy(DuplicateLoadPolicy::Error);
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn make_manager_for_type_with<T, F>(
+        &self,
+        plugin_type: &str,
+        customize: F,
+    ) -> Result<PluginManager<T>>
+    where
+        T: Plugin,
+        F: FnOnce(&mut PluginManager<T>),
+    {
+        if let Some(config) = self.plugins.get(plugin_type) {
             let mut manager: PluginManager<T> = PluginManager::default();
-            manager.load_plugins_from_all(
-                &library_list
-                    .iter()
-                    .map(|p| p.as_path())
-                    .collect::<Vec<&Path>>(),
-            )?;
+            manager.set_plugin_type(plugin_type);
+            manager.set_auto_platform_names(self.auto_platform_names);
+            for path in self.search_path.iter().chain(&config.search_path) {
+                manager.add_search_path(path);
+            }
+            if let Some(register_fn) = &config.register_fn {
+                let register_fn = CString::new(register_fn.as_str())
+                    .map_err(|e| Error::configuration_failed(plugin_type, e))?;
+                manager.set_registration_fn_name(&register_fn);
+            }
+            customize(&mut manager);
+            manager.load_plugins_from_all(&config.libraries.iter().collect::<Vec<&PathBuf>>())?;
+            if manager.is_empty() {
+                if config.required {
+                    return Err(Error::no_plugins_loaded(plugin_type));
+                }
+                warn!("No plugins loaded for plugin type {:?}", plugin_type);
+            }
+            manager.disable_configured_plugins(self)?;
             Ok(manager)
         } else {
-            Err(Error::from(ErrorKind::UnknownPluginManagerType(
-                plugin_type.to_string(),
-            )))
+            Err(Error::unknown_plugin_manager_type(plugin_type))
         }
     }
+
+    /// Construct and return a new [`PluginManager`](../manager/struct.PluginManager.html) for
+    /// the plugin type marker `P`, as for [`make_manager_for_type`](#method.make_manager_for_type)
+    /// with the identifier `P::ID`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use dygpi::config::PluginManagerConfiguration;
+    /// use dygpi::manager::PluginManager;
+    /// # use dygpi::plugin::Plugin;
+    /// # #[derive(Debug)]
+    /// # struct SoundEffectPlugin;
+    /// # impl Plugin for SoundEffectPlugin {
+    /// #     fn plugin_id(&self) -> &str { unimplemented!() }
+    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// # }
+    /// # fn load_config_file() -> PluginManagerConfiguration { PluginManagerConfiguration::default() }
+    ///
+    /// dygpi::plugin_type!(SoundEffects = "sound_effects" for SoundEffectPlugin);
+    ///
+    /// let config = load_config_file();
+    /// let manager: PluginManager<SoundEffectPlugin> =
+    ///     config.make_manager::<SoundEffects>().unwrap();
+    /// ```
+    pub fn make_manager<P>(&self) -> Result<PluginManager<P::Plugin>>
+    where
+        P: PluginType,
+    {
+        self.make_manager_for_type(P::ID)
+    }
+
+    /// Construct and return a new [`PluginManager`](../manager/struct.PluginManager.html) for
+    /// the plugin type marker `P`, as for
+    /// [`make_manager_for_type_with`](#method.make_manager_for_type_with) with the identifier
+    /// `P::ID`.
+    pub fn make_manager_with<P, F>(&self, customize: F) -> Result<PluginManager<P::Plugin>>
+    where
+        P: PluginType,
+        F: FnOnce(&mut PluginManager<P::Plugin>),
+    {
+        self.make_manager_for_type_with(P::ID, customize)
+    }
+
+    /// Call `f` once for each plugin type in the configuration, in plugin type identifier order,
+    /// so that the plugin manager for each may be created in one pass; typically `f` matches on
+    /// [`ConfiguredPluginType::plugin_type`](struct.ConfiguredPluginType.html#method.plugin_type)
+    /// and calls
+    /// [`ConfiguredPluginType::make_manager`](struct.ConfiguredPluginType.html#method.make_manager)
+    /// with the corresponding plugin type. Every manager uses the search path of the
+    /// configuration, and shares open libraries through the process-wide
+    /// [`LibraryCache`](../cache/struct.LibraryCache.html), so a library providing plugins of more
+    /// than one type is opened once. The first error returned by `f` is returned, and no further
+    /// plugin types are visited.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use dygpi::config::PluginManagerConfiguration;
+    /// use dygpi::manager::PluginManager;
+    /// # use dygpi::plugin::Plugin;
+    /// # #[derive(Debug)]
+    /// # struct SoundEffectPlugin;
+    /// # impl Plugin for SoundEffectPlugin {
+    /// #     fn plugin_id(&self) -> &str { unimplemented!() }
+    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// # }
+    /// # #[derive(Debug)]
+    /// # struct LightEffectPlugin;
+    /// # impl Plugin for LightEffectPlugin {
+    /// #     fn plugin_id(&self) -> &str { unimplemented!() }
+    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// # }
+    /// # fn load_config_file() -> PluginManagerConfiguration { PluginManagerConfiguration::default() }
+    ///
+    /// let config = load_config_file();
+    /// let mut sounds: Option<PluginManager<SoundEffectPlugin>> = None;
+    /// let mut lights: Option<PluginManager<LightEffectPlugin>> = None;
+    /// config
+    ///     .make_all(|configured| {
+    ///         match configured.plugin_type() {
+    ///             "sound_effects" => sounds = Some(configured.make_manager()?),
+    ///             "light_effects" => lights = Some(configured.make_manager()?),
+    ///             other => println!("ignoring plugin type {}", other),
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn make_all<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(ConfiguredPluginType<'_>) -> Result<()>,
+    {
+        let mut plugin_types: Vec<&String> = self.plugins.keys().collect();
+        plugin_types.sort();
+        for plugin_type in plugin_types {
+            trace!(
+                "PluginManagerConfiguration::make_all() > plugin type {:?}",
+                plugin_type
+            );
+            f(ConfiguredPluginType {
+                config: self,
+                plugin_type,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "dirs")]
+impl PluginManagerConfiguration {
+    /// The path of the user's configuration file for `application`, in the platform's per-user
+    /// configuration directory. Returns `None` if the platform has no such directory, for example
+    /// if the user's home directory cannot be determined.
+    pub fn user_config_file(application: &str) -> Option<PathBuf> {
+        dirs::config_dir().map(|directory| directory.join(application).join(CONFIG_FILE_NAME))
+    }
+
+    /// The paths of the system-wide configuration files for `application`, in the order in which
+    /// they are merged, so that later files take precedence.
+    pub fn system_config_files(application: &str) -> Vec<PathBuf> {
+        system_config_dirs()
+            .into_iter()
+            .map(|directory| directory.join(application).join(CONFIG_FILE_NAME))
+            .collect()
+    }
+
+    /// Read, and merge, the system-wide configuration files for `application` followed by the
+    /// user's configuration file; files that do not exist are skipped, so an application with no
+    /// configuration files has an empty configuration. An error is returned if any existing file
+    /// cannot be read or parsed.
+    pub fn load_for_application(application: &str) -> Result<Self> {
+        info!(
+            "PluginManagerConfiguration::load_for_application({:?})",
+            application
+        );
+        let mut config = Self::default();
+        for file_name in Self::system_config_files(application)
+            .into_iter()
+            .chain(Self::user_config_file(application))
+        {
+            if file_name.is_file() {
+                config.merge_configuration(Self::read_from(&file_name)?);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Write this configuration to the user's configuration file for `application`, creating
+    /// its directory if necessary, and return the path of the file.
+    pub fn save_for_user(&self, application: &str) -> Result<PathBuf> {
+        info!(
+            "PluginManagerConfiguration::save_for_user({:?})",
+            application
+        );
+        let file_name = Self::user_config_file(application).ok_or_else(|| {
+            Error::configuration_failed(application, "there is no user configuration directory")
+        })?;
+        self.write_to(&file_name)?;
+        Ok(file_name)
+    }
+
+    /// Read a configuration from the TOML file `file_name`.
+    pub fn read_from(file_name: &Path) -> Result<Self> {
+        trace!("PluginManagerConfiguration::read_from({:?})", file_name);
+        let text = std::fs::read_to_string(file_name)
+            .map_err(|e| config_failed(file_name, Box::new(e)))?;
+        toml::from_str(&text).map_err(|e| config_failed(file_name, Box::new(e)))
+    }
+
+    /// Write this configuration to the TOML file `file_name`, creating its directory if
+    /// necessary.
+    pub fn write_to(&self, file_name: &Path) -> Result<()> {
+        trace!("PluginManagerConfiguration::write_to({:?})", file_name);
+        let text = toml::to_string(self).map_err(|e| config_failed(file_name, Box::new(e)))?;
+        if let Some(directory) = file_name.parent() {
+            std::fs::create_dir_all(directory)
+                .map_err(|e| config_failed(file_name, Box::new(e)))?;
+        }
+        std::fs::write(file_name, text).map_err(|e| config_failed(file_name, Box::new(e)))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl PluginTypeConfiguration {
+    /// Construct a plugin type configuration with the list of libraries, and no options.
+    pub fn new(library_list: &[&Path]) -> Self {
+        Self {
+            libraries: library_list.iter().map(|p| p.to_path_buf()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Return an iterator over the library paths for the plugin type.
+    pub fn libraries(&self) -> impl Iterator<Item = &PathBuf> {
+        self.libraries.iter()
+    }
+
+    /// Add the library path to those for the plugin type.
+    pub fn add_library(&mut self, library: &Path) {
+        let _ = self.libraries.insert(library.to_path_buf());
+    }
+
+    /// Return an iterator over the directories added to the plugin manager's search path, in the
+    /// order they are searched.
+    pub fn search_path(&self) -> impl Iterator<Item = &PathBuf> {
+        self.search_path.iter()
+    }
+
+    /// Add a directory to the end of the plugin manager's search path, see
+    /// [`PluginManager::add_search_path`](../manager/struct.PluginManager.html#method.add_search_path).
+    pub fn add_search_path(&mut self, path: &Path) {
+        self.search_path.push(path.to_path_buf())
+    }
+
+    /// Set the name of the registration function called in each library, see
+    /// [`PluginManager::set_registration_fn_name`](../manager/struct.PluginManager.html#method.set_registration_fn_name).
+    pub fn set_register_fn(&mut self, register_fn: &str) {
+        self.register_fn = Some(register_fn.to_string())
+    }
+
+    /// Return the name of the registration function called in each library, if set.
+    pub fn register_fn(&self) -> Option<&String> {
+        self.register_fn.as_ref()
+    }
+
+    /// Set whether the plugin type is required; if it is, and its libraries register no plugins,
+    /// creating its plugin manager fails with a
+    /// [`NoPluginsLoaded`](../error/enum.ErrorKind.html#variant.NoPluginsLoaded) error rather than
+    /// a warning being logged. The default is `false`.
+    pub fn set_required(&mut self, required: bool) {
+        self.required = required
+    }
+
+    /// Returns `true` if the plugin type is required, else `false`.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Return an iterator over the application-defined settings for the plugin type, in name
+    /// order.
+    pub fn settings(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.settings.iter()
+    }
+
+    /// Return the value of the named setting, if set.
+    pub fn setting(&self, name: &str) -> Option<&String> {
+        self.settings.get(name)
+    }
+
+    /// Set the value of the named setting, returning any previous value.
+    pub fn set_setting(&mut self, name: &str, value: &str) -> Option<String> {
+        self.settings.insert(name.to_string(), value.to_string())
+    }
+
+    /// Returns `true` if any option is set, in which case the plugin type is serialized as a
+    /// table rather than a list of libraries.
+    #[cfg(feature = "config_serde")]
+    fn has_options(&self) -> bool {
+        !self.search_path.is_empty()
+            || self.register_fn.is_some()
+            || self.required
+            || !self.settings.is_empty()
+    }
+
+    fn merge_configuration(&mut self, other: Self) {
+        self.libraries.extend(other.libraries);
+        for path in other.search_path {
+            if !self.search_path.contains(&path) {
+                self.search_path.push(path);
+            }
+        }
+        if other.register_fn.is_some() {
+            self.register_fn = other.register_fn;
+        }
+        self.required |= other.required;
+        self.settings.extend(other.settings);
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ConfiguredPluginType<'_> {
+    /// The plugin type identifier.
+    pub fn plugin_type(&self) -> &str {
+        self.plugin_type
+    }
+
+    /// Returns `true` if this is the plugin type of the plugin type marker `P`, else `false`.
+    pub fn is<P>(&self) -> bool
+    where
+        P: PluginType,
+    {
+        self.plugin_type == P::ID
+    }
+
+    /// The libraries, and options, configured for the plugin type.
+    pub fn configuration(&self) -> &PluginTypeConfiguration {
+        &self.config.plugins[self.plugin_type]
+    }
+
+    ///
+    /// Construct and return the plugin manager for the plugin type, for plugins of type `T`, as
+    /// for [`PluginManagerConfiguration::make_manager_for_type`](struct.PluginManagerConfiguration.html#method.make_manager_for_type).
+    ///
+    pub fn make_manager<T>(&self) -> Result<PluginManager<T>>
+    where
+        T: Plugin,
+    {
+        self.config.make_manager_for_type(self.plugin_type)
+    }
+
+    ///
+    /// Construct and return the plugin manager for the plugin type, for plugins of type `T`,
+    /// calling `customize` with the new manager before its libraries are loaded, as for
+    /// [`PluginManagerConfiguration::make_manager_for_type_with`](struct.PluginManagerConfiguration.html#method.make_manager_for_type_with).
+    ///
+    pub fn make_manager_with<T, F>(&self, customize: F) -> Result<PluginManager<T>>
+    where
+        T: Plugin,
+        F: FnOnce(&mut PluginManager<T>),
+    {
+        self.config
+            .make_manager_for_type_with(self.plugin_type, customize)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(feature = "config_serde")]
+impl From<PluginTypeEntry> for PluginTypeConfiguration {
+    fn from(entry: PluginTypeEntry) -> Self {
+        match entry {
+            PluginTypeEntry::Libraries(libraries) => Self {
+                libraries,
+                ..Default::default()
+            },
+            PluginTypeEntry::Options(config) => config,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl FromIterator<(String, Vec<String>)> for PluginManagerConfiguration {
+    fn from_iter<I: IntoIterator<Item = (String, Vec<String>)>>(iter: I) -> Self {
+        let mut config = Self::default();
+        config.extend(iter);
+        config
+    }
+}
+
+impl Extend<(String, Vec<String>)> for PluginManagerConfiguration {
+    /// Merge each plugin type and library list into the configuration, as for
+    /// [`merge`](#method.merge); plugin types with an empty library list, or that are not valid,
+    /// are ignored.
+    fn extend<I: IntoIterator<Item = (String, Vec<String>)>>(&mut self, iter: I) {
+        for (plugin_type, library_list) in iter {
+            if !library_list.is_empty() && is_valid_plugin_type(&plugin_type) {
+                self.plugins
+                    .entry(plugin_type)
+                    .or_default()
+                    .libraries
+                    .extend(library_list.into_iter().map(PathBuf::from));
+            }
+        }
+    }
+}
+
+impl IntoIterator for PluginManagerConfiguration {
+    type Item = (String, PluginTypeConfiguration);
+    type IntoIter = std::collections::hash_map::IntoIter<String, PluginTypeConfiguration>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.plugins.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a PluginManagerConfiguration {
+    type Item = (&'a String, &'a PluginTypeConfiguration);
+    type IntoIter = std::collections::hash_map::Iter<'a, String, PluginTypeConfiguration>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.plugins.iter()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ConfigDiff {
+    /// Returns `true` if the two configurations are the same, else `false`.
+    pub fn is_empty(&self) -> bool {
+        self.added_plugin_types.is_empty()
+            && self.removed_plugin_types.is_empty()
+            && self.added_libraries.is_empty()
+            && self.removed_libraries.is_empty()
+    }
+
+    /// Return an iterator over the plugin type identifiers only in the new configuration.
+    pub fn added_plugin_types(&self) -> impl Iterator<Item = &String> {
+        self.added_plugin_types.iter()
+    }
+
+    /// Return an iterator over the plugin type identifiers only in the old configuration.
+    pub fn removed_plugin_types(&self) -> impl Iterator<Item = &String> {
+        self.removed_plugin_types.iter()
+    }
+
+    /// Return an iterator over the plugin type identifier and library path pairs only in the new
+    /// configuration.
+    pub fn added_libraries(&self) -> impl Iterator<Item = (&String, &PathBuf)> {
+        self.added_libraries
+            .iter()
+            .map(|(plugin_type, library)| (plugin_type, library))
+    }
+
+    /// Return an iterator over the plugin type identifier and library path pairs only in the old
+    /// configuration.
+    pub fn removed_libraries(&self) -> impl Iterator<Item = (&String, &PathBuf)> {
+        self.removed_libraries
+            .iter()
+            .map(|(plugin_type, library)| (plugin_type, library))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn check_library_list(plugin_type: &str, library_list: &[&Path]) -> Result<()> {
+    if !is_valid_plugin_type(plugin_type) {
+        Err(Error::invalid_plugin_type(plugin_type))
+    } else if library_list.is_empty() {
+        Err(Error::empty_library_list(plugin_type))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "dirs")]
+fn config_failed(file_name: &Path, error: Box<dyn std::error::Error>) -> Error {
+    Error::configuration_failed(file_name, error)
+}
+
+///
+/// The system-wide configuration directories, in increasing order of precedence.
+///
+#[cfg(all(feature = "dirs", target_os = "macos"))]
+fn system_config_dirs() -> Vec<PathBuf> {
+    vec![PathBuf::from("/Library/Application Support")]
+}
+
+#[cfg(all(feature = "dirs", windows))]
+fn system_config_dirs() -> Vec<PathBuf> {
+    vec![env::var_os("PROGRAMDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("C:\\ProgramData"))]
+}
+
+#[cfg(all(feature = "dirs", not(any(target_os = "macos", windows))))]
+fn system_config_dirs() -> Vec<PathBuf> {
+    // XDG_CONFIG_DIRS lists directories in decreasing order of precedence.
+    let mut directories: Vec<PathBuf> = env::var_os("XDG_CONFIG_DIRS")
+        .map(|dirs| {
+            env::split_paths(&dirs)
+                .filter(|directory| directory.is_absolute())
+                .collect()
+        })
+        .unwrap_or_default();
+    if directories.is_empty() {
+        directories.push(PathBuf::from("/etc/xdg"));
+    }
+    directories.reverse();
+    directories
+}
+
+#[cfg(feature = "config_serde")]
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(feature = "config_serde")]
+fn deserialize_plugins<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<String, PluginTypeConfiguration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entries: HashMap<String, PluginTypeEntry> = HashMap::deserialize(deserializer)?;
+    for plugin_type in entries
+        .keys()
+        .filter(|plugin_type| !is_valid_plugin_type(plugin_type))
+    {
+        warn!(
+            "Plugin type {:?} is not a valid plugin type identifier; this is deprecated and will be rejected in a future release",
+            plugin_type
+        );
+    }
+    Ok(entries
+        .into_iter()
+        .map(|(plugin_type, entry)| (plugin_type, entry.into()))
+        .collect())
+}
+
+#[cfg(feature = "config_serde")]
+fn serialize_plugins<S>(
+    plugins: &HashMap<String, PluginTypeConfiguration>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(plugins.len()))?;
+    for (plugin_type, config) in plugins {
+        if config.has_options() {
+            map.serialize_entry(plugin_type, config)?;
+        } else {
+            map.serialize_entry(plugin_type, &config.libraries)?;
+        }
+    }
+    map.end()
+}
+
+fn missing_plugin_types(
+    from: &PluginManagerConfiguration,
+    other: &PluginManagerConfiguration,
+) -> Vec<String> {
+    from.plugin_types()
+        .filter(|plugin_type| !other.contains_plugin_type(plugin_type))
+        .cloned()
+        .collect()
+}
+
+fn missing_libraries(
+    from: &PluginManagerConfiguration,
+    other: &PluginManagerConfiguration,
+) -> Vec<(String, PathBuf)> {
+    from.plugins
+        .iter()
+        .flat_map(|(plugin_type, config)| {
+            config
+                .libraries
+                .iter()
+                .filter(move |library| {
+                    !other
+                        .plugins
+                        .get(plugin_type)
+                        .is_some_and(|others| others.libraries.contains(*library))
+                })
+                .map(move |library| (plugin_type.clone(), library.clone()))
+        })
+        .collect()
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -260,11 +1306,286 @@ impl PluginManagerConfiguration {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_from_vars() {
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        let config = PluginManagerConfiguration::from_vars(
+            DEFAULT_ENV_PREFIX,
+            vec![
+                (
+                    OsString::from("DYGPI_PLUGINS_SOUND_EFFECTS"),
+                    OsString::from(format!("libdelay{}libreverb", separator)),
+                ),
+                (
+                    OsString::from("DYGPI_PLUGINS_LIGHT_EFFECTS"),
+                    OsString::new(),
+                ),
+                (OsString::from("DYGPI_PLUGINS_"), OsString::from("libnone")),
+                (OsString::from("PATH"), OsString::from("/usr/bin")),
+            ],
+        );
+        assert_eq!(config.len(), 1);
+        let mut libraries: Vec<&PathBuf> = config
+            .plugin_libraries_for_type("sound_effects")
+            .unwrap()
+            .collect();
+        libraries.sort();
+        assert_eq!(
+            libraries,
+            vec![&PathBuf::from("libdelay"), &PathBuf::from("libreverb")]
+        );
+    }
+
+    #[test]
+    fn test_from_and_into_iter() {
+        let mut config: PluginManagerConfiguration = vec![
+            ("sound".to_string(), vec!["beep".to_string()]),
+            ("light".to_string(), vec![]),
+        ]
+        .into_iter()
+        .collect();
+        config.extend(vec![("sound".to_string(), vec!["boop".to_string()])]);
+        assert_eq!(config.len(), 1);
+
+        for (plugin_type, config) in &config {
+            assert_eq!(plugin_type, "sound");
+            assert_eq!(config.libraries().count(), 2);
+        }
+        let entries: Vec<(String, PluginTypeConfiguration)> = config.into_iter().collect();
+        assert!(entries[0].1.libraries.contains(&PathBuf::from("boop")));
+    }
+
+    #[test]
+    fn test_insert_errors() {
+        let mut config = PluginManagerConfiguration::default();
+        assert!(config.insert("sound", &[]).is_err());
+        assert!(config.merge("sound", &[]).is_err());
+        assert!(config.insert("", &["beep".as_ref()]).is_err());
+        assert!(config.merge("sound effects", &["beep".as_ref()]).is_err());
+        assert!(config.is_empty());
+
+        assert!(config.insert("sound-effects_2", &["beep".as_ref()]).is_ok());
+        config.merge("sound-effects_2", &["boop".as_ref()]).unwrap();
+        assert_eq!(
+            config
+                .plugin_libraries_for_type("sound-effects_2")
+                .unwrap()
+                .count(),
+            2
+        );
+
+        let config: PluginManagerConfiguration =
+            toml::from_str("[plugins]\n\"sound effects\" = [\"beep\"]\n").unwrap();
+        assert_eq!(
+            config
+                .plugin_libraries_for_type("sound effects")
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_valid_plugin_types() {
+        assert!(is_valid_plugin_type("sound_effects"));
+        assert!(is_valid_plugin_type("Sound-Effects-2"));
+        assert!(!is_valid_plugin_type(""));
+        assert!(!is_valid_plugin_type("sound effects"));
+        assert!(!is_valid_plugin_type("sound.effects"));
+        assert!(!is_valid_plugin_type("effets_sonores_é"));
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut old = PluginManagerConfiguration::default();
+        let _ = old
+            .insert("sound", &["beep".as_ref(), "boop".as_ref()])
+            .unwrap();
+        let _ = old.insert("light", &["bright".as_ref()]).unwrap();
+        let mut new = PluginManagerConfiguration::default();
+        let _ = new
+            .insert("sound", &["boop".as_ref(), "buzz".as_ref()])
+            .unwrap();
+        let _ = new.insert("video", &["fade".as_ref()]).unwrap();
+
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.added_plugin_types().collect::<Vec<&String>>(),
+            vec!["video"]
+        );
+        assert_eq!(
+            diff.removed_plugin_types().collect::<Vec<&String>>(),
+            vec!["light"]
+        );
+        assert_eq!(
+            diff.added_libraries()
+                .map(|(t, l)| (t.as_str(), l.to_str().unwrap()))
+                .collect::<Vec<(&str, &str)>>(),
+            vec![("sound", "buzz"), ("video", "fade")]
+        );
+        assert_eq!(
+            diff.removed_libraries()
+                .map(|(t, l)| (t.as_str(), l.to_str().unwrap()))
+                .collect::<Vec<(&str, &str)>>(),
+            vec![("light", "bright"), ("sound", "beep")]
+        );
+
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_merge_configuration() {
+        let mut system = PluginManagerConfiguration::default();
+        let _ = system.insert("sound", &["beep".as_ref()]).unwrap();
+        system.set_plugin_disabled("sound::Beep", true);
+        let mut user = PluginManagerConfiguration::default();
+        let _ = user.insert("sound", &["boop".as_ref()]).unwrap();
+
+        system.merge_configuration(user);
+        assert_eq!(
+            system.plugin_libraries_for_type("sound").unwrap().count(),
+            2
+        );
+        assert!(system.is_plugin_disabled("sound::Beep"));
+
+        let mut user = PluginManagerConfiguration::default();
+        user.set_plugin_disabled("sound::Boop", true);
+        user.set_plugin_disabled("sound::Boop", false);
+        system.merge_configuration(user);
+        assert_eq!(system.disabled_plugins().count(), 0);
+    }
+
+    #[cfg(feature = "dirs")]
+    #[test]
+    fn test_write_and_read() {
+        let directory = tempfile::tempdir().unwrap();
+        let file_name = directory.path().join("sound_host").join(CONFIG_FILE_NAME);
+        let mut config = PluginManagerConfiguration::default();
+        let _ = config
+            .insert("sound", &["beep".as_ref(), "boop".as_ref()])
+            .unwrap();
+        config.set_plugin_disabled("sound::Beep", true);
+        config.write_to(&file_name).unwrap();
+
+        let read = PluginManagerConfiguration::read_from(&file_name).unwrap();
+        assert!(read.diff(&config).is_empty());
+        assert_eq!(
+            read.disabled_plugins().collect::<Vec<&String>>(),
+            vec!["sound::Beep"]
+        );
+        assert!(PluginManagerConfiguration::read_from(directory.path()).is_err());
+    }
+
+    #[test]
+    fn test_auto_platform_names() {
+        let config: PluginManagerConfiguration =
+            toml::from_str("auto_platform_names = true\n[plugins]\nsound = [\"delay\"]\n").unwrap();
+        assert!(config.auto_platform_names());
+        assert!(toml::to_string(&config)
+            .unwrap()
+            .contains("auto_platform_names = true"));
+
+        let mut config = PluginManagerConfiguration::default();
+        assert!(!toml::to_string(&config)
+            .unwrap()
+            .contains("auto_platform_names"));
+        let mut other = PluginManagerConfiguration::default();
+        other.set_auto_platform_names(true);
+        config.merge_configuration(other);
+        assert!(config.auto_platform_names());
+    }
+
+    #[test]
+    fn test_plugin_type_options() {
+        let config: PluginManagerConfiguration = toml::from_str(
+            r#"
+[plugins]
+light = ["bright"]
+
+[plugins.sound]
+libraries = ["beep"]
+search_path = ["/opt/sound"]
+register_fn = "register_effects"
+required = true
+
+[plugins.sound.settings]
+volume = "11"
+"#,
+        )
+        .unwrap();
+        let light = config.plugin_type_configuration("light").unwrap();
+        assert_eq!(light, &PluginTypeConfiguration::new(&["bright".as_ref()]));
+        let sound = config.plugin_type_configuration("sound").unwrap();
+        assert_eq!(
+            sound.libraries().collect::<Vec<&PathBuf>>(),
+            vec![&PathBuf::from("beep")]
+        );
+        assert_eq!(
+            sound.search_path().collect::<Vec<&PathBuf>>(),
+            vec![&PathBuf::from("/opt/sound")]
+        );
+        assert_eq!(sound.register_fn().unwrap(), "register_effects");
+        assert!(sound.is_required());
+        assert_eq!(sound.setting("volume").unwrap(), "11");
+
+        let text = toml::to_string(&config).unwrap();
+        assert!(text.contains("light = [\"bright\"]"));
+        assert!(text.contains("[plugins.sound]"));
+        let read: PluginManagerConfiguration = toml::from_str(&text).unwrap();
+        assert_eq!(read.plugin_type_configuration("sound"), Some(sound));
+
+        let mut system = PluginManagerConfiguration::default();
+        let _ = system.insert("sound", &["boop".as_ref()]).unwrap();
+        system.merge_configuration(config);
+        let sound = system.plugin_type_configuration("sound").unwrap();
+        assert_eq!(sound.libraries().count(), 2);
+        assert_eq!(sound.register_fn().unwrap(), "register_effects");
+    }
+
+    #[test]
+    fn test_required_plugin_type() {
+        #[derive(Debug)]
+        struct TestPlugin;
+
+        impl Plugin for TestPlugin {
+            fn plugin_id(&self) -> &str {
+                "test::plugin"
+            }
+            fn on_load(&self) -> Result<()> {
+                Ok(())
+            }
+            fn on_unload(&self) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut config: PluginManagerConfiguration =
+            toml::from_str("[plugins.sound]\nlibraries = []\n").unwrap();
+        assert!(config.make_manager_for_type::<TestPlugin>("sound").is_ok());
+
+        config
+            .plugin_type_configuration_mut("sound")
+            .unwrap()
+            .set_required(true);
+        let error = config
+            .make_manager_for_type::<TestPlugin>("sound")
+            .unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            crate::error::ErrorKind::NoPluginsLoaded { plugin_type } if plugin_type == "sound"
+        ));
+    }
+
     #[test]
     fn test_serialize_toml() {
         let mut config = PluginManagerConfiguration::default();
-        let _ = config.insert("sound", &["beep".as_ref(), "boop".as_ref()]);
-        let _ = config.insert("light", &["bright".as_ref(), "mood".as_ref()]);
+        let _ = config
+            .insert("sound", &["beep".as_ref(), "boop".as_ref()])
+            .unwrap();
+        let _ = config
+            .insert("light", &["bright".as_ref(), "mood".as_ref()])
+            .unwrap();
 
         println!("{}", toml::to_string(&config).unwrap());
     }
@@ -272,8 +1593,12 @@ mod tests {
     #[test]
     fn test_serialize_json() {
         let mut config = PluginManagerConfiguration::default();
-        let _ = config.insert("sound", &["beep".as_ref(), "boop".as_ref()]);
-        let _ = config.insert("light", &["bright".as_ref(), "mood".as_ref()]);
+        let _ = config
+            .insert("sound", &["beep".as_ref(), "boop".as_ref()])
+            .unwrap();
+        let _ = config
+            .insert("light", &["bright".as_ref(), "mood".as_ref()])
+            .unwrap();
 
         println!("{}", serde_json::to_string(&config).unwrap());
     }
@@ -281,8 +1606,12 @@ mod tests {
     #[test]
     fn test_serialize_yaml() {
         let mut config = PluginManagerConfiguration::default();
-        let _ = config.insert("sound", &["beep".as_ref(), "boop".as_ref()]);
-        let _ = config.insert("light", &["bright".as_ref(), "mood".as_ref()]);
+        let _ = config
+            .insert("sound", &["beep".as_ref(), "boop".as_ref()])
+            .unwrap();
+        let _ = config
+            .insert("light", &["bright".as_ref(), "mood".as_ref()])
+            .unwrap();
 
         println!("{}", serde_yaml::to_string(&config).unwrap());
     }
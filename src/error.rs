@@ -3,47 +3,395 @@ Provides the [`Error`](struct.Error.html), [`ErrorKind`](enum.ErrorKind.html), a
 [`Result`](enum.Result.html) type used in the rest of this crate.
 */
 
-use std::fmt::{Display, Formatter};
+use crate::manager::PluginState;
+use crate::plugin::Compatibility;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
 // ------------------------------------------------------------------------------------------------
 
 ///
-/// Errors returned by functions and methods in this crate.
+/// Errors returned by functions and methods in this crate. Each variant has a corresponding
+/// constructor on [`Error`](struct.Error.html), for example
+/// [`Error::library_open_failed`](struct.Error.html#method.library_open_failed), which accepts
+/// paths and any error type as the source.
 ///
 #[derive(Debug)]
 pub enum ErrorKind {
     ///
     /// Failed to load the dynamic library specified by file name.
-    /// The first parameter is the library path, the second is the underlying system error.
     ///
-    LibraryOpenFailed(String, Box<dyn std::error::Error>),
+    LibraryOpenFailed {
+        /// The path of the library.
+        library: String,
+        /// The likely cause of the failure.
+        reason: OpenFailureReason,
+        /// The underlying system error.
+        source: Box<dyn std::error::Error>,
+    },
     ///
     /// Failed to close the dynamic library and free any resources.
-    /// The first parameter is the library path, the second is the underlying system error.
     ///
-    LibraryCloseFailed(String, Box<dyn std::error::Error>),
+    LibraryCloseFailed {
+        /// The path of the library.
+        library: String,
+        /// The underlying system error.
+        source: Box<dyn std::error::Error>,
+    },
     ///
     /// Failed to find the symbol within the dynamic library.
-    /// The first parameter is the library path, the second is the underlying system error.
     ///
-    SymbolNotFound(String, Box<dyn std::error::Error>),
+    SymbolNotFound {
+        /// The name of the symbol.
+        symbol: String,
+        /// The path of the library.
+        library: String,
+        /// The underlying system error.
+        source: Box<dyn std::error::Error>,
+    },
     ///
-    /// The plugin host and plugin library are incompatible.
-    /// The parameter contains the path of the incompatible library.
+    /// The plugin host and plugin library are incompatible, see
+    /// [`Compatibility`](../plugin/struct.Compatibility.html).
     ///
-    IncompatibleLibraryVersion(String),
+    IncompatibleLibraryVersion {
+        /// The path of the library.
+        library: String,
+        /// The compatibility inputs of the plugin host.
+        host: Box<Compatibility>,
+        /// The compatibility inputs of the library, if known; they are not known if the library
+        /// was not opened as it is known to be incompatible, or was built with a version of
+        /// `dygpi` that does not export `compatibility_info`.
+        found: Option<Box<Compatibility>>,
+    },
+    ///
+    /// The dynamic library was built for a different architecture than the plugin host, see
+    /// [`PluginManager::set_check_architecture`](../manager/struct.PluginManager.html#method.set_check_architecture).
+    ///
+    WrongArchitecture {
+        /// The path of the library.
+        library: String,
+        /// The architecture of the plugin host.
+        expected: String,
+        /// The architecture read from the library's header.
+        found: String,
+    },
     ///
     /// An error was reported by the plugin library when attempting to register a plugin.
-    /// The parameter is the error the plugin library provided to the registrar.
     ///
-    PluginRegistration(Box<dyn std::error::Error>),
+    PluginRegistration {
+        /// The identifier of the plugin that failed, if the plugin library provided one, see
+        /// [`PluginRegistrar::error_for`](../plugin/struct.PluginRegistrar.html#method.error_for).
+        plugin_id: Option<String>,
+        /// The error the plugin library provided to the registrar.
+        source: Box<dyn std::error::Error>,
+    },
     ///
     /// The plugin manager type is not known in the configuration.
-    /// The parameter is the plugin type identifier that could not be found.
     ///
-    UnknownPluginManagerType(String),
+    UnknownPluginManagerType {
+        /// The plugin type identifier that could not be found.
+        plugin_type: String,
+    },
+    ///
+    /// No plugin is registered with the plugin identifier.
+    ///
+    UnknownPlugin {
+        /// The plugin identifier that could not be found.
+        plugin_id: String,
+    },
+    ///
+    /// A plugin provider refused to register plugins as it does not support the host's plugin
+    /// API version.
+    ///
+    IncompatibleHostApiVersion {
+        /// The host's API version.
+        api_version: u32,
+    },
+    ///
+    /// A plugin provider registered plugins of a different type to that of the plugin manager.
+    ///
+    IncompatiblePluginType {
+        /// The name of the type the provider was built against.
+        type_name: String,
+    },
+    ///
+    /// Failed to read the symbol table of the dynamic library specified by file name.
+    ///
+    LibraryInspectionFailed {
+        /// The path of the library.
+        library: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// A plugin manifest could not be parsed.
+    ///
+    InvalidPluginManifest {
+        /// A description of the problem with the manifest.
+        message: String,
+    },
+    ///
+    /// Failed to read, or parse, the manifest file of a plugin bundle.
+    ///
+    BundleManifestFailed {
+        /// The path of the manifest.
+        path: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// The library has already been loaded by the plugin manager.
+    ///
+    LibraryAlreadyLoaded {
+        /// The canonical path of the library.
+        library: String,
+    },
+    ///
+    /// A bulk load was cancelled, and the plugins it had already registered were unloaded.
+    ///
+    LoadCancelled {
+        /// The identifiers of the unloaded plugins.
+        unloaded: Vec<String>,
+    },
+    ///
+    /// Failed to list the contents of a plugin directory.
+    ///
+    DirectoryReadFailed {
+        /// The path of the directory.
+        directory: String,
+        /// The underlying system error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// The library previously failed to load and has been quarantined by the plugin manager.
+    ///
+    LibraryQuarantined {
+        /// The canonical path of the library.
+        library: String,
+    },
+    ///
+    /// Failed to read, or write, a persisted check cache file.
+    ///
+    CheckCacheFailed {
+        /// The path of the cache file.
+        path: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// Failed to read, or write, a persisted plugin index file.
+    ///
+    PluginIndexFailed {
+        /// The path of the index file.
+        path: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// The library is not loaded by the plugin manager.
+    ///
+    LibraryNotLoaded {
+        /// The canonical path of the library.
+        library: String,
+    },
+    ///
+    /// The configuration file could not be read, parsed, or written.
+    ///
+    ConfigurationFailed {
+        /// The path of the configuration file.
+        path: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// The registration function is only exported for registration protocol versions that are
+    /// not supported by this crate.
+    ///
+    UnsupportedRegistrationProtocol {
+        /// The name of the registration function.
+        registration_fn: String,
+        /// The protocol versions exported.
+        versions: Vec<u32>,
+    },
+    ///
+    /// The plugin does not declare capabilities required by the plugin manager.
+    ///
+    MissingCapabilities {
+        /// The plugin identifier.
+        plugin_id: String,
+        /// The required capabilities not declared by the plugin.
+        capabilities: Vec<String>,
+    },
+    ///
+    /// Loading the library would exceed a limit set on the plugin manager.
+    ///
+    PluginLimitExceeded {
+        /// The path of the library.
+        library: String,
+        /// The limit exceeded.
+        limit: PluginLimit,
+        /// The value of the limit.
+        value: usize,
+    },
+    ///
+    /// A plugin provider crate could not be generated.
+    ///
+    ProviderGenerationFailed {
+        /// The name of the crate.
+        crate_name: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// A plugin was selected to be instanced per use but was not registered with a factory.
+    ///
+    NoPluginFactory {
+        /// The plugin identifier.
+        plugin_id: String,
+    },
+    ///
+    /// A plugin cannot be instantiated with parameters as it was not registered with a factory
+    /// that accepts them.
+    ///
+    NotInstantiable {
+        /// The plugin identifier.
+        plugin_id: String,
+    },
+    ///
+    /// A plugin's factory was given instantiation parameters of the wrong type, or with values it
+    /// does not support.
+    ///
+    InvalidParameters {
+        /// The plugin identifier.
+        plugin_id: String,
+    },
+    ///
+    /// A plugin's factory was given instantiation parameters of a type other than the one it
+    /// accepts, see [`Parameters::get`](../plugin/struct.Parameters.html#method.get).
+    ///
+    WrongParameterType {
+        /// The plugin identifier.
+        plugin_id: String,
+        /// The name of the type the factory accepts.
+        expected: String,
+        /// The name of the type the factory was given.
+        found: String,
+    },
+    ///
+    /// The operation is not valid for the plugin in its current lifecycle state, for example
+    /// disabling a plugin that has failed.
+    ///
+    InvalidStateTransition {
+        /// The plugin identifier.
+        plugin_id: String,
+        /// The current state of the plugin.
+        from: PluginState,
+        /// The state the operation would move the plugin to.
+        to: PluginState,
+    },
+    ///
+    /// The library could not be copied to the shadow copy directory before loading, see
+    /// [`PluginManager::enable_shadow_copy`](../manager/struct.PluginManager.html#method.enable_shadow_copy).
+    ///
+    ShadowCopyFailed {
+        /// The path of the library.
+        library: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// The library, or directory, could not be watched for changes, see
+    /// [`PluginManager::watch`](../manager/struct.PluginManager.html#method.watch).
+    ///
+    WatchFailed {
+        /// The path being watched.
+        path: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// The environment variable listing the libraries to load is not set, see
+    /// [`EnvLoadPolicy::set_required`](../manager/struct.EnvLoadPolicy.html#method.set_required).
+    ///
+    EnvVarNotSet {
+        /// The name of the environment variable.
+        name: String,
+    },
+    ///
+    /// The libraries configured for a required plugin type registered no plugins, see
+    /// [`PluginTypeConfiguration::set_required`](../config/struct.PluginTypeConfiguration.html#method.set_required).
+    ///
+    NoPluginsLoaded {
+        /// The plugin type identifier.
+        plugin_type: String,
+    },
+    ///
+    /// An empty list of libraries was provided for a plugin type, see
+    /// [`PluginManagerConfiguration::insert`](../config/struct.PluginManagerConfiguration.html#method.insert).
+    ///
+    EmptyLibraryList {
+        /// The plugin type identifier.
+        plugin_type: String,
+    },
+    ///
+    /// A plugin type identifier was empty, or contained characters other than ASCII letters,
+    /// digits, `'_'`, and `'-'`.
+    ///
+    InvalidPluginType {
+        /// The plugin type identifier.
+        plugin_type: String,
+    },
+    ///
+    /// The library's signature could not be verified before loading, see
+    /// [`PluginManager::set_signature_verifier`](../manager/struct.PluginManager.html#method.set_signature_verifier).
+    ///
+    SignatureVerificationFailed {
+        /// The path of the library.
+        library: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error>,
+    },
+    ///
+    /// An empty list of alternative libraries was provided, see
+    /// [`PluginManager::load_plugins_from_first`](../manager/struct.PluginManager.html#method.load_plugins_from_first).
+    ///
+    NoLibrariesProvided,
+}
+
+///
+/// The likely cause of a [`LibraryOpenFailed`](enum.ErrorKind.html#variant.LibraryOpenFailed)
+/// error, determined by examining the library file once the platform loader has failed.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpenFailureReason {
+    /// The library file does not exist.
+    FileNotFound,
+    /// The library file exists but could not be read by this process.
+    PermissionDenied,
+    /// The file is not a dynamic library.
+    NotALibrary,
+    /// The file is a dynamic library built for a different architecture, or platform.
+    WrongArchitecture,
+    /// The file is a dynamic library, but a library it depends upon could not be found.
+    MissingDependency,
+    /// The cause could not be determined, see the underlying system error.
+    Unknown,
+}
+
+///
+/// The limits that may be set on a plugin manager, reported by a
+/// [`PluginLimitExceeded`](enum.ErrorKind.html#variant.PluginLimitExceeded) error.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PluginLimit {
+    /// The number of plugins registered by a single library.
+    PluginsPerLibrary,
+    /// The number of plugins registered with the plugin manager.
+    Plugins,
+    /// The number of libraries providing plugins to the plugin manager.
+    Libraries,
 }
 
 ///
@@ -58,6 +406,21 @@ pub struct Error(ErrorKind);
 ///
 pub type Result<T> = std::result::Result<T, Error>;
 
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A copy of an error, and its source chain, that owns only data allocated by the host. Errors
+/// created by a plugin library carry vtables, and possibly data, that belong to the library; these
+/// must be detached before the library is closed.
+///
+struct DetachedError {
+    display: String,
+    debug: String,
+    source: Option<Box<DetachedError>>,
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
@@ -68,20 +431,178 @@ impl Display for ErrorKind {
             f,
             "{}",
             match self {
-                ErrorKind::LibraryOpenFailed(path, error) =>
-                    format!("Library '{}' failed to close; error: '{}'", path, error),
-                ErrorKind::SymbolNotFound(name, in_library) => format!(
-                    "Could not find symbol '{}' in library '{}'",
-                    name, in_library
-                ),
-                ErrorKind::LibraryCloseFailed(path, error) =>
-                    format!("Library '{}' failed to close; error: '{}'", path, error),
-                ErrorKind::IncompatibleLibraryVersion(path) =>
-                    format!("Library '{}' has incompatible version", path),
-                ErrorKind::PluginRegistration(error) =>
-                    format!("Plugin(s) failed to register; error: '{}'", error),
-                ErrorKind::UnknownPluginManagerType(plugin_type) =>
+                ErrorKind::LibraryOpenFailed {
+                    library,
+                    reason,
+                    source,
+                } => format!(
+                    "Library '{}' failed to open, {}; error: '{}'",
+                    library, reason, source
+                ),
+                ErrorKind::SymbolNotFound {
+                    symbol, library, ..
+                } => format!("Could not find symbol '{}' in library '{}'", symbol, library),
+                ErrorKind::LibraryCloseFailed { library, source } =>
+                    format!("Library '{}' failed to close; error: '{}'", library, source),
+                ErrorKind::IncompatibleLibraryVersion {
+                    library,
+                    host,
+                    found,
+                } => match found.as_ref().map(|found| found.differences(host)) {
+                    Some(differences) if !differences.is_empty() => format!(
+                        "Library '{}' has incompatible version; {}",
+                        library,
+                        differences
+                            .iter()
+                            .map(|(name, found, expected)| format!(
+                                "{} version is {}, expected {}",
+                                name, found, expected
+                            ))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                    _ => format!(
+                        "Library '{}' has incompatible version, expected {}",
+                        library, host
+                    ),
+                },
+                ErrorKind::WrongArchitecture {
+                    library,
+                    expected,
+                    found,
+                } => format!(
+                    "Library '{}' was built for the {} architecture, expected {}",
+                    library, found, expected
+                ),
+                ErrorKind::PluginRegistration {
+                    plugin_id: Some(plugin_id),
+                    source,
+                } => format!(
+                    "Plugin '{}' failed to register; error: '{}'",
+                    plugin_id, source
+                ),
+                ErrorKind::PluginRegistration {
+                    plugin_id: None,
+                    source,
+                } => format!("Plugin(s) failed to register; error: '{}'", source),
+                ErrorKind::UnknownPluginManagerType { plugin_type } =>
                     format!("No Configured plugins for type '{}'", plugin_type),
+                ErrorKind::UnknownPlugin { plugin_id } =>
+                    format!("No plugin registered with id '{}'", plugin_id),
+                ErrorKind::IncompatibleHostApiVersion { api_version } =>
+                    format!("Host API version {} is not supported", api_version),
+                ErrorKind::IncompatiblePluginType { type_name } =>
+                    format!("Provider plugin type '{}' is incompatible", type_name),
+                ErrorKind::LibraryInspectionFailed { library, source } => format!(
+                    "Library '{}' could not be inspected; error: '{}'",
+                    library, source
+                ),
+                ErrorKind::InvalidPluginManifest { message } =>
+                    format!("Invalid plugin manifest; {}", message),
+                ErrorKind::BundleManifestFailed { path, source } => format!(
+                    "Bundle manifest '{}' could not be read; error: '{}'",
+                    path, source
+                ),
+                ErrorKind::LibraryAlreadyLoaded { library } =>
+                    format!("Library '{}' is already loaded", library),
+                ErrorKind::LoadCancelled { unloaded } => format!(
+                    "Load cancelled, {} plugin(s) were unloaded",
+                    unloaded.len()
+                ),
+                ErrorKind::DirectoryReadFailed { directory, source } => format!(
+                    "Directory '{}' could not be read; error: '{}'",
+                    directory, source
+                ),
+                ErrorKind::LibraryQuarantined { library } =>
+                    format!("Library '{}' is quarantined after failing to load", library),
+                ErrorKind::CheckCacheFailed { path, source } => format!(
+                    "Check cache '{}' could not be read or written; error: '{}'",
+                    path, source
+                ),
+                ErrorKind::PluginIndexFailed { path, source } => format!(
+                    "Plugin index '{}' could not be read or written; error: '{}'",
+                    path, source
+                ),
+                ErrorKind::LibraryNotLoaded { library } =>
+                    format!("Library '{}' is not loaded", library),
+                ErrorKind::ConfigurationFailed { path, source } => format!(
+                    "Configuration file '{}' could not be read or written; error: '{}'",
+                    path, source
+                ),
+                ErrorKind::UnsupportedRegistrationProtocol {
+                    registration_fn,
+                    versions,
+                } => format!(
+                    "Registration function '{}' is only exported for unsupported protocol versions {:?}",
+                    registration_fn, versions
+                ),
+                ErrorKind::MissingCapabilities {
+                    plugin_id,
+                    capabilities,
+                } => format!(
+                    "Plugin '{}' does not declare the required capabilities {:?}",
+                    plugin_id, capabilities
+                ),
+                ErrorKind::PluginLimitExceeded {
+                    library,
+                    limit,
+                    value,
+                } => format!(
+                    "Library '{}' exceeds the limit of {} {}",
+                    library, value, limit
+                ),
+                ErrorKind::ProviderGenerationFailed { crate_name, source } => format!(
+                    "Provider crate '{}' could not be generated; error: '{}'",
+                    crate_name, source
+                ),
+                ErrorKind::NoPluginFactory { plugin_id } => format!(
+                    "Plugin '{}' was not registered with a factory, and cannot be instanced per use",
+                    plugin_id
+                ),
+                ErrorKind::NotInstantiable { plugin_id } => format!(
+                    "Plugin '{}' was not registered with a factory accepting parameters",
+                    plugin_id
+                ),
+                ErrorKind::InvalidParameters { plugin_id } => format!(
+                    "Plugin '{}' cannot be instantiated with the provided parameters",
+                    plugin_id
+                ),
+                ErrorKind::WrongParameterType {
+                    plugin_id,
+                    expected,
+                    found,
+                } => format!(
+                    "Plugin '{}' expects parameters of type '{}', but was given '{}'",
+                    plugin_id, expected, found
+                ),
+                ErrorKind::InvalidStateTransition {
+                    plugin_id,
+                    from,
+                    to,
+                } => format!(
+                    "Plugin '{}' cannot change from the {} state to {}",
+                    plugin_id, from, to
+                ),
+                ErrorKind::ShadowCopyFailed { library, source } => format!(
+                    "Library '{}' could not be shadow copied; error: '{}'",
+                    library, source
+                ),
+                ErrorKind::WatchFailed { path, source } =>
+                    format!("Path '{}' could not be watched; error: '{}'", path, source),
+                ErrorKind::EnvVarNotSet { name } =>
+                    format!("Environment variable '{}' is not set", name),
+                ErrorKind::NoPluginsLoaded { plugin_type } =>
+                    format!("No plugins loaded for required type '{}'", plugin_type),
+                ErrorKind::EmptyLibraryList { plugin_type } =>
+                    format!("No libraries provided for plugin type '{}'", plugin_type),
+                ErrorKind::InvalidPluginType { plugin_type } =>
+                    format!("Invalid plugin type identifier '{}'", plugin_type),
+                ErrorKind::SignatureVerificationFailed { library, source } => format!(
+                    "Library '{}' failed signature verification; error: '{}'",
+                    library, source
+                ),
+                ErrorKind::NoLibrariesProvided =>
+                    "No alternative libraries were provided to load plugins from".to_string(),
             }
         )
     }
@@ -89,7 +610,11 @@ impl Display for ErrorKind {
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        if f.alternate() {
+            write!(f, "{}", self.full_chain())
+        } else {
+            write!(f, "{}", self.0)
+        }
     }
 }
 
@@ -99,21 +624,748 @@ impl From<ErrorKind> for Error {
     }
 }
 
+impl From<libloading::Error> for Error {
+    ///
+    /// Convert an error from opening, closing, or looking up a symbol in a dynamic library. The
+    /// error does not carry the library path, or symbol name; add the library path with
+    /// [`with_library`](#method.with_library).
+    ///
+    fn from(error: libloading::Error) -> Self {
+        match error {
+            libloading::Error::DlSym { .. }
+            | libloading::Error::DlSymUnknown
+            | libloading::Error::GetProcAddress { .. }
+            | libloading::Error::GetProcAddressUnknown => Self::symbol_not_found("", "", error),
+            libloading::Error::DlClose { .. }
+            | libloading::Error::DlCloseUnknown
+            | libloading::Error::FreeLibrary { .. }
+            | libloading::Error::FreeLibraryUnknown => Self::library_close_failed("", error),
+            error => Self::library_open_failed("", error),
+        }
+    }
+}
+
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match &self.0 {
-            ErrorKind::LibraryOpenFailed(_, error) => Some(error.as_ref()),
-            ErrorKind::LibraryCloseFailed(_, error) => Some(error.as_ref()),
-            ErrorKind::PluginRegistration(error) => Some(error.as_ref()),
+            ErrorKind::LibraryOpenFailed { source, .. }
+            | ErrorKind::LibraryCloseFailed { source, .. }
+            | ErrorKind::SymbolNotFound { source, .. }
+            | ErrorKind::PluginRegistration { source, .. }
+            | ErrorKind::LibraryInspectionFailed { source, .. }
+            | ErrorKind::BundleManifestFailed { source, .. }
+            | ErrorKind::DirectoryReadFailed { source, .. }
+            | ErrorKind::CheckCacheFailed { source, .. }
+            | ErrorKind::PluginIndexFailed { source, .. }
+            | ErrorKind::ConfigurationFailed { source, .. }
+            | ErrorKind::ProviderGenerationFailed { source, .. }
+            | ErrorKind::ShadowCopyFailed { source, .. }
+            | ErrorKind::WatchFailed { source, .. }
+            | ErrorKind::SignatureVerificationFailed { source, .. } => Some(source.as_ref()),
             _ => None,
         }
     }
 }
 
+impl Error {
+    pub(crate) fn kind(&self) -> &ErrorKind {
+        &self.0
+    }
+
+    ///
+    /// Create a [`LibraryOpenFailed`](enum.ErrorKind.html#variant.LibraryOpenFailed) error, the
+    /// reason is `Unknown` unless set with
+    /// [`with_open_failure_reason`](#method.with_open_failure_reason).
+    ///
+    pub fn library_open_failed<P, E>(library: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::LibraryOpenFailed {
+            library: path_string(library),
+            reason: OpenFailureReason::Unknown,
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`LibraryCloseFailed`](enum.ErrorKind.html#variant.LibraryCloseFailed) error.
+    ///
+    pub fn library_close_failed<P, E>(library: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::LibraryCloseFailed {
+            library: path_string(library),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`SymbolNotFound`](enum.ErrorKind.html#variant.SymbolNotFound) error.
+    ///
+    pub fn symbol_not_found<P, E>(library: P, symbol: &str, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::SymbolNotFound {
+            symbol: symbol.to_string(),
+            library: path_string(library),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`PluginRegistration`](enum.ErrorKind.html#variant.PluginRegistration) error, as
+    /// reported by a plugin library to the registrar.
+    ///
+    pub fn plugin_registration<E>(source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::PluginRegistration {
+            plugin_id: None,
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`PluginRegistration`](enum.ErrorKind.html#variant.PluginRegistration) error
+    /// attributed to the plugin with the identifier `plugin_id`.
+    ///
+    pub fn plugin_registration_for<E>(plugin_id: &str, source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::PluginRegistration {
+            plugin_id: Some(plugin_id.to_string()),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create an [`UnknownPluginManagerType`](enum.ErrorKind.html#variant.UnknownPluginManagerType)
+    /// error.
+    ///
+    pub fn unknown_plugin_manager_type(plugin_type: &str) -> Self {
+        Self(ErrorKind::UnknownPluginManagerType {
+            plugin_type: plugin_type.to_string(),
+        })
+    }
+
+    ///
+    /// Create an [`UnknownPlugin`](enum.ErrorKind.html#variant.UnknownPlugin) error.
+    ///
+    pub fn unknown_plugin(plugin_id: &str) -> Self {
+        Self(ErrorKind::UnknownPlugin {
+            plugin_id: plugin_id.to_string(),
+        })
+    }
+
+    ///
+    /// Create a [`LibraryInspectionFailed`](enum.ErrorKind.html#variant.LibraryInspectionFailed)
+    /// error.
+    ///
+    pub fn library_inspection_failed<P, E>(library: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::LibraryInspectionFailed {
+            library: path_string(library),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create an [`InvalidPluginManifest`](enum.ErrorKind.html#variant.InvalidPluginManifest) error.
+    ///
+    pub fn invalid_plugin_manifest(message: &str) -> Self {
+        Self(ErrorKind::InvalidPluginManifest {
+            message: message.to_string(),
+        })
+    }
+
+    ///
+    /// Create a [`BundleManifestFailed`](enum.ErrorKind.html#variant.BundleManifestFailed) error.
+    ///
+    pub fn bundle_manifest_failed<P, E>(path: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::BundleManifestFailed {
+            path: path_string(path),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`LibraryAlreadyLoaded`](enum.ErrorKind.html#variant.LibraryAlreadyLoaded) error.
+    ///
+    pub fn library_already_loaded<P>(library: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self(ErrorKind::LibraryAlreadyLoaded {
+            library: path_string(library),
+        })
+    }
+
+    ///
+    /// Create a [`DirectoryReadFailed`](enum.ErrorKind.html#variant.DirectoryReadFailed) error.
+    ///
+    pub fn directory_read_failed<P, E>(directory: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::DirectoryReadFailed {
+            directory: path_string(directory),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`LibraryQuarantined`](enum.ErrorKind.html#variant.LibraryQuarantined) error.
+    ///
+    pub fn library_quarantined<P>(library: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self(ErrorKind::LibraryQuarantined {
+            library: path_string(library),
+        })
+    }
+
+    ///
+    /// Create a [`CheckCacheFailed`](enum.ErrorKind.html#variant.CheckCacheFailed) error.
+    ///
+    pub fn check_cache_failed<P, E>(path: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::CheckCacheFailed {
+            path: path_string(path),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`PluginIndexFailed`](enum.ErrorKind.html#variant.PluginIndexFailed) error.
+    ///
+    pub fn plugin_index_failed<P, E>(path: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::PluginIndexFailed {
+            path: path_string(path),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`LibraryNotLoaded`](enum.ErrorKind.html#variant.LibraryNotLoaded) error.
+    ///
+    pub fn library_not_loaded<P>(library: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self(ErrorKind::LibraryNotLoaded {
+            library: path_string(library),
+        })
+    }
+
+    ///
+    /// Create a [`ConfigurationFailed`](enum.ErrorKind.html#variant.ConfigurationFailed) error.
+    ///
+    pub fn configuration_failed<P, E>(path: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::ConfigurationFailed {
+            path: path_string(path),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`ProviderGenerationFailed`](enum.ErrorKind.html#variant.ProviderGenerationFailed)
+    /// error.
+    ///
+    pub fn provider_generation_failed<E>(crate_name: &str, source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::ProviderGenerationFailed {
+            crate_name: crate_name.to_string(),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`NoPluginFactory`](enum.ErrorKind.html#variant.NoPluginFactory) error.
+    ///
+    pub fn no_plugin_factory(plugin_id: &str) -> Self {
+        Self(ErrorKind::NoPluginFactory {
+            plugin_id: plugin_id.to_string(),
+        })
+    }
+
+    ///
+    /// Create a [`NotInstantiable`](enum.ErrorKind.html#variant.NotInstantiable) error.
+    ///
+    pub fn not_instantiable(plugin_id: &str) -> Self {
+        Self(ErrorKind::NotInstantiable {
+            plugin_id: plugin_id.to_string(),
+        })
+    }
+
+    ///
+    /// Create an [`InvalidParameters`](enum.ErrorKind.html#variant.InvalidParameters) error, for
+    /// a plugin factory given parameters it does not support.
+    ///
+    pub fn invalid_parameters(plugin_id: &str) -> Self {
+        Self(ErrorKind::InvalidParameters {
+            plugin_id: plugin_id.to_string(),
+        })
+    }
+
+    ///
+    /// Create a [`WrongParameterType`](enum.ErrorKind.html#variant.WrongParameterType) error.
+    ///
+    pub fn wrong_parameter_type(plugin_id: &str, expected: &str, found: &str) -> Self {
+        Self(ErrorKind::WrongParameterType {
+            plugin_id: plugin_id.to_string(),
+            expected: expected.to_string(),
+            found: found.to_string(),
+        })
+    }
+
+    ///
+    /// Create a [`ShadowCopyFailed`](enum.ErrorKind.html#variant.ShadowCopyFailed) error.
+    ///
+    pub fn shadow_copy_failed<P, E>(library: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::ShadowCopyFailed {
+            library: path_string(library),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`WatchFailed`](enum.ErrorKind.html#variant.WatchFailed) error.
+    ///
+    pub fn watch_failed<P, E>(path: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::WatchFailed {
+            path: path_string(path),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create an [`EnvVarNotSet`](enum.ErrorKind.html#variant.EnvVarNotSet) error.
+    ///
+    pub fn env_var_not_set(name: &str) -> Self {
+        Self(ErrorKind::EnvVarNotSet {
+            name: name.to_string(),
+        })
+    }
+
+    ///
+    /// Create a [`NoPluginsLoaded`](enum.ErrorKind.html#variant.NoPluginsLoaded) error.
+    ///
+    pub fn no_plugins_loaded(plugin_type: &str) -> Self {
+        Self(ErrorKind::NoPluginsLoaded {
+            plugin_type: plugin_type.to_string(),
+        })
+    }
+
+    ///
+    /// Create an [`EmptyLibraryList`](enum.ErrorKind.html#variant.EmptyLibraryList) error.
+    ///
+    pub fn empty_library_list(plugin_type: &str) -> Self {
+        Self(ErrorKind::EmptyLibraryList {
+            plugin_type: plugin_type.to_string(),
+        })
+    }
+
+    ///
+    /// Create an [`InvalidPluginType`](enum.ErrorKind.html#variant.InvalidPluginType) error.
+    ///
+    pub fn invalid_plugin_type(plugin_type: &str) -> Self {
+        Self(ErrorKind::InvalidPluginType {
+            plugin_type: plugin_type.to_string(),
+        })
+    }
+
+    ///
+    /// Create a [`SignatureVerificationFailed`](enum.ErrorKind.html#variant.SignatureVerificationFailed)
+    /// error.
+    ///
+    pub fn signature_verification_failed<P, E>(library: P, source: E) -> Self
+    where
+        P: AsRef<Path>,
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self(ErrorKind::SignatureVerificationFailed {
+            library: path_string(library),
+            source: source.into(),
+        })
+    }
+
+    ///
+    /// Create a [`NoLibrariesProvided`](enum.ErrorKind.html#variant.NoLibrariesProvided) error.
+    ///
+    pub fn no_libraries_provided() -> Self {
+        Self(ErrorKind::NoLibrariesProvided)
+    }
+
+    ///
+    /// Set the library path of a [`LibraryOpenFailed`](enum.ErrorKind.html#variant.LibraryOpenFailed)
+    /// or [`LibraryCloseFailed`](enum.ErrorKind.html#variant.LibraryCloseFailed) error, such as one
+    /// converted from a `libloading::Error`; other errors are returned unchanged.
+    ///
+    pub fn with_library<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        match &mut self.0 {
+            ErrorKind::LibraryOpenFailed { library, .. }
+            | ErrorKind::LibraryCloseFailed { library, .. }
+            | ErrorKind::SymbolNotFound { library, .. } => *library = path_string(path),
+            _ => {}
+        }
+        self
+    }
+
+    ///
+    /// Set the likely cause of a
+    /// [`LibraryOpenFailed`](enum.ErrorKind.html#variant.LibraryOpenFailed) error; other errors are
+    /// returned unchanged.
+    ///
+    pub fn with_open_failure_reason(mut self, open_failure_reason: OpenFailureReason) -> Self {
+        if let ErrorKind::LibraryOpenFailed { reason, .. } = &mut self.0 {
+            *reason = open_failure_reason;
+        }
+        self
+    }
+
+    ///
+    /// Return the likely cause if this is a
+    /// [`LibraryOpenFailed`](enum.ErrorKind.html#variant.LibraryOpenFailed) error, else `None`.
+    ///
+    pub fn open_failure_reason(&self) -> Option<OpenFailureReason> {
+        match &self.0 {
+            ErrorKind::LibraryOpenFailed { reason, .. } => Some(*reason),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Return the identifier of the plugin that failed if this is a
+    /// [`PluginRegistration`](enum.ErrorKind.html#variant.PluginRegistration) error attributed to
+    /// a plugin, else `None`.
+    ///
+    pub fn failed_plugin_id(&self) -> Option<&str> {
+        match &self.0 {
+            ErrorKind::PluginRegistration {
+                plugin_id: Some(plugin_id),
+                ..
+            } => Some(plugin_id),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Return the error, followed by each error in its source chain on a separate, indented,
+    /// line; for example:
+    ///
+    /// ```text
+    /// Plugin(s) failed to register
+    ///   caused by: No plugin registered with id 'delay'
+    /// ```
+    ///
+    /// As each cause is on its own line it is removed from the message of the error it caused,
+    /// where the message ends with `; error: '<cause>'`. This is also the result of formatting
+    /// the error with the alternate flag, `{:#}`.
+    ///
+    pub fn full_chain(&self) -> String {
+        let mut chain = String::new();
+        let mut next: Option<&(dyn std::error::Error + 'static)> = Some(self);
+        while let Some(error) = next {
+            next = error.source();
+            let message = error.to_string();
+            let message = match next {
+                Some(source) => message
+                    .strip_suffix(&format!("; error: '{}'", source))
+                    .map(str::to_string)
+                    .unwrap_or(message),
+                None => message,
+            };
+            if !chain.is_empty() {
+                chain.push_str("\n  caused by: ");
+            }
+            chain.push_str(&message);
+        }
+        chain
+    }
+
+    ///
+    /// Replace any boxed source errors with detached copies, see `DetachedError`.
+    ///
+    pub(crate) fn detach(self) -> Self {
+        Self(match self.0 {
+            ErrorKind::LibraryOpenFailed {
+                library,
+                reason,
+                source,
+            } => ErrorKind::LibraryOpenFailed {
+                library,
+                reason,
+                source: detach_error(source),
+            },
+            ErrorKind::LibraryCloseFailed { library, source } => ErrorKind::LibraryCloseFailed {
+                library,
+                source: detach_error(source),
+            },
+            ErrorKind::SymbolNotFound {
+                symbol,
+                library,
+                source,
+            } => ErrorKind::SymbolNotFound {
+                symbol,
+                library,
+                source: detach_error(source),
+            },
+            ErrorKind::PluginRegistration { plugin_id, source } => ErrorKind::PluginRegistration {
+                plugin_id,
+                source: detach_error(source),
+            },
+            kind => kind,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for OpenFailureReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                OpenFailureReason::FileNotFound => "the file was not found",
+                OpenFailureReason::PermissionDenied => "permission to read the file was denied",
+                OpenFailureReason::NotALibrary => "the file is not a dynamic library",
+                OpenFailureReason::WrongArchitecture =>
+                    "the library was built for a different architecture or platform",
+                OpenFailureReason::MissingDependency =>
+                    "a library it depends upon could not be found",
+                OpenFailureReason::Unknown => "the cause is unknown",
+            }
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PluginLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PluginLimit::PluginsPerLibrary => "plugins per library",
+                PluginLimit::Plugins => "plugins",
+                PluginLimit::Libraries => "libraries",
+            }
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for DetachedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.display)
+    }
+}
+
+impl Debug for DetachedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.debug)
+    }
+}
+
+impl std::error::Error for DetachedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.source {
+            Some(source) => Some(source.as_ref()),
+            None => None,
+        }
+    }
+}
+
+impl DetachedError {
+    fn new(error: &dyn std::error::Error) -> Self {
+        Self {
+            display: error.to_string(),
+            debug: format!("{:?}", error),
+            source: error.source().map(|source| Box::new(Self::new(source))),
+        }
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
+fn path_string<P>(path: P) -> String
+where
+    P: AsRef<Path>,
+{
+    path.as_ref().to_string_lossy().to_string()
+}
+
+///
+/// Return a detached copy of the error, see `DetachedError`.
+///
+fn detach_error(error: Box<dyn std::error::Error>) -> Box<dyn std::error::Error> {
+    Box::new(DetachedError::new(error.as_ref()))
+}
+
 // ------------------------------------------------------------------------------------------------
 // Modules
 // ------------------------------------------------------------------------------------------------
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detach_keeps_formatting() {
+        let error = Error::plugin_registration(Error::unknown_plugin("delay"));
+        let display = error.to_string();
+        let debug = format!("{:?}", error);
+
+        let detached = error.detach();
+        assert_eq!(detached.to_string(), display);
+        assert_eq!(format!("{:?}", detached), debug);
+    }
+
+    #[test]
+    fn test_full_chain() {
+        let error =
+            Error::plugin_registration(Error::library_close_failed("libdelay.so", "still in use"));
+        let chain = error.full_chain();
+        assert_eq!(
+            chain,
+            "Plugin(s) failed to register\n  caused by: Library 'libdelay.so' failed to close\n  caused by: still in use"
+        );
+        assert_eq!(chain.matches("libdelay.so").count(), 1);
+        assert_eq!(chain.matches("still in use").count(), 1);
+        assert_eq!(format!("{:#}", error), chain);
+        assert_eq!(
+            error.to_string(),
+            "Plugin(s) failed to register; error: 'Library 'libdelay.so' failed to close; error: 'still in use''"
+        );
+        assert_eq!(error.detach().full_chain(), chain);
+
+        let error = Error::unknown_plugin("delay");
+        assert_eq!(error.full_chain(), "No plugin registered with id 'delay'");
+        assert_eq!(format!("{:#}", error), format!("{}", error));
+    }
+
+    #[test]
+    fn test_library_open_failed() {
+        let error = Error::library_open_failed("libdelay.so", "no such file")
+            .with_open_failure_reason(OpenFailureReason::FileNotFound);
+        assert_eq!(
+            error.to_string(),
+            "Library 'libdelay.so' failed to open, the file was not found; error: 'no such file'"
+        );
+        assert_eq!(
+            error.open_failure_reason(),
+            Some(OpenFailureReason::FileNotFound)
+        );
+    }
+
+    #[test]
+    fn test_from_libloading() {
+        let error = Error::from(libloading::Error::DlOpenUnknown).with_library("libdelay.so");
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::LibraryOpenFailed {
+                library,
+                reason: OpenFailureReason::Unknown,
+                ..
+            } if library == "libdelay.so"
+        ));
+        assert!(std::error::Error::source(&error).is_some());
+
+        let error = Error::from(libloading::Error::DlCloseUnknown).with_library("libdelay.so");
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::LibraryCloseFailed { library, .. } if library == "libdelay.so"
+        ));
+
+        let error = Error::from(libloading::Error::DlSymUnknown).with_library("libdelay.so");
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::SymbolNotFound { library, .. } if library == "libdelay.so"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Could not find symbol '' in library 'libdelay.so'"
+        );
+    }
+
+    #[test]
+    fn test_incompatible_library_version() {
+        let host = Compatibility::host();
+        let error = Error::from(ErrorKind::IncompatibleLibraryVersion {
+            library: "libdelay.so".to_string(),
+            host: Box::new(host.clone()),
+            found: None,
+        });
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Library 'libdelay.so' has incompatible version, expected {}",
+                host
+            )
+        );
+
+        let found = Compatibility::new("0.0.1", host.rustc_version());
+        let error = Error::from(ErrorKind::IncompatibleLibraryVersion {
+            library: "libdelay.so".to_string(),
+            host: Box::new(host.clone()),
+            found: Some(Box::new(found)),
+        });
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Library 'libdelay.so' has incompatible version; dygpi version is 0.0.1, expected {}",
+                host.dygpi_version()
+            )
+        );
+    }
+}
@@ -0,0 +1,130 @@
+/*!
+Provides the [`EventSink`](struct.EventSink.html) type, used by plugins to send typed events to
+the plugin host, which receives them from a channel.
+
+The host creates a sink, and the receiving end of its channel, with
+[`EventSink::channel`](struct.EventSink.html#method.channel) and gives the sink to a plugin
+manager with
+[`PluginManager::set_event_sink`](../manager/struct.PluginManager.html#method.set_event_sink).
+The manager hands the sink to each library's registration function, where it is retrieved with
+[`PluginRegistrar::event_sink`](../plugin/struct.PluginRegistrar.html#method.event_sink) and may
+be cloned into the plugins registered. The event type is usually defined in the plugin API crate,
+alongside the plugin type, and the sink is matched to the event type as described in
+[sharing values](../plugin/index.html#sharing-values).
+
+# Example
+
+In the plugin host:
+
+```rust,no_run
+use dygpi::event::EventSink;
+use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+#[derive(Debug)]
+enum SoundEvent {
+    LevelChanged(String, u32),
+}
+
+let (sink, events) = EventSink::<SoundEvent>::channel();
+let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.set_event_sink(sink);
+plugin_manager.load_plugins_from("libsound_one.dylib").unwrap();
+
+for event in events.try_iter() {
+    println!("{:?}", event);
+}
+```
+
+*/
+
+use std::fmt::{Debug, Formatter};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The sending end of a channel of events of type `E` from plugins to the plugin host.
+///
+pub struct EventSink<E>
+where
+    E: Send + 'static,
+{
+    sender: Sender<E>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<E> Clone for EventSink<E>
+where
+    E: Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<E> Debug for EventSink<E>
+where
+    E: Send + 'static,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSink")
+            .field("event_type", &std::any::type_name::<E>())
+            .finish()
+    }
+}
+
+impl<E> EventSink<E>
+where
+    E: Send + 'static,
+{
+    ///
+    /// Create a new sink, and the receiving end of its channel.
+    ///
+    pub fn channel() -> (Self, Receiver<E>) {
+        let (sender, receiver) = channel();
+        (Self { sender }, receiver)
+    }
+
+    ///
+    /// Send an event to the host; returns `false` if the host has dropped the receiving end of
+    /// the channel, and the event was discarded.
+    ///
+    pub fn emit(&self, event: E) -> bool {
+        self.sender.send(event).is_ok()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emit_events() {
+        let (sink, events) = EventSink::<u32>::channel();
+        let other = sink.clone();
+        assert!(sink.emit(1));
+        assert!(other.emit(2));
+        assert_eq!(events.try_iter().collect::<Vec<u32>>(), vec![1, 2]);
+
+        drop(events);
+        assert!(!sink.emit(3));
+    }
+}
@@ -0,0 +1,283 @@
+/*!
+Provides the [`PluginIndex`](struct.PluginIndex.html) type, a map from plugin identifiers to the
+libraries that provide them, built from the plugin manifests of the libraries in a directory by
+[`scan_and_index`](fn.scan_and_index.html) without opening any library. The index may be saved to
+disk and read back at the next startup, and given to a plugin manager so that
+[`PluginManager::get_or_load`](../manager/struct.PluginManager.html#method.get_or_load)
+loads only the library providing a requested plugin.
+
+# Example
+
+```rust,no_run
+use dygpi::index::{scan_and_index, PluginIndex};
+use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+use std::path::Path;
+
+let index_file = Path::new("plugins.index");
+let index = match PluginIndex::load(index_file) {
+    Ok(index) => index,
+    Err(_) => {
+        let index = scan_and_index(Path::new("plugins")).unwrap();
+        index.save(index_file).unwrap();
+        index
+    }
+};
+
+let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.set_plugin_index(index);
+let plugin = plugin_manager
+    .get_or_load("sound_one::sound_one::DelayEffect")
+    .unwrap();
+```
+
+*/
+
+use crate::error::{Error, Result};
+use crate::inspect::read_manifest;
+use crate::manager::{parse_dylib_name, platform_plugin_directory};
+use crate::manifest::PluginManifest;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// An index of the plugins provided by a set of libraries, built from their plugin manifests.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginIndex {
+    libraries: Vec<IndexedLibrary>,
+}
+
+///
+/// A library within a [`PluginIndex`](struct.PluginIndex.html), and its plugin manifest.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexedLibrary {
+    library: PathBuf,
+    manifest: PluginManifest,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Build an index from the plugin manifests of the libraries in `directory`, or the
+/// subdirectory for the running platform as selected by
+/// [`platform_plugin_directory`](../manager/fn.platform_plugin_directory.html). Only files named
+/// according to the platform's conventions are read, and libraries without a manifest, or that
+/// cannot be inspected, are skipped with a warning.
+///
+pub fn scan_and_index(directory: &Path) -> Result<PluginIndex> {
+    let directory = platform_plugin_directory(directory);
+    info!("scan_and_index({:?})", directory);
+    let read_failed = |e: std::io::Error| Error::directory_read_failed(&directory, e);
+    let mut file_names: Vec<PathBuf> = Default::default();
+    for entry in std::fs::read_dir(&directory).map_err(read_failed)? {
+        let file_name = entry.map_err(read_failed)?.path();
+        if file_name.is_file() && parse_dylib_name(&file_name).is_some() {
+            file_names.push(file_name);
+        }
+    }
+    file_names.sort();
+
+    let mut index = PluginIndex::default();
+    for file_name in file_names {
+        match read_manifest(&file_name) {
+            Ok(Some(manifest)) => index.add(file_name, manifest),
+            Ok(None) => warn!("Library {:?} has no plugin manifest, skipping", file_name),
+            Err(e) => warn!(
+                "Library {:?} could not be inspected, skipping; {}",
+                file_name, e
+            ),
+        }
+    }
+    Ok(index)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+const PLUGIN_INDEX_HEADER: &str = "dygpi-plugin-index 1";
+
+impl PluginIndex {
+    ///
+    /// Read an index previously written with [`save`](#method.save).
+    ///
+    pub fn load(file_name: &Path) -> Result<Self> {
+        trace!("PluginIndex::load({:?})", file_name);
+        let index_failed = |e: Box<dyn std::error::Error>| Error::plugin_index_failed(file_name, e);
+        let text = std::fs::read_to_string(file_name).map_err(|e| index_failed(Box::new(e)))?;
+        parse_index(&text).map_err(index_failed)
+    }
+
+    ///
+    /// Write the index to `file_name`, replacing any existing file.
+    ///
+    pub fn save(&self, file_name: &Path) -> Result<()> {
+        trace!("PluginIndex::save({:?})", file_name);
+        let mut text = String::new();
+        let _ = writeln!(text, "{}", PLUGIN_INDEX_HEADER);
+        for library in &self.libraries {
+            let _ = writeln!(text, "library {}", library.library.to_string_lossy());
+            for line in library.manifest.to_string().lines() {
+                let _ = writeln!(text, "manifest {}", line);
+            }
+        }
+        std::fs::write(file_name, text).map_err(|e| Error::plugin_index_failed(file_name, e))
+    }
+
+    /// Returns `true` if the index contains no libraries, else `false`.
+    pub fn is_empty(&self) -> bool {
+        self.libraries.is_empty()
+    }
+
+    ///
+    /// Return an iterator over the libraries in the index, in file name order.
+    ///
+    pub fn libraries(&self) -> impl Iterator<Item = &IndexedLibrary> {
+        self.libraries.iter()
+    }
+
+    ///
+    /// Return the library providing the plugin with the provided plugin identifier, if any; if
+    /// more than one library provides the plugin the first, in file name order, is returned.
+    ///
+    pub fn library_for(&self, plugin_id: &str) -> Option<&PathBuf> {
+        self.libraries
+            .iter()
+            .find(|library| library.provides(plugin_id))
+            .map(|library| &library.library)
+    }
+
+    fn add(&mut self, library: PathBuf, manifest: PluginManifest) {
+        self.libraries.push(IndexedLibrary { library, manifest })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl IndexedLibrary {
+    /// The path of the library.
+    pub fn library(&self) -> &PathBuf {
+        &self.library
+    }
+
+    /// The plugin manifest read from the library.
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    /// Returns `true` if the library's manifest describes the plugin, else `false`.
+    pub fn provides(&self, plugin_id: &str) -> bool {
+        self.manifest
+            .plugins()
+            .any(|plugin| plugin.plugin_id() == plugin_id)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn parse_index(text: &str) -> std::result::Result<PluginIndex, Box<dyn std::error::Error>> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    match lines.next() {
+        Some(PLUGIN_INDEX_HEADER) => {}
+        other => return Err(format!("unexpected index header {:?}", other).into()),
+    }
+
+    let mut index = PluginIndex::default();
+    let mut current: Option<(PathBuf, Vec<&str>)> = None;
+    for line in lines {
+        match (line.split_once(' '), current.as_mut()) {
+            (Some(("library", library)), _) => {
+                if let Some((library, manifest)) = current.take() {
+                    index.add(library, manifest.join("\n").parse()?);
+                }
+                current = Some((PathBuf::from(library), Vec::new()));
+            }
+            (Some(("manifest", manifest_line)), Some((_, manifest))) => {
+                manifest.push(manifest_line)
+            }
+            _ => return Err(format!("unexpected index line {:?}", line).into()),
+        }
+    }
+    if let Some((library, manifest)) = current.take() {
+        index.add(library, manifest.join("\n").parse()?);
+    }
+    Ok(index)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::PluginDescriptor;
+
+    fn make_index() -> PluginIndex {
+        let mut index = PluginIndex::default();
+        index.add(
+            PathBuf::from("/lib/lib effects.so"),
+            PluginManifest::new(
+                Some(2..=3),
+                vec![
+                    PluginDescriptor::new("effects::Delay", "0.1.0"),
+                    PluginDescriptor::new("effects::Reverb", "0.2.0"),
+                ],
+            ),
+        );
+        index.add(
+            PathBuf::from("/lib/libsources.so"),
+            PluginManifest::new(None, vec![PluginDescriptor::new("sources::Lfo", "1.0.0")]),
+        );
+        index
+    }
+
+    #[test]
+    fn test_library_for() {
+        let index = make_index();
+        assert_eq!(
+            index.library_for("effects::Reverb"),
+            Some(&PathBuf::from("/lib/lib effects.so"))
+        );
+        assert_eq!(
+            index.library_for("sources::Lfo"),
+            Some(&PathBuf::from("/lib/libsources.so"))
+        );
+        assert!(index.library_for("unknown").is_none());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let directory = tempfile::tempdir().unwrap();
+        let index_file = directory.path().join("plugins.index");
+        let index = make_index();
+        index.save(&index_file).unwrap();
+        assert_eq!(PluginIndex::load(&index_file).unwrap(), index);
+    }
+
+    #[test]
+    fn test_load_invalid_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let index_file = directory.path().join("plugins.index");
+        std::fs::write(&index_file, "dygpi-plugin-index 1\nmanifest plugin a 1.0\n").unwrap();
+        assert!(PluginIndex::load(&index_file).is_err());
+        assert!(PluginIndex::load(&directory.path().join("missing.index")).is_err());
+    }
+}
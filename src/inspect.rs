@@ -0,0 +1,798 @@
+/*!
+Provides functions to inspect a dynamic library file without opening, and so without running any
+code in, the library. The formats supported are ELF (Linux and other Unix platforms), Mach-O
+(macOS), and PE (Windows), regardless of the platform the host is running on.
+
+# Example
+
+```rust,no_run
+use dygpi::inspect::{exported_symbols, validate_library};
+use dygpi::plugin::HostInfo;
+
+let symbols = exported_symbols("libsound_one.so".as_ref()).unwrap();
+for symbol in symbols.iter().filter(|s| s.starts_with("register_")) {
+    println!("{}", symbol);
+}
+
+let manifest =
+    validate_library("libsound_one.so".as_ref(), &HostInfo::new(2, "sound_host", "0.1.0")).unwrap();
+for plugin in manifest.plugins() {
+    println!("{} {}", plugin.plugin_id(), plugin.version());
+}
+```
+
+When a library fails to open because a library it depends upon cannot be found, the missing
+libraries may be found with [`explain_load_failure`](fn.explain_load_failure.html).
+
+```rust,no_run
+use dygpi::inspect::explain_load_failure;
+
+let explanation = explain_load_failure("libsound_one.so".as_ref()).unwrap();
+for dependency in explanation.unresolved() {
+    println!("{:?} requires {}", dependency.required_by(), dependency.name());
+}
+```
+
+*/
+
+use crate::error::{Error, ErrorKind, OpenFailureReason, Result};
+use crate::manifest::{PluginManifest, PLUGIN_MANIFEST_SYMBOL_NAME};
+use crate::plugin::HostInfo;
+use object::read::elf::{Dyn, ElfFile, FileHeader};
+use object::read::macho::{LoadCommandVariant, MachHeader, MachOFile};
+use object::{elf, macho};
+use object::{Architecture, BinaryFormat, Object, ObjectKind, ObjectSection};
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The result of [`explain_load_failure`](fn.explain_load_failure.html); the libraries a dynamic
+/// library depends upon, and those of its direct and transitive dependencies that could not be
+/// found.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadFailureExplanation {
+    file_name: PathBuf,
+    dependencies: Vec<String>,
+    unresolved: Vec<UnresolvedDependency>,
+}
+
+///
+/// A dependent library that could not be found, and the library that requires it.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnresolvedDependency {
+    name: String,
+    required_by: PathBuf,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+const HEADER_READ_LENGTH: u64 = 4096;
+
+///
+/// A library named in a dynamic library's dependencies, and the paths the platform loader would
+/// look for it at, in order. System libraries, which may not exist as files, are always assumed to
+/// be present.
+///
+#[derive(Debug)]
+struct Dependency {
+    name: String,
+    candidates: Vec<PathBuf>,
+    is_system: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Return the names of all symbols exported by the dynamic library at `file_name`. On Mach-O the
+/// leading underscore added by the platform C ABI is removed, so that names match those passed
+/// to `dlsym` on all platforms. Symbols whose names are not valid UTF-8 are skipped.
+///
+pub fn exported_symbols(file_name: &Path) -> Result<Vec<String>> {
+    trace!("exported_symbols({:?})", file_name);
+    let data = read_library(file_name)?;
+    let file = parse_library(file_name, &data)?;
+    Ok(exports(file_name, &file)?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect())
+}
+
+///
+/// Return the plugin manifest exported by the dynamic library at `file_name`, see the
+/// [`manifest`](../manifest/index.html) module, or `None` if the library does not export a
+/// `PLUGIN_MANIFEST` symbol.
+///
+pub fn read_manifest(file_name: &Path) -> Result<Option<PluginManifest>> {
+    trace!("read_manifest({:?})", file_name);
+    let data = read_library(file_name)?;
+    let file = parse_library(file_name, &data)?;
+    let address = match exports(file_name, &file)?
+        .into_iter()
+        .find(|(name, _)| name == PLUGIN_MANIFEST_SYMBOL_NAME)
+    {
+        None => return Ok(None),
+        Some((_, address)) => address,
+    };
+
+    let bytes = file
+        .sections()
+        .filter_map(|section| {
+            let offset = address.checked_sub(section.address())?;
+            if offset < section.size() {
+                let data = section.data().ok()?;
+                data.get(offset as usize..)
+            } else {
+                None
+            }
+        })
+        .next()
+        .ok_or_else(|| {
+            Error::invalid_plugin_manifest(&format!(
+                "symbol address {:#x} is not within a section",
+                address
+            ))
+        })?;
+    let text = bytes
+        .iter()
+        .position(|b| *b == 0)
+        .and_then(|end| std::str::from_utf8(&bytes[..end]).ok())
+        .ok_or_else(|| {
+            Error::invalid_plugin_manifest("manifest is not a NUL-terminated UTF-8 string")
+        })?;
+    Ok(Some(text.parse()?))
+}
+
+///
+/// Validate the dynamic library at `file_name` against the provided host information without
+/// running any code in the library. The library must export a plugin manifest, and the manifest
+/// must support the host's API version; the manifest is returned if the library is valid.
+///
+pub fn validate_library(file_name: &Path, host_info: &HostInfo) -> Result<PluginManifest> {
+    trace!("validate_library({:?}, {:?})", file_name, host_info);
+    check_manifest(file_name, read_manifest(file_name)?, host_info)
+}
+
+///
+/// Explain why the dynamic library at `file_name` may fail to load, by reading the dependent
+/// libraries from its dynamic section (ELF), load commands (Mach-O), or import table (PE), and
+/// searching for each in the same locations, and order, as the platform loader. The search is
+/// repeated for each dependency that is found, and so unresolved transitive dependencies are also
+/// reported.
+///
+/// The search is an approximation of the platform loader's, for example it does not consult the
+/// ELF loader cache or check that a dependency found is built for the same architecture, and so
+/// an empty list of unresolved dependencies does not guarantee that the library will load.
+///
+pub fn explain_load_failure(file_name: &Path) -> Result<LoadFailureExplanation> {
+    trace!("explain_load_failure({:?})", file_name);
+    let data = read_library(file_name)?;
+    let file = parse_library(file_name, &data)?;
+    let dependencies = library_dependencies(file_name, &file)
+        .map_err(|e| inspection_failed(file_name, Box::new(e)))?;
+
+    let mut explanation = LoadFailureExplanation {
+        file_name: file_name.to_path_buf(),
+        dependencies: dependencies.iter().map(|d| d.name.clone()).collect(),
+        unresolved: Default::default(),
+    };
+    let mut visited: HashSet<String> = Default::default();
+    let mut pending = vec![(file_name.to_path_buf(), dependencies)];
+    while let Some((required_by, dependencies)) = pending.pop() {
+        for dependency in dependencies {
+            if dependency.is_system || !visited.insert(dependency.name.clone()) {
+                continue;
+            }
+            match dependency
+                .candidates
+                .into_iter()
+                .find(|path| path.is_file())
+            {
+                None => {
+                    debug!(
+                        "explain_load_failure() > {:?} requires {:?}, not found",
+                        required_by, dependency.name
+                    );
+                    explanation.unresolved.push(UnresolvedDependency {
+                        name: dependency.name,
+                        required_by: required_by.clone(),
+                    })
+                }
+                Some(path) => {
+                    trace!(
+                        "explain_load_failure() > {:?} resolved to {:?}",
+                        dependency.name,
+                        path
+                    );
+                    let transitive = std::fs::read(&path).ok().and_then(|data| {
+                        let file = object::File::parse(data.as_slice()).ok()?;
+                        library_dependencies(&path, &file).ok()
+                    });
+                    if let Some(transitive) = transitive {
+                        pending.push((path, transitive));
+                    }
+                }
+            }
+        }
+    }
+    Ok(explanation)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for LoadFailureExplanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.unresolved.is_empty() {
+            write!(
+                f,
+                "Library '{}' has no unresolved dependencies",
+                self.file_name.to_string_lossy()
+            )
+        } else {
+            write!(
+                f,
+                "Library '{}' has unresolved dependencies: {}",
+                self.file_name.to_string_lossy(),
+                self.unresolved
+                    .iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        }
+    }
+}
+
+impl LoadFailureExplanation {
+    /// The library that was examined.
+    pub fn file_name(&self) -> &PathBuf {
+        &self.file_name
+    }
+
+    /// The names of the libraries the examined library depends upon directly, in declared order.
+    pub fn dependencies(&self) -> &Vec<String> {
+        &self.dependencies
+    }
+
+    /// The direct and transitive dependencies that could not be found.
+    pub fn unresolved(&self) -> &Vec<UnresolvedDependency> {
+        &self.unresolved
+    }
+
+    /// Returns `true` if every dependency was found, else `false`.
+    pub fn is_resolved(&self) -> bool {
+        self.unresolved.is_empty()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for UnresolvedDependency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' required by '{}'",
+            self.name,
+            self.required_by.to_string_lossy()
+        )
+    }
+}
+
+impl UnresolvedDependency {
+    /// The name of the library that could not be found, as recorded in the library requiring it.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// The library that requires the missing library.
+    pub fn required_by(&self) -> &PathBuf {
+        &self.required_by
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Determine the likely cause of the platform loader's failure to open the library at
+/// `file_name`, by examining the file and the loader's error.
+///
+pub(crate) fn classify_open_failure(
+    file_name: &Path,
+    error: &(dyn std::error::Error + 'static),
+) -> OpenFailureReason {
+    let data = match std::fs::read(file_name) {
+        Ok(data) => data,
+        Err(e) => {
+            return match e.kind() {
+                std::io::ErrorKind::NotFound => OpenFailureReason::FileNotFound,
+                std::io::ErrorKind::PermissionDenied => OpenFailureReason::PermissionDenied,
+                _ => OpenFailureReason::Unknown,
+            }
+        }
+    };
+    match object::File::parse(data.as_slice()) {
+        Ok(file) if file.kind() == ObjectKind::Dynamic => {
+            let host_architecture = host_architecture();
+            if file.format() != host_binary_format()
+                || (host_architecture != Architecture::Unknown
+                    && file.architecture() != host_architecture)
+            {
+                return OpenFailureReason::WrongArchitecture;
+            }
+        }
+        _ => return OpenFailureReason::NotALibrary,
+    }
+
+    let mut messages = Vec::new();
+    let mut source = Some(error);
+    while let Some(error) = source {
+        messages.push(error.to_string());
+        source = error.source();
+    }
+    let messages = messages.join("; ");
+    if [
+        "cannot open shared object file",
+        "Library not loaded",
+        "os error 126",
+    ]
+    .iter()
+    .any(|pattern| messages.contains(pattern))
+    {
+        OpenFailureReason::MissingDependency
+    } else {
+        OpenFailureReason::Unknown
+    }
+}
+
+///
+/// Check that the library at `file_name` was built for the host's architecture, reading only the
+/// ELF, Mach-O, or PE header. Files that cannot be read, or whose format or architecture is not
+/// recognized, are not rejected; the platform loader reports any problem with them.
+///
+pub(crate) fn check_architecture(file_name: &Path) -> Result<()> {
+    let mut header: Vec<u8> = Vec::with_capacity(HEADER_READ_LENGTH as usize);
+    if std::fs::File::open(file_name)
+        .and_then(|file| file.take(HEADER_READ_LENGTH).read_to_end(&mut header))
+        .is_err()
+    {
+        return Ok(());
+    }
+    let expected = std::env::consts::ARCH;
+    match header_architecture(&header) {
+        Some(found) if found != expected && host_architecture() != Architecture::Unknown => {
+            warn!(
+                "Library {:?} was built for {}, not {}",
+                file_name, found, expected
+            );
+            Err(ErrorKind::WrongArchitecture {
+                library: file_name.to_string_lossy().to_string(),
+                expected: expected.to_string(),
+                found,
+            }
+            .into())
+        }
+        _ => Ok(()),
+    }
+}
+
+///
+/// Check the manifest, if any, read from the library at `file_name` against the host information.
+///
+pub(crate) fn check_manifest(
+    file_name: &Path,
+    manifest: Option<PluginManifest>,
+    host_info: &HostInfo,
+) -> Result<PluginManifest> {
+    let manifest = manifest.ok_or_else(|| {
+        Error::symbol_not_found(
+            file_name,
+            PLUGIN_MANIFEST_SYMBOL_NAME,
+            "the library has no plugin manifest",
+        )
+    })?;
+    if !manifest.supports_host_api_version(host_info.api_version()) {
+        return Err(ErrorKind::IncompatibleHostApiVersion {
+            api_version: host_info.api_version(),
+        }
+        .into());
+    }
+    Ok(manifest)
+}
+
+fn inspection_failed(file_name: &Path, error: Box<dyn std::error::Error>) -> Error {
+    Error::library_inspection_failed(file_name, error)
+}
+
+fn read_library(file_name: &Path) -> Result<Vec<u8>> {
+    std::fs::read(file_name).map_err(|e| inspection_failed(file_name, Box::new(e)))
+}
+
+fn parse_library<'a>(file_name: &Path, data: &'a [u8]) -> Result<object::File<'a>> {
+    let file = object::File::parse(data).map_err(|e| inspection_failed(file_name, Box::new(e)))?;
+    if file.kind() != ObjectKind::Dynamic {
+        return Err(inspection_failed(
+            file_name,
+            Box::from("file is not a dynamic library"),
+        ));
+    }
+    Ok(file)
+}
+
+///
+/// Return the architecture named in an ELF, Mach-O, or PE header, using the names of
+/// `std::env::consts::ARCH`; architectures without a name are described by their header value.
+///
+fn header_architecture(header: &[u8]) -> Option<String> {
+    let read_u16 = |offset: usize, big_endian: bool| -> Option<u16> {
+        let bytes: [u8; 2] = header.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    };
+    let read_u32 = |offset: usize, big_endian: bool| -> Option<u32> {
+        let bytes: [u8; 4] = header.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+
+    if header.starts_with(&elf::ELFMAG) {
+        let is_64 = header.get(4)? == &elf::ELFCLASS64;
+        let big_endian = header.get(5)? == &elf::ELFDATA2MSB;
+        let machine = read_u16(18, big_endian)?;
+        Some(match machine {
+            elf::EM_386 => "x86".to_string(),
+            elf::EM_X86_64 => "x86_64".to_string(),
+            elf::EM_ARM => "arm".to_string(),
+            elf::EM_AARCH64 => "aarch64".to_string(),
+            elf::EM_RISCV if is_64 => "riscv64".to_string(),
+            elf::EM_RISCV => "riscv32".to_string(),
+            elf::EM_PPC => "powerpc".to_string(),
+            elf::EM_PPC64 => "powerpc64".to_string(),
+            elf::EM_S390 => "s390x".to_string(),
+            elf::EM_LOONGARCH => "loongarch64".to_string(),
+            _ => format!("ELF machine {:#x}", machine),
+        })
+    } else if header.starts_with(b"MZ") {
+        let pe_offset = read_u32(0x3c, false)? as usize;
+        if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+            return None;
+        }
+        let machine = read_u16(pe_offset + 4, false)?;
+        Some(match machine {
+            object::pe::IMAGE_FILE_MACHINE_I386 => "x86".to_string(),
+            object::pe::IMAGE_FILE_MACHINE_AMD64 => "x86_64".to_string(),
+            object::pe::IMAGE_FILE_MACHINE_ARMNT => "arm".to_string(),
+            object::pe::IMAGE_FILE_MACHINE_ARM64 => "aarch64".to_string(),
+            _ => format!("PE machine {:#x}", machine),
+        })
+    } else {
+        let big_endian = match read_u32(0, false)? {
+            macho::MH_MAGIC | macho::MH_MAGIC_64 => false,
+            macho::MH_CIGAM | macho::MH_CIGAM_64 => true,
+            _ => return None,
+        };
+        let cpu_type = read_u32(4, big_endian)?;
+        Some(match cpu_type {
+            macho::CPU_TYPE_X86 => "x86".to_string(),
+            macho::CPU_TYPE_X86_64 => "x86_64".to_string(),
+            macho::CPU_TYPE_ARM => "arm".to_string(),
+            macho::CPU_TYPE_ARM64 => "aarch64".to_string(),
+            macho::CPU_TYPE_POWERPC => "powerpc".to_string(),
+            macho::CPU_TYPE_POWERPC64 => "powerpc64".to_string(),
+            _ => format!("Mach-O CPU type {:#x}", cpu_type),
+        })
+    }
+}
+
+fn host_binary_format() -> BinaryFormat {
+    if cfg!(target_vendor = "apple") {
+        BinaryFormat::MachO
+    } else if cfg!(target_os = "windows") {
+        BinaryFormat::Pe
+    } else {
+        BinaryFormat::Elf
+    }
+}
+
+fn host_architecture() -> Architecture {
+    match std::env::consts::ARCH {
+        "x86" => Architecture::I386,
+        "x86_64" => Architecture::X86_64,
+        "arm" => Architecture::Arm,
+        "aarch64" => Architecture::Aarch64,
+        "riscv32" => Architecture::Riscv32,
+        "riscv64" => Architecture::Riscv64,
+        "powerpc" => Architecture::PowerPc,
+        "powerpc64" => Architecture::PowerPc64,
+        "s390x" => Architecture::S390x,
+        "loongarch64" => Architecture::LoongArch64,
+        _ => Architecture::Unknown,
+    }
+}
+
+fn exports(file_name: &Path, file: &object::File<'_>) -> Result<Vec<(String, u64)>> {
+    let is_mach_o = file.format() == BinaryFormat::MachO;
+    Ok(file
+        .exports()
+        .map_err(|e| inspection_failed(file_name, Box::new(e)))?
+        .iter()
+        .filter_map(|export| {
+            std::str::from_utf8(export.name())
+                .ok()
+                .map(|name| (name, export.address()))
+        })
+        .map(|(name, address)| match name.strip_prefix('_') {
+            Some(name) if is_mach_o => (name.to_string(), address),
+            _ => (name.to_string(), address),
+        })
+        .collect())
+}
+
+fn library_dependencies(
+    file_name: &Path,
+    file: &object::File<'_>,
+) -> object::Result<Vec<Dependency>> {
+    let directory = file_name
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    match file {
+        object::File::Elf32(file) => elf_dependencies(&directory, file),
+        object::File::Elf64(file) => elf_dependencies(&directory, file),
+        object::File::MachO32(file) => mach_o_dependencies(&directory, file),
+        object::File::MachO64(file) => mach_o_dependencies(&directory, file),
+        _ => pe_dependencies(&directory, file),
+    }
+}
+
+fn elf_dependencies<Elf>(
+    directory: &Path,
+    file: &ElfFile<'_, Elf>,
+) -> object::Result<Vec<Dependency>>
+where
+    Elf: FileHeader,
+{
+    let endian = file.endian();
+    let sections = file.elf_section_table();
+    let (entries, strings_index) = match sections.dynamic(endian, file.data())? {
+        None => return Ok(Default::default()),
+        Some(dynamic) => dynamic,
+    };
+    let strings = sections.strings(endian, file.data(), strings_index)?;
+
+    let mut needed: Vec<String> = Default::default();
+    let mut rpath: Vec<PathBuf> = Default::default();
+    let mut runpath: Vec<PathBuf> = Default::default();
+    for entry in entries {
+        let tag = entry.tag32(endian);
+        if !matches!(
+            tag,
+            Some(elf::DT_NEEDED) | Some(elf::DT_RPATH) | Some(elf::DT_RUNPATH)
+        ) {
+            continue;
+        }
+        let value = String::from_utf8_lossy(entry.string(endian, strings)?).to_string();
+        match tag {
+            Some(elf::DT_NEEDED) => needed.push(value),
+            Some(elf::DT_RPATH) => rpath.extend(elf_search_paths(directory, &value)),
+            _ => runpath.extend(elf_search_paths(directory, &value)),
+        }
+    }
+
+    let mut search_paths: Vec<PathBuf> = Default::default();
+    if runpath.is_empty() {
+        search_paths.append(&mut rpath);
+    }
+    if let Ok(library_path) = std::env::var("LD_LIBRARY_PATH") {
+        search_paths.extend(elf_search_paths(directory, &library_path));
+    }
+    search_paths.append(&mut runpath);
+    ld_so_conf_directories(Path::new("/etc/ld.so.conf"), &mut search_paths);
+    search_paths.extend(
+        ["/lib64", "/usr/lib64", "/lib", "/usr/lib"]
+            .iter()
+            .map(PathBuf::from),
+    );
+
+    Ok(needed
+        .into_iter()
+        .map(|name| Dependency {
+            candidates: if name.contains('/') {
+                vec![PathBuf::from(&name)]
+            } else {
+                search_paths.iter().map(|path| path.join(&name)).collect()
+            },
+            name,
+            is_system: false,
+        })
+        .collect())
+}
+
+fn elf_search_paths(directory: &Path, paths: &str) -> Vec<PathBuf> {
+    let origin = directory.to_string_lossy();
+    paths
+        .split(':')
+        .filter(|path| !path.is_empty())
+        .map(|path| {
+            PathBuf::from(
+                path.replace("${ORIGIN}", &origin)
+                    .replace("$ORIGIN", &origin),
+            )
+        })
+        .collect()
+}
+
+fn ld_so_conf_directories(file_name: &Path, directories: &mut Vec<PathBuf>) {
+    let text = match std::fs::read_to_string(file_name) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if let Some(pattern) = line.strip_prefix("include ") {
+            let pattern = file_name
+                .parent()
+                .unwrap_or_else(|| Path::new("/"))
+                .join(pattern.trim());
+            let (parent, file_pattern) = match (pattern.parent(), pattern.file_name()) {
+                (Some(parent), Some(file_pattern)) => {
+                    (parent, file_pattern.to_string_lossy().to_string())
+                }
+                _ => continue,
+            };
+            let mut included: Vec<PathBuf> = std::fs::read_dir(parent)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| {
+                    matches_pattern(&file_pattern, &entry.file_name().to_string_lossy())
+                })
+                .map(|entry| entry.path())
+                .collect();
+            included.sort();
+            for file_name in included {
+                ld_so_conf_directories(&file_name, directories);
+            }
+        } else if !line.is_empty() {
+            directories.push(PathBuf::from(line));
+        }
+    }
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, suffix)) => {
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+        }
+    }
+}
+
+fn mach_o_dependencies<Mach>(
+    directory: &Path,
+    file: &MachOFile<'_, Mach>,
+) -> object::Result<Vec<Dependency>>
+where
+    Mach: MachHeader,
+{
+    let endian = file.endian();
+    let executable_directory = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    let expand = |path: &str| -> PathBuf {
+        if let Some(path) = path.strip_prefix("@loader_path/") {
+            directory.join(path)
+        } else if let Some(path) = path.strip_prefix("@executable_path/") {
+            executable_directory.join(path)
+        } else {
+            PathBuf::from(path)
+        }
+    };
+
+    let mut needed: Vec<String> = Default::default();
+    let mut rpaths: Vec<PathBuf> = Default::default();
+    let mut commands = file.macho_load_commands()?;
+    while let Some(command) = commands.next()? {
+        match command.variant()? {
+            LoadCommandVariant::Dylib(dylib) if command.cmd() != macho::LC_LOAD_WEAK_DYLIB => {
+                needed.push(
+                    String::from_utf8_lossy(command.string(endian, dylib.dylib.name)?).to_string(),
+                );
+            }
+            LoadCommandVariant::Rpath(rpath) => {
+                rpaths.push(expand(&String::from_utf8_lossy(
+                    command.string(endian, rpath.path)?,
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    let library_paths: Vec<PathBuf> = std::env::var_os("DYLD_LIBRARY_PATH")
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default();
+    Ok(needed
+        .into_iter()
+        .map(|name| {
+            let leaf_name = Path::new(&name)
+                .file_name()
+                .map(|leaf_name| leaf_name.to_os_string())
+                .unwrap_or_default();
+            let mut candidates: Vec<PathBuf> = library_paths
+                .iter()
+                .map(|path| path.join(&leaf_name))
+                .collect();
+            if let Some(path) = name.strip_prefix("@rpath/") {
+                candidates.extend(rpaths.iter().map(|rpath| rpath.join(path)));
+            } else {
+                candidates.push(expand(&name));
+            }
+            Dependency {
+                is_system: name.starts_with("/usr/lib/") || name.starts_with("/System/Library/"),
+                candidates,
+                name,
+            }
+        })
+        .collect())
+}
+
+fn pe_dependencies(directory: &Path, file: &object::File<'_>) -> object::Result<Vec<Dependency>> {
+    let system_root = std::env::var_os("SystemRoot")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("C:\\Windows"));
+    let mut search_paths = vec![
+        directory.to_path_buf(),
+        system_root.join("System32"),
+        system_root.join("System"),
+        system_root.clone(),
+    ];
+    if let Ok(current_dir) = std::env::current_dir() {
+        search_paths.push(current_dir);
+    }
+    if let Some(paths) = std::env::var_os("PATH") {
+        search_paths.extend(std::env::split_paths(&paths));
+    }
+
+    let mut needed: Vec<String> = Default::default();
+    for import in file.imports()? {
+        let name = String::from_utf8_lossy(import.library()).to_string();
+        if !name.is_empty() && !needed.contains(&name) {
+            needed.push(name);
+        }
+    }
+    Ok(needed
+        .into_iter()
+        .map(|name| {
+            let lower_name = name.to_lowercase();
+            Dependency {
+                candidates: search_paths.iter().map(|path| path.join(&name)).collect(),
+                is_system: lower_name.starts_with("api-ms-") || lower_name.starts_with("ext-ms-"),
+                name,
+            }
+        })
+        .collect())
+}
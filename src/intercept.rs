@@ -0,0 +1,286 @@
+/*!
+Provides the [`Interceptor`](trait.Interceptor.html) trait, used to wrap the lifecycle methods of
+every plugin in a plugin manager, for example for timing, logging, auditing, or feature gating.
+
+Interceptors are added to a plugin manager with
+[`PluginManager::add_interceptor`](../manager/struct.PluginManager.html#method.add_interceptor)
+and form a chain, in the order they were added; the first interceptor added is the outermost.
+Each interceptor is given a `next` function that calls the next interceptor in the chain, or the
+plugin's lifecycle method itself. An interceptor may act before and after calling `next`, alter
+its result, or not call it at all.
+
+# Example
+
+```rust
+use dygpi::error::Result;
+use dygpi::intercept::{Hook, Interceptor};
+use dygpi::manager::PluginManager;
+use dygpi::plugin::Plugin;
+use std::sync::Arc;
+use std::time::Instant;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+
+#[derive(Debug)]
+struct Timing;
+
+impl<T: Plugin> Interceptor<T> for Timing {
+    fn intercept(
+        &self,
+        plugin: &T,
+        hook: Hook,
+        next: &mut dyn FnMut() -> Result<()>,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let result = next();
+        println!("{} {} took {:?}", plugin.plugin_id(), hook, start.elapsed());
+        result
+    }
+}
+
+let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.add_interceptor(Arc::new(Timing));
+```
+
+*/
+
+use crate::error::Result;
+use crate::plugin::Plugin;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The plugin lifecycle methods that may be intercepted.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Hook {
+    /// The plugin's `on_load` method.
+    Load,
+    /// The plugin's `on_unload` method.
+    Unload,
+    /// The plugin's `on_enable` method.
+    Enable,
+    /// The plugin's `on_disable` method.
+    Disable,
+    /// The plugin's `on_pause` method.
+    Pause,
+    /// The plugin's `on_resume` method.
+    Resume,
+    /// The plugin's `on_config_changed` method.
+    ConfigChanged,
+}
+
+///
+/// Implemented by types that wrap the lifecycle methods of plugins, see the
+/// [`intercept`](index.html) module.
+///
+pub trait Interceptor<T>: Debug + Send + Sync
+where
+    T: Plugin,
+{
+    ///
+    /// Called in place of the lifecycle method `hook` of `plugin`; call `next` to continue along
+    /// the chain to the lifecycle method itself.
+    ///
+    fn intercept(&self, plugin: &T, hook: Hook, next: &mut dyn FnMut() -> Result<()>)
+        -> Result<()>;
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(crate) struct InterceptorChain<T>
+where
+    T: Plugin,
+{
+    interceptors: Vec<Arc<dyn Interceptor<T>>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Hook {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Hook::Load => "on_load",
+                Hook::Unload => "on_unload",
+                Hook::Enable => "on_enable",
+                Hook::Disable => "on_disable",
+                Hook::Pause => "on_pause",
+                Hook::Resume => "on_resume",
+                Hook::ConfigChanged => "on_config_changed",
+            }
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Default for InterceptorChain<T>
+where
+    T: Plugin,
+{
+    fn default() -> Self {
+        Self {
+            interceptors: Default::default(),
+        }
+    }
+}
+
+impl<T> Clone for InterceptorChain<T>
+where
+    T: Plugin,
+{
+    fn clone(&self) -> Self {
+        Self {
+            interceptors: self.interceptors.clone(),
+        }
+    }
+}
+
+impl<T> InterceptorChain<T>
+where
+    T: Plugin,
+{
+    pub(crate) fn push(&mut self, interceptor: Arc<dyn Interceptor<T>>) {
+        self.interceptors.push(interceptor)
+    }
+
+    ///
+    /// Call the lifecycle method `hook` of `plugin` through all interceptors in the chain.
+    ///
+    pub(crate) fn call(&self, plugin: &T, hook: Hook) -> Result<()> {
+        self.call_from(0, plugin, hook)
+    }
+
+    fn call_from(&self, index: usize, plugin: &T, hook: Hook) -> Result<()> {
+        match self.interceptors.get(index) {
+            None => call_hook(plugin, hook),
+            Some(interceptor) => interceptor.intercept(plugin, hook, &mut || {
+                self.call_from(index + 1, plugin, hook)
+            }),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn call_hook<T>(plugin: &T, hook: Hook) -> Result<()>
+where
+    T: Plugin,
+{
+    match hook {
+        Hook::Load => plugin.on_load(),
+        Hook::Unload => plugin.on_unload(),
+        Hook::Enable => plugin.on_enable(),
+        Hook::Disable => plugin.on_disable(),
+        Hook::Pause => plugin.on_pause(),
+        Hook::Resume => plugin.on_resume(),
+        Hook::ConfigChanged => plugin.on_config_changed(),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    struct TestPlugin(String);
+
+    impl Plugin for TestPlugin {
+        fn plugin_id(&self) -> &str {
+            &self.0
+        }
+        fn on_load(&self) -> Result<()> {
+            Ok(())
+        }
+        fn on_unload(&self) -> Result<()> {
+            Err(Error::unknown_plugin(&self.0))
+        }
+    }
+
+    #[derive(Debug)]
+    struct Recorder(&'static str, Arc<Mutex<Vec<String>>>);
+
+    impl Interceptor<TestPlugin> for Recorder {
+        fn intercept(
+            &self,
+            _: &TestPlugin,
+            hook: Hook,
+            next: &mut dyn FnMut() -> Result<()>,
+        ) -> Result<()> {
+            self.1.lock().unwrap().push(format!("{} {}", self.0, hook));
+            next()
+        }
+    }
+
+    #[derive(Debug)]
+    struct Gate;
+
+    impl Interceptor<TestPlugin> for Gate {
+        fn intercept(
+            &self,
+            _: &TestPlugin,
+            hook: Hook,
+            next: &mut dyn FnMut() -> Result<()>,
+        ) -> Result<()> {
+            match hook {
+                Hook::Unload => Ok(()),
+                _ => next(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_chain_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut chain: InterceptorChain<TestPlugin> = Default::default();
+        chain.push(Arc::new(Recorder("outer", calls.clone())));
+        chain.push(Arc::new(Recorder("inner", calls.clone())));
+
+        let plugin = TestPlugin("test".to_string());
+        chain.call(&plugin, Hook::Load).unwrap();
+        assert!(chain.call(&plugin, Hook::Unload).is_err());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "outer on_load",
+                "inner on_load",
+                "outer on_unload",
+                "inner on_unload"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interceptor_skips_hook() {
+        let mut chain: InterceptorChain<TestPlugin> = Default::default();
+        chain.push(Arc::new(Gate));
+
+        let plugin = TestPlugin("test".to_string());
+        chain.call(&plugin, Hook::Unload).unwrap();
+    }
+}
@@ -16,10 +16,13 @@ external dynamic libraries at runtime.
       provided to the plugin manager via the
       [`set_registration_fn_name`](manager/struct.PluginManager.html#method.set_registration_fn_name)
       method.
+   1. A plugin _host_ can instead discover every function exported with a name starting with a
+      prefix, see [`set_registration_fn_prefix`](manager/struct.PluginManager.html#method.set_registration_fn_prefix);
+      the plugin _provider_ **MUST NOT** then export any other function with that prefix.
 1. The plugin _host_ then uses the [`PluginManager`](manager/struct.PluginManager.html) to load libraries,
    and register plugins, that have the same type as the plugin _type_.
 1. The plugin _host_ **MAY** then use plugin manager's [`get`](manager/struct.PluginManager.html#method.get)
-    method to fetch a specific plugin by _id_, **OR** use
+   method to fetch a specific plugin by _id_, **OR** use
    plugin manager's [`plugins`](manager/struct.PluginManager.html#method.plugins) method to iterate
    over all plugins.
 
@@ -39,7 +42,7 @@ use std::sync::Arc;
 # #[derive(Debug)]
 # struct SoundEffectPlugin;
 # impl Plugin for SoundEffectPlugin {
-#     fn plugin_id(&self) -> &String {
+#     fn plugin_id(&self) -> &str {
 #         todo!()
 #     }
 #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
@@ -50,10 +53,10 @@ use std::sync::Arc;
 # }
 
 fn main() {
-    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
 
     plugin_manager
-        .load_plugins_from("libsound_one.dylib".as_ref())
+        .load_plugins_from("libsound_one.dylib")
         .unwrap();
 
     let plugin: Arc<SoundEffectPlugin> = plugin_manager
@@ -70,7 +73,9 @@ fn main() {
 
 `config_serde`: Adds [Serde](https://serde.rs/)'s `Serialize` and `Deserialize` traits to the
 [`PluginManagerConfiguration`](config/struct.PluginManagerConfiguration.html) type so that it can
-be used in configuration files.
+be used in configuration files. It also adds `Serialize` to the
+[`Topology`](topology/struct.Topology.html) and [`StatusReport`](status/struct.StatusReport.html)
+types so that they can be exported as JSON.
 
 ```toml
 [plugins]
@@ -78,6 +83,43 @@ source = ["analog_oscillator", "lfo"]
 effect = ["delay", "reverb"]
 ```
 
+`bundle`: Adds the [`bundle`](bundle/index.html) module, and the plugin manager's
+[`load_bundle`](manager/struct.PluginManager.html#method.load_bundle) method, which select
+libraries to load from a `plugin.toml` file placed alongside them.
+
+`metrics`: Reports the load timing and failure counts recorded by the plugin manager through the
+[metrics](https://docs.rs/metrics) crate facade, see the [`metrics`](metrics/index.html) module.
+
+`tracing`: Adds [tracing](https://docs.rs/tracing) spans around library loading, compatibility
+checking, plugin registration, and unloading. Spans carry the library path (`library`), plugin
+identifier (`plugin_id`), and plugin instance identifier (`instance_id`) as fields; the existing
+`log` output is unchanged.
+
+`regex`: Adds the plugin manager's
+[`get_matching_regex`](manager/struct.PluginManager.html#method.get_matching_regex) method, which
+returns the plugins whose identifiers match a [regex](https://docs.rs/regex) pattern.
+
+`dirs`: Adds methods to
+[`PluginManagerConfiguration`](config/struct.PluginManagerConfiguration.html) that locate the
+per-user and system configuration files of an application in the platform's standard directories,
+load and merge them, and save the user's changes; see the
+[configuration module](config/index.html#example---configuration-files).
+
+`watch`: Adds the [`watch`](watch/index.html) module, and the plugin manager's
+[`watch`](manager/struct.PluginManager.html#method.watch) method, which uses the
+[notify](https://docs.rs/notify) crate to reload, load, and unload plugin libraries as they are
+rebuilt, added, and removed.
+
+`verify`: Adds the [`verify`](verify/index.html) module, and the plugin manager's
+[`set_signature_verifier`](manager/struct.PluginManager.html#method.set_signature_verifier)
+method, which checks the signature of each library before it is opened; on Windows and macOS the
+platform's code signing is checked by the provided verifier.
+
+`clap`: Adds the [`cli`](cli/index.html) module, which provides `--plugin`, `--plugin-dir`, and
+`--plugin-config` flags for [clap](https://docs.rs/clap) command lines and feeds their values into
+the plugin manager and configuration types. It also adds the [`scaffold`](scaffold/index.html)
+module, which generates the skeleton of a new plugin provider crate.
+
 */
 
 #![warn(
@@ -111,6 +153,49 @@ pub mod config;
 
 pub mod error;
 
+pub mod event;
+
 pub mod plugin;
 
+pub mod platform;
+
+pub mod index;
+
+pub mod inspect;
+
+pub mod intercept;
+
+pub mod manifest;
+
+#[cfg(feature = "bundle")]
+pub mod bundle;
+
+pub mod cache;
+
+pub mod check_cache;
+
+#[cfg(feature = "clap")]
+pub mod cli;
+
 pub mod manager;
+
+pub mod metrics;
+
+#[cfg(feature = "clap")]
+pub mod scaffold;
+
+pub mod source;
+
+pub mod state;
+
+pub mod status;
+
+pub mod testing;
+
+pub mod topology;
+
+#[cfg(feature = "verify")]
+pub mod verify;
+
+#[cfg(feature = "watch")]
+pub mod watch;
@@ -21,7 +21,7 @@ use std::sync::Arc;
 # #[derive(Debug)]
 # struct SoundEffectPlugin;
 # impl Plugin for SoundEffectPlugin {
-#     fn plugin_id(&self) -> &String {
+#     fn plugin_id(&self) -> &str {
 #         unimplemented!()
 #     }
 #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
@@ -30,10 +30,10 @@ use std::sync::Arc;
 # impl SoundEffectPlugin {
 #     pub fn play(&self) {}
 # }
-let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
 
 plugin_manager
-    .load_plugins_from("libsound_one.dylib".as_ref())
+    .load_plugins_from("libsound_one.dylib")
     .unwrap();
 
 let plugin: Arc<SoundEffectPlugin> = plugin_manager
@@ -47,18 +47,57 @@ plugin.play();
 
 */
 
-use crate::error::{Error, ErrorKind, Result};
+#[cfg(feature = "bundle")]
+use crate::bundle::BundleManifest;
+use crate::cache::{
+    canonical_path, close_when_released, is_orphaned, LibraryCache, LibraryKind, LoadedLibrary,
+    ShadowCopy,
+};
+use crate::check_cache::CheckCache;
+use crate::config::PluginManagerConfiguration;
+use crate::error::{Error, ErrorKind, OpenFailureReason, PluginLimit, Result};
+use crate::event::EventSink;
+use crate::index::PluginIndex;
+use crate::inspect::{
+    check_architecture, classify_open_failure, explain_load_failure, exported_symbols,
+    read_manifest,
+};
+use crate::intercept::{Hook, Interceptor, InterceptorChain};
+use crate::metrics::LoadMetrics;
+use crate::platform::Platform;
 use crate::plugin::{
-    compatibility_hash, CompatibilityFn, Plugin, PluginRegistrar, PluginRegistrationFn,
-    COMPATIBILITY_FN_NAME, PLUGIN_REGISTRATION_FN_NAME,
+    compatibility_hash, plugin_type_hash, stable_hash, Compatibility, CompatibilityFn,
+    CompatibilityInfoFn, CompatibilityInputsFn, HostApi, HostInfo, Parameters, Plugin,
+    PluginFactory, PluginRegistrar, PluginRegistrationFn, PluginType, ValueRegistration,
+    COMPATIBILITY_FN_NAME, COMPATIBILITY_INFO_FN_NAME, COMPATIBILITY_INPUTS_FN_NAME,
+    DEFAULT_REGISTRATION_FN_PREFIX, PLUGIN_REGISTRATION_FN_NAME, REGISTRATION_PROTOCOL_VERSION,
 };
+use crate::source::{PluginSource, SourceLocation};
+use crate::state::Blackboard;
+use crate::status::StatusReport;
+use crate::topology::Topology;
+#[cfg(feature = "verify")]
+use crate::verify::SignatureVerifier;
+#[cfg(feature = "watch")]
+use crate::watch::PluginWatcher;
+use arc_swap::{ArcSwap, Guard};
 use libloading::{Library, Symbol};
 use search_path::SearchPath;
-use std::collections::HashMap;
+use semver::{Version, VersionReq};
+#[cfg(feature = "config_serde")]
+use serde::Serialize;
+#[cfg(feature = "bundle")]
+use std::any::type_name;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::env;
-use std::ffi::OsString;
+use std::ffi::{CStr, CString};
+use std::fmt::{Debug, Display, Formatter};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -68,14 +107,333 @@ use std::sync::{Arc, RwLock};
 /// The plugin manager loads and unloads plugins from a library which is dynamically opened and
 /// closed as necessary.
 ///
+/// Loading and unloading take `&self`, so a plugin manager may be shared between threads with an
+/// `Arc` once configured. Operations that change the set of loaded plugins are serialized with
+/// respect to each other. Lookups such as `get`, `contains`, and `plugins` never block; they read
+/// an immutable snapshot of the registry, and each change publishes a new snapshot once complete,
+/// so plugins being loaded are not visible until their `on_load` methods have run. A library
+/// whose plugins are unloaded while a lookup is in progress is closed once the lookup completes.
+///
 #[derive(Debug)]
 pub struct PluginManager<T>
 where
     T: Plugin,
 {
     search_path: SearchPath,
-    registration_fn_name: Vec<u8>,
-    plugins: RwLock<HashMap<String, LoadedPlugin<T>>>,
+    dll_directories: Vec<PathBuf>,
+    check_architecture: bool,
+    auto_platform_names: bool,
+    shadow_copy_directory: Option<PathBuf>,
+    entry_point: EntryPoint,
+    plugin_type: Option<String>,
+    host_info: HostInfo,
+    host_compatibility: Compatibility,
+    host_api: HostApi,
+    event_sink: Option<Arc<dyn Any + Send + Sync>>,
+    blackboard: Blackboard,
+    library_cache: Arc<LibraryCache>,
+    check_cache: Option<Arc<CheckCache>>,
+    plugin_index: Option<PluginIndex>,
+    plugin_libraries: HashMap<String, PathBuf>,
+    duplicate_load_policy: DuplicateLoadPolicy,
+    required_capabilities: Vec<String>,
+    capability_policy: CapabilityPolicy,
+    max_plugins_per_library: Option<usize>,
+    max_plugins: Option<usize>,
+    max_libraries: Option<usize>,
+    id_normalization: IdNormalization,
+    reload_policy: ReloadPolicy,
+    drop_policy: DropPolicy,
+    drop_error_handler: Option<fn(&ShutdownReport)>,
+    progress_handler: Option<fn(ProgressEvent)>,
+    transition_handler: Option<fn(StateTransition)>,
+    cancellation_token: Option<CancellationToken>,
+    quarantine: Option<Mutex<HashMap<PathBuf, QuarantinedLibrary>>>,
+    #[cfg(feature = "verify")]
+    signature_verifier: Option<Arc<dyn SignatureVerifier>>,
+    interceptors: InterceptorChain<T>,
+    parent: Option<Arc<PluginManager<T>>>,
+    plugins: ArcSwap<Registry<T>>,
+    deferred_closes: Mutex<Vec<DeferredClose<T>>>,
+    states: Mutex<HashMap<String, PluginState>>,
+    groups: RwLock<HashMap<String, BTreeSet<String>>>,
+    values: RwLock<HashMap<(TypeId, String), RegisteredValue>>,
+    metrics: RwLock<LoadMetrics>,
+    next_sequence: AtomicU64,
+    updates: Mutex<()>,
+}
+
+///
+/// A diagnostic record describing a registered plugin, see
+/// [`PluginManager::plugin_info`](struct.PluginManager.html#method.plugin_info). The `Display`
+/// implementation formats the record as a single line.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluginInfo {
+    plugin_id: Arc<str>,
+    instance_id: InstanceId,
+    version: Option<Version>,
+    description: String,
+    library: PathBuf,
+    library_specifier: PathBuf,
+    registration_fn: String,
+    loaded_at: SystemTime,
+    library_references: usize,
+    enabled: bool,
+    paused: bool,
+    state: PluginState,
+}
+
+///
+/// Reported to the handler set with
+/// [`PluginManager::set_progress_handler`](struct.PluginManager.html#method.set_progress_handler)
+/// as each library in a bulk load is loaded. The `index` is the 1-based position of the library
+/// within the `count` libraries being loaded; the `Display` implementation formats the event for
+/// a progress message, for example `Loading libreverb.so (12/60)`.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// A library is about to be loaded.
+    LibraryStarted {
+        /// The library file name, as provided to the plugin manager.
+        library: PathBuf,
+        /// The position of the library within the batch.
+        index: usize,
+        /// The number of libraries in the batch.
+        count: usize,
+    },
+    /// A library has been loaded, or failed to load.
+    LibraryFinished {
+        /// The library file name, as provided to the plugin manager.
+        library: PathBuf,
+        /// The position of the library within the batch.
+        index: usize,
+        /// The number of libraries in the batch.
+        count: usize,
+        /// The time taken to load the library, and its plugins.
+        duration: Duration,
+        /// `true` if the library loaded without error, else `false`.
+        succeeded: bool,
+    },
+}
+
+///
+/// The lifecycle state of a plugin, see
+/// [`PluginManager::state_of`](struct.PluginManager.html#method.state_of).
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+pub enum PluginState {
+    /// The plugin is provided by a library known to the plugin manager, for example from a plugin
+    /// index or manifest, but has not been loaded.
+    Discovered,
+    /// The plugin's `on_load` method has been called, but the plugin is not active; either it is
+    /// being registered, or it is paused.
+    Loaded,
+    /// The plugin is registered, enabled, and not paused.
+    Active,
+    /// The plugin is registered, but has been disabled.
+    Disabled,
+    /// A lifecycle method of the plugin, or an interceptor wrapping it, reported an error. A
+    /// failed plugin may only be unloaded.
+    Failed,
+    /// The plugin has been unloaded.
+    Unloaded,
+}
+
+///
+/// Reported to the handler set with
+/// [`PluginManager::set_transition_handler`](struct.PluginManager.html#method.set_transition_handler)
+/// as a plugin moves between lifecycle states. The `Display` implementation formats the event as,
+/// for example, `delay: Active -> Disabled`.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateTransition {
+    plugin_id: String,
+    instance_id: InstanceId,
+    from: PluginState,
+    to: PluginState,
+}
+
+///
+/// A runtime identifier assigned to each plugin instance as it is registered, unique within the
+/// process. Unlike the plugin identifier it differs between plugin managers, and between loads of
+/// the same plugin, and so distinguishes instances of the same plugin; it is included in the
+/// manager's log lines. The `Display` implementation formats the identifier as, for example,
+/// `#42`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+pub struct InstanceId(u64);
+
+///
+/// An opaque handle to a library loaded by a plugin manager, returned by
+/// [`PluginManager::load_plugins_from`](struct.PluginManager.html#method.load_plugins_from). The
+/// handle identifies the library by the canonical path it was resolved to, and may be passed in
+/// place of a file name to the plugin manager's methods that take a library, such as
+/// [`unload_library`](struct.PluginManager.html#method.unload_library) and
+/// [`reload_library`](struct.PluginManager.html#method.reload_library); so the host need not keep
+/// the name the library was loaded with, which may have been resolved using the search path. The
+/// `Display` implementation formats the canonical path.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LibraryHandle(Arc<Path>);
+
+///
+/// A token, shared between threads, used to cancel a bulk load; see
+/// [`PluginManager::set_cancellation_token`](struct.PluginManager.html#method.set_cancellation_token).
+/// Clones of a token share the same state.
+///
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+///
+/// A library that failed to load and is skipped by later loads, see
+/// [`PluginManager::enable_quarantine`](struct.PluginManager.html#method.enable_quarantine).
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+pub struct QuarantinedLibrary {
+    library: PathBuf,
+    reason: String,
+}
+
+///
+/// Determines the behavior of the plugin manager when plugins fail to unload as it is dropped.
+/// In either case all plugins are unloaded, and any handler set with
+/// [`PluginManager::set_drop_error_handler`](struct.PluginManager.html#method.set_drop_error_handler)
+/// is called with the errors, first.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Log each error and continue, this is the default.
+    #[default]
+    Log,
+    /// Log each error and then abort the process.
+    Abort,
+}
+
+///
+/// The outcome of [`PluginManager::shutdown`](struct.PluginManager.html#method.shutdown), listing
+/// each plugin in the order in which it was unloaded.
+///
+#[derive(Debug, Default)]
+pub struct ShutdownReport {
+    unloaded: Vec<String>,
+    failed: Vec<(String, Error)>,
+    timed_out: Vec<String>,
+}
+
+///
+/// Determines the behavior of the plugin manager when asked to load a library that it has already
+/// loaded; libraries are compared by their canonical path.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateLoadPolicy {
+    /// Loading an already loaded library does nothing, this is the default.
+    #[default]
+    Ignore,
+    /// Loading an already loaded library returns a
+    /// [`LibraryAlreadyLoaded`](../error/enum.ErrorKind.html#variant.LibraryAlreadyLoaded) error.
+    Error,
+    /// Loading an already loaded library registers its plugins again, replacing those with the
+    /// same identifier.
+    Replace,
+}
+
+///
+/// Determines the behavior of the plugin manager when a library registers a plugin that does not
+/// declare all of the manager's required capabilities, see
+/// [`PluginManager::set_required_capabilities`](struct.PluginManager.html#method.set_required_capabilities).
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CapabilityPolicy {
+    /// Loading the library fails with a
+    /// [`MissingCapabilities`](../error/enum.ErrorKind.html#variant.MissingCapabilities) error,
+    /// and none of its plugins are loaded; this is the default.
+    #[default]
+    Reject,
+    /// Plugins that lack a required capability are skipped, with a warning, and the remaining
+    /// plugins from the library are loaded.
+    Skip,
+}
+
+///
+/// Determines whether lookups, such as [`PluginManager::get`](struct.PluginManager.html#method.get),
+/// return the registered instance of a plugin, or a new instance created by the factory the plugin
+/// was registered with, see
+/// [`PluginRegistrar::register_factory`](../plugin/struct.PluginRegistrar.html#method.register_factory).
+/// The instancing of a plugin may be changed by the host with
+/// [`PluginManager::set_instancing`](struct.PluginManager.html#method.set_instancing).
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Instancing {
+    /// Every lookup returns the registered instance of the plugin; this is the default for
+    /// plugins registered without a factory.
+    #[default]
+    Shared,
+    /// Every lookup returns a new instance created by the plugin's factory; this is the default
+    /// for plugins registered with a factory.
+    PerUse,
+}
+
+///
+/// Determines how the plugin identifiers passed to lookups, such as
+/// [`PluginManager::get`](struct.PluginManager.html#method.get), are matched against the
+/// identifiers of registered plugins, see
+/// [`PluginManager::set_id_normalization`](struct.PluginManager.html#method.set_id_normalization).
+/// The default matches identifiers exactly.
+///
+/// An identifier is first replaced by the identifier it is an alias for, if any, and then
+/// compared with each registered identifier after trimming whitespace and folding case, if these
+/// are enabled. An identifier that matches a registered identifier exactly is always used as-is.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdNormalization {
+    case_insensitive: bool,
+    trim_whitespace: bool,
+    aliases: HashMap<String, String>,
+}
+
+///
+/// Determines how libraries are reloaded, by
+/// [`PluginManager::reload_libraries`](struct.PluginManager.html#method.reload_libraries) and by
+/// a watcher, see [`PluginManager::set_reload_policy`](struct.PluginManager.html#method.set_reload_policy).
+/// The default debounces changes for 500 milliseconds, reloads each library separately, and has
+/// a watcher load added, and unload removed, libraries.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct ReloadPolicy {
+    debounce: Duration,
+    batch: bool,
+    synchronize: bool,
+    quiesce_handler: Option<fn(&[PathBuf])>,
+    resume_handler: Option<fn(&[PathBuf])>,
+}
+
+///
+/// Determines how the value of an environment variable is interpreted by
+/// [`PluginManager::load_all_plugins_from_env_with`](struct.PluginManager.html#method.load_all_plugins_from_env_with).
+/// The default treats each entry as a library, and loads nothing if the variable is not set.
+///
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnvLoadPolicy {
+    scan_directories: bool,
+    required: bool,
+}
+
+///
+/// A view of the plugins registered in a plugin manager, returned by
+/// [`PluginManager::read`](struct.PluginManager.html#method.read), which borrows the plugins
+/// rather than returning a new reference to each. The view does not include plugins inherited
+/// from a parent plugin manager.
+///
+pub struct ReadGuard<'a, T>
+where
+    T: Plugin,
+{
+    plugin_manager: &'a PluginManager<T>,
+    registry: Guard<Arc<Registry<T>>>,
 }
 
 #[cfg(target_os = "macos")]
@@ -102,30 +460,202 @@ pub const PLATFORM_DYLIB_PREFIX: &str = "lib";
 // Private Types
 // ------------------------------------------------------------------------------------------------
 
-#[derive(Clone, Debug)]
+///
+/// The registration function(s) called when loading a library, or `Source` for a location loaded
+/// from a plugin source.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum EntryPoint {
+    Function(CString),
+    Prefix(String),
+    Source,
+}
+
+#[derive(Debug)]
 struct LoadedPlugin<T>
 where
     T: Plugin,
 {
     plugin: Arc<T>,
+    plugin_id: Arc<str>,
+    instance_id: InstanceId,
+    factory: Option<PluginFactory<T>>,
+    instancing: Instancing,
+    instances: Arc<Instances<T>>,
+    version: Option<Version>,
+    in_library: Arc<LoadedLibrary>,
+    library_specifier: Arc<Path>,
+    entry_point: Arc<EntryPoint>,
+    registration_fn: Arc<str>,
+    loaded_at: SystemTime,
+    sequence: u64,
+    enabled: bool,
+    paused: bool,
+    failed: bool,
+}
+
+///
+/// A value registered by a library's registration function, see
+/// [`PluginRegistrar::register_value`](../plugin/struct.PluginRegistrar.html#method.register_value).
+/// The value is declared, and so dropped, first as its drop code may be in the library.
+///
+#[derive(Debug)]
+struct RegisteredValue {
+    value: Arc<dyn Any + Send + Sync>,
     in_library: Arc<LoadedLibrary>,
 }
 
+///
+/// A plugin returned by a registration function, or a plugin source, that has not yet been
+/// loaded.
+///
+#[derive(Debug)]
+struct RegisteredPlugin<T>
+where
+    T: Plugin,
+{
+    plugin: Arc<T>,
+    instance_id: InstanceId,
+    factory: Option<PluginFactory<T>>,
+    registration_fn: Arc<str>,
+    priority: i32,
+}
+
+///
+/// A library opened, and its plugins registered, but not yet loaded.
+///
 #[derive(Debug)]
-struct LoadedLibrary {
+struct PreparedLibrary<T>
+where
+    T: Plugin,
+{
     file_name: PathBuf,
-    library: Library,
+    library_specifier: PathBuf,
+    entry_point: EntryPoint,
+    library: Arc<LoadedLibrary>,
+    plugins: Vec<RegisteredPlugin<T>>,
+    values: Vec<ValueRegistration>,
+    duration: Duration,
+}
+
+///
+/// A library in a batch once it has been prepared; `prepared` is `None` if the library need not
+/// be loaded, or is an error if it is quarantined.
+///
+#[derive(Debug)]
+struct BatchEntry<T>
+where
+    T: Plugin,
+{
+    index: usize,
+    library: PathBuf,
+    prepared: Result<Option<PreparedLibrary<T>>>,
+}
+
+///
+/// The instances of a plugin created by its factory which, like the registered instance, keep
+/// the plugin's library open while they are referenced.
+///
+#[derive(Debug)]
+struct Instances<T>(Mutex<Vec<Weak<T>>>)
+where
+    T: Plugin;
+
+///
+/// A library whose plugin was unloaded while the host still held references to it, or which
+/// was deactivated; the library is released once the last reference is dropped, and, if `held`,
+/// [`PluginManager::close_idle_libraries`] is called.
+///
+#[derive(Debug)]
+struct DeferredClose<T>
+where
+    T: Plugin,
+{
+    plugin: Weak<T>,
+    instances: Arc<Instances<T>>,
+    library: Arc<LoadedLibrary>,
+    held: bool,
+}
+
+///
+/// The members of a plugin group; the plugins that declare the group, and those the host added
+/// to it by identifier.
+///
+#[derive(Debug)]
+struct GroupMembers {
+    group: String,
+    added: BTreeSet<String>,
+}
+
+///
+/// The registered plugins, keyed by plugin identifier and then by version. A plugin that does not
+/// report a version is keyed by `None`, which orders before all versions. The aliases declared by
+/// registered plugins are kept alongside, keyed by alias.
+///
+/// The plugins are divided into shards by a hash of their identifier, each shared between copies
+/// of the registry until it is changed, so that an update copies only the shards it changes
+/// rather than every registered plugin. Within a shard the versions of each plugin are shared in
+/// the same way, so that copying a shard copies only the versions of the plugins changed.
+///
+/// The number of plugins registered from each library is kept, keyed by the library's canonical
+/// path, so that checking whether a library is loaded does not visit every registered plugin.
+///
+/// Identifiers are interned; every version of a plugin, and every alias for it, shares the
+/// identifier held as the plugin's key, so that looking up, listing, and removing plugins does not
+/// copy identifiers.
+///
+#[derive(Debug)]
+struct Registry<T>
+where
+    T: Plugin,
+{
+    shards: Vec<Arc<Shard<T>>>,
+    aliases: Arc<HashMap<String, Arc<str>>>,
+    libraries: Arc<HashMap<Arc<Path>, usize>>,
+    len: usize,
 }
 
+type Shard<T> = HashMap<Arc<str>, Arc<Versions<T>>>;
+
+type Versions<T> = BTreeMap<Option<Version>, LoadedPlugin<T>>;
+
+///
+/// The identifier and version of a plugin whose state was saved before a reload or swap.
+///
+type StateKey = (String, Option<String>);
+
+const REGISTRY_SHARDS: usize = 32;
+
 // ------------------------------------------------------------------------------------------------
 // Public Functions
 // ------------------------------------------------------------------------------------------------
 
+///
+/// Return the directory within `directory` that holds the plugin libraries for the running
+/// platform. A tree holding the plugins for several platforms may have a subdirectory named for
+/// each target triple, such as `plugins/x86_64-unknown-linux-gnu`, or for each platform, such as
+/// `plugins/linux` (see [`Platform`](../platform/enum.Platform.html) for platform names). The
+/// target triple subdirectory is preferred, then the platform subdirectory; if neither exists
+/// `directory` itself is returned.
+///
+pub fn platform_plugin_directory(directory: &Path) -> PathBuf {
+    Platform::current_target_triple()
+        .map(|target| directory.join(target))
+        .into_iter()
+        .chain(std::iter::once(
+            directory.join(Platform::current().to_string()),
+        ))
+        .find(|subdirectory| subdirectory.is_dir())
+        .unwrap_or_else(|| directory.to_path_buf())
+}
+
 ///
 /// Given a file name, or path with a file name, return a new path that formats the file name
-/// according to common platform conventions. `PluginManager` does not use this function directly,
-/// it is up to the client to determine whether to use this before passing a file path to the
-/// manager.
+/// according to common platform conventions. `PluginManager` only applies this to library names
+/// without an extension, and only when
+/// [`set_auto_platform_names`](struct.PluginManager.html#method.set_auto_platform_names) is
+/// enabled; otherwise it is up to the client to determine whether to use this before passing a
+/// file path to the manager.
 ///
 /// # Example
 ///
@@ -148,360 +678,5451 @@ struct LoadedLibrary {
 /// ```
 ///
 pub fn make_platform_dylib_name(file_path: &Path) -> PathBuf {
-    if let Some(file_stem) = file_path.file_stem() {
-        let file_name = if !PLATFORM_DYLIB_PREFIX.is_empty() {
-            let mut prefixed = OsString::from(PLATFORM_DYLIB_PREFIX);
-            prefixed.push(file_stem);
-            prefixed
-        } else {
-            file_stem.to_os_string()
-        };
-        let mut file_path = file_path.to_path_buf();
-        file_path.set_file_name(file_name);
-        let _ = file_path.set_extension(PLATFORM_DYLIB_EXTENSION);
-        file_path
-    } else {
-        file_path.to_path_buf()
-    }
+    Platform::current().make_dylib_name(file_path)
+}
+
+///
+/// As [`make_platform_dylib_name`](fn.make_platform_dylib_name.html), but format the file name
+/// according to the conventions of the provided platform rather than the current one. This is
+/// useful for build tools and deployment code that prepare libraries for another target.
+///
+/// # Example
+///
+/// ```rust
+/// use dygpi::manager::make_dylib_name_for;
+/// use dygpi::platform::Platform;
+///
+/// let platform = Platform::from_target_triple("aarch64-apple-darwin").unwrap();
+/// let dylib_name = make_dylib_name_for("plugins".as_ref(), platform);
+/// assert_eq!(dylib_name.to_str(), Some("libplugins.dylib"));
+/// ```
+///
+pub fn make_dylib_name_for(file_path: &Path, platform: Platform) -> PathBuf {
+    platform.make_dylib_name(file_path)
+}
+
+///
+/// The inverse of [`make_platform_dylib_name`](fn.make_platform_dylib_name.html); given a file
+/// name, or path with a file name, return the library name without the platform prefix and
+/// extension. This function returns `None` if the file name does not follow the conventions of
+/// the current platform.
+///
+/// # Example
+///
+/// ```rust
+/// use dygpi::manager::{make_platform_dylib_name, parse_dylib_name};
+///
+/// let dylib_name = make_platform_dylib_name("plugins/aplugin".as_ref());
+/// assert_eq!(parse_dylib_name(&dylib_name), Some("aplugin".to_string()));
+/// ```
+///
+pub fn parse_dylib_name(file_path: &Path) -> Option<String> {
+    Platform::current().parse_dylib_name(file_path)
 }
 
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
-const UTF8_STRING_PANIC: &str = "Invalid UTF8 symbol name when converting to string";
-
-// ------------------------------------------------------------------------------------------------
+impl Default for EntryPoint {
+    fn default() -> Self {
+        EntryPoint::Function(PLUGIN_REGISTRATION_FN_NAME.to_owned())
+    }
+}
 
-impl<T> Default for PluginManager<T>
+impl<T> Clone for LoadedPlugin<T>
 where
     T: Plugin,
 {
-    fn default() -> Self {
+    fn clone(&self) -> Self {
         Self {
-            search_path: Default::default(),
-            registration_fn_name: PLUGIN_REGISTRATION_FN_NAME.to_vec(),
-            plugins: Default::default(),
+            plugin: self.plugin.clone(),
+            plugin_id: self.plugin_id.clone(),
+            instance_id: self.instance_id,
+            factory: self.factory.clone(),
+            instancing: self.instancing,
+            instances: self.instances.clone(),
+            version: self.version.clone(),
+            in_library: self.in_library.clone(),
+            library_specifier: self.library_specifier.clone(),
+            entry_point: self.entry_point.clone(),
+            registration_fn: self.registration_fn.clone(),
+            loaded_at: self.loaded_at,
+            sequence: self.sequence,
+            enabled: self.enabled,
+            paused: self.paused,
+            failed: self.failed,
         }
     }
 }
 
-impl<T> Drop for PluginManager<T>
+impl<T> LoadedPlugin<T>
 where
     T: Plugin,
 {
-    fn drop(&mut self) {
-        info!("PluginManager::drop()");
-        self.unload_all().unwrap();
+    fn key(&self) -> (Arc<str>, Option<Version>) {
+        (self.plugin_id.clone(), self.version.clone())
     }
-}
 
-impl<T> PluginManager<T>
-where
-    T: Plugin,
-{
-    ///
-    /// Construct a new plugin manager and have it use the values of the string slice
-    /// as a search path when loading libraries.
-    ///
-    pub fn new_with_search_path(search_path: SearchPath) -> Self {
-        Self {
-            search_path,
-            registration_fn_name: PLUGIN_REGISTRATION_FN_NAME.to_vec(),
-            plugins: Default::default(),
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            plugin_id: self.plugin_id.clone(),
+            instance_id: self.instance_id,
+            version: self.version.clone(),
+            description: self.plugin.plugin_description().to_string(),
+            library: self.in_library.canonical_name.clone(),
+            library_specifier: self.library_specifier.to_path_buf(),
+            registration_fn: self.registration_fn.to_string(),
+            loaded_at: self.loaded_at,
+            library_references: Arc::strong_count(&self.in_library),
+            enabled: self.enabled,
+            paused: self.paused,
+            state: self.state(),
         }
     }
 
-    ///
-    /// Load all plugins from the libraries that are specified in the named environment variable.
-    ///
-    /// The environment variable's value is assumed to be a list of paths separated by the colon,
-    /// `':'` character.
-    ///
-    pub fn load_all_plugins_from_env(&mut self, env_var: &str) -> Result<()> {
-        info!("PluginManager::load_all_plugins_from_env({:?})", env_var);
-        if let Ok(env_value) = env::var(env_var) {
-            for file_name in env_value.split(":") {
-                self.load_plugins_from(&PathBuf::from(file_name))?;
-            }
+    fn state(&self) -> PluginState {
+        if self.failed {
+            PluginState::Failed
+        } else if !self.enabled {
+            PluginState::Disabled
+        } else if self.paused {
+            PluginState::Loaded
         } else {
-            warn!("Failed to find environment variable '{}'", env_var);
+            PluginState::Active
         }
-        Ok(())
     }
 
     ///
-    /// Load all plugins from the libraries specified in the string slice, each value is a file path.
+    /// Return the registered instance, or a new instance if the plugin is instanced per use.
     ///
-    pub fn load_plugins_from_all(&mut self, file_names: &[&Path]) -> Result<()> {
-        info!("PluginManager::load_all_plugins_from({:?})", file_names);
-        for file_name in file_names {
-            self.load_plugins_from(file_name)?;
+    fn instance(&self) -> Arc<T> {
+        match (self.instancing, &self.factory) {
+            (Instancing::PerUse, Some(PluginFactory::PerUse(factory))) => {
+                let instance = Arc::new(factory());
+                self.instances.add(&instance);
+                instance
+            }
+            _ => self.plugin.clone(),
         }
-        Ok(())
     }
 
-    ///
-    /// Load all plugins from a single library with the provided file name/path.
-    ///
-    #[allow(unsafe_code)]
-    pub fn load_plugins_from(&mut self, file_name: &Path) -> Result<()> {
-        info!("PluginManager::load_plugins_from({:?})", file_name);
+    fn instantiate(&self, params: &Parameters<'_>) -> Result<Arc<T>> {
+        match &self.factory {
+            Some(PluginFactory::Parameterized(factory)) => {
+                let instance = Arc::new(factory(params).map_err(Error::detach)?);
+                self.instances.add(&instance);
+                Ok(instance)
+            }
+            _ => Err(Error::not_instantiable(&self.plugin_id)),
+        }
+    }
+}
 
-        let file_name = if (file_name.is_absolute() || file_name.parent().is_some())
-            && !self.search_path.is_empty()
-        {
-            self.find_library(file_name)
-        } else {
-            file_name.to_path_buf()
-        };
+// ------------------------------------------------------------------------------------------------
 
-        trace!("PluginManager::load_plugins_from() > opening library");
-        let library = unsafe {
-            Library::new(&file_name).map_err(|e| {
-                Error::from(ErrorKind::LibraryOpenFailed(
-                    file_name.to_string_lossy().to_string(),
-                    Box::new(e),
-                ))
-            })?
-        };
+impl<T> Default for Instances<T>
+where
+    T: Plugin,
+{
+    fn default() -> Self {
+        Self(Mutex::default())
+    }
+}
 
-        let loaded_library = LoadedLibrary { file_name, library };
+impl<T> Instances<T>
+where
+    T: Plugin,
+{
+    fn add(&self, instance: &Arc<T>) {
+        let mut instances = self.0.lock().unwrap();
+        instances.retain(|instance| instance.strong_count() > 0);
+        instances.push(Arc::downgrade(instance));
+    }
 
-        trace!("PluginManager::load_plugins_from() > checking compatibility");
-        self.check_compatibility(&loaded_library)?;
+    fn is_referenced(&self) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|instance| instance.strong_count() > 0)
+    }
+}
 
-        trace!("PluginManager::load_plugins_from() > registering the plugins");
-        self.register_plugins(loaded_library)?;
+// ------------------------------------------------------------------------------------------------
 
-        Ok(())
+impl<T> DeferredClose<T>
+where
+    T: Plugin,
+{
+    fn is_referenced(&self) -> bool {
+        self.plugin.strong_count() > 0 || self.instances.is_referenced()
     }
 
-    ///
-    /// Override the default registration function name
-    /// [`PLUGIN_REGISTRATION_FN_NAME`](../plugin/const.PLUGIN_REGISTRATION_FN_NAME.html).
-    ///
-    /// This function **must** conform to the type
-    /// [`PluginRegistrationFn`](../plugin/function.PluginRegistrationFn.html), and must be marked
-    /// as `#[no_mangle] pub extern "C"` in the same manner as the standard registration function.
-    ///
-    /// # Example
-    ///
-    /// ```rust
-    /// use dygpi::plugin::{Plugin, PluginRegistrar};
-    /// # #[derive(Debug)]
-    /// # struct SoundSourcePlugin;
-    /// # impl Plugin for SoundSourcePlugin {
-    /// #     fn plugin_id(&self) -> &String {
-    /// #         unimplemented!()
-    /// #     }
-    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
-    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
-    /// # }
-    /// # impl SoundSourcePlugin {
-    /// #     pub fn new(id: &str) -> Self { Self {} }
-    /// # }
-    /// # #[derive(Debug)]
-    /// # struct SoundEffectPlugin;
+    fn close_when_released(self) {
+        let DeferredClose {
+            plugin,
+            instances,
+            library,
+            ..
+        } = self;
+        close_when_released(library, move || {
+            plugin.strong_count() > 0 || instances.is_referenced()
+        });
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl GroupMembers {
+    fn contains<T>(&self, plugin: &LoadedPlugin<T>) -> bool
+    where
+        T: Plugin,
+    {
+        self.added.contains(&*plugin.plugin_id)
+            || plugin.plugin.plugin_groups().contains(&self.group.as_str())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Default for Registry<T>
+where
+    T: Plugin,
+{
+    fn default() -> Self {
+        Self {
+            shards: (0..REGISTRY_SHARDS).map(|_| Default::default()).collect(),
+            aliases: Default::default(),
+            libraries: Default::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> Clone for Registry<T>
+where
+    T: Plugin,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shards: self.shards.clone(),
+            aliases: self.aliases.clone(),
+            libraries: self.libraries.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl Default for ReloadPolicy {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(500),
+            batch: false,
+            synchronize: true,
+            quiesce_handler: None,
+            resume_handler: None,
+        }
+    }
+}
+
+impl ReloadPolicy {
+    ///
+    /// Set the period for which a watcher waits, once a change is seen, for further changes
+    /// before acting, so that a library is not reloaded while it is still being written and a
+    /// series of rebuilds causes a single reload.
+    ///
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce
+    }
+
+    /// The period for which changes are debounced.
+    pub fn debounce(&self) -> Duration {
+        self.debounce
+    }
+
+    ///
+    /// Set whether libraries reloaded together are reloaded as a single batch; the plugin
+    /// manager's other loads and unloads wait until the whole batch is complete, and the quiesce
+    /// and resume handlers are called once for the batch rather than once for each library. A
+    /// batch is not rolled back; if a library fails to reload those before it remain reloaded,
+    /// those after it are not reloaded, and the resume handler is still called. The default is
+    /// `false`.
+    ///
+    pub fn set_batch(&mut self, batch: bool) {
+        self.batch = batch
+    }
+
+    /// Returns `true` if libraries reloaded together are reloaded as a single batch, else `false`.
+    pub fn is_batch(&self) -> bool {
+        self.batch
+    }
+
+    ///
+    /// Set whether a watcher loads libraries that are added, and unloads libraries that are
+    /// removed, as well as reloading libraries that change. The default is `true`.
+    ///
+    pub fn set_synchronize(&mut self, synchronize: bool) {
+        self.synchronize = synchronize
+    }
+
+    /// Returns `true` if a watcher loads added, and unloads removed, libraries, else `false`.
+    pub fn is_synchronize(&self) -> bool {
+        self.synchronize
+    }
+
+    ///
+    /// Set a handler called with the libraries about to be reloaded, before any of their plugins
+    /// are unloaded; for example to stop, and wait for, threads calling those plugins.
+    ///
+    pub fn set_quiesce_handler(&mut self, handler: fn(&[PathBuf])) {
+        self.quiesce_handler = Some(handler)
+    }
+
+    ///
+    /// Set a handler called with the libraries reloaded, once the reload is complete, or has
+    /// failed; for example to restart the threads stopped by the quiesce handler.
+    ///
+    pub fn set_resume_handler(&mut self, handler: fn(&[PathBuf])) {
+        self.resume_handler = Some(handler)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl EnvLoadPolicy {
+    ///
+    /// Set whether entries that name a directory are scanned for libraries, as for
+    /// [`PluginManager::load_plugins_from_dir`](struct.PluginManager.html#method.load_plugins_from_dir),
+    /// rather than loaded as a library. The default is `false`.
+    ///
+    pub fn set_scan_directories(&mut self, scan_directories: bool) {
+        self.scan_directories = scan_directories
+    }
+
+    /// Returns `true` if entries that name a directory are scanned for libraries, else `false`.
+    pub fn is_scan_directories(&self) -> bool {
+        self.scan_directories
+    }
+
+    ///
+    /// Set whether the environment variable must be set; if it is not, an
+    /// [`EnvVarNotSet`](../error/enum.ErrorKind.html#variant.EnvVarNotSet) error is returned
+    /// rather than a warning logged. The default is `false`.
+    ///
+    pub fn set_required(&mut self, required: bool) {
+        self.required = required
+    }
+
+    /// Returns `true` if the environment variable must be set, else `false`.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> BatchEntry<T>
+where
+    T: Plugin,
+{
+    ///
+    /// The priority of the library, the highest set by any of its registration functions.
+    ///
+    fn priority(&self) -> i32 {
+        match &self.prepared {
+            Ok(Some(prepared)) => prepared
+                .plugins
+                .iter()
+                .map(|registered| registered.priority)
+                .max()
+                .unwrap_or_default(),
+            _ => 0,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl IdNormalization {
+    ///
+    /// Set whether identifiers are compared ignoring case; the default is `false`.
+    ///
+    pub fn set_case_insensitive(&mut self, case_insensitive: bool) {
+        self.case_insensitive = case_insensitive
+    }
+
+    /// Returns `true` if identifiers are compared ignoring case, else `false`.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    ///
+    /// Set whether leading and trailing whitespace is ignored when comparing identifiers; the
+    /// default is `false`.
+    ///
+    pub fn set_trim_whitespace(&mut self, trim_whitespace: bool) {
+        self.trim_whitespace = trim_whitespace
+    }
+
+    /// Returns `true` if leading and trailing whitespace is ignored, else `false`.
+    pub fn trims_whitespace(&self) -> bool {
+        self.trim_whitespace
+    }
+
+    ///
+    /// Add `alias` as an alternate identifier for the plugin `plugin_id`; aliases are themselves
+    /// matched with the case and whitespace rules above.
+    ///
+    pub fn add_alias(&mut self, alias: &str, plugin_id: &str) {
+        let _ = self
+            .aliases
+            .insert(alias.to_string(), plugin_id.to_string());
+    }
+
+    ///
+    /// Return the identifier `alias` is an alternate for, if any.
+    ///
+    pub fn alias(&self, alias: &str) -> Option<&String> {
+        self.aliases.get(alias).or_else(|| {
+            let key = self.key(alias);
+            self.aliases
+                .iter()
+                .find(|(candidate, _)| self.key(candidate) == key)
+                .map(|(_, plugin_id)| plugin_id)
+        })
+    }
+
+    /// Returns `true` if identifiers are matched exactly, else `false`.
+    pub fn is_exact(&self) -> bool {
+        !self.case_insensitive && !self.trim_whitespace && self.aliases.is_empty()
+    }
+
+    fn key<'a>(&self, plugin_id: &'a str) -> Cow<'a, str> {
+        let plugin_id = if self.trim_whitespace {
+            plugin_id.trim()
+        } else {
+            plugin_id
+        };
+        if self.case_insensitive {
+            Cow::Owned(plugin_id.to_lowercase())
+        } else {
+            Cow::Borrowed(plugin_id)
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Registry<T>
+where
+    T: Plugin,
+{
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn contains(&self, plugin_id: &str) -> bool {
+        self.shard(plugin_id).contains_key(plugin_id)
+    }
+
+    /// Returns `true` if any plugin is registered from the library with the canonical path.
+    fn contains_library(&self, canonical_name: &Path) -> bool {
+        self.libraries.contains_key(canonical_name)
+    }
+
+    /// The number of libraries from which plugins are registered.
+    fn library_count(&self) -> usize {
+        self.libraries.len()
+    }
+
+    fn shard(&self, plugin_id: &str) -> &Shard<T> {
+        &self.shards[shard_index(plugin_id)]
+    }
+
+    fn shard_mut(&mut self, plugin_id: &str) -> &mut Shard<T> {
+        Arc::make_mut(&mut self.shards[shard_index(plugin_id)])
+    }
+
+    /// Each registered identifier, with the registered versions of the plugin.
+    fn entries(&self) -> impl Iterator<Item = (&Arc<str>, &Versions<T>)> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.iter())
+            .map(|(plugin_id, versions)| (plugin_id, versions.as_ref()))
+    }
+
+    ///
+    /// The registered identifier that `plugin_id` matches under `normalization`, or `plugin_id`
+    /// itself if it is registered or matches no registered identifier. If more than one
+    /// registered identifier matches, which is returned is unspecified.
+    ///
+    fn resolve<'a>(&'a self, normalization: &IdNormalization, plugin_id: &'a str) -> &'a str {
+        if self.contains(plugin_id) {
+            return plugin_id;
+        }
+        let target = normalization
+            .alias(plugin_id)
+            .map(String::as_str)
+            .unwrap_or(plugin_id);
+        if let Some((registered, _)) = self.shard(target).get_key_value(target) {
+            return registered;
+        }
+        if let Some(registered) = self.aliases.get(target) {
+            return registered;
+        }
+        if normalization.is_exact() {
+            return plugin_id;
+        }
+        let key = normalization.key(target);
+        self.entries()
+            .map(|(registered, _)| registered)
+            .find(|registered| normalization.key(registered) == key)
+            .or_else(|| {
+                self.aliases
+                    .iter()
+                    .find(|(alias, _)| normalization.key(alias) == key)
+                    .map(|(_, registered)| registered)
+            })
+            .map(|registered| registered.as_ref())
+            .unwrap_or(plugin_id)
+    }
+
+    ///
+    /// The registered versions of the plugin that `plugin_id` matches under `normalization`. A
+    /// registered identifier is found with a single lookup, only if it is not registered is the
+    /// identifier resolved.
+    ///
+    fn find(&self, normalization: &IdNormalization, plugin_id: &str) -> Option<&Versions<T>> {
+        if let Some(versions) = self.shard(plugin_id).get(plugin_id) {
+            return Some(versions);
+        }
+        let registered = self.resolve(normalization, plugin_id);
+        self.shard(registered)
+            .get(registered)
+            .map(|versions| versions.as_ref())
+    }
+
+    /// The highest version of the plugin that `plugin_id` matches under `normalization`.
+    fn latest(&self, normalization: &IdNormalization, plugin_id: &str) -> Option<&LoadedPlugin<T>> {
+        self.find(normalization, plugin_id)
+            .and_then(|versions| versions.values().next_back())
+    }
+
+    ///
+    /// The highest version of the plugin that `plugin_id` matches under `normalization` which
+    /// also matches `requirement`.
+    ///
+    fn matching(
+        &self,
+        normalization: &IdNormalization,
+        plugin_id: &str,
+        requirement: &VersionReq,
+    ) -> Option<&LoadedPlugin<T>> {
+        self.find(normalization, plugin_id).and_then(|versions| {
+            versions
+                .iter()
+                .rev()
+                .find(|(version, _)| version.as_ref().is_some_and(|v| requirement.matches(v)))
+                .map(|(_, plugin)| plugin)
+        })
+    }
+
+    fn values(&self) -> impl Iterator<Item = &LoadedPlugin<T>> {
+        self.entries().flat_map(|(_, versions)| versions.values())
+    }
+
+    fn values_mut(&mut self) -> impl Iterator<Item = &mut LoadedPlugin<T>> {
+        self.shards
+            .iter_mut()
+            .flat_map(|shard| Arc::make_mut(shard).values_mut())
+            .flat_map(|versions| Arc::make_mut(versions).values_mut())
+    }
+
+    fn versions_mut(
+        &mut self,
+        plugin_id: &str,
+    ) -> Option<impl Iterator<Item = &mut LoadedPlugin<T>>> {
+        if !self.contains(plugin_id) {
+            return None;
+        }
+        self.shard_mut(plugin_id)
+            .get_mut(plugin_id)
+            .map(|versions| Arc::make_mut(versions).values_mut())
+    }
+
+    ///
+    /// Insert the plugin, returning any plugin with the same identifier and version it replaced.
+    /// If other versions of the plugin are registered the plugin shares their identifier.
+    ///
+    fn insert(&mut self, mut plugin: LoadedPlugin<T>) -> Option<LoadedPlugin<T>> {
+        if let Some((registered, _)) = self
+            .shard(&plugin.plugin_id)
+            .get_key_value(&plugin.plugin_id)
+        {
+            plugin.plugin_id = registered.clone();
+        }
+        let plugin_id = plugin.plugin_id.clone();
+        for alias in plugin.plugin.plugin_aliases() {
+            match self.aliases.get(*alias) {
+                Some(existing) if *existing != plugin_id => warn!(
+                    "Plugin {:?} declares alias {:?}, replacing the alias for {:?}",
+                    plugin_id, alias, existing
+                ),
+                _ => {}
+            }
+            let _ = Arc::make_mut(&mut self.aliases).insert(alias.to_string(), plugin_id.clone());
+        }
+        self.add_library_plugin(&plugin.in_library.canonical_name);
+        let replaced = Arc::make_mut(self.shard_mut(&plugin_id).entry(plugin_id).or_default())
+            .insert(plugin.version.clone(), plugin);
+        match &replaced {
+            Some(replaced) => self.remove_library_plugin(&replaced.in_library.canonical_name),
+            None => self.len += 1,
+        }
+        replaced
+    }
+
+    fn remove(&mut self, plugin_id: &str, version: &Option<Version>) -> Option<LoadedPlugin<T>> {
+        if !self.contains(plugin_id) {
+            return None;
+        }
+        let shard = self.shard_mut(plugin_id);
+        let versions = Arc::make_mut(shard.get_mut(plugin_id)?);
+        let removed = versions.remove(version);
+        if versions.is_empty() {
+            let _ = shard.remove(plugin_id);
+            self.remove_aliases(plugin_id);
+        }
+        if let Some(removed) = &removed {
+            self.remove_library_plugin(&removed.in_library.canonical_name);
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Remove all versions of the plugin, in the reverse of the order they were registered.
+    fn remove_all(&mut self, plugin_id: &str) -> Vec<LoadedPlugin<T>> {
+        if !self.contains(plugin_id) {
+            return Vec::default();
+        }
+        let mut removed: Vec<LoadedPlugin<T>> = self
+            .shard_mut(plugin_id)
+            .remove(plugin_id)
+            .map(|versions| Arc::unwrap_or_clone(versions).into_values().collect())
+            .unwrap_or_default();
+        self.remove_aliases(plugin_id);
+        for plugin in &removed {
+            self.remove_library_plugin(&plugin.in_library.canonical_name);
+        }
+        self.len -= removed.len();
+        removed.sort_by_key(|p| std::cmp::Reverse(p.sequence));
+        removed
+    }
+
+    fn remove_aliases(&mut self, plugin_id: &str) {
+        if self
+            .aliases
+            .values()
+            .any(|registered| registered.as_ref() == plugin_id)
+        {
+            Arc::make_mut(&mut self.aliases)
+                .retain(|_, registered| registered.as_ref() != plugin_id);
+        }
+    }
+
+    fn add_library_plugin(&mut self, canonical_name: &Path) {
+        let libraries = Arc::make_mut(&mut self.libraries);
+        match libraries.get_mut(canonical_name) {
+            Some(count) => *count += 1,
+            None => {
+                let _ = libraries.insert(Arc::from(canonical_name), 1);
+            }
+        }
+    }
+
+    fn remove_library_plugin(&mut self, canonical_name: &Path) {
+        let libraries = Arc::make_mut(&mut self.libraries);
+        if let Some(count) = libraries.get_mut(canonical_name) {
+            *count -= 1;
+            if *count == 0 {
+                let _ = libraries.remove(canonical_name);
+            }
+        }
+    }
+
+    /// Remove all plugins registered from the library with the provided canonical path.
+    fn remove_library(&mut self, canonical_name: &Path) -> Vec<LoadedPlugin<T>> {
+        if !self.contains_library(canonical_name) {
+            return Vec::default();
+        }
+        let keys: Vec<(Arc<str>, Option<Version>)> = self
+            .values()
+            .filter(|p| p.in_library.canonical_name == canonical_name)
+            .map(LoadedPlugin::key)
+            .collect();
+        keys.iter()
+            .filter_map(|(plugin_id, version)| self.remove(plugin_id, version))
+            .collect()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for EntryPoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryPoint::Function(name) => write!(f, "{}", name.to_string_lossy()),
+            EntryPoint::Prefix(prefix) => write!(f, "{}*", prefix),
+            EntryPoint::Source => write!(f, "source"),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PluginInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let loaded_at = self
+            .loaded_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        write!(f, "{}", self.plugin_id)?;
+        if let Some(version) = &self.version {
+            write!(f, "@{}", version)?;
+        }
+        write!(
+            f,
+            " library={:?} registration_fn={} loaded_at={}.{:03} library_references={} enabled={} paused={} instance={}",
+            self.library,
+            self.registration_fn,
+            loaded_at.as_secs(),
+            loaded_at.subsec_millis(),
+            self.library_references,
+            self.enabled,
+            self.paused,
+            self.instance_id
+        )
+    }
+}
+
+impl PluginInfo {
+    /// The plugin identifier.
+    pub fn plugin_id(&self) -> &str {
+        &self.plugin_id
+    }
+
+    /// The runtime identifier of this instance of the plugin.
+    pub fn instance_id(&self) -> InstanceId {
+        self.instance_id
+    }
+
+    /// The version of the plugin, if it reports one.
+    pub fn version(&self) -> Option<&Version> {
+        self.version.as_ref()
+    }
+
+    /// The description of the plugin, empty if it does not provide one.
+    pub fn description(&self) -> &String {
+        &self.description
+    }
+
+    ///
+    /// The canonical path of the library the plugin was loaded from; the file the library was
+    /// resolved to, using the search path if necessary, which identifies the library within the
+    /// plugin manager.
+    ///
+    pub fn library(&self) -> &PathBuf {
+        &self.library
+    }
+
+    ///
+    /// The file name, or path, of the library the plugin was loaded from as provided to the
+    /// plugin manager, before it was resolved to the canonical path returned by
+    /// [`library`](#method.library).
+    ///
+    pub fn library_specifier(&self) -> &PathBuf {
+        &self.library_specifier
+    }
+
+    /// The name of the registration function that registered the plugin.
+    pub fn registration_fn(&self) -> &String {
+        &self.registration_fn
+    }
+
+    /// The time at which the plugin was loaded.
+    pub fn loaded_at(&self) -> SystemTime {
+        self.loaded_at
+    }
+
+    ///
+    /// The number of references to the plugin's library when this record was created; there is
+    /// one for each plugin registered from the library, in any plugin manager sharing the same
+    /// [`LibraryCache`](../cache/struct.LibraryCache.html).
+    ///
+    pub fn library_references(&self) -> usize {
+        self.library_references
+    }
+
+    /// Returns `true` if the plugin is enabled, else `false`.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns `true` if the plugin is paused, else `false`.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// The lifecycle state of the plugin, see [`PluginState`](enum.PluginState.html).
+    pub fn state(&self) -> PluginState {
+        self.state
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PluginState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PluginState::Discovered => "Discovered",
+                PluginState::Loaded => "Loaded",
+                PluginState::Active => "Active",
+                PluginState::Disabled => "Disabled",
+                PluginState::Failed => "Failed",
+                PluginState::Unloaded => "Unloaded",
+            }
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for StateTransition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.plugin_id, self.from, self.to)
+    }
+}
+
+impl StateTransition {
+    /// The identifier of the plugin changing state.
+    pub fn plugin_id(&self) -> &String {
+        &self.plugin_id
+    }
+
+    /// The runtime identifier of the plugin instance changing state.
+    pub fn instance_id(&self) -> InstanceId {
+        self.instance_id
+    }
+
+    /// The state of the plugin before the transition.
+    pub fn from(&self) -> PluginState {
+        self.from
+    }
+
+    /// The state of the plugin after the transition.
+    pub fn to(&self) -> PluginState {
+        self.to
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for InstanceId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+impl Display for LibraryHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+impl AsRef<Path> for LibraryHandle {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl LibraryHandle {
+    /// The canonical path of the library.
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl InstanceId {
+    ///
+    /// Allocate the next identifier; identifiers start at 1 and are never reused.
+    ///
+    fn next() -> Self {
+        static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// The identifier as an integer.
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for ProgressEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgressEvent::LibraryStarted {
+                library,
+                index,
+                count,
+            } => write!(f, "Loading {} ({}/{})", display_name(library), index, count),
+            ProgressEvent::LibraryFinished {
+                library,
+                index,
+                count,
+                duration,
+                succeeded,
+            } => write!(
+                f,
+                "{} {} ({}/{}) in {:?}",
+                if *succeeded {
+                    "Loaded"
+                } else {
+                    "Failed loading"
+                },
+                display_name(library),
+                index,
+                count,
+                duration
+            ),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl QuarantinedLibrary {
+    /// The canonical path of the library.
+    pub fn library(&self) -> &PathBuf {
+        &self.library
+    }
+
+    /// The error that caused the library to be quarantined.
+    pub fn reason(&self) -> &String {
+        &self.reason
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl CancellationToken {
+    ///
+    /// Request that any bulk load using this token stops before loading its next library.
+    ///
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst)
+    }
+
+    ///
+    /// Returns `true` if [`cancel`](#method.cancel) has been called, else `false`.
+    ///
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    ///
+    /// Clear a previous cancellation so that the token may be used for another bulk load.
+    ///
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Debug for ReadGuard<'_, T>
+where
+    T: Plugin,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadGuard")
+            .field("len", &self.registry.len())
+            .finish()
+    }
+}
+
+impl<T> ReadGuard<'_, T>
+where
+    T: Plugin,
+{
+    /// Returns `true` if the view contains no plugins, else `false`.
+    pub fn is_empty(&self) -> bool {
+        self.registry.is_empty()
+    }
+
+    /// The number of plugins in the view, including all versions of each plugin.
+    pub fn len(&self) -> usize {
+        self.registry.len()
+    }
+
+    ///
+    /// Returns `true` if the view contains a plugin with the provided plugin identifier, else
+    /// `false`.
+    ///
+    pub fn contains(&self, plugin_id: &str) -> bool {
+        self.registry
+            .find(&self.plugin_manager.id_normalization, plugin_id)
+            .is_some()
+    }
+
+    ///
+    /// Returns the plugin with the provided plugin identifier, if one exists, else `None`. If
+    /// more than one version of the plugin is registered the highest version is returned. Unlike
+    /// [`PluginManager::get`](struct.PluginManager.html#method.get) the registered instance is
+    /// returned, even if the plugin is instanced per use.
+    ///
+    pub fn get(&self, plugin_id: &str) -> Option<&Arc<T>> {
+        self.registry
+            .latest(&self.plugin_manager.id_normalization, plugin_id)
+            .map(|plugin| &plugin.plugin)
+    }
+
+    ///
+    /// Return an iterator over the plugins in the view, including all versions of each plugin.
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<T>> {
+        self.registry.values().map(|plugin| &plugin.plugin)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl ShutdownReport {
+    ///
+    /// Returns `true` if every plugin was unloaded without error, else `false`.
+    ///
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty() && self.timed_out.is_empty()
+    }
+
+    ///
+    /// The identifiers of the plugins that were unloaded without error.
+    ///
+    pub fn unloaded(&self) -> impl Iterator<Item = &String> {
+        self.unloaded.iter()
+    }
+
+    ///
+    /// The identifiers of the plugins that reported an error when unloaded, and the error. These
+    /// plugins have been removed from the plugin manager.
+    ///
+    pub fn failed(&self) -> impl Iterator<Item = &(String, Error)> {
+        self.failed.iter()
+    }
+
+    ///
+    /// The identifiers of the plugins removed without calling `on_unload` because the shutdown
+    /// timed out.
+    ///
+    pub fn timed_out(&self) -> impl Iterator<Item = &String> {
+        self.timed_out.iter()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Default for PluginManager<T>
+where
+    T: Plugin,
+{
+    fn default() -> Self {
+        Self {
+            search_path: Default::default(),
+            dll_directories: Default::default(),
+            check_architecture: true,
+            auto_platform_names: false,
+            shadow_copy_directory: None,
+            entry_point: Default::default(),
+            plugin_type: None,
+            host_info: Default::default(),
+            host_compatibility: Compatibility::host(),
+            host_api: Default::default(),
+            event_sink: None,
+            blackboard: Default::default(),
+            library_cache: LibraryCache::global(),
+            check_cache: None,
+            plugin_index: None,
+            plugin_libraries: Default::default(),
+            duplicate_load_policy: Default::default(),
+            required_capabilities: Default::default(),
+            capability_policy: Default::default(),
+            max_plugins_per_library: None,
+            max_plugins: None,
+            max_libraries: None,
+            id_normalization: Default::default(),
+            reload_policy: Default::default(),
+            drop_policy: Default::default(),
+            drop_error_handler: None,
+            progress_handler: None,
+            transition_handler: None,
+            cancellation_token: None,
+            quarantine: None,
+            #[cfg(feature = "verify")]
+            signature_verifier: None,
+            interceptors: Default::default(),
+            parent: None,
+            plugins: Default::default(),
+            deferred_closes: Default::default(),
+            states: Default::default(),
+            groups: Default::default(),
+            values: Default::default(),
+            metrics: Default::default(),
+            next_sequence: Default::default(),
+            updates: Default::default(),
+        }
+    }
+}
+
+impl<T> Drop for PluginManager<T>
+where
+    T: Plugin,
+{
+    fn drop(&mut self) {
+        info!("PluginManager::drop()");
+        let report = self.shutdown_until(None);
+        let _ = self.close_released_libraries(true);
+        for deferred in self.deferred_closes.get_mut().unwrap().drain(..) {
+            warn!(
+                "Plugin from library {:?} is still referenced, the library will be closed once it is released",
+                deferred.library.file_name
+            );
+            deferred.close_when_released();
+        }
+        if report.is_clean() {
+            return;
+        }
+        if let Some(handler) = self.drop_error_handler {
+            handler(&report);
+        }
+        for (plugin_id, e) in report.failed() {
+            error!("Error unloading plugin {:?} on drop; {}", plugin_id, e);
+        }
+        if self.drop_policy == DropPolicy::Abort {
+            error!("PluginManager::drop() > aborting after unload failures");
+            std::process::abort();
+        }
+    }
+}
+
+impl<T> PluginManager<T>
+where
+    T: Plugin,
+{
+    ///
+    /// Construct a new plugin manager for the plugin type marker `P`, with its plugin type
+    /// identifier set to `P::ID`, see [`set_plugin_type`](#method.set_plugin_type).
+    ///
+    pub fn new_for_type<P>() -> Self
+    where
+        P: PluginType<Plugin = T>,
+    {
+        let mut plugin_manager = Self::default();
+        plugin_manager.set_plugin_type(P::ID);
+        plugin_manager
+    }
+
+    ///
+    /// Construct a new plugin manager and have it use the values of the string slice
+    /// as a search path when loading libraries.
+    ///
+    pub fn new_with_search_path(search_path: SearchPath) -> Self {
+        Self {
+            search_path,
+            dll_directories: Default::default(),
+            check_architecture: true,
+            auto_platform_names: false,
+            shadow_copy_directory: None,
+            entry_point: Default::default(),
+            plugin_type: None,
+            host_info: Default::default(),
+            host_compatibility: Compatibility::host(),
+            host_api: Default::default(),
+            event_sink: None,
+            blackboard: Default::default(),
+            library_cache: LibraryCache::global(),
+            check_cache: None,
+            plugin_index: None,
+            plugin_libraries: Default::default(),
+            duplicate_load_policy: Default::default(),
+            required_capabilities: Default::default(),
+            capability_policy: Default::default(),
+            max_plugins_per_library: None,
+            max_plugins: None,
+            max_libraries: None,
+            id_normalization: Default::default(),
+            reload_policy: Default::default(),
+            drop_policy: Default::default(),
+            drop_error_handler: None,
+            progress_handler: None,
+            transition_handler: None,
+            cancellation_token: None,
+            quarantine: None,
+            #[cfg(feature = "verify")]
+            signature_verifier: None,
+            interceptors: Default::default(),
+            parent: None,
+            plugins: Default::default(),
+            deferred_closes: Default::default(),
+            states: Default::default(),
+            groups: Default::default(),
+            values: Default::default(),
+            metrics: Default::default(),
+            next_sequence: Default::default(),
+            updates: Default::default(),
+        }
+    }
+
+    ///
+    /// Construct a child of the plugin manager `parent`, which inherits the parent's plugins
+    /// read-only and loads additional plugins of its own; for example, a plugin manager per
+    /// document or session sharing a core set of plugins loaded once by the host.
+    ///
+    /// The child starts with a copy of the parent's configuration, and shares its library cache,
+    /// blackboard, and interceptors. Lookups, [`contains`](#method.contains), [`get`](#method.get),
+    /// [`get_version`](#method.get_version), [`plugins`](#method.plugins), and
+    /// [`plugin_info`](#method.plugin_info), find the child's own plugins first, and then those of
+    /// its parent. All other methods, including those that unload, enable, or pause plugins, and
+    /// [`len`](#method.len), apply only to the child's own plugins, which are unloaded when the
+    /// child is dropped; the parent's plugins are unaffected.
+    ///
+    pub fn new_child(parent: &Arc<PluginManager<T>>) -> Self {
+        info!("PluginManager::new_child()");
+        Self {
+            search_path: parent.search_path.clone(),
+            dll_directories: parent.dll_directories.clone(),
+            check_architecture: parent.check_architecture,
+            auto_platform_names: parent.auto_platform_names,
+            shadow_copy_directory: parent.shadow_copy_directory.clone(),
+            entry_point: parent.entry_point.clone(),
+            plugin_type: parent.plugin_type.clone(),
+            host_info: parent.host_info.clone(),
+            host_compatibility: parent.host_compatibility.clone(),
+            host_api: parent.host_api,
+            event_sink: parent.event_sink.clone(),
+            blackboard: parent.blackboard.clone(),
+            library_cache: parent.library_cache.clone(),
+            check_cache: parent.check_cache.clone(),
+            plugin_index: parent.plugin_index.clone(),
+            plugin_libraries: parent.plugin_libraries.clone(),
+            duplicate_load_policy: parent.duplicate_load_policy,
+            required_capabilities: parent.required_capabilities.clone(),
+            capability_policy: parent.capability_policy,
+            max_plugins_per_library: parent.max_plugins_per_library,
+            max_plugins: parent.max_plugins,
+            max_libraries: parent.max_libraries,
+            id_normalization: parent.id_normalization.clone(),
+            reload_policy: parent.reload_policy,
+            drop_policy: parent.drop_policy,
+            drop_error_handler: parent.drop_error_handler,
+            progress_handler: parent.progress_handler,
+            transition_handler: parent.transition_handler,
+            cancellation_token: parent.cancellation_token.clone(),
+            quarantine: parent.quarantine.as_ref().map(|_| Default::default()),
+            #[cfg(feature = "verify")]
+            signature_verifier: parent.signature_verifier.clone(),
+            interceptors: parent.interceptors.clone(),
+            parent: Some(parent.clone()),
+            plugins: Default::default(),
+            deferred_closes: Default::default(),
+            states: Default::default(),
+            groups: Default::default(),
+            values: Default::default(),
+            metrics: Default::default(),
+            next_sequence: Default::default(),
+            updates: Default::default(),
+        }
+    }
+
+    ///
+    /// Return the parent of this plugin manager, if it was constructed with
+    /// [`new_child`](#method.new_child).
+    ///
+    pub fn parent(&self) -> Option<&Arc<PluginManager<T>>> {
+        self.parent.as_ref()
+    }
+
+    ///
+    /// Construct a new plugin manager with a search path assembled from the provided environment
+    /// variables, in order, followed by the default paths. Each environment variable is split
+    /// using the platform path separator (see `std::env::split_paths`), and any variables that
+    /// are not set are skipped. Duplicate paths are removed, keeping the first occurrence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dygpi::manager::PluginManager;
+    /// # use dygpi::plugin::Plugin;
+    /// # #[derive(Debug)]
+    /// # struct SoundEffectPlugin;
+    /// # impl Plugin for SoundEffectPlugin {
+    /// #     fn plugin_id(&self) -> &str {
+    /// #         unimplemented!()
+    /// #     }
+    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// # }
+    ///
+    /// let plugin_manager: PluginManager<SoundEffectPlugin> =
+    ///     PluginManager::new_with_search_path_from_env(
+    ///         &["MYAPP_PLUGIN_PATH", "MYAPP_HOME"],
+    ///         &["/usr/local/lib/myapp".as_ref()],
+    ///     );
+    /// ```
+    ///
+    pub fn new_with_search_path_from_env(env_vars: &[&str], defaults: &[&Path]) -> Self {
+        let mut manager = Self::default();
+        for env_var in env_vars {
+            let _ = manager.add_search_path_from_env(env_var);
+        }
+        for path in defaults {
+            manager.search_path.append(path.to_path_buf());
+        }
+        manager.search_path.dedup();
+        manager
+    }
+
+    ///
+    /// Return the search path used by this plugin manager to resolve library file names.
+    ///
+    pub fn search_path(&self) -> &SearchPath {
+        &self.search_path
+    }
+
+    ///
+    /// Append a directory to the end of this plugin manager's search path, if it is not already
+    /// present. Libraries loaded after this call will consider the new directory.
+    ///
+    pub fn add_search_path(&mut self, path: &Path) {
+        info!("PluginManager::add_search_path({:?})", path);
+        let path = path.to_path_buf();
+        if !self.search_path.contains(&path) {
+            self.search_path.append(path);
+        }
+    }
+
+    ///
+    /// Insert a directory at the start of this plugin manager's search path, so that it is
+    /// searched before any existing directories. If the directory is already present it is
+    /// moved to the front.
+    ///
+    pub fn prepend_search_path(&mut self, path: &Path) {
+        info!("PluginManager::prepend_search_path({:?})", path);
+        let path = path.to_path_buf();
+        self.search_path.remove(&path);
+        self.search_path.prepend(path);
+    }
+
+    ///
+    /// Remove a directory from this plugin manager's search path. This method returns `true` if
+    /// the directory was present, else `false`. Note that this has no effect on libraries that
+    /// have already been loaded.
+    ///
+    pub fn remove_search_path(&mut self, path: &Path) -> bool {
+        info!("PluginManager::remove_search_path({:?})", path);
+        let path = path.to_path_buf();
+        if self.search_path.contains(&path) {
+            self.search_path.remove(&path);
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// Append the paths found in the named environment variable to this plugin manager's search
+    /// path. The value is split using the platform path separator (see `std::env::split_paths`),
+    /// and empty segments are ignored. This method returns `false` if the environment variable
+    /// was not set, else `true`.
+    ///
+    pub fn add_search_path_from_env(&mut self, env_var: &str) -> bool {
+        info!("PluginManager::add_search_path_from_env({:?})", env_var);
+        if let Some(env_value) = env::var_os(env_var) {
+            for path in env::split_paths(&env_value) {
+                if !path.as_os_str().is_empty() && !self.search_path.contains(&path) {
+                    self.search_path.append(path);
+                }
+            }
+            true
+        } else {
+            warn!("Failed to find environment variable '{}'", env_var);
+            false
+        }
+    }
+
+    ///
+    /// Load all plugins from the libraries that are specified in the named environment variable,
+    /// returning the number of libraries loaded; libraries already loaded are not counted.
+    ///
+    /// The value is split using the platform path separator, `':'`, or `';'` on Windows (see
+    /// `std::env::split_paths`), and empty segments are ignored. The libraries are loaded as for
+    /// [`load_plugins_from_all`](#method.load_plugins_from_all). If the environment variable is
+    /// not set a warning is logged and no libraries are loaded; to return an error instead see
+    /// [`EnvLoadPolicy::set_required`](struct.EnvLoadPolicy.html#method.set_required).
+    ///
+    pub fn load_all_plugins_from_env(&self, env_var: &str) -> Result<usize> {
+        self.load_all_plugins_from_env_with(env_var, &EnvLoadPolicy::default())
+    }
+
+    ///
+    /// Load all plugins from the libraries that are specified in the named environment variable,
+    /// as [`load_all_plugins_from_env`](#method.load_all_plugins_from_env), with the entries
+    /// interpreted according to `policy`. If the policy scans directories, the libraries in each
+    /// directory named are loaded in file name order, as for
+    /// [`load_plugins_from_dir`](#method.load_plugins_from_dir), in place of the entry.
+    ///
+    pub fn load_all_plugins_from_env_with(
+        &self,
+        env_var: &str,
+        policy: &EnvLoadPolicy,
+    ) -> Result<usize> {
+        info!(
+            "PluginManager::load_all_plugins_from_env_with({:?}, {:?})",
+            env_var, policy
+        );
+        let env_value = match env::var_os(env_var) {
+            Some(env_value) => env_value,
+            None if policy.is_required() => {
+                return Err(Error::env_var_not_set(env_var));
+            }
+            None => {
+                warn!("Failed to find environment variable '{}'", env_var);
+                return Ok(0);
+            }
+        };
+        let mut file_names: Vec<PathBuf> = Default::default();
+        for path in env::split_paths(&env_value).filter(|path| !path.as_os_str().is_empty()) {
+            if policy.is_scan_directories() && path.is_dir() {
+                trace!(
+                    "PluginManager::load_all_plugins_from_env_with() > scanning directory {:?}",
+                    path
+                );
+                file_names.extend(dylib_files_in(&platform_plugin_directory(&path))?);
+            } else {
+                file_names.push(path);
+            }
+        }
+
+        let _updates = self.updates.lock().unwrap();
+        self.load_batch(
+            file_names
+                .into_iter()
+                .map(|file_name| (file_name, self.entry_point.clone()))
+                .collect(),
+        )
+    }
+
+    ///
+    /// Load all plugins from the libraries specified in the slice, each value is a file path. Any
+    /// type that can be viewed as a `Path` may be used, for example `&[PathBuf]`, `&[&Path]`, or
+    /// `&[&str]`. Progress is reported to any handler set with
+    /// [`set_progress_handler`](#method.set_progress_handler).
+    ///
+    /// Every library is opened, and its plugins registered, before any plugins are loaded; the
+    /// libraries are then loaded in the order of their priority, see
+    /// [`PluginRegistrar::set_priority`](../plugin/struct.PluginRegistrar.html#method.set_priority).
+    ///
+    pub fn load_plugins_from_all<P>(&self, file_names: &[P]) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        info!(
+            "PluginManager::load_all_plugins_from({:?})",
+            file_names
+                .iter()
+                .map(|p| p.as_ref())
+                .collect::<Vec<&Path>>()
+        );
+        let _updates = self.updates.lock().unwrap();
+        self.load_batch(
+            file_names
+                .iter()
+                .map(|file_name| (file_name.as_ref().to_path_buf(), self.entry_point.clone()))
+                .collect(),
+        )
+        .map(|_| ())
+    }
+
+    ///
+    /// Load all plugins from a single library with the provided file name/path. If the file name
+    /// is relative, and the plugin manager has a search path, the search path is used to locate
+    /// the library first. File names are kept as platform strings throughout, so paths that are
+    /// not valid UTF-8 are supported.
+    ///
+    /// If the file name has no extension, for example `"sound"`, and no such file exists, the
+    /// library file names returned by
+    /// [`Platform::dylib_name_candidates`](../platform/enum.Platform.html#method.dylib_name_candidates)
+    /// are tried in turn, so `libsound.so`, `sound.so`, `libsound.dylib`, and `sound.dll`, with
+    /// the current platform's convention first; each name is looked for across the whole search
+    /// path before the next is tried.
+    ///
+    /// On macOS the file name may be a framework bundle, such as `"Sound.framework"`, in which
+    /// case the library within the bundle is loaded, see
+    /// [`Platform::framework_library`](../platform/enum.Platform.html#method.framework_library).
+    /// The file name may also start with `@executable_path` or `@loader_path`, both of which refer
+    /// to the directory of the running executable, or `@rpath`, which is tried against each
+    /// directory in the search path, then the executable's directory, then the `Frameworks`
+    /// directory alongside it, as in an application bundle.
+    ///
+    /// The resolved path is canonicalized, and if the library has already been loaded by this
+    /// plugin manager, using the same registration function, the
+    /// [`DuplicateLoadPolicy`](enum.DuplicateLoadPolicy.html) determines the result; by default
+    /// loading the same library again does nothing.
+    ///
+    /// Registration is all-or-nothing; the plugins from a library are added to the registry only
+    /// once every plugin's `on_load` method has succeeded. If any fails, the plugins already
+    /// loaded are unloaded (`on_unload`) in reverse order, the library is closed if it is not
+    /// otherwise in use, and the error is returned.
+    ///
+    /// On success a [`LibraryHandle`](struct.LibraryHandle.html) for the library is returned,
+    /// which may be kept by the host to later unload, or reload, the library.
+    ///
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "info", skip_all, fields(library = ?file_name.as_ref()))
+    )]
+    pub fn load_plugins_from<P>(&self, file_name: P) -> Result<LibraryHandle>
+    where
+        P: AsRef<Path>,
+    {
+        let file_name = file_name.as_ref();
+        info!("PluginManager::load_plugins_from({:?})", file_name);
+
+        let canonical_name = self.find_library(file_name);
+        let _updates = self.updates.lock().unwrap();
+        self.load_plugins_with(&canonical_name, file_name, &self.entry_point)?;
+        Ok(LibraryHandle(canonical_name.into()))
+    }
+
+    ///
+    /// Load all plugins from `location` in the provided plugin source, see the
+    /// [`source`](../source/index.html) module. The location is treated as a library, identified
+    /// by its path; the [`DuplicateLoadPolicy`](enum.DuplicateLoadPolicy.html) applies, and
+    /// registration is all-or-nothing, as for [`load_plugins_from`](#method.load_plugins_from).
+    ///
+    pub fn load_plugins_from_source<P>(
+        &self,
+        source: &Arc<dyn PluginSource<T>>,
+        location: P,
+    ) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let location = location.as_ref();
+        info!(
+            "PluginManager::load_plugins_from_source({:?}, {:?})",
+            source, location
+        );
+
+        let _updates = self.updates.lock().unwrap();
+        self.load_source_location(source, location)
+    }
+
+    ///
+    /// Load all plugins from every location listed by the provided plugin source, in the order
+    /// listed, stopping at the first location that fails to load.
+    ///
+    pub fn load_all_plugins_from_source(&self, source: &Arc<dyn PluginSource<T>>) -> Result<()> {
+        info!("PluginManager::load_all_plugins_from_source({:?})", source);
+
+        let _updates = self.updates.lock().unwrap();
+        for location in source.list()? {
+            self.load_source_location(source, &location)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Load all plugins from the libraries in `directory`, or the subdirectory for the running
+    /// platform as selected by [`platform_plugin_directory`](fn.platform_plugin_directory.html).
+    /// Only files named according to the platform's conventions, see
+    /// [`parse_dylib_name`](fn.parse_dylib_name.html), and on macOS framework bundles, are
+    /// loaded, in file name order; other subdirectories are not searched. Progress is reported,
+    /// and the load may be cancelled, as for
+    /// [`load_plugins_from_all`](#method.load_plugins_from_all).
+    ///
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "info", skip_all, fields(directory = ?directory.as_ref()))
+    )]
+    pub fn load_plugins_from_dir<P>(&self, directory: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let directory = platform_plugin_directory(directory.as_ref());
+        info!("PluginManager::load_plugins_from_dir({:?})", directory);
+
+        let file_names = dylib_files_in(&directory)?;
+
+        let _updates = self.updates.lock().unwrap();
+        self.load_batch(
+            file_names
+                .into_iter()
+                .map(|file_name| (file_name, self.entry_point.clone()))
+                .collect(),
+        )
+        .map(|_| ())
+    }
+
+    ///
+    /// Load all plugins from the first library in the ordered list of alternatives that loads
+    /// successfully, for example builds of the same plugins for different CPU features, from the
+    /// most to the least specific. A library that cannot be found or opened, is incompatible, or
+    /// fails to register its plugins is skipped, with a warning, and the next tried. The file name
+    /// of the library loaded is returned, or the error from the last alternative if none loaded; if
+    /// the list is empty a [`NoLibrariesProvided`](../error/enum.ErrorKind.html#variant.NoLibrariesProvided)
+    /// error is returned.
+    ///
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "info", skip_all))]
+    pub fn load_plugins_from_first<P>(&self, file_names: &[P]) -> Result<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        info!(
+            "PluginManager::load_plugins_from_first({:?})",
+            file_names
+                .iter()
+                .map(|p| p.as_ref())
+                .collect::<Vec<&Path>>()
+        );
+        let _updates = self.updates.lock().unwrap();
+        let mut last_error = None;
+        for file_name in file_names {
+            let file_name = file_name.as_ref();
+            match self.load_plugins_with(file_name, file_name, &self.entry_point) {
+                Ok(()) => return Ok(file_name.to_path_buf()),
+                Err(e) => {
+                    warn!("Library {:?} did not load, trying next; {}", file_name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(Error::no_libraries_provided))
+    }
+
+    ///
+    /// Load plugins from the bundle in `directory`, as described by its `plugin.toml` manifest
+    /// file, see the [`bundle`](../bundle/index.html) module. Only the libraries providing plugins
+    /// of this plugin manager's type, on the current platform, are opened; each library is loaded
+    /// once per registration function named in the manifest. A warning is logged for any plugin
+    /// in the manifest that was not registered by its library.
+    ///
+    #[cfg(feature = "bundle")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "info", skip_all, fields(bundle = ?directory.as_ref()))
+    )]
+    pub fn load_bundle<P>(&self, directory: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let directory = directory.as_ref();
+        info!("PluginManager::load_bundle({:?})", directory);
+
+        let manifest = BundleManifest::read_from(directory)?;
+        let _updates = self.updates.lock().unwrap();
+        let mut batch: Vec<(&String, Option<&String>)> = Default::default();
+        for entry in manifest.plugins_for(type_name::<T>()) {
+            let key = (entry.library(), entry.entry());
+            if !batch.contains(&key) {
+                batch.push(key);
+            }
+        }
+
+        let batch = batch
+            .into_iter()
+            .map(|(library, entry)| {
+                let entry_point = match entry {
+                    None => EntryPoint::default(),
+                    Some(entry) => EntryPoint::Function(
+                        CString::new(entry.as_str())
+                            .map_err(|e| Error::bundle_manifest_failed(directory, e))?,
+                    ),
+                };
+                Ok((
+                    directory.join(make_platform_dylib_name(library.as_ref())),
+                    entry_point,
+                ))
+            })
+            .collect::<Result<Vec<(PathBuf, EntryPoint)>>>()?;
+        let _ = self.load_batch(batch)?;
+
+        for entry in manifest.plugins_for(type_name::<T>()) {
+            if !self.contains(entry.id()) {
+                warn!(
+                    "Plugin {:?} in bundle manifest was not registered by library {:?}",
+                    entry.id(),
+                    entry.library()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Replace the plugins registered from one library with those from another, for example to
+    /// upgrade a library without a window where its plugins are missing. The new library is
+    /// opened, checked, and all of its plugins loaded (`on_load`), before any change is made to
+    /// the registry. If any of these steps fail, the plugins from the new library that were
+    /// already loaded are unloaded (`on_unload`), the new library is closed, and the old library
+    /// remains in place.
+    ///
+    /// Once the new plugins are loaded they replace the old library's plugins in the registry in
+    /// a single step; the state of each old plugin is handed to the new plugin with the same
+    /// identifier and version (see `Plugin::save_state`) before the swap, and the old plugins are
+    /// unloaded and the old library closed afterwards.
+    ///
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "info",
+            skip_all,
+            fields(old_library = ?old_file_name.as_ref(), new_library = ?new_file_name.as_ref())
+        )
+    )]
+    pub fn swap_library<P1, P2>(&self, old_file_name: P1, new_file_name: P2) -> Result<()>
+    where
+        P1: AsRef<Path>,
+        P2: AsRef<Path>,
+    {
+        let new_library_specifier: Arc<Path> = Arc::from(new_file_name.as_ref());
+        let old_file_name = self.find_library(old_file_name);
+        let new_file_name = self.find_library(new_file_name);
+        let old_canonical_name = canonical_path(&old_file_name);
+        info!(
+            "PluginManager::swap_library({:?}, {:?})",
+            old_file_name, new_file_name
+        );
+        let _updates = self.updates.lock().unwrap();
+
+        let start = Instant::now();
+        let new_library = self
+            .open_compatible_library(new_file_name.clone())
+            .and_then(|new_library| {
+                self.registered_plugins(&new_library, &self.entry_point)
+                    .map(|(new_plugins, new_values)| (new_library, new_plugins, new_values))
+            });
+        let (new_library, new_plugins, new_values) = match new_library {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.metrics.write().unwrap().record_library_load(
+                    &new_file_name,
+                    start.elapsed(),
+                    false,
+                );
+                return Err(e);
+            }
+        };
+
+        trace!("PluginManager::swap_library() > saving old plugin state");
+        let saved_state = self.save_library_state(&old_canonical_name);
+
+        trace!("PluginManager::swap_library() > loading new plugins");
+        let mut loaded: Vec<RegisteredPlugin<T>> = Vec::with_capacity(new_plugins.len());
+        for registered in new_plugins {
+            let plugin = &registered.plugin;
+            let from = self.previous_state(plugin.plugin_id());
+            let result = self
+                .interceptors
+                .call(plugin, Hook::Load)
+                .and_then(|_| match saved_state.get(&state_key(plugin.as_ref())) {
+                    Some(state) if !state.is_empty() => plugin.restore_state(state),
+                    _ => Ok(()),
+                });
+            if let Err(e) = result {
+                let e = e.detach();
+                error!("Swap failed loading new plugin, rolling back; {}", e);
+                self.record_state(
+                    plugin.plugin_id(),
+                    registered.instance_id,
+                    from,
+                    PluginState::Failed,
+                );
+                self.roll_back(&loaded);
+                drop(new_values);
+                drop(registered);
+                drop(loaded);
+                self.metrics.write().unwrap().record_library_load(
+                    &new_file_name,
+                    start.elapsed(),
+                    false,
+                );
+                release_library(new_library)?;
+                return Err(e);
+            }
+            self.record_state(
+                plugin.plugin_id(),
+                registered.instance_id,
+                from,
+                PluginState::Loaded,
+            );
+            loaded.push(registered);
+        }
+
+        self.metrics
+            .write()
+            .unwrap()
+            .record_library_load(&new_file_name, start.elapsed(), true);
+
+        trace!("PluginManager::swap_library() > swapping plugins in registry");
+        let entry_point = Arc::new(self.entry_point.clone());
+        let (old_plugins, plugin_ids) = self.update_registry(|registry| {
+            let old_plugins = registry.remove_library(&old_canonical_name);
+            let mut plugin_ids = Vec::with_capacity(loaded.len());
+            for registered in loaded {
+                let plugin = self.new_loaded_plugin(
+                    registered,
+                    &new_library,
+                    &new_library_specifier,
+                    &entry_point,
+                );
+                plugin_ids.push((plugin.plugin_id.clone(), plugin.instance_id));
+                let _ = registry.insert(plugin);
+            }
+            (old_plugins, plugin_ids)
+        });
+        self.store_values(&new_library, new_values);
+
+        trace!("PluginManager::swap_library() > unloading old plugins");
+        let mut result = Ok(());
+        for old_plugin in old_plugins {
+            if let Err(e) = self.unload_loaded_plugin(old_plugin) {
+                error!("Error unloading old plugin after swap; {}", e);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        for (plugin_id, instance_id) in plugin_ids {
+            self.record_state(
+                &plugin_id,
+                instance_id,
+                PluginState::Loaded,
+                PluginState::Active,
+            );
+        }
+        result
+    }
+
+    ///
+    /// Reload the library with the provided file name/path; all plugins registered from the
+    /// library are unloaded, the library is closed and opened again, and its plugins registered
+    /// once more. The state of each plugin is captured with `Plugin::save_state` before it is
+    /// unloaded and handed to the new plugin instance with the same identifier and version with
+    /// `Plugin::restore_state`. Every plugin is unloaded, and the library reloaded, even if one
+    /// reports an error from `on_unload`, in which case the first error is returned.
+    ///
+    /// The library is always opened again, rather than shared from the plugin manager's
+    /// [`LibraryCache`](../cache/struct.LibraryCache.html) while the host still references
+    /// plugins from the old library. However, while the old library remains open the platform
+    /// loader returns it again for the same path, and so the old code is registered once more;
+    /// a warning is logged in this case. Set a
+    /// [`shadow_copy_directory`](#method.set_shadow_copy_directory) so that each reload opens a
+    /// new copy of the library.
+    ///
+    /// If the library is not currently loaded this is equivalent to
+    /// [`load_plugins_from`](#method.load_plugins_from).
+    ///
+    /// The quiesce and resume handlers of the plugin manager's
+    /// [`ReloadPolicy`](struct.ReloadPolicy.html) are called before and after the reload.
+    ///
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "info", skip_all, fields(library = ?file_name.as_ref()))
+    )]
+    pub fn reload_library<P>(&self, file_name: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        info!("PluginManager::reload_library({:?})", file_name.as_ref());
+        self.reload_libraries(&[file_name])
+    }
+
+    ///
+    /// Reload each of the libraries with the provided file names/paths, as for
+    /// [`reload_library`](#method.reload_library), stopping at the first library that fails to
+    /// reload. If the plugin manager's [`ReloadPolicy`](struct.ReloadPolicy.html) batches reloads
+    /// the libraries are reloaded as a single batch, otherwise each is reloaded in turn.
+    ///
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "info", skip_all))]
+    pub fn reload_libraries<P>(&self, file_names: &[P]) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let file_names: Vec<PathBuf> = file_names
+            .iter()
+            .map(|file_name| self.find_library(file_name))
+            .collect();
+        info!("PluginManager::reload_libraries({:?})", file_names);
+        self.reload_libraries_with(&file_names, &self.reload_policy)
+    }
+
+    pub(crate) fn reload_libraries_with(
+        &self,
+        file_names: &[PathBuf],
+        reload_policy: &ReloadPolicy,
+    ) -> Result<()> {
+        if reload_policy.batch {
+            let _updates = self.updates.lock().unwrap();
+            self.reload_batch(file_names, reload_policy)
+        } else {
+            file_names.iter().try_for_each(|file_name| {
+                let _updates = self.updates.lock().unwrap();
+                self.reload_batch(std::slice::from_ref(file_name), reload_policy)
+            })
+        }
+    }
+
+    ///
+    /// Reload the libraries, between calls to the policy's quiesce and resume handlers, stopping
+    /// at the first library that fails to reload; libraries already reloaded are kept. The
+    /// caller must hold the `updates` lock.
+    ///
+    fn reload_batch(&self, file_names: &[PathBuf], reload_policy: &ReloadPolicy) -> Result<()> {
+        if let Some(handler) = reload_policy.quiesce_handler {
+            handler(file_names);
+        }
+        let result = file_names
+            .iter()
+            .try_for_each(|file_name| self.reload_locked(file_name));
+        if let Some(handler) = reload_policy.resume_handler {
+            handler(file_names);
+        }
+        result
+    }
+
+    fn reload_locked(&self, file_name: &Path) -> Result<()> {
+        let canonical_name = canonical_path(file_name);
+        trace!("PluginManager::reload_library() > saving plugin state");
+        let saved_state = self.save_library_state(&canonical_name);
+        if saved_state.is_empty() {
+            warn!("Library {:?} is not loaded, loading instead", file_name);
+        }
+
+        let mut old_plugins =
+            self.update_registry(|registry| registry.remove_library(&canonical_name));
+        let library_specifier = old_plugins
+            .first()
+            .map(|p| p.library_specifier.to_path_buf())
+            .unwrap_or_else(|| file_name.to_path_buf());
+        old_plugins.sort_by_key(|p| std::cmp::Reverse(p.sequence));
+        let result = self.unload_loaded_plugins(old_plugins);
+
+        if self.shadow_copy_directory.is_none()
+            && (self.library_cache.contains(&canonical_name) || is_orphaned(&canonical_name))
+        {
+            warn!(
+                "Library {:?} is still open, as plugins from it are referenced, and will be registered again rather than reloaded; set a shadow copy directory to reload it",
+                file_name
+            );
+        }
+        self.library_cache.evict(&canonical_name);
+        if let Err(e) = self.load_plugins_with(file_name, &library_specifier, &self.entry_point) {
+            return result.and(Err(e));
+        }
+
+        trace!("PluginManager::reload_library() > restoring plugin state");
+        for (key, state) in saved_state {
+            if state.is_empty() {
+                continue;
+            }
+            let plugin = self
+                .plugins
+                .load()
+                .values()
+                .find(|p| {
+                    p.in_library.canonical_name == canonical_name
+                        && state_key(p.plugin.as_ref()) == key
+                })
+                .map(|p| p.plugin.clone());
+            if let Some(plugin) = plugin {
+                if let Err(e) = plugin.restore_state(&state) {
+                    return result.and(Err(e));
+                }
+            } else {
+                warn!("Plugin {:?} was not registered after reload", key);
+            }
+        }
+
+        result
+    }
+
+    ///
+    /// Unload all plugins registered from the library with the provided file name/path, in the
+    /// reverse of the order in which they were registered, and close the library. An error is
+    /// returned if the library is not loaded by this plugin manager. Every plugin is unloaded
+    /// even if one reports an error, in which case the first error is returned.
+    ///
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "info", skip_all, fields(library = ?file_name.as_ref()))
+    )]
+    pub fn unload_library<P>(&self, file_name: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let canonical_name = self.find_library(file_name);
+        info!("PluginManager::unload_library({:?})", canonical_name);
+        let _updates = self.updates.lock().unwrap();
+        let mut old_plugins =
+            self.update_registry(|registry| registry.remove_library(&canonical_name));
+        if old_plugins.is_empty() {
+            return Err(Error::library_not_loaded(&canonical_name));
+        }
+        old_plugins.sort_by_key(|p| std::cmp::Reverse(p.sequence));
+        self.unload_loaded_plugins(old_plugins)
+    }
+
+    ///
+    /// Watch the plugin libraries, and directories of plugin libraries, in `paths` for changes,
+    /// and reload, load, or unload libraries as determined by `policy`, see the
+    /// [`watch`](../watch/index.html) module. Watching continues until the returned watcher, or
+    /// the plugin manager, is dropped. This method is only available if the feature `watch` is
+    /// enabled.
+    ///
+    #[cfg(feature = "watch")]
+    pub fn watch<P>(self: &Arc<Self>, paths: &[P], policy: ReloadPolicy) -> Result<PluginWatcher>
+    where
+        P: AsRef<Path>,
+    {
+        let paths: Vec<&Path> = paths.iter().map(|path| path.as_ref()).collect();
+        info!("PluginManager::watch({:?}, {:?})", paths, policy);
+        PluginWatcher::new(Arc::downgrade(self), &paths, policy)
+    }
+
+    ///
+    /// Apply a new configuration to the plugin manager without rebuilding it. The libraries
+    /// configured for the manager's plugin type, see [`set_plugin_type`](#method.set_plugin_type),
+    /// are compared with the libraries currently loaded; the plugins from libraries no longer
+    /// configured are unloaded, newly configured libraries are loaded, any plugins the
+    /// configuration lists as disabled are disabled, and the `on_config_changed` method of every
+    /// remaining plugin is called. Locations loaded from a
+    /// [`PluginSource`](../source/trait.PluginSource.html) are not part of the configuration and
+    /// are never unloaded.
+    ///
+    /// An error is returned if the manager has no plugin type, or the configuration has no
+    /// libraries for it; in either case nothing is unloaded.
+    ///
+    pub fn apply_configuration(&self, config: &PluginManagerConfiguration) -> Result<()> {
+        let plugin_type = self.plugin_type.clone().unwrap_or_default();
+        info!("PluginManager::apply_configuration({:?})", plugin_type);
+        let configured: Vec<(PathBuf, PathBuf)> = config
+            .plugin_libraries_for_type(&plugin_type)
+            .ok_or_else(|| Error::unknown_plugin_manager_type(&plugin_type))?
+            .map(|file_name| (file_name.clone(), self.find_library(file_name)))
+            .collect();
+        let _updates = self.updates.lock().unwrap();
+
+        let loaded: HashSet<PathBuf> = self
+            .plugins
+            .load()
+            .values()
+            .map(|p| p.in_library.canonical_name.clone())
+            .collect();
+        let from_sources: HashSet<PathBuf> = self
+            .plugins
+            .load()
+            .values()
+            .filter(|p| p.in_library.is_source())
+            .map(|p| p.in_library.canonical_name.clone())
+            .collect();
+        let mut removed: Vec<&PathBuf> = loaded
+            .iter()
+            .filter(|library| {
+                !from_sources.contains(*library)
+                    && !configured.iter().any(|(_, found)| found == *library)
+            })
+            .collect();
+        removed.sort();
+
+        for library in removed {
+            trace!(
+                "PluginManager::apply_configuration() > unloading {:?}",
+                library
+            );
+            let mut old_plugins = self.update_registry(|registry| registry.remove_library(library));
+            old_plugins.sort_by_key(|p| std::cmp::Reverse(p.sequence));
+            self.unload_loaded_plugins(old_plugins)?;
+        }
+
+        let _ = self.load_batch(
+            configured
+                .iter()
+                .filter(|(_, found)| !loaded.contains(found))
+                .map(|(file_name, _)| (file_name.clone(), self.entry_point.clone()))
+                .collect(),
+        )?;
+
+        self.try_update_registry(|registry| self.disable_plugins_in(registry, config))?;
+
+        trace!("PluginManager::apply_configuration() > notifying remaining plugins");
+        let remaining = self.ordered_plugins(|p| {
+            loaded
+                .contains(&p.in_library.canonical_name)
+                .then(|| p.plugin.clone())
+        });
+        for plugin in remaining.into_iter().flatten() {
+            self.interceptors
+                .call(&plugin, Hook::ConfigChanged)
+                .map_err(Error::detach)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Override the default registration function name
+    /// [`PLUGIN_REGISTRATION_FN_NAME`](../plugin/const.PLUGIN_REGISTRATION_FN_NAME.html).
+    ///
+    /// This function **must** conform to the type
+    /// [`PluginRegistrationFn`](../plugin/type.PluginRegistrationFn.html), and must be marked
+    /// as `#[no_mangle] pub extern "C"` in the same manner as the standard registration function.
+    /// The [`symbol!`](../macro.symbol.html) macro constructs the name, with its terminating NUL
+    /// character, from a string literal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dygpi::plugin::{Plugin, PluginRegistrar};
+    /// # #[derive(Debug)]
+    /// # struct SoundSourcePlugin;
+    /// # impl Plugin for SoundSourcePlugin {
+    /// #     fn plugin_id(&self) -> &str {
+    /// #         unimplemented!()
+    /// #     }
+    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// # }
+    /// # impl SoundSourcePlugin {
+    /// #     pub fn new(id: &str) -> Self { Self {} }
+    /// # }
+    /// # #[derive(Debug)]
+    /// # struct SoundEffectPlugin;
+    /// # impl Plugin for SoundEffectPlugin {
+    /// #     fn plugin_id(&self) -> &str {
+    /// #         unimplemented!()
+    /// #     }
+    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// # }
+    /// # impl SoundEffectPlugin {
+    /// #     pub fn new(id: &str) -> Self { Self {} }
+    /// # }
+    /// # const PLUGIN_NAME: &str = "RandomSource";
+    /// # const OTHER_PLUGIN_NAME: &str = "DelayEffect";
+    ///
+    /// #[no_mangle]
+    /// pub extern "C" fn register_sources(registrar: &mut PluginRegistrar<SoundSourcePlugin>) {
+    ///     registrar.register(SoundSourcePlugin::new(PLUGIN_NAME));
+    /// }
+    ///
+    /// #[no_mangle]
+    /// pub extern "C" fn register_effects(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    ///     registrar.register(SoundEffectPlugin::new(OTHER_PLUGIN_NAME));
+    /// }
+    /// ```
+    ///
+    pub fn set_registration_fn_name(&mut self, name: &CStr) {
+        self.entry_point = EntryPoint::Function(name.to_owned())
+    }
+
+    ///
+    /// Enable registration function discovery using the
+    /// [`DEFAULT_REGISTRATION_FN_PREFIX`](../plugin/const.DEFAULT_REGISTRATION_FN_PREFIX.html),
+    /// see [`set_registration_fn_prefix`](#method.set_registration_fn_prefix).
+    ///
+    /// # Safety
+    ///
+    /// As for [`set_registration_fn_prefix`](#method.set_registration_fn_prefix).
+    ///
+    #[allow(unsafe_code)]
+    pub unsafe fn enable_registration_fn_discovery(&mut self) {
+        self.set_registration_fn_prefix(DEFAULT_REGISTRATION_FN_PREFIX)
+    }
+
+    ///
+    /// Enable registration function discovery; rather than calling a single, named, registration
+    /// function the plugin manager reads the export table of each library and calls every
+    /// exported function whose name starts with `prefix`.
+    ///
+    /// Registration functions that reject the host, either because the plugin type does not match
+    /// or the host's API version is not supported, are skipped. If every discovered function is
+    /// skipped the load fails with the error reported by the last of them. Calling
+    /// [`set_registration_fn_name`](#method.set_registration_fn_name) disables discovery.
+    ///
+    /// # Safety
+    ///
+    /// The export table records only symbol names, so the plugin manager cannot check the type of
+    /// a discovered function, or that the provider intended it as an entry point. Every function
+    /// exported, by any library loaded by this plugin manager, with a name starting with `prefix`
+    /// **must** conform to the type
+    /// [`PluginRegistrationFn`](../plugin/type.PluginRegistrationFn.html); calling any other
+    /// function is undefined behavior.
+    ///
+    #[allow(unsafe_code)]
+    pub unsafe fn set_registration_fn_prefix(&mut self, prefix: &str) {
+        self.entry_point = EntryPoint::Prefix(prefix.to_string())
+    }
+
+    ///
+    /// Return the registration function prefix, if registration function discovery is enabled.
+    ///
+    pub fn registration_fn_prefix(&self) -> Option<&String> {
+        match &self.entry_point {
+            EntryPoint::Function(_) | EntryPoint::Source => None,
+            EntryPoint::Prefix(prefix) => Some(prefix),
+        }
+    }
+
+    ///
+    /// Set the plugin type identifier used to select this manager's libraries from a
+    /// configuration, see [`apply_configuration`](#method.apply_configuration). This is set by
+    /// [`PluginManagerConfiguration::make_manager_for_type`](../config/struct.PluginManagerConfiguration.html#method.make_manager_for_type).
+    ///
+    pub fn set_plugin_type(&mut self, plugin_type: &str) {
+        self.plugin_type = Some(plugin_type.to_string())
+    }
+
+    ///
+    /// Return the plugin type identifier of this manager, if set.
+    ///
+    pub fn plugin_type(&self) -> Option<&String> {
+        self.plugin_type.as_ref()
+    }
+
+    ///
+    /// Set the host information passed to each library's registration function via the
+    /// registrar, see [`HostInfo`](../plugin/struct.HostInfo.html). By default the API version is
+    /// `0` and the host name and version are empty.
+    ///
+    pub fn set_host_info(&mut self, host_info: HostInfo) {
+        self.host_info = host_info
+    }
+
+    ///
+    /// Return the host information passed to each library's registration function.
+    ///
+    pub fn host_info(&self) -> &HostInfo {
+        &self.host_info
+    }
+
+    ///
+    /// Add an input to the compatibility check, such as the host application's version; a
+    /// library is only loaded if it declares the same input, with the same value, using the
+    /// [`compatibility_inputs`](../macro.compatibility_inputs.html) macro. Otherwise an
+    /// [`IncompatibleLibraryVersion`](../error/enum.ErrorKind.html#variant.IncompatibleLibraryVersion)
+    /// error is returned.
+    ///
+    pub fn add_compatibility_input(&mut self, name: &str, value: &str) {
+        self.host_compatibility.add_input(name, value)
+    }
+
+    ///
+    /// Return the compatibility inputs of the plugin host, including any added with
+    /// [`add_compatibility_input`](#method.add_compatibility_input).
+    ///
+    pub fn host_compatibility(&self) -> &Compatibility {
+        &self.host_compatibility
+    }
+
+    ///
+    /// Set the services passed to each library's registration function, which allow plugins to
+    /// call back into the host; see [`HostApi`](../plugin/struct.HostApi.html).
+    ///
+    pub fn set_host_api(&mut self, host_api: HostApi) {
+        self.host_api = host_api
+    }
+
+    ///
+    /// Return the services passed to each library's registration function.
+    ///
+    pub fn host_api(&self) -> &HostApi {
+        &self.host_api
+    }
+
+    ///
+    /// Set the sink passed to each library's registration function, through which plugins send
+    /// events of type `E` to the host; see the [`event`](../event/index.html) module.
+    ///
+    pub fn set_event_sink<E>(&mut self, event_sink: EventSink<E>)
+    where
+        E: Send + 'static,
+    {
+        self.event_sink = Some(Arc::new(event_sink));
+    }
+
+    ///
+    /// Set the blackboard passed to each library's registration function, allowing plugins
+    /// loaded by several plugin managers to share data; see the [`state`](../state/index.html)
+    /// module. By default each plugin manager has its own, empty, blackboard.
+    ///
+    pub fn set_blackboard(&mut self, blackboard: Blackboard) {
+        self.blackboard = blackboard
+    }
+
+    ///
+    /// Return the blackboard passed to each library's registration function.
+    ///
+    pub fn blackboard(&self) -> &Blackboard {
+        &self.blackboard
+    }
+
+    ///
+    /// Set the library cache used to share open libraries with other plugin managers, see the
+    /// [`cache`](../cache/index.html) module. By default this is the process-wide cache returned
+    /// by [`LibraryCache::global`](../cache/struct.LibraryCache.html#method.global). Libraries
+    /// already loaded by this plugin manager are not affected.
+    ///
+    pub fn set_library_cache(&mut self, library_cache: Arc<LibraryCache>) {
+        self.library_cache = library_cache
+    }
+
+    ///
+    /// Return the library cache used by this plugin manager.
+    ///
+    pub fn library_cache(&self) -> &Arc<LibraryCache> {
+        &self.library_cache
+    }
+
+    ///
+    /// Set a cache of library check results, see the [`check_cache`](../check_cache/index.html)
+    /// module; a library previously found to be incompatible, and unchanged since, is rejected
+    /// without being opened. By default no check cache is used.
+    ///
+    pub fn set_check_cache(&mut self, check_cache: Arc<CheckCache>) {
+        self.check_cache = Some(check_cache);
+    }
+
+    ///
+    /// Return the cache of library check results, if one has been set.
+    ///
+    pub fn check_cache(&self) -> Option<&Arc<CheckCache>> {
+        self.check_cache.as_ref()
+    }
+
+    ///
+    /// Set the index used by [`get_or_load`](#method.get_or_load) to find the library providing
+    /// a plugin, see the [`index`](../index/index.html) module.
+    ///
+    pub fn set_plugin_index(&mut self, plugin_index: PluginIndex) {
+        self.plugin_index = Some(plugin_index);
+    }
+
+    ///
+    /// Return the index used to find the library providing a plugin, if one has been set.
+    ///
+    pub fn plugin_index(&self) -> Option<&PluginIndex> {
+        self.plugin_index.as_ref()
+    }
+
+    ///
+    /// Record that the plugin with the provided plugin identifier is provided by the library
+    /// `file_name`, for [`get_or_load`](#method.get_or_load); this takes precedence over any
+    /// plugin index.
+    ///
+    pub fn set_plugin_library<P>(&mut self, plugin_id: &str, file_name: P)
+    where
+        P: AsRef<Path>,
+    {
+        let _ = self
+            .plugin_libraries
+            .insert(plugin_id.to_string(), file_name.as_ref().to_path_buf());
+    }
+
+    ///
+    /// Set the behavior when loading a library that this plugin manager has already loaded.
+    ///
+    pub fn set_duplicate_load_policy(&mut self, policy: DuplicateLoadPolicy) {
+        self.duplicate_load_policy = policy
+    }
+
+    ///
+    /// Return the behavior when loading a library that this plugin manager has already loaded.
+    ///
+    pub fn duplicate_load_policy(&self) -> DuplicateLoadPolicy {
+        self.duplicate_load_policy
+    }
+
+    ///
+    /// Set the capabilities, as returned by
+    /// [`Plugin::plugin_capabilities`](../plugin/trait.Plugin.html#method.plugin_capabilities),
+    /// that every plugin loaded by this plugin manager must declare. Plugins that are already
+    /// loaded are not checked again.
+    ///
+    pub fn set_required_capabilities<I, S>(&mut self, capabilities: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.required_capabilities = capabilities.into_iter().map(Into::into).collect()
+    }
+
+    ///
+    /// Return the capabilities that every plugin loaded by this plugin manager must declare.
+    ///
+    pub fn required_capabilities(&self) -> &Vec<String> {
+        &self.required_capabilities
+    }
+
+    ///
+    /// Set the behavior when a library registers a plugin that does not declare all of the
+    /// required capabilities.
+    ///
+    pub fn set_capability_policy(&mut self, policy: CapabilityPolicy) {
+        self.capability_policy = policy
+    }
+
+    ///
+    /// Return the behavior when a library registers a plugin that does not declare all of the
+    /// required capabilities.
+    ///
+    pub fn capability_policy(&self) -> CapabilityPolicy {
+        self.capability_policy
+    }
+
+    ///
+    /// Limit the number of plugins a single library may register; a library registering more
+    /// fails to load with a
+    /// [`PluginLimitExceeded`](../error/enum.ErrorKind.html#variant.PluginLimitExceeded) error,
+    /// and plugins registered beyond the limit are discarded as they are registered. The default
+    /// is `None`, no limit.
+    ///
+    pub fn set_max_plugins_per_library(&mut self, max_plugins_per_library: Option<usize>) {
+        self.max_plugins_per_library = max_plugins_per_library
+    }
+
+    /// The limit on the number of plugins a single library may register, if any.
+    pub fn max_plugins_per_library(&self) -> Option<usize> {
+        self.max_plugins_per_library
+    }
+
+    ///
+    /// Limit the number of plugins registered with this plugin manager, counting each version of
+    /// a plugin separately; a library whose plugins would exceed the limit fails to load with a
+    /// [`PluginLimitExceeded`](../error/enum.ErrorKind.html#variant.PluginLimitExceeded) error.
+    /// The default is `None`, no limit.
+    ///
+    pub fn set_max_plugins(&mut self, max_plugins: Option<usize>) {
+        self.max_plugins = max_plugins
+    }
+
+    /// The limit on the number of plugins registered with this plugin manager, if any.
+    pub fn max_plugins(&self) -> Option<usize> {
+        self.max_plugins
+    }
+
+    ///
+    /// Limit the number of libraries, including plugin source locations, providing plugins to
+    /// this plugin manager; loading plugins from another library fails with a
+    /// [`PluginLimitExceeded`](../error/enum.ErrorKind.html#variant.PluginLimitExceeded) error.
+    /// The default is `None`, no limit.
+    ///
+    pub fn set_max_libraries(&mut self, max_libraries: Option<usize>) {
+        self.max_libraries = max_libraries
+    }
+
+    /// The limit on the number of libraries providing plugins to this plugin manager, if any.
+    pub fn max_libraries(&self) -> Option<usize> {
+        self.max_libraries
+    }
+
+    ///
+    /// Set how plugin identifiers passed to [`contains`](#method.contains), [`get`](#method.get),
+    /// [`unload_plugin`](#method.unload_plugin), and the other methods taking a single plugin
+    /// identifier, are matched against registered identifiers; see
+    /// [`IdNormalization`](struct.IdNormalization.html). An identifier that is not registered
+    /// exactly is compared with each registered identifier in turn.
+    ///
+    pub fn set_id_normalization(&mut self, id_normalization: IdNormalization) {
+        self.id_normalization = id_normalization
+    }
+
+    /// The rules used to match plugin identifiers against registered identifiers.
+    pub fn id_normalization(&self) -> &IdNormalization {
+        &self.id_normalization
+    }
+
+    ///
+    /// Set how libraries are reloaded by [`reload_library`](#method.reload_library) and
+    /// [`reload_libraries`](#method.reload_libraries); see
+    /// [`ReloadPolicy`](struct.ReloadPolicy.html). A watcher is given its own policy, see
+    /// [`watch`](#method.watch).
+    ///
+    pub fn set_reload_policy(&mut self, reload_policy: ReloadPolicy) {
+        self.reload_policy = reload_policy
+    }
+
+    /// The policy used when reloading libraries.
+    pub fn reload_policy(&self) -> &ReloadPolicy {
+        &self.reload_policy
+    }
+
+    ///
+    /// Add `alias` as an alternate identifier for the plugin `plugin_id`, so that an identifier
+    /// which a provider has since renamed continues to resolve in lookups; see
+    /// [`IdNormalization::add_alias`](struct.IdNormalization.html#method.add_alias). Aliases may
+    /// also be declared by plugins themselves, see `Plugin::plugin_aliases`; an alias added here
+    /// takes precedence.
+    ///
+    pub fn add_alias(&mut self, alias: &str, plugin_id: &str) {
+        self.id_normalization.add_alias(alias, plugin_id)
+    }
+
+    ///
+    /// Set the behavior when plugins fail to unload as this plugin manager is dropped.
+    ///
+    pub fn set_drop_policy(&mut self, policy: DropPolicy) {
+        self.drop_policy = policy
+    }
+
+    ///
+    /// Return the behavior when plugins fail to unload as this plugin manager is dropped.
+    ///
+    pub fn drop_policy(&self) -> DropPolicy {
+        self.drop_policy
+    }
+
+    ///
+    /// Set a function to be called with a [`ProgressEvent`](enum.ProgressEvent.html) before and
+    /// after each library is loaded by [`load_plugins_from_all`](#method.load_plugins_from_all),
+    /// [`load_all_plugins_from_env`](#method.load_all_plugins_from_env),
+    /// [`load_plugins_from_dir`](#method.load_plugins_from_dir), and
+    /// [`load_bundle`](#method.load_bundle).
+    ///
+    pub fn set_progress_handler(&mut self, handler: fn(ProgressEvent)) {
+        self.progress_handler = Some(handler)
+    }
+
+    ///
+    /// Set a function to be called with a [`StateTransition`](struct.StateTransition.html) each
+    /// time a plugin moves between lifecycle states, see [`state_of`](#method.state_of). The
+    /// function is called while the plugin manager is being updated, and so must not call back
+    /// into this plugin manager.
+    ///
+    pub fn set_transition_handler(&mut self, handler: fn(StateTransition)) {
+        self.transition_handler = Some(handler)
+    }
+
+    ///
+    /// Set a token that may be used, from another thread, to cancel the bulk loads performed by
+    /// [`load_plugins_from_all`](#method.load_plugins_from_all),
+    /// [`load_all_plugins_from_env`](#method.load_all_plugins_from_env),
+    /// [`load_plugins_from_dir`](#method.load_plugins_from_dir), and
+    /// [`load_bundle`](#method.load_bundle). The token is checked before each library is loaded;
+    /// if it has been cancelled the plugins already registered by the bulk load are unloaded, in
+    /// reverse order, and a [`LoadCancelled`](../error/enum.ErrorKind.html#variant.LoadCancelled)
+    /// error is returned. Plugins replaced by the bulk load, see
+    /// [`DuplicateLoadPolicy::Replace`](enum.DuplicateLoadPolicy.html#variant.Replace), are not
+    /// restored.
+    ///
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation_token = Some(token)
+    }
+
+    ///
+    /// Add an interceptor that wraps the lifecycle methods of every plugin in this plugin
+    /// manager, see the [`intercept`](../intercept/index.html) module. Interceptors are called in
+    /// the order they were added, the first added being the outermost.
+    ///
+    pub fn add_interceptor(&mut self, interceptor: Arc<dyn Interceptor<T>>) {
+        self.interceptors.push(interceptor)
+    }
+
+    ///
+    /// Enable the quarantine of libraries that fail to load. A library that fails the
+    /// compatibility check, fails to register its plugins, or has a plugin whose `on_load` method
+    /// fails, is added to the quarantine with the error; a library that cannot be found or opened
+    /// is not. A quarantined library is skipped by the bulk loads, such as
+    /// [`load_plugins_from_dir`](#method.load_plugins_from_dir), and loading it directly returns a
+    /// [`LibraryQuarantined`](../error/enum.ErrorKind.html#variant.LibraryQuarantined) error,
+    /// until [`retry_quarantined`](#method.retry_quarantined) is called.
+    ///
+    pub fn enable_quarantine(&mut self) {
+        if self.quarantine.is_none() {
+            self.quarantine = Some(Default::default())
+        }
+    }
+
+    ///
+    /// Return the libraries currently in quarantine, if quarantine is enabled.
+    ///
+    pub fn quarantined(&self) -> Vec<QuarantinedLibrary> {
+        match &self.quarantine {
+            None => Default::default(),
+            Some(quarantine) => quarantine.lock().unwrap().values().cloned().collect(),
+        }
+    }
+
+    ///
+    /// Release all libraries from quarantine, so that the next load of each is attempted again.
+    /// The released libraries are returned.
+    ///
+    pub fn retry_quarantined(&self) -> Vec<QuarantinedLibrary> {
+        info!("PluginManager::retry_quarantined()");
+        match &self.quarantine {
+            None => Default::default(),
+            Some(quarantine) => quarantine
+                .lock()
+                .unwrap()
+                .drain()
+                .map(|(_, library)| library)
+                .collect(),
+        }
+    }
+
+    ///
+    /// Set a function to be called with the report of any plugins that fail to unload as this
+    /// plugin manager is dropped; it is not called if all plugins unload without error. Use
+    /// [`shutdown`](#method.shutdown) to inspect the outcome before the manager is dropped.
+    ///
+    pub fn set_drop_error_handler(&mut self, handler: fn(&ShutdownReport)) {
+        self.drop_error_handler = Some(handler)
+    }
+
+    ///
+    /// Add a directory that will be searched for any DLLs that a plugin library depends upon. This
+    /// is only meaningful on Windows where, by default, dependent DLLs are not resolved relative
+    /// to the plugin library itself; the directories are added to the process DLL search
+    /// (`AddDllDirectory`) for the duration of each library load. On other platforms the
+    /// directories are recorded but ignored, dependent libraries are resolved by the platform
+    /// loader (`rpath`, `LD_LIBRARY_PATH`, etc.).
+    ///
+    /// Relative directories are resolved against the current working directory.
+    ///
+    pub fn add_dll_directory(&mut self, directory: &Path) {
+        let directory = if directory.is_absolute() {
+            directory.to_path_buf()
+        } else {
+            env::current_dir()
+                .map(|cwd| cwd.join(directory))
+                .unwrap_or_else(|_| directory.to_path_buf())
+        };
+        if !self.dll_directories.contains(&directory) {
+            self.dll_directories.push(directory);
+        }
+    }
+
+    ///
+    /// Return an iterator over the directories added with
+    /// [`add_dll_directory`](#method.add_dll_directory).
+    ///
+    pub fn dll_directories(&self) -> impl Iterator<Item = &PathBuf> {
+        self.dll_directories.iter()
+    }
+
+    ///
+    /// Set whether the header of each library file is read before it is opened, to check that it
+    /// was built for the host's architecture; a library built for another architecture fails to
+    /// load with a `WrongArchitecture` error rather than the platform loader's error. The default
+    /// is `true`.
+    ///
+    pub fn set_check_architecture(&mut self, check_architecture: bool) {
+        self.check_architecture = check_architecture
+    }
+
+    /// Returns `true` if library architectures are checked before opening, else `false`.
+    pub fn check_architecture(&self) -> bool {
+        self.check_architecture
+    }
+
+    ///
+    /// Set the verifier used to check the signature of each library file before it is opened, see
+    /// the [`verify`](../verify/index.html) module; a library that fails verification is not
+    /// opened, and fails to load with a
+    /// [`SignatureVerificationFailed`](../error/enum.ErrorKind.html#variant.SignatureVerificationFailed)
+    /// error. The file verified is the one handed to the platform loader, the shadow copy if
+    /// [`set_shadow_copy_directory`](#method.set_shadow_copy_directory) is set, and a library
+    /// that cannot be resolved to a file, such as a bare name the platform loader would search
+    /// for, fails verification. Libraries are verified each time they are loaded by this plugin
+    /// manager, even if already opened by another manager sharing its library cache. By default
+    /// libraries are not verified. This is only available if the feature `verify` is enabled.
+    ///
+    #[cfg(feature = "verify")]
+    pub fn set_signature_verifier(&mut self, signature_verifier: Arc<dyn SignatureVerifier>) {
+        self.signature_verifier = Some(signature_verifier)
+    }
+
+    ///
+    /// Return the verifier used to check the signature of each library before it is opened, if
+    /// set.
+    ///
+    #[cfg(feature = "verify")]
+    pub fn signature_verifier(&self) -> Option<&Arc<dyn SignatureVerifier>> {
+        self.signature_verifier.as_ref()
+    }
+
+    ///
+    /// Set whether library names without an extension, such as `"delay"` or `"plugins/delay"`,
+    /// are formatted according to the platform's conventions with
+    /// [`make_platform_dylib_name`](fn.make_platform_dylib_name.html) before they are loaded, so
+    /// that `"delay"` loads `libdelay.so` on Linux, `libdelay.dylib` on macOS, and `delay.dll` on
+    /// Windows. This allows a single configuration to be used on all platforms. Names with an
+    /// extension are used as given, unlike `make_platform_dylib_name` which replaces any
+    /// extension; so a dotted name such as `"sound.delay"` is not formatted, as its `"delay"`
+    /// suffix cannot be told apart from an extension, and must be given in full. The default is
+    /// `false`.
+    ///
+    pub fn set_auto_platform_names(&mut self, auto_platform_names: bool) {
+        self.auto_platform_names = auto_platform_names
+    }
+
+    /// Returns `true` if library names without an extension are given the platform's prefix and
+    /// extension before loading, else `false`.
+    pub fn auto_platform_names(&self) -> bool {
+        self.auto_platform_names
+    }
+
+    ///
+    /// Enable shadow copy loading, with copies made in the `dygpi-shadow` directory within the
+    /// platform's temporary directory; see
+    /// [`set_shadow_copy_directory`](#method.set_shadow_copy_directory).
+    ///
+    pub fn enable_shadow_copy(&mut self) {
+        if self.shadow_copy_directory.is_none() {
+            self.shadow_copy_directory = Some(env::temp_dir().join("dygpi-shadow"))
+        }
+    }
+
+    ///
+    /// Enable shadow copy loading, with copies made in `directory`. Each library file is copied
+    /// into a new directory within `directory`, and the copy is opened in place of the original,
+    /// so that the original may be overwritten, for example by a rebuild, while it is loaded;
+    /// on Windows a loaded DLL is locked and cannot otherwise be replaced. The copy is removed
+    /// once the library is closed, and
+    /// [`reload_library`](#method.reload_library) opens a fresh copy of the original file.
+    ///
+    /// The library continues to be identified by its original path, and the directory of the
+    /// original is searched for the DLLs it depends upon, as for
+    /// [`add_dll_directory`](#method.add_dll_directory). The copy used for a loaded library is
+    /// returned by [`shadow_copy_of`](#method.shadow_copy_of).
+    ///
+    pub fn set_shadow_copy_directory(&mut self, directory: &Path) {
+        self.shadow_copy_directory = Some(directory.to_path_buf())
+    }
+
+    /// The directory in which shadow copies are made, if shadow copy loading is enabled.
+    pub fn shadow_copy_directory(&self) -> Option<&PathBuf> {
+        self.shadow_copy_directory.as_ref()
+    }
+
+    ///
+    /// Returns the path of the shadow copy opened in place of the library `file_name`, or `None`
+    /// if the library is not loaded by this plugin manager, or was not shadow copied.
+    ///
+    pub fn shadow_copy_of<P>(&self, file_name: P) -> Option<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        let canonical_name = canonical_path(&self.find_library(file_name));
+        self.plugins
+            .load()
+            .values()
+            .find(|p| p.in_library.canonical_name == canonical_name)
+            .and_then(|p| p.in_library.shadow_copy.as_ref())
+            .map(|shadow_copy| shadow_copy.path().clone())
+    }
+
+    ///
+    /// Return a snapshot of the timing and failure metrics recorded by this plugin manager, see
+    /// the [`metrics`](../metrics/index.html) module.
+    ///
+    pub fn metrics(&self) -> LoadMetrics {
+        self.metrics.read().unwrap().clone()
+    }
+
+    ///
+    /// Discard all metrics recorded by this plugin manager so far.
+    ///
+    pub fn reset_metrics(&self) {
+        *self.metrics.write().unwrap() = LoadMetrics::default();
+    }
+
+    ///
+    /// Returns `true` if the plugin manager has no plugins registered, else `false`.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.plugins.load().is_empty()
+    }
+
+    ///
+    /// Return the number of plugins registered in this plugin manager, counting each version of
+    /// a plugin separately.
+    ///
+    pub fn len(&self) -> usize {
+        self.plugins.load().len()
+    }
+
+    ///
+    /// Returns `true` if this plugin manager has a registered plugin with the provided plugin
+    /// identifier, else `false`.
+    pub fn contains(&self, plugin_id: &str) -> bool {
+        let plugins = self.plugins.load();
+        plugins.find(&self.id_normalization, plugin_id).is_some()
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.contains(plugin_id))
+    }
+
+    ///
+    /// Returns `true` if any plugin is registered from the library with the provided file
+    /// name/path, else `false`.
+    ///
+    pub fn is_library_loaded<P>(&self, file_name: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        let canonical_name = self.find_library(file_name);
+        self.plugins.load().contains_library(&canonical_name)
+    }
+
+    ///
+    /// Return the value of type `V` registered with the name `name` by a library's registration
+    /// function, see
+    /// [`PluginRegistrar::register_value`](../plugin/struct.PluginRegistrar.html#method.register_value),
+    /// or `None` if no value of that type and name is registered. A child plugin manager also
+    /// returns the values registered with its parent.
+    ///
+    /// Values are matched to their type as described in
+    /// [sharing values](../plugin/index.html#sharing-values).
+    ///
+    /// The value may refer to code in the library that registered it, and so should not be held
+    /// once the library's plugins have been unloaded.
+    ///
+    pub fn registered_value<V>(&self, name: &str) -> Option<Arc<V>>
+    where
+        V: Any + Send + Sync,
+    {
+        let value = self
+            .values
+            .read()
+            .unwrap()
+            .get(&(TypeId::of::<V>(), name.to_string()))
+            .and_then(|registered| registered.value.clone().downcast::<V>().ok());
+        match (value, &self.parent) {
+            (None, Some(parent)) => parent.registered_value(name),
+            (value, _) => value,
+        }
+    }
+
+    ///
+    /// Return the names of all values of type `V` registered by libraries' registration
+    /// functions, in name order; including those registered with the parent of a child plugin
+    /// manager.
+    ///
+    pub fn registered_value_names<V>(&self) -> Vec<String>
+    where
+        V: Any + Send + Sync,
+    {
+        let mut names: BTreeSet<String> = match &self.parent {
+            Some(parent) => parent.registered_value_names::<V>().into_iter().collect(),
+            None => Default::default(),
+        };
+        names.extend(
+            self.values
+                .read()
+                .unwrap()
+                .keys()
+                .filter(|(type_id, _)| *type_id == TypeId::of::<V>())
+                .map(|(_, name)| name.clone()),
+        );
+        names.into_iter().collect()
+    }
+
+    ///
+    /// Returns the plugin with the provided plugin identifier, if one exists, else `None`. If
+    /// more than one version of the plugin is registered the highest version is returned. If the
+    /// plugin is instanced per use a new instance is returned, see
+    /// [`set_instancing`](#method.set_instancing).
+    pub fn get(&self, plugin_id: &str) -> Option<Arc<T>> {
+        let plugins = self.plugins.load();
+        plugins
+            .latest(&self.id_normalization, plugin_id)
+            .map(LoadedPlugin::instance)
+            .or_else(|| self.parent.as_ref()?.get(plugin_id))
+    }
+
+    ///
+    /// Create a new instance of the plugin with the provided plugin identifier, configured with
+    /// `params`, using the factory the plugin was registered with, see
+    /// [`PluginRegistrar::register_instantiable`](../plugin/struct.PluginRegistrar.html#method.register_instantiable).
+    /// If more than one version of the plugin is registered the highest version is used. The new
+    /// instance is not registered, and receives no lifecycle calls, but keeps the plugin's library
+    /// open while it is referenced.
+    ///
+    /// An error is returned if no plugin is registered with the identifier, if it was not
+    /// registered with a factory accepting parameters, or by the factory itself.
+    ///
+    pub fn instantiate<P>(&self, plugin_id: &str, params: &P) -> Result<Arc<T>>
+    where
+        P: Any,
+    {
+        info!("PluginManager::instantiate({:?})", plugin_id);
+        self.instantiate_with(plugin_id, &Parameters::new(params))
+    }
+
+    fn instantiate_with(&self, plugin_id: &str, params: &Parameters<'_>) -> Result<Arc<T>> {
+        let plugins = self.plugins.load();
+        match plugins.latest(&self.id_normalization, plugin_id) {
+            Some(plugin) => plugin.instantiate(params),
+            None => match &self.parent {
+                Some(parent) => parent.instantiate_with(plugin_id, params),
+                None => Err(Error::unknown_plugin(plugin_id)),
+            },
+        }
+    }
+
+    ///
+    /// Returns the plugin with the provided plugin identifier, loading the library that provides
+    /// it if the plugin is not already registered. The library is found, in order, from:
+    ///
+    /// 1. the libraries set with [`set_plugin_library`](#method.set_plugin_library),
+    /// 1. the index set with [`set_plugin_index`](#method.set_plugin_index),
+    /// 1. the plugin manifests of the libraries in the directories of the search path, which are
+    ///    read without opening the libraries; see the [`manifest`](../manifest/index.html) module.
+    ///
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "info", skip(self)))]
+    pub fn get_or_load(&self, plugin_id: &str) -> Result<Arc<T>> {
+        if let Some(plugin) = self.get(plugin_id) {
+            return Ok(plugin);
+        }
+        info!("PluginManager::get_or_load({:?})", plugin_id);
+        let library = self
+            .plugin_library(plugin_id)
+            .ok_or_else(|| Error::unknown_plugin(plugin_id))?;
+        let _ = self.load_plugins_from(library)?;
+        self.get(plugin_id)
+            .ok_or_else(|| Error::unknown_plugin(plugin_id))
+    }
+
+    ///
+    /// Returns the highest version of the plugin with the provided plugin identifier that
+    /// matches the semantic version requirement, if one exists, else `None`. Plugins that do not
+    /// report a version, see `Plugin::plugin_version`, never match.
+    ///
+    pub fn get_version(&self, plugin_id: &str, requirement: &VersionReq) -> Option<Arc<T>> {
+        let plugins = self.plugins.load();
+        plugins
+            .matching(&self.id_normalization, plugin_id, requirement)
+            .map(LoadedPlugin::instance)
+            .or_else(|| self.parent.as_ref()?.get_version(plugin_id, requirement))
+    }
+
+    ///
+    /// Return a view of the plugins registered in this plugin manager which borrows them, rather
+    /// than cloning a reference to each as [`plugins`](#method.plugins) does; for use on hot
+    /// paths that look up, or iterate over, many plugins.
+    ///
+    /// The view is a snapshot; it does not hold a lock, and so holding it cannot deadlock, or
+    /// block, any other call to this plugin manager from this or any other thread. Plugins may be
+    /// loaded and unloaded while it is held, and the view continues to show the plugins registered
+    /// when it was taken. However, a plugin unloaded while a view is held is still referenced by
+    /// it, and so closing the plugin's library is deferred, as for plugins still held by the host,
+    /// see [`unload_plugin`](#method.unload_plugin). A view should therefore be held only briefly,
+    /// and not stored; clone the plugins needed for longer.
+    ///
+    /// ```rust
+    /// # use dygpi::error::Result;
+    /// # use dygpi::plugin::Plugin;
+    /// # use dygpi::testing::mock_manager;
+    /// # #[derive(Debug)]
+    /// # struct SoundEffectPlugin(String);
     /// # impl Plugin for SoundEffectPlugin {
-    /// #     fn plugin_id(&self) -> &String {
-    /// #         unimplemented!()
-    /// #     }
-    /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
-    /// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #     fn plugin_id(&self) -> &str { &self.0 }
+    /// #     fn on_load(&self) -> Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> Result<()> { Ok(()) }
     /// # }
-    /// # impl SoundEffectPlugin {
-    /// #     pub fn new(id: &str) -> Self { Self {} }
-    /// # }
-    /// # const PLUGIN_NAME: &str = "RandomSource";
-    /// # const OTHER_PLUGIN_NAME: &str = "DelayEffect";
+    /// let plugin_manager = mock_manager(vec![
+    ///     SoundEffectPlugin("test::Delay".to_string()),
+    ///     SoundEffectPlugin("test::Reverb".to_string()),
+    /// ])
+    /// .unwrap();
+    ///
+    /// let plugins = plugin_manager.read();
+    /// assert_eq!(plugins.len(), 2);
+    /// assert!(plugins.get("test::Delay").is_some());
+    /// assert_eq!(plugins.iter().filter(|p| p.0.ends_with("Reverb")).count(), 1);
+    /// ```
+    ///
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        ReadGuard {
+            plugin_manager: self,
+            registry: self.plugins.load(),
+        }
+    }
+
+    ///
+    /// Return all the plugins, including all versions of each plugin, registered in this plugin
+    /// manager as a vector, followed by those inherited from the parent plugin manager, if any.
+    ///
+    pub fn plugins(&self) -> Vec<Arc<T>> {
+        let plugins = self.plugins.load();
+        let mut all: Vec<Arc<T>> = Vec::with_capacity(plugins.len());
+        all.extend(plugins.values().map(|p| p.plugin.clone()));
+        if let Some(parent) = &self.parent {
+            all.extend(parent.plugins());
+        }
+        all
+    }
+
+    ///
+    /// Return the plugins whose identifiers match the glob `pattern`, in which `*` matches any
+    /// sequence of characters, including `::`, and `?` matches any single character; for example
+    /// `sound_*::*::Delay*`. This allows saved references to plugins to survive changes to the
+    /// module paths of their providers. If more than one version of a plugin is registered the
+    /// highest version is returned, and plugins registered in this plugin manager hide those with
+    /// the same identifier in the parent plugin manager. The plugins are returned in plugin
+    /// identifier order.
+    ///
+    pub fn get_matching(&self, pattern: &str) -> Vec<Arc<T>> {
+        trace!("PluginManager::get_matching({:?})", pattern);
+        let pattern: Vec<char> = pattern.chars().collect();
+        self.matching_plugins(&|plugin_id| glob_match(&pattern, plugin_id))
+            .into_values()
+            .collect()
+    }
+
+    ///
+    /// Return the plugins whose identifiers match the regular expression `pattern`, which is not
+    /// anchored unless it includes `^` and `$`; otherwise as [`get_matching`](#method.get_matching).
+    /// This method is only available if the feature `regex` is enabled.
+    ///
+    #[cfg(feature = "regex")]
+    pub fn get_matching_regex(&self, pattern: &regex::Regex) -> Vec<Arc<T>> {
+        trace!("PluginManager::get_matching_regex({:?})", pattern.as_str());
+        self.matching_plugins(&|plugin_id| pattern.is_match(plugin_id))
+            .into_values()
+            .collect()
+    }
+
+    ///
+    /// Look up the additional symbol `name`, for example an optional capabilities function or a
+    /// C entry point, exported by the library `file_name`, which must have been loaded by this
+    /// plugin manager, and call `f` with it. The library remains open until `f` returns, even if
+    /// its plugins are unloaded concurrently.
+    ///
+    /// # Safety
+    ///
+    /// The type `F` must be the type of the exported symbol, as for `libloading::Library::get`;
+    /// and values that depend on the library, for example a copy of a function pointer, must not
+    /// be retained once `f` returns.
+    ///
+    #[allow(unsafe_code)]
+    pub unsafe fn with_symbol<P, F, R, C>(&self, file_name: P, name: &CStr, f: C) -> Result<R>
+    where
+        P: AsRef<Path>,
+        C: FnOnce(&F) -> R,
+    {
+        let canonical_name = self.find_library(file_name);
+        trace!(
+            "PluginManager::with_symbol({:?}, {:?})",
+            canonical_name,
+            name
+        );
+        let library = self
+            .plugins
+            .load()
+            .values()
+            .find(|p| p.in_library.canonical_name == canonical_name)
+            .map(|p| p.in_library.clone())
+            .ok_or_else(|| Error::library_not_loaded(&canonical_name))?;
+        let symbol: Symbol<'_, F> = library.get(name)?;
+        Ok(f(&symbol))
+    }
+
+    ///
+    /// Returns `true` if the plugin with the provided plugin identifier is enabled, else `false`.
+    /// Plugins are enabled when they are loaded.
+    ///
+    pub fn is_enabled(&self, plugin_id: &str) -> bool {
+        let plugins = self.plugins.load();
+        plugins
+            .latest(&self.id_normalization, plugin_id)
+            .is_some_and(|p| p.enabled)
+    }
+
+    ///
+    /// Returns `true` if the plugin with the provided plugin identifier is paused, else `false`.
+    ///
+    pub fn is_paused(&self, plugin_id: &str) -> bool {
+        let plugins = self.plugins.load();
+        plugins
+            .latest(&self.id_normalization, plugin_id)
+            .is_some_and(|p| p.paused)
+    }
+
+    ///
+    /// Returns the lifecycle state of the plugin with the provided plugin identifier; if more
+    /// than one version of the plugin is registered the state of the highest version is returned.
+    /// Plugins that are no longer registered are reported as `Failed` or `Unloaded`, and those
+    /// not yet loaded are reported as `Discovered` if the library providing them is known, see
+    /// [`get_or_load`](#method.get_or_load). Returns `None` if the plugin is not known.
+    ///
+    pub fn state_of(&self, plugin_id: &str) -> Option<PluginState> {
+        let plugins = self.plugins.load();
+        let plugin_id = plugins.resolve(&self.id_normalization, plugin_id);
+        if let Some(plugin) = plugins.latest(&self.id_normalization, plugin_id) {
+            return Some(plugin.state());
+        }
+        if let Some(state) = self.parent.as_ref().and_then(|p| p.state_of(plugin_id)) {
+            return Some(state);
+        }
+        if let Some(state) = self.states.lock().unwrap().get(plugin_id) {
+            return Some(*state);
+        }
+        self.plugin_library(plugin_id)
+            .map(|_| PluginState::Discovered)
+    }
+
+    ///
+    /// Returns the runtime identifier of the plugin with the provided plugin identifier; if more
+    /// than one version of the plugin is registered the identifier of the highest version is
+    /// returned. Returns `None` if the plugin is not registered.
+    ///
+    pub fn instance_id(&self, plugin_id: &str) -> Option<InstanceId> {
+        let plugins = self.plugins.load();
+        plugins
+            .latest(&self.id_normalization, plugin_id)
+            .map(|p| p.instance_id)
+            .or_else(|| self.parent.as_ref()?.instance_id(plugin_id))
+    }
+
+    ///
+    /// Returns the plugin instance with the provided runtime identifier, if it is still
+    /// registered, else `None`. The registered instance is returned even if the plugin is
+    /// instanced per use.
+    ///
+    pub fn get_instance(&self, instance_id: InstanceId) -> Option<Arc<T>> {
+        self.plugins
+            .load()
+            .values()
+            .find(|p| p.instance_id == instance_id)
+            .map(|p| p.plugin.clone())
+            .or_else(|| self.parent.as_ref()?.get_instance(instance_id))
+    }
+
+    ///
+    /// Returns a diagnostic record for the plugin with the provided plugin identifier, if one
+    /// exists, else `None`. If more than one version of the plugin is registered the record for
+    /// the highest version is returned.
+    ///
+    pub fn plugin_info(&self, plugin_id: &str) -> Option<PluginInfo> {
+        let plugins = self.plugins.load();
+        plugins
+            .latest(&self.id_normalization, plugin_id)
+            .map(|p| p.info())
+            .or_else(|| self.parent.as_ref()?.plugin_info(plugin_id))
+    }
+
+    ///
+    /// Returns diagnostic records for all the plugins registered in this plugin manager, in the
+    /// order in which they were registered.
+    ///
+    pub fn plugin_infos(&self) -> Vec<PluginInfo> {
+        self.ordered_plugins(LoadedPlugin::info)
+    }
+
+    ///
+    /// Returns a graph of the libraries loaded by this plugin manager and the plugins each
+    /// provides, see the [`topology`](../topology/index.html) module.
+    ///
+    pub fn topology(&self) -> Topology {
+        let mut topology = Topology::default();
+        for (library, plugin_id) in self.ordered_plugins(|p| {
+            (
+                p.in_library.file_name.clone(),
+                p.plugin.plugin_id().to_string(),
+            )
+        }) {
+            topology.add(&library, &plugin_id);
+        }
+        topology
+    }
+
+    ///
+    /// Returns a snapshot of the libraries, plugins, and plugin health of this plugin manager,
+    /// see the [`status`](../status/index.html) module.
+    ///
+    pub fn status(&self) -> StatusReport {
+        StatusReport::new(self.plugin_infos(), self.quarantined())
+    }
+
+    ///
+    /// Enable a plugin previously disabled with [`disable_plugin`](#method.disable_plugin),
+    /// calling the plugin's `on_enable` method. This has no effect if the plugin is already
+    /// enabled.
+    /// A plugin that has failed cannot be changed, and an
+    /// [`InvalidStateTransition`](../error/enum.ErrorKind.html#variant.InvalidStateTransition)
+    /// error is returned.
+    ///
+    pub fn enable_plugin(&self, plugin_id: &str) -> Result<()> {
+        info!("PluginManager::enable_plugin({:?})", plugin_id);
+        self.update_plugin(plugin_id, |plugin| self.change_state(plugin, Hook::Enable))
+    }
+
+    ///
+    /// Disable a plugin, calling the plugin's `on_disable` method, without unloading it or
+    /// closing its library. This has no effect if the plugin is already disabled.
+    /// A plugin that has failed cannot be changed, and an
+    /// [`InvalidStateTransition`](../error/enum.ErrorKind.html#variant.InvalidStateTransition)
+    /// error is returned.
+    ///
+    pub fn disable_plugin(&self, plugin_id: &str) -> Result<()> {
+        info!("PluginManager::disable_plugin({:?})", plugin_id);
+        self.update_plugin(plugin_id, |plugin| self.change_state(plugin, Hook::Disable))
+    }
+
+    ///
+    /// Temporarily suspend a plugin, calling the plugin's `on_pause` method. This has no effect
+    /// if the plugin is already paused.
+    /// A plugin that has failed cannot be changed, and an
+    /// [`InvalidStateTransition`](../error/enum.ErrorKind.html#variant.InvalidStateTransition)
+    /// error is returned.
+    ///
+    pub fn pause_plugin(&self, plugin_id: &str) -> Result<()> {
+        info!("PluginManager::pause_plugin({:?})", plugin_id);
+        self.update_plugin(plugin_id, |plugin| self.change_state(plugin, Hook::Pause))
+    }
+
+    ///
+    /// Resume a plugin suspended with [`pause_plugin`](#method.pause_plugin), calling the
+    /// plugin's `on_resume` method. This has no effect if the plugin is not paused. As with
+    /// [`pause_plugin`](#method.pause_plugin) a plugin that has failed cannot be resumed.
+    ///
+    pub fn resume_plugin(&self, plugin_id: &str) -> Result<()> {
+        info!("PluginManager::resume_plugin({:?})", plugin_id);
+        self.update_plugin(plugin_id, |plugin| self.change_state(plugin, Hook::Resume))
+    }
+
+    ///
+    /// Add the plugin with the provided plugin identifier to the named group, in addition to the
+    /// groups the plugin declares, see
+    /// [`Plugin::plugin_groups`](../plugin/trait.Plugin.html#method.plugin_groups). The plugin
+    /// need not be registered; membership is kept by identifier, so that a plugin remains in the
+    /// group when it is unloaded and loaded again.
+    ///
+    pub fn add_to_group(&self, group: &str, plugin_id: &str) {
+        info!("PluginManager::add_to_group({:?}, {:?})", group, plugin_id);
+        let plugin_id = self
+            .plugins
+            .load()
+            .resolve(&self.id_normalization, plugin_id)
+            .to_string();
+        let _ = self
+            .groups
+            .write()
+            .unwrap()
+            .entry(group.to_string())
+            .or_default()
+            .insert(plugin_id);
+    }
+
+    ///
+    /// Remove the plugin with the provided plugin identifier from a group it was added to with
+    /// [`add_to_group`](#method.add_to_group), returning `true` if it was a member. Groups the
+    /// plugin declares itself are not affected.
+    ///
+    pub fn remove_from_group(&self, group: &str, plugin_id: &str) -> bool {
+        info!(
+            "PluginManager::remove_from_group({:?}, {:?})",
+            group, plugin_id
+        );
+        let plugin_id = self
+            .plugins
+            .load()
+            .resolve(&self.id_normalization, plugin_id)
+            .to_string();
+        let mut groups = self.groups.write().unwrap();
+        match groups.get_mut(group) {
+            Some(members) => {
+                let removed = members.remove(&plugin_id);
+                if members.is_empty() {
+                    let _ = groups.remove(group);
+                }
+                removed
+            }
+            None => false,
+        }
+    }
+
+    ///
+    /// Return the plugins registered in this plugin manager that are in the named group, either
+    /// because they declare it or were added to it, including all versions of each plugin, in
+    /// the order in which they were registered.
+    ///
+    pub fn plugins_in_group(&self, group: &str) -> Vec<Arc<T>> {
+        let members = self.group_members(group);
+        self.ordered_plugins(|p| members.contains(p).then(|| p.plugin.clone()))
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    ///
+    /// Enable all the plugins in the named group, see [`enable_plugin`](#method.enable_plugin).
+    /// The plugins are enabled in the order in which they were registered, stopping at the first
+    /// that returns an error. A group with no registered plugins is ignored.
+    ///
+    pub fn enable_group(&self, group: &str) -> Result<()> {
+        info!("PluginManager::enable_group({:?})", group);
+        self.update_group(group, Hook::Enable)
+    }
+
+    ///
+    /// Disable all the plugins in the named group, see [`disable_plugin`](#method.disable_plugin).
+    /// The plugins are disabled in the order in which they were registered, stopping at the first
+    /// that returns an error. A group with no registered plugins is ignored.
+    ///
+    pub fn disable_group(&self, group: &str) -> Result<()> {
+        info!("PluginManager::disable_group({:?})", group);
+        self.update_group(group, Hook::Disable)
+    }
+
+    ///
+    /// Unload all the plugins in the named group, including all versions of each plugin, in the
+    /// reverse of the order in which they were registered, see
+    /// [`unload_plugin`](#method.unload_plugin). Unloading stops at the first plugin that returns
+    /// an error. Plugins added to the group remain members once unloaded. A group with no
+    /// registered plugins is ignored.
+    ///
+    pub fn unload_group(&self, group: &str) -> Result<()> {
+        info!("PluginManager::unload_group({:?})", group);
+        let _updates = self.updates.lock().unwrap();
+        let members = self.group_members(group);
+        let mut keys = self.ordered_plugins(|p| members.contains(p).then(|| p.key()));
+        keys.reverse();
+        let _ = self.close_released_libraries(false);
+        for (plugin_id, version) in keys.into_iter().flatten() {
+            if let Some(plugin) =
+                self.update_registry(|plugins| plugins.remove(&plugin_id, &version))
+            {
+                self.unload_loaded_plugin(plugin)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Select whether lookups of a plugin, including all its registered versions, return the
+    /// registered instance or a new instance for each use, see [`Instancing`](enum.Instancing.html).
+    /// Only plugins registered with a factory may be instanced per use, for others a
+    /// [`NoPluginFactory`](../error/enum.ErrorKind.html#variant.NoPluginFactory) error is
+    /// returned.
+    ///
+    pub fn set_instancing(&self, plugin_id: &str, instancing: Instancing) -> Result<()> {
+        info!(
+            "PluginManager::set_instancing({:?}, {:?})",
+            plugin_id, instancing
+        );
+        self.update_plugin(plugin_id, |plugin| {
+            if instancing == Instancing::PerUse
+                && !matches!(plugin.factory, Some(PluginFactory::PerUse(_)))
+            {
+                Err(Error::no_plugin_factory(plugin.plugin.plugin_id()))
+            } else {
+                plugin.instancing = instancing;
+                Ok(())
+            }
+        })
+    }
+
+    ///
+    /// Returns how lookups of the plugin with the provided plugin identifier return instances, if
+    /// the plugin is registered, else `None`.
+    ///
+    pub fn instancing(&self, plugin_id: &str) -> Option<Instancing> {
+        let plugins = self.plugins.load();
+        plugins
+            .latest(&self.id_normalization, plugin_id)
+            .map(|p| p.instancing)
+    }
+
+    ///
+    /// Pause all plugins that are not already paused, see
+    /// [`pause_plugin`](#method.pause_plugin). Plugins that have failed are skipped, and this
+    /// stops at the first plugin to report an error.
+    ///
+    pub fn pause_all(&self) -> Result<()> {
+        info!("PluginManager::pause_all()");
+        let _updates = self.updates.lock().unwrap();
+        self.try_update_registry(|plugins| {
+            plugins
+                .values_mut()
+                .filter(|plugin| !plugin.failed)
+                .try_for_each(|plugin| self.change_state(plugin, Hook::Pause))
+        })
+    }
+
+    ///
+    /// Resume all paused plugins, see [`resume_plugin`](#method.resume_plugin). Plugins that have
+    /// failed are skipped, and this stops at the first plugin to report an error.
+    ///
+    pub fn resume_all(&self) -> Result<()> {
+        info!("PluginManager::resume_all()");
+        let _updates = self.updates.lock().unwrap();
+        self.try_update_registry(|plugins| {
+            plugins
+                .values_mut()
+                .filter(|plugin| !plugin.failed)
+                .try_for_each(|plugin| self.change_state(plugin, Hook::Resume))
+        })
+    }
+
+    ///
+    /// Unload all plugins, and associated libraries, that are currently registered in this
+    /// plugin manager. Plugins are unloaded in the reverse of the order in which they were
+    /// registered, and this stops at the first plugin to report an error; see
+    /// [`shutdown`](#method.shutdown) to unload all plugins regardless of errors.
+    ///
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "info", skip_all))]
+    pub fn unload_all(&self) -> Result<()> {
+        info!("PluginManager::unload_all()");
+        let _updates = self.updates.lock().unwrap();
+        let _ = self.close_released_libraries(false);
+        for (plugin_id, version) in self.unload_order() {
+            if let Some(plugin) =
+                self.update_registry(|plugins| plugins.remove(&plugin_id, &version))
+            {
+                self.unload_loaded_plugin(plugin)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Unload all plugins, and associated libraries; this is an alias for
+    /// [`unload_all`](#method.unload_all).
+    ///
+    pub fn clear(&self) -> Result<()> {
+        self.unload_all()
+    }
+
+    ///
+    /// Keep only the plugins for which the predicate `f`, called with each plugin identifier
+    /// and plugin, returns `true`, and unload the rest. The predicate is called for every
+    /// version of every plugin, in the order in which they were registered, before any plugin is
+    /// unloaded. Plugins are then unloaded in the reverse of the order in which they were
+    /// registered, and as for [`unload_all`](#method.unload_all) this stops at the first plugin
+    /// to report an error.
+    ///
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "info", skip_all))]
+    pub fn retain<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&str, &Arc<T>) -> bool,
+    {
+        info!("PluginManager::retain(_)");
+        let _updates = self.updates.lock().unwrap();
+        let mut keys: Vec<(Arc<str>, Option<Version>)> = self
+            .ordered_plugins(|p| (p.key(), p.plugin.clone()))
+            .into_iter()
+            .filter(|((plugin_id, _), plugin)| !f(plugin_id, plugin))
+            .map(|(key, _)| key)
+            .collect();
+        keys.reverse();
+        for (plugin_id, version) in keys {
+            if let Some(plugin) =
+                self.update_registry(|plugins| plugins.remove(&plugin_id, &version))
+            {
+                self.unload_loaded_plugin(plugin)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Unload all plugins, and associated libraries, in the reverse of the order in which they
+    /// were registered. Unlike [`unload_all`](#method.unload_all) this continues past plugins
+    /// that report an error from `on_unload`, and returns a report of the outcome for each plugin.
+    ///
+    /// A plugin's `on_unload` method cannot be interrupted; once `timeout` has elapsed the
+    /// remaining plugins are removed without calling `on_unload` and are reported as timed out.
+    /// The plugin manager is empty when this returns.
+    ///
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "info", skip(self)))]
+    pub fn shutdown(&self, timeout: Duration) -> ShutdownReport {
+        info!("PluginManager::shutdown({:?})", timeout);
+        let _updates = self.updates.lock().unwrap();
+        self.shutdown_until(Instant::now().checked_add(timeout))
+    }
+
+    ///
+    /// Unload the plugin identified by the provided plugin identifier, if one exists, including
+    /// all registered versions of the plugin. Note that this method will also close the plugin
+    /// library if no other plugins are using it. If the host still holds references to the
+    /// plugin, for example returned by [`get`](#method.get), closing the library is deferred
+    /// until they have all been dropped; the library is then closed by a later unload, by
+    /// [`close_idle_libraries`](#method.close_idle_libraries), or when the plugin manager is
+    /// dropped. A library whose plugins are still referenced when the plugin manager is
+    /// dropped is closed in the background once they have all been dropped.
+    ///
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "info", skip(self), fields(plugin_id = plugin_name))
+    )]
+    pub fn unload_plugin(&self, plugin_name: &str) -> Result<()> {
+        info!("PluginManager::unload_plugin({:?})", plugin_name);
+        let _updates = self.updates.lock().unwrap();
+        let plugin_id = self
+            .plugins
+            .load()
+            .resolve(&self.id_normalization, plugin_name)
+            .to_string();
+        let _ = self.close_released_libraries(false);
+        self.unload_registered_plugin(&plugin_id)
+    }
+
+    ///
+    /// The first phase of a two-phase unload; calls the `on_unload` method of each registered
+    /// version of the plugin and removes them from the plugin manager, but leaves the plugin's
+    /// library open, even once no plugins from it remain. The library is only closed by a call
+    /// to [`close_idle_libraries`](#method.close_idle_libraries), the second phase, so that the
+    /// host controls exactly when native code is unmapped; for example at a safe point outside
+    /// a real-time thread. Every version is removed even if one reports an error, in which case
+    /// the first error is returned.
+    ///
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "info", skip(self), fields(plugin_id = plugin_id))
+    )]
+    pub fn deactivate_plugin(&self, plugin_id: &str) -> Result<()> {
+        info!("PluginManager::deactivate_plugin({:?})", plugin_id);
+        let _updates = self.updates.lock().unwrap();
+        let plugin_id = self
+            .plugins
+            .load()
+            .resolve(&self.id_normalization, plugin_id)
+            .to_string();
+        let plugins = self.update_registry(|plugins| plugins.remove_all(&plugin_id));
+        if plugins.is_empty() {
+            return Err(Error::unknown_plugin(&plugin_id));
+        }
+        let mut result = Ok(());
+        for plugin in plugins {
+            trace!("PluginManager::deactivate_plugin() > calling plugin `on_unload`");
+            let from = plugin.state();
+            let to = match self.interceptors.call(&plugin.plugin, Hook::Unload) {
+                Ok(()) => PluginState::Unloaded,
+                Err(e) => {
+                    error!("Error unloading plugin {:?}; {}", plugin_id, e);
+                    if result.is_ok() {
+                        result = Err(e.detach());
+                    }
+                    PluginState::Failed
+                }
+            };
+            self.record_state(plugin.plugin.plugin_id(), plugin.instance_id, from, to);
+            self.release_values(&plugin.in_library.canonical_name);
+            let LoadedPlugin {
+                plugin,
+                factory,
+                instances,
+                in_library,
+                ..
+            } = plugin;
+            drop(factory);
+            self.deferred_closes.lock().unwrap().push(DeferredClose {
+                plugin: Arc::downgrade(&plugin),
+                instances,
+                library: in_library,
+                held: true,
+            });
+        }
+        result
+    }
+
+    ///
+    /// The second phase of a two-phase unload; close the libraries of plugins deactivated with
+    /// [`deactivate_plugin`](#method.deactivate_plugin), and those whose close was deferred by
+    /// [`unload_plugin`](#method.unload_plugin), once no plugins from them remain registered and
+    /// the host holds no references to their plugins. Libraries still in use remain open until a
+    /// later call. All idle libraries are closed even if one fails, in which case the first error
+    /// is returned.
+    ///
+    pub fn close_idle_libraries(&self) -> Result<()> {
+        info!("PluginManager::close_idle_libraries()");
+        let _updates = self.updates.lock().unwrap();
+        self.close_released_libraries(true)
+    }
+
+    // --------------------------------------------------------------------------------------------
+
+    fn ordered_plugins<F, R>(&self, f: F) -> Vec<R>
+    where
+        F: Fn(&LoadedPlugin<T>) -> R,
+    {
+        let plugins = self.plugins.load();
+        let mut ordered: Vec<&LoadedPlugin<T>> = Vec::with_capacity(plugins.len());
+        ordered.extend(plugins.values());
+        ordered.sort_by_key(|p| p.sequence);
+        ordered.into_iter().map(f).collect()
+    }
+
+    fn unload_order(&self) -> Vec<(Arc<str>, Option<Version>)> {
+        let mut keys = self.ordered_plugins(LoadedPlugin::key);
+        keys.reverse();
+        keys
+    }
+
+    fn shutdown_until(&self, deadline: Option<Instant>) -> ShutdownReport {
+        let mut report = ShutdownReport::default();
+        for (plugin_id, version) in self.unload_order() {
+            let plugin = match self.update_registry(|plugins| plugins.remove(&plugin_id, &version))
+            {
+                Some(plugin) => plugin,
+                None => continue,
+            };
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                warn!(
+                    "Shutdown timed out, removing plugin {:?} without unloading",
+                    plugin_id
+                );
+                if let Err(e) = self.release_plugin(plugin, PluginState::Unloaded) {
+                    warn!("Error closing library during shutdown; {}", e);
+                }
+                report.timed_out.push(plugin_id.to_string());
+            } else {
+                match self.unload_loaded_plugin(plugin) {
+                    Ok(()) => report.unloaded.push(plugin_id.to_string()),
+                    Err(e) => {
+                        error!("Error unloading plugin {:?}; {}", plugin_id, e);
+                        report.failed.push((plugin_id.to_string(), e));
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    fn unload_loaded_plugin(&self, plugin: LoadedPlugin<T>) -> Result<()> {
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!("on_unload", plugin_id = %plugin.plugin.plugin_id(), instance_id = %plugin.instance_id).entered();
+        trace!(
+            "PluginManager::unload_plugin() > calling plugin `on_unload` for {:?} ({})",
+            plugin.plugin.plugin_id(),
+            plugin.instance_id
+        );
+        match self.interceptors.call(&plugin.plugin, Hook::Unload) {
+            Ok(()) => self.release_plugin(plugin, PluginState::Unloaded),
+            Err(e) => {
+                let e = e.detach();
+                if let Err(close_error) = self.release_plugin(plugin, PluginState::Failed) {
+                    warn!(
+                        "Error closing library after unload failure; {}",
+                        close_error
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+
+    ///
+    /// Unload each of the plugins, which have already been removed from the registry, in order.
+    /// Every plugin is unloaded, and its library released, even if one reports an error, in
+    /// which case the first error is returned.
+    ///
+    fn unload_loaded_plugins(&self, plugins: Vec<LoadedPlugin<T>>) -> Result<()> {
+        let mut result = Ok(());
+        for plugin in plugins {
+            let plugin_id = plugin.plugin_id.clone();
+            if let Err(e) = self.unload_loaded_plugin(plugin) {
+                error!("Error unloading plugin {:?}; {}", plugin_id, e);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    ///
+    /// Call the lifecycle method `hook`, one of enable, disable, pause, or resume, of a registered
+    /// plugin if it is not already in the resulting state. A plugin whose lifecycle method
+    /// reports an error is marked as failed, and a failed plugin cannot be changed.
+    ///
+    fn change_state(&self, plugin: &mut LoadedPlugin<T>, hook: Hook) -> Result<()> {
+        let (enabled, paused) = match hook {
+            Hook::Enable => (true, plugin.paused),
+            Hook::Disable => (false, plugin.paused),
+            Hook::Pause => (plugin.enabled, true),
+            _ => (plugin.enabled, false),
+        };
+        if !plugin.failed && enabled == plugin.enabled && paused == plugin.paused {
+            return Ok(());
+        }
+        let from = plugin.state();
+        if plugin.failed {
+            let to = match hook {
+                Hook::Enable | Hook::Resume => PluginState::Active,
+                Hook::Disable => PluginState::Disabled,
+                _ => PluginState::Loaded,
+            };
+            return Err(ErrorKind::InvalidStateTransition {
+                plugin_id: plugin.plugin.plugin_id().to_string(),
+                from,
+                to,
+            }
+            .into());
+        }
+        trace!("PluginManager::change_state() > calling plugin `{}`", hook);
+        if let Err(e) = self.interceptors.call(&plugin.plugin, hook) {
+            plugin.failed = true;
+            self.record_state(
+                plugin.plugin.plugin_id(),
+                plugin.instance_id,
+                from,
+                PluginState::Failed,
+            );
+            return Err(e.detach());
+        }
+        plugin.enabled = enabled;
+        plugin.paused = paused;
+        self.record_state(
+            plugin.plugin.plugin_id(),
+            plugin.instance_id,
+            from,
+            plugin.state(),
+        );
+        Ok(())
+    }
+
+    ///
+    /// The state of a plugin that is not registered, before it is loaded.
+    ///
+    fn previous_state(&self, plugin_id: &str) -> PluginState {
+        self.states
+            .lock()
+            .unwrap()
+            .get(plugin_id)
+            .copied()
+            .unwrap_or(PluginState::Discovered)
+    }
+
+    ///
+    /// Record the new state of a plugin, remembering it if the plugin is no longer registered,
+    /// and report the transition to the handler.
+    ///
+    fn record_state(
+        &self,
+        plugin_id: &str,
+        instance_id: InstanceId,
+        from: PluginState,
+        to: PluginState,
+    ) {
+        {
+            let mut states = self.states.lock().unwrap();
+            match to {
+                PluginState::Failed | PluginState::Unloaded => {
+                    let _ = states.insert(plugin_id.to_string(), to);
+                }
+                _ => {
+                    let _ = states.remove(plugin_id);
+                }
+            }
+        }
+        if from != to {
+            debug!(
+                "PluginManager::record_state() > {:?} ({}): {} -> {}",
+                plugin_id, instance_id, from, to
+            );
+            if let Some(handler) = self.transition_handler {
+                handler(StateTransition {
+                    plugin_id: plugin_id.to_string(),
+                    instance_id,
+                    from,
+                    to,
+                });
+            }
+        }
+    }
+
+    ///
+    /// The highest version of each plugin, in this manager or its parent, whose identifier
+    /// matches, keyed by plugin identifier.
+    ///
+    fn matching_plugins(&self, is_match: &dyn Fn(&str) -> bool) -> BTreeMap<String, Arc<T>> {
+        let mut matches = self
+            .parent
+            .as_ref()
+            .map(|parent| parent.matching_plugins(is_match))
+            .unwrap_or_default();
+        let plugins = self.plugins.load();
+        for (plugin_id, versions) in plugins.entries() {
+            if is_match(plugin_id) {
+                if let Some(plugin) = versions.values().next_back() {
+                    let _ = matches.insert(plugin_id.to_string(), plugin.instance());
+                }
+            }
+        }
+        matches
+    }
+
+    ///
+    /// Drop the manager's reference to an unloaded plugin, recording its final `state`, and
+    /// release its library. If the host still holds references to the plugin, closing the
+    /// library is deferred until they have all been dropped, as code from the library may still
+    /// be called through them; this includes instances created by the plugin's factory.
+    /// Locations loaded from a plugin source hold no code, and are released immediately.
+    ///
+    fn release_plugin(&self, plugin: LoadedPlugin<T>, state: PluginState) -> Result<()> {
+        self.record_state(
+            plugin.plugin.plugin_id(),
+            plugin.instance_id,
+            plugin.state(),
+            state,
+        );
+        self.release_values(&plugin.in_library.canonical_name);
+        let LoadedPlugin {
+            plugin,
+            instance_id,
+            factory,
+            instances,
+            in_library,
+            ..
+        } = plugin;
+        drop(factory);
+        if !in_library.is_source() && (Arc::strong_count(&plugin) > 1 || instances.is_referenced())
+        {
+            debug!(
+                "Plugin {:?} ({}) is still referenced, deferring close of library {:?}",
+                plugin.plugin_id(),
+                instance_id,
+                in_library.file_name
+            );
+            self.deferred_closes.lock().unwrap().push(DeferredClose {
+                plugin: Arc::downgrade(&plugin),
+                instances,
+                library: in_library,
+                held: false,
+            });
+            Ok(())
+        } else {
+            drop(plugin);
+            release_library(in_library)
+        }
+    }
+
+    ///
+    /// Release the libraries whose close was deferred, and whose plugins are no longer
+    /// referenced; those held by deactivated plugins are only released if `include_held`.
+    ///
+    fn close_released_libraries(&self, include_held: bool) -> Result<()> {
+        let released: Vec<DeferredClose<T>> = {
+            let mut deferred_closes = self.deferred_closes.lock().unwrap();
+            let (released, deferred) = deferred_closes.drain(..).partition(|deferred| {
+                (include_held || !deferred.held) && !deferred.is_referenced()
+            });
+            *deferred_closes = deferred;
+            released
+        };
+        let mut result = Ok(());
+        for deferred in released {
+            trace!(
+                "PluginManager::close_released_libraries() > releasing {:?}",
+                deferred.library.file_name
+            );
+            if let Err(e) = release_library(deferred.library) {
+                warn!("Error closing library after deferral; {}", e);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    fn unload_registered_plugin(&self, plugin_id: &str) -> Result<()> {
+        self.unload_loaded_plugins(self.update_registry(|plugins| plugins.remove_all(plugin_id)))
+    }
+
+    fn group_members(&self, group: &str) -> GroupMembers {
+        GroupMembers {
+            group: group.to_string(),
+            added: self
+                .groups
+                .read()
+                .unwrap()
+                .get(group)
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+
+    fn update_group(&self, group: &str, hook: Hook) -> Result<()> {
+        let _updates = self.updates.lock().unwrap();
+        let members = self.group_members(group);
+        self.try_update_registry(|registry| {
+            let mut plugins: Vec<&mut LoadedPlugin<T>> = registry
+                .values_mut()
+                .filter(|plugin| members.contains(plugin))
+                .collect();
+            plugins.sort_by_key(|plugin| plugin.sequence);
+            plugins
+                .into_iter()
+                .try_for_each(|plugin| self.change_state(plugin, hook))
+        })
+    }
+
+    ///
+    /// Apply `f` to every registered version of a plugin. This is all-or-nothing: if `f` fails
+    /// for any version the versions already changed are returned to their previous lifecycle
+    /// state, and the registry is left unchanged.
+    ///
+    fn update_plugin<F>(&self, plugin_id: &str, mut f: F) -> Result<()>
+    where
+        F: FnMut(&mut LoadedPlugin<T>) -> Result<()>,
+    {
+        let _updates = self.updates.lock().unwrap();
+        self.try_update_registry(|plugins| {
+            let plugin_id = plugins
+                .resolve(&self.id_normalization, plugin_id)
+                .to_string();
+            let versions = match plugins.versions_mut(&plugin_id) {
+                Some(versions) => versions,
+                None => return Err(Error::unknown_plugin(&plugin_id)),
+            };
+            let mut changed = Vec::new();
+            for plugin in versions {
+                let previous = (plugin.enabled, plugin.paused);
+                if let Err(e) = f(plugin) {
+                    for (plugin, (enabled, paused)) in changed.into_iter().rev() {
+                        self.revert_state(plugin, enabled, paused);
+                    }
+                    return Err(e);
+                }
+                if (plugin.enabled, plugin.paused) != previous {
+                    changed.push((plugin, previous));
+                }
+            }
+            Ok(())
+        })
+    }
+
     ///
-    /// #[no_mangle]
-    /// pub extern "C" fn register_sources(registrar: &mut PluginRegistrar<SoundSourcePlugin>) {
-    ///     registrar.register(SoundSourcePlugin::new(PLUGIN_NAME));
-    /// }
+    /// Return a plugin changed by [`change_state`](#method.change_state) to its previous
+    /// lifecycle state by calling the inverse lifecycle methods; an error is logged, and the
+    /// plugin marked as failed.
     ///
-    /// #[no_mangle]
-    /// pub extern "C" fn register_effects(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
-    ///     registrar.register(SoundEffectPlugin::new(OTHER_PLUGIN_NAME));
-    /// }
-    /// ```
+    fn revert_state(&self, plugin: &mut LoadedPlugin<T>, enabled: bool, paused: bool) {
+        let hooks = [
+            (plugin.paused != paused).then_some(if paused { Hook::Pause } else { Hook::Resume }),
+            (plugin.enabled != enabled).then_some(if enabled {
+                Hook::Enable
+            } else {
+                Hook::Disable
+            }),
+        ];
+        for hook in hooks.iter().flatten().copied() {
+            if let Err(e) = self.change_state(plugin, hook) {
+                warn!(
+                    "Error reverting plugin {:?} with `{}`; {}",
+                    plugin.plugin.plugin_id(),
+                    hook,
+                    e
+                );
+                return;
+            }
+        }
+    }
+
+    ///
+    /// Disable the registered plugins listed as disabled in `config`, see
+    /// [`PluginManagerConfiguration::disabled_plugins`](../config/struct.PluginManagerConfiguration.html#method.disabled_plugins).
+    ///
+    pub(crate) fn disable_configured_plugins(
+        &self,
+        config: &PluginManagerConfiguration,
+    ) -> Result<()> {
+        let _updates = self.updates.lock().unwrap();
+        self.try_update_registry(|registry| self.disable_plugins_in(registry, config))
+    }
+
+    fn disable_plugins_in(
+        &self,
+        registry: &mut Registry<T>,
+        config: &PluginManagerConfiguration,
+    ) -> Result<()> {
+        registry
+            .values_mut()
+            .filter(|p| !p.failed && config.is_plugin_disabled(p.plugin.plugin_id()))
+            .try_for_each(|p| self.change_state(p, Hook::Disable))
+    }
+
+    ///
+    /// Apply `f` to a copy of the registry and publish the copy; readers holding the previous
+    /// snapshot are unaffected. The caller must hold the `updates` lock.
+    ///
+    fn update_registry<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Registry<T>) -> R,
+    {
+        let mut registry = Registry::clone(&self.plugins.load());
+        let result = f(&mut registry);
+        self.plugins.store(Arc::new(registry));
+        result
+    }
+
+    ///
+    /// Apply `f` to a copy of the registry and publish the copy only if `f` succeeds. If `f`
+    /// fails the registry is left unchanged, except that any plugin `f` marked as failed is
+    /// marked as failed in the published registry. The caller must hold the `updates` lock.
+    ///
+    fn try_update_registry<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Registry<T>) -> Result<()>,
+    {
+        let current = self.plugins.load_full();
+        let mut registry = Registry::clone(&current);
+        match f(&mut registry) {
+            Ok(()) => {
+                self.plugins.store(Arc::new(registry));
+                Ok(())
+            }
+            Err(e) => {
+                let failed: HashSet<InstanceId> = registry
+                    .values_mut()
+                    .filter(|plugin| plugin.failed)
+                    .map(|plugin| plugin.instance_id)
+                    .collect();
+                let mut registry = Registry::clone(&current);
+                let mut changed = false;
+                for plugin in registry
+                    .values_mut()
+                    .filter(|plugin| !plugin.failed && failed.contains(&plugin.instance_id))
+                {
+                    plugin.failed = true;
+                    changed = true;
+                }
+                if changed {
+                    self.plugins.store(Arc::new(registry));
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn plugin_library(&self, plugin_id: &str) -> Option<PathBuf> {
+        if let Some(library) = self.plugin_libraries.get(plugin_id) {
+            trace!("PluginManager::plugin_library() > found in configured libraries");
+            return Some(library.clone());
+        }
+        if let Some(library) = self
+            .plugin_index
+            .as_ref()
+            .and_then(|plugin_index| plugin_index.library_for(plugin_id))
+        {
+            trace!("PluginManager::plugin_library() > found in plugin index");
+            return Some(library.clone());
+        }
+        trace!("PluginManager::plugin_library() > checking manifests on search path");
+        self.search_path
+            .iter()
+            .filter_map(|directory| std::fs::read_dir(directory).ok())
+            .flat_map(|entries| entries.filter_map(|entry| entry.ok().map(|e| e.path())))
+            .filter(|file_name| file_name.is_file() && parse_dylib_name(file_name).is_some())
+            .find(|file_name| {
+                let manifest = match &self.check_cache {
+                    Some(check_cache) => check_cache.read_manifest(file_name),
+                    None => read_manifest(file_name),
+                };
+                manifest.ok().flatten().is_some_and(|manifest| {
+                    manifest
+                        .plugins()
+                        .any(|plugin| plugin.plugin_id() == plugin_id)
+                })
+            })
+    }
+
+    fn find_library<P>(&self, file_name: P) -> PathBuf
+    where
+        P: AsRef<Path>,
+    {
+        canonical_path(&self.resolve_library(file_name.as_ref()))
+    }
+
+    fn resolve_library(&self, file_name: &Path) -> PathBuf {
+        let platform = Platform::current();
+        if platform == Platform::MacOs {
+            let loader_directory = executable_directory();
+            let mut run_paths: Vec<PathBuf> = self.search_path.iter().cloned().collect();
+            run_paths.push(loader_directory.clone());
+            run_paths.push(loader_directory.join("../Frameworks"));
+            if let Some(candidates) =
+                platform.expand_install_name(file_name, &loader_directory, &run_paths)
+            {
+                trace!(
+                    "PluginManager::find_library() > expanded install name to {:?}",
+                    candidates
+                );
+                return candidates
+                    .iter()
+                    .map(|candidate| self.resolve_library_name(candidate))
+                    .find(|candidate| candidate.is_file())
+                    .unwrap_or_else(|| file_name.to_path_buf());
+            }
+        }
+        self.resolve_library_name(file_name)
+    }
+
+    fn resolve_library_name(&self, file_name: &Path) -> PathBuf {
+        let platform = Platform::current();
+        let platform_name;
+        let file_name = if self.auto_platform_names && file_name.extension().is_none() {
+            platform_name = platform.make_dylib_name(file_name);
+            trace!(
+                "PluginManager::find_library() > using platform name {:?}",
+                platform_name
+            );
+            platform_name.as_path()
+        } else {
+            file_name
+        };
+        if file_name.is_relative() && !self.search_path.is_empty() {
+            trace!("PluginManager::find_library() > checking search path for library");
+            if let Some(found) = self.search_path.find_file(file_name) {
+                return found;
+            }
+            trace!("PluginManager::find_library() > checking search path for versioned library");
+            if let Some(found) = self
+                .search_path
+                .iter()
+                .find_map(|directory| platform.find_versioned_dylib(&directory.join(file_name)))
+            {
+                return found;
+            }
+            if file_name.extension().is_none() {
+                trace!("PluginManager::find_library() > checking search path for platform names");
+                if let Some(found) = platform
+                    .dylib_name_candidates(file_name)
+                    .iter()
+                    .find_map(|candidate| self.search_path.find_file(candidate))
+                {
+                    return found;
+                }
+            }
+            if let Some(found) = self
+                .search_path
+                .iter()
+                .find_map(|directory| platform.framework_library(&directory.join(file_name)))
+            {
+                trace!("PluginManager::find_library() > found framework on search path");
+                return found;
+            }
+        } else if let Some(found) = platform.framework_library(file_name) {
+            trace!("PluginManager::find_library() > found framework library");
+            return found;
+        } else if !file_name.exists() {
+            if file_name
+                .parent()
+                .is_some_and(|p| !p.as_os_str().is_empty())
+            {
+                trace!("PluginManager::find_library() > checking for versioned library");
+                if let Some(found) = platform.find_versioned_dylib(file_name) {
+                    return found;
+                }
+            }
+            if file_name.extension().is_none() {
+                trace!("PluginManager::find_library() > checking for platform names");
+                if let Some(found) = platform
+                    .dylib_name_candidates(file_name)
+                    .into_iter()
+                    .find(|candidate| candidate.is_file())
+                {
+                    return found;
+                }
+            }
+        }
+        file_name.to_path_buf()
+    }
+
+    #[cfg(target_os = "windows")]
+    #[allow(unsafe_code)]
+    fn open_library(
+        &self,
+        file_name: &Path,
+        dependency_directory: Option<&Path>,
+    ) -> std::result::Result<Library, libloading::Error> {
+        use libloading::os::windows::{
+            Library as WindowsLibrary, LOAD_LIBRARY_SEARCH_DEFAULT_DIRS,
+            LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR,
+        };
+
+        if self.dll_directories.is_empty() && dependency_directory.is_none() {
+            return unsafe { Library::new(file_name) };
+        }
+
+        trace!("PluginManager::open_library() > adding DLL directories");
+        let cookies: Vec<windows::DllDirectoryCookie> = self
+            .dll_directories
+            .iter()
+            .map(PathBuf::as_path)
+            .chain(dependency_directory)
+            .filter_map(windows::add_dll_directory)
+            .collect();
+
+        // LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR is only valid for fully qualified paths.
+        let flags = if file_name.is_absolute() {
+            LOAD_LIBRARY_SEARCH_DEFAULT_DIRS | LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR
+        } else {
+            LOAD_LIBRARY_SEARCH_DEFAULT_DIRS
+        };
+        let result = unsafe { WindowsLibrary::load_with_flags(file_name, flags) };
+
+        trace!("PluginManager::open_library() > removing DLL directories");
+        for cookie in cookies {
+            windows::remove_dll_directory(cookie);
+        }
+
+        result.map(Library::from)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[allow(unsafe_code)]
+    fn open_library(
+        &self,
+        file_name: &Path,
+        _dependency_directory: Option<&Path>,
+    ) -> std::result::Result<Library, libloading::Error> {
+        if !self.dll_directories.is_empty() {
+            debug!("PluginManager::open_library() > DLL directories ignored on this platform");
+        }
+        unsafe { Library::new(file_name) }
+    }
+
+    #[allow(unsafe_code)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(library = ?library.file_name))
+    )]
+    fn check_compatibility(&self, library: &LoadedLibrary) -> Result<()> {
+        let compatibility_fn = unsafe {
+            let loader_fn: Symbol<'_, CompatibilityFn> = library.get(COMPATIBILITY_FN_NAME)?;
+            loader_fn
+        };
+        trace!("PluginManager::check_compatibility() > fetching library compatibility hash");
+        let lib_compatibility_hash: u64 = compatibility_fn();
+        if let Some(check_cache) = &self.check_cache {
+            check_cache.set_compatibility_hash(&library.canonical_name, lib_compatibility_hash);
+        }
+        trace!("PluginManager::check_compatibility() > fetching local compatibility hash");
+        let local_compatibility_hash: u64 = compatibility_hash();
+        if lib_compatibility_hash != local_compatibility_hash {
+            error!(
+                "Version incompatibility {:?} != {:?}",
+                lib_compatibility_hash, local_compatibility_hash
+            );
+            return Err(incompatible_library(
+                &library.file_name,
+                &self.host_compatibility,
+                library_compatibility(library),
+            ));
+        }
+        trace!("PluginManager::check_compatibility() > compatibility version check passed");
+        Ok(())
+    }
+
+    ///
+    /// Verify the signature of `opened_name`, the file that is, or was, handed to the platform
+    /// loader for `file_name`; this is the shadow copy if there is one. A library that cannot be
+    /// resolved to a file, for example a bare name left for the platform loader to search for,
+    /// fails verification as the file the loader would open is not known.
+    ///
+    #[cfg(feature = "verify")]
+    fn verify_signature(&self, file_name: &Path, opened_name: &Path) -> Result<()> {
+        match &self.signature_verifier {
+            Some(_) if !opened_name.is_file() => Err(Error::signature_verification_failed(
+                file_name,
+                "the library could not be resolved to a file to verify",
+            )),
+            Some(signature_verifier) => {
+                trace!("PluginManager::verify_signature({:?})", opened_name);
+                signature_verifier.verify(opened_name)
+            }
+            None => Ok(()),
+        }
+    }
+
+    ///
+    /// Check the inputs added with `add_compatibility_input`; unlike the compatibility hash these
+    /// belong to this plugin manager, and so are checked even when the library was already
+    /// opened by another.
+    ///
+    fn check_compatibility_inputs(&self, library: &LoadedLibrary) -> Result<()> {
+        if self.host_compatibility.inputs().next().is_some() {
+            trace!("PluginManager::check_compatibility_inputs() > checking host inputs");
+            let found = library_compatibility(library);
+            if found
+                .as_ref()
+                .is_none_or(|found| !found.differences(&self.host_compatibility).is_empty())
+            {
+                error!(
+                    "Compatibility input mismatch for library {:?}",
+                    library.file_name
+                );
+                return Err(incompatible_library(
+                    &library.file_name,
+                    &self.host_compatibility,
+                    found,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Load a batch of libraries in two passes, returning the number of libraries loaded;
+    /// libraries already loaded, and quarantined libraries, are not counted. First each library
+    /// is opened and its plugins registered, then the libraries are loaded in priority order, see
+    /// `PluginRegistrar::set_priority`. If a library fails to open or register, those before it
+    /// in the batch are still loaded before the error is returned.
+    ///
+    fn load_batch(&self, batch: Vec<(PathBuf, EntryPoint)>) -> Result<usize> {
+        let first_sequence = self.next_sequence.load(Ordering::Relaxed);
+        let count = batch.len();
+        let mut loaded = 0;
+        let mut entries: Vec<BatchEntry<T>> = Vec::with_capacity(count);
+        let mut failed = None;
+        for (index, (file_name, entry_point)) in batch.into_iter().enumerate() {
+            if self.is_load_cancelled() {
+                info!("PluginManager::load_batch() > cancelled, rolling back");
+                return Err(ErrorKind::LoadCancelled {
+                    unloaded: self.roll_back_batch(first_sequence),
+                }
+                .into());
+            }
+            let start = Instant::now();
+            match self.prepare_library(&file_name, &file_name, &entry_point, &entries) {
+                Err(e) if !matches!(e.kind(), ErrorKind::LibraryQuarantined { .. }) => {
+                    failed = Some((index + 1, file_name, start.elapsed(), e));
+                    break;
+                }
+                prepared => entries.push(BatchEntry {
+                    index: index + 1,
+                    library: file_name,
+                    prepared,
+                }),
+            }
+        }
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.priority()));
+        for entry in entries {
+            if self.is_load_cancelled() {
+                info!("PluginManager::load_batch() > cancelled, rolling back");
+                return Err(ErrorKind::LoadCancelled {
+                    unloaded: self.roll_back_batch(first_sequence),
+                }
+                .into());
+            }
+            self.report_started(&entry.library, entry.index, count);
+            let (duration, result) = match entry.prepared {
+                Ok(Some(prepared)) => {
+                    let duration = prepared.duration;
+                    let start = Instant::now();
+                    let result = self.load_prepared_library(prepared);
+                    if result.is_ok() {
+                        loaded += 1;
+                    }
+                    (duration + start.elapsed(), result)
+                }
+                Ok(None) => (Duration::default(), Ok(())),
+                Err(e) => {
+                    info!("Skipping quarantined library {:?}; {}", entry.library, e);
+                    (Duration::default(), Err(e))
+                }
+            };
+            self.report_finished(&entry.library, entry.index, count, duration, result.is_ok());
+            match result {
+                Err(e) if !matches!(e.kind(), ErrorKind::LibraryQuarantined { .. }) => {
+                    return Err(e)
+                }
+                _ => {}
+            }
+        }
+
+        match failed {
+            None => Ok(loaded),
+            Some((index, file_name, duration, e)) => {
+                self.report_started(&file_name, index, count);
+                self.report_finished(&file_name, index, count, duration, false);
+                Err(e)
+            }
+        }
+    }
+
+    fn is_load_cancelled(&self) -> bool {
+        self.cancellation_token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    fn roll_back_batch(&self, first_sequence: u64) -> Vec<String> {
+        let mut keys = self.ordered_plugins(|p| (p.sequence >= first_sequence).then(|| p.key()));
+        keys.reverse();
+        keys.into_iter()
+            .flatten()
+            .map(|(plugin_id, version)| {
+                if let Some(plugin) =
+                    self.update_registry(|plugins| plugins.remove(&plugin_id, &version))
+                {
+                    if let Err(e) = self.unload_loaded_plugin(plugin) {
+                        warn!("Error unloading plugin during roll back; {}", e);
+                    }
+                }
+                plugin_id.to_string()
+            })
+            .collect()
+    }
+
+    fn report_started(&self, library: &Path, index: usize, count: usize) {
+        if let Some(handler) = self.progress_handler {
+            handler(ProgressEvent::LibraryStarted {
+                library: library.to_path_buf(),
+                index,
+                count,
+            });
+        }
+    }
+
+    fn report_finished(
+        &self,
+        library: &Path,
+        index: usize,
+        count: usize,
+        duration: Duration,
+        succeeded: bool,
+    ) {
+        if let Some(handler) = self.progress_handler {
+            handler(ProgressEvent::LibraryFinished {
+                library: library.to_path_buf(),
+                index,
+                count,
+                duration,
+                succeeded,
+            });
+        }
+    }
+
+    fn load_plugins_with(
+        &self,
+        file_name: &Path,
+        library_specifier: &Path,
+        entry_point: &EntryPoint,
+    ) -> Result<()> {
+        match self.prepare_library(file_name, library_specifier, entry_point, &[])? {
+            Some(prepared) => self.load_prepared_library(prepared),
+            None => Ok(()),
+        }
+    }
+
+    ///
+    /// Open the library and register its plugins, returning `None` if the library is already
+    /// loaded, or is `pending` in the current batch, and need not be loaded again. A library
+    /// already opened for another entry point in the batch is not opened again. The
+    /// `library_specifier` is the name the library was provided as, which is usually `file_name`.
+    ///
+    fn prepare_library(
+        &self,
+        file_name: &Path,
+        library_specifier: &Path,
+        entry_point: &EntryPoint,
+        pending: &[BatchEntry<T>],
+    ) -> Result<Option<PreparedLibrary<T>>> {
+        let file_name = self.find_library(file_name);
+        if self.is_quarantined(&file_name) {
+            return Err(Error::library_quarantined(&file_name));
+        }
+        if !self.should_load(&file_name, entry_point, pending)? {
+            return Ok(None);
+        }
+        let start = Instant::now();
+        let opened = pending.iter().find_map(|entry| match &entry.prepared {
+            Ok(Some(prepared)) if prepared.library.canonical_name == file_name => {
+                Some(prepared.library.clone())
+            }
+            _ => None,
+        });
+        let result = match opened {
+            Some(library) => {
+                trace!("PluginManager::prepare_library() > library already opened in this batch");
+                Ok(library)
+            }
+            None => self.open_compatible_library(file_name.clone()),
+        }
+        .and_then(|library| {
+            trace!("PluginManager::prepare_library() > registering the plugins");
+            let (plugins, values) = self.registered_plugins(&library, entry_point)?;
+            Ok(PreparedLibrary {
+                file_name: file_name.clone(),
+                library_specifier: library_specifier.to_path_buf(),
+                entry_point: entry_point.clone(),
+                library,
+                plugins,
+                values,
+                duration: start.elapsed(),
+            })
+        });
+        match result {
+            Ok(prepared) => Ok(Some(prepared)),
+            Err(e) => {
+                self.record_library_load(&file_name, start.elapsed(), Some(&e));
+                Err(e)
+            }
+        }
+    }
+
+    fn load_prepared_library(&self, prepared: PreparedLibrary<T>) -> Result<()> {
+        let PreparedLibrary {
+            file_name,
+            library_specifier,
+            entry_point,
+            library,
+            plugins,
+            values,
+            duration,
+        } = prepared;
+        let start = Instant::now();
+        let result = self.load_registered_plugins(
+            library,
+            &library_specifier,
+            &entry_point,
+            plugins,
+            values,
+        );
+        self.record_library_load(
+            &file_name,
+            duration + start.elapsed(),
+            result.as_ref().err(),
+        );
+        result
+    }
+
+    fn should_load(
+        &self,
+        file_name: &Path,
+        entry_point: &EntryPoint,
+        pending: &[BatchEntry<T>],
+    ) -> Result<bool> {
+        let registry = self.plugins.load();
+        let loaded = (registry.contains_library(file_name)
+            && registry.values().any(|p| {
+                p.in_library.canonical_name == file_name && *p.entry_point == *entry_point
+            }))
+            || pending.iter().any(|entry| {
+                entry.prepared.as_ref().is_ok_and(|prepared| {
+                    prepared.as_ref().is_some_and(|prepared| {
+                        prepared.library.canonical_name == file_name
+                            && prepared.entry_point == *entry_point
+                    })
+                })
+            });
+        if !loaded {
+            return Ok(true);
+        }
+        match self.duplicate_load_policy {
+            DuplicateLoadPolicy::Ignore => {
+                info!("Library {:?} is already loaded, ignoring", file_name);
+                Ok(false)
+            }
+            DuplicateLoadPolicy::Error => Err(Error::library_already_loaded(file_name)),
+            DuplicateLoadPolicy::Replace => {
+                warn!(
+                    "Library {:?} is already loaded, replacing plugins",
+                    file_name
+                );
+                Ok(true)
+            }
+        }
+    }
+
+    fn record_library_load(&self, file_name: &Path, duration: Duration, error: Option<&Error>) {
+        self.metrics
+            .write()
+            .unwrap()
+            .record_library_load(file_name, duration, error.is_none());
+
+        if let (Some(quarantine), Some(e)) = (&self.quarantine, error) {
+            if !matches!(e.kind(), ErrorKind::LibraryOpenFailed { .. }) {
+                warn!("Quarantining library {:?}; {}", file_name, e);
+                let _ = quarantine.lock().unwrap().insert(
+                    file_name.to_path_buf(),
+                    QuarantinedLibrary {
+                        library: file_name.to_path_buf(),
+                        reason: e.to_string(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn load_source_location(
+        &self,
+        source: &Arc<dyn PluginSource<T>>,
+        location: &Path,
+    ) -> Result<()> {
+        let canonical_name = canonical_path(location);
+        if !self.should_load(&canonical_name, &EntryPoint::Source, &[])? {
+            return Ok(());
+        }
+        let start = Instant::now();
+        let result = source.load(location).and_then(|plugins| {
+            trace!("PluginManager::load_source_location() > registering the plugins");
+            let from_library = Arc::new(LoadedLibrary {
+                file_name: location.to_path_buf(),
+                canonical_name,
+                library: LibraryKind::Source(SourceLocation::new(source, location)),
+                shadow_copy: None,
+            });
+            let registration_fn: Arc<str> = Arc::from(EntryPoint::Source.to_string());
+            let plugins = self.capable_plugins(
+                plugins
+                    .into_iter()
+                    .map(|plugin| RegisteredPlugin {
+                        plugin,
+                        instance_id: InstanceId::next(),
+                        factory: None,
+                        registration_fn: registration_fn.clone(),
+                        priority: 0,
+                    })
+                    .collect(),
+            )?;
+            self.load_registered_plugins(
+                from_library,
+                location,
+                &EntryPoint::Source,
+                plugins,
+                Vec::default(),
+            )
+        });
+        self.metrics.write().unwrap().record_library_load(
+            location,
+            start.elapsed(),
+            result.is_ok(),
+        );
+        result
+    }
+
+    fn is_quarantined(&self, file_name: &Path) -> bool {
+        self.quarantine
+            .as_ref()
+            .is_some_and(|quarantine| quarantine.lock().unwrap().contains_key(file_name))
+    }
+
+    fn open_compatible_library(&self, file_name: PathBuf) -> Result<Arc<LoadedLibrary>> {
+        #[cfg(feature = "verify")]
+        let mut verified = false;
+        let library = self
+            .library_cache
+            .get_or_open(&file_name, |file_name, canonical_name| {
+                if self.check_cache.as_ref().is_some_and(|check_cache| {
+                    check_cache
+                        .compatibility_hash(&canonical_name)
+                        .is_some_and(|hash| hash != compatibility_hash())
+                }) {
+                    info!(
+                        "Library {:?} is unchanged and known to be incompatible, not opening",
+                        file_name
+                    );
+                    return Err(incompatible_library(
+                        &file_name,
+                        &self.host_compatibility,
+                        None,
+                    ));
+                }
+
+                if self.check_architecture && file_name.is_file() {
+                    trace!("PluginManager::open_compatible_library() > checking architecture");
+                    check_architecture(&file_name)?;
+                }
+
+                let shadow_copy = match &self.shadow_copy_directory {
+                    Some(directory) if file_name.is_file() => {
+                        trace!("PluginManager::open_compatible_library() > shadow copying");
+                        Some(
+                            ShadowCopy::create(directory, &file_name)
+                                .map_err(|e| Error::shadow_copy_failed(&file_name, e))?,
+                        )
+                    }
+                    _ => None,
+                };
+
+                #[cfg(feature = "verify")]
+                {
+                    let opened_name = shadow_copy
+                        .as_ref()
+                        .map_or(file_name.as_path(), |shadow_copy| shadow_copy.path());
+                    self.verify_signature(&file_name, opened_name)?;
+                    verified = true;
+                }
+
+                trace!("PluginManager::open_compatible_library() > opening library");
+                let library = match &shadow_copy {
+                    Some(shadow_copy) => {
+                        self.open_library(shadow_copy.path(), canonical_name.parent())
+                    }
+                    None => self.open_library(&file_name, None),
+                }
+                .map_err(|e| {
+                    let reason = classify_open_failure(&file_name, &e);
+                    error!("Library {:?} failed to open, {}; {}", file_name, reason, e);
+                    if reason == OpenFailureReason::MissingDependency {
+                        if let Ok(explanation) = explain_load_failure(&file_name) {
+                            error!("{}", explanation);
+                        }
+                    }
+                    Error::from(e)
+                        .with_library(&file_name)
+                        .with_open_failure_reason(reason)
+                })?;
+
+                let loaded_library = LoadedLibrary {
+                    file_name,
+                    canonical_name,
+                    library: LibraryKind::Dynamic(library),
+                    shadow_copy,
+                };
+
+                trace!("PluginManager::open_compatible_library() > checking compatibility");
+                self.check_compatibility(&loaded_library)?;
+
+                Ok(loaded_library)
+            })?;
+        #[cfg(feature = "verify")]
+        if !verified && !library.is_source() {
+            self.verify_signature(&library.file_name, library.opened_name())?;
+        }
+        self.check_compatibility_inputs(&library)?;
+        Ok(library)
+    }
+
+    fn registered_plugins(
+        &self,
+        from_library: &Arc<LoadedLibrary>,
+        entry_point: &EntryPoint,
+    ) -> Result<(Vec<RegisteredPlugin<T>>, Vec<ValueRegistration>)> {
+        trace!(
+            "PluginManager::registered_plugins(_, {:?}, {})",
+            &from_library.file_name,
+            entry_point
+        );
+        let (plugins, values) = match entry_point {
+            EntryPoint::Function(fn_name) => {
+                let fn_name = self.registration_symbol(from_library, fn_name)?;
+                let mut registrar = self.call_registration_fn(from_library, &fn_name)?;
+                let priority = registrar.priority();
+                let registration_fn: Arc<str> = Arc::from(fn_name.to_string_lossy());
+                let values = registrar.take_values();
+                let plugins = registrar
+                    .plugins()
+                    .map_err(Error::detach)?
+                    .into_iter()
+                    .map(|(plugin, factory)| RegisteredPlugin {
+                        plugin,
+                        instance_id: InstanceId::next(),
+                        factory,
+                        registration_fn: registration_fn.clone(),
+                        priority,
+                    })
+                    .collect();
+                (plugins, values)
+            }
+            EntryPoint::Prefix(prefix) => self.discovered_plugins(from_library, prefix)?,
+            EntryPoint::Source => {
+                return Err(Error::symbol_not_found(
+                    &from_library.file_name,
+                    &entry_point.to_string(),
+                    "plugins from a plugin source have no registration function",
+                ))
+            }
+        };
+        Ok((self.capable_plugins(plugins)?, values))
+    }
+
+    fn capable_plugins(
+        &self,
+        plugins: Vec<RegisteredPlugin<T>>,
+    ) -> Result<Vec<RegisteredPlugin<T>>> {
+        if self.required_capabilities.is_empty() {
+            return Ok(plugins);
+        }
+        let mut capable = Vec::with_capacity(plugins.len());
+        for registered in plugins {
+            let plugin = &registered.plugin;
+            let declared = plugin.plugin_capabilities();
+            let missing: Vec<String> = self
+                .required_capabilities
+                .iter()
+                .filter(|required| !declared.contains(&required.as_str()))
+                .cloned()
+                .collect();
+            if missing.is_empty() {
+                capable.push(registered);
+            } else if self.capability_policy == CapabilityPolicy::Skip {
+                warn!(
+                    "Plugin `{}` does not declare the required capabilities {:?}, skipping",
+                    plugin.plugin_id(),
+                    missing
+                );
+            } else {
+                error!(
+                    "Plugin `{}` does not declare the required capabilities {:?}",
+                    plugin.plugin_id(),
+                    missing
+                );
+                return Err(ErrorKind::MissingCapabilities {
+                    plugin_id: plugin.plugin_id().to_string(),
+                    capabilities: missing,
+                }
+                .into());
+            }
+        }
+        Ok(capable)
+    }
+
+    fn discovered_plugins(
+        &self,
+        from_library: &Arc<LoadedLibrary>,
+        prefix: &str,
+    ) -> Result<(Vec<RegisteredPlugin<T>>, Vec<ValueRegistration>)> {
+        let mut supported: BTreeMap<String, (u32, String)> = Default::default();
+        for name in exported_symbols(&from_library.file_name)?
+            .into_iter()
+            .filter(|name| name.starts_with(prefix))
+        {
+            let (base_name, version) = split_protocol_version(&name);
+            if version > REGISTRATION_PROTOCOL_VERSION {
+                debug!(
+                    "PluginManager::discovered_plugins() > skipping `{}`, unsupported protocol",
+                    name
+                );
+                continue;
+            }
+            let base_name = base_name.to_string();
+            if supported
+                .get(&base_name)
+                .is_none_or(|(current, _)| version > *current)
+            {
+                let _ = supported.insert(base_name, (version, name));
+            }
+        }
+        let fn_names: Vec<String> = supported.into_values().map(|(_, name)| name).collect();
+        if fn_names.is_empty() {
+            return Err(Error::symbol_not_found(
+                &from_library.file_name,
+                &format!("{}*", prefix),
+                "no exported symbols match the registration function prefix",
+            ));
+        }
+
+        let mut plugins = Vec::default();
+        let mut values = Vec::default();
+        let mut last_incompatible = None;
+        for fn_name in fn_names {
+            let symbol_name = CString::new(fn_name.as_str())
+                .map_err(|e| Error::symbol_not_found(&from_library.file_name, &fn_name, e))?;
+            let mut registrar = self.call_registration_fn(from_library, &symbol_name)?;
+            if registrar.is_incompatible() {
+                debug!(
+                    "PluginManager::discovered_plugins() > skipping incompatible `{}`",
+                    fn_name
+                );
+                last_incompatible = registrar.plugins().err();
+            } else {
+                let priority = registrar.priority();
+                let registration_fn: Arc<str> = Arc::from(fn_name.as_str());
+                values.extend(registrar.take_values());
+                plugins.extend(registrar.plugins().map_err(Error::detach)?.into_iter().map(
+                    |(plugin, factory)| RegisteredPlugin {
+                        plugin,
+                        instance_id: InstanceId::next(),
+                        factory,
+                        registration_fn: registration_fn.clone(),
+                        priority,
+                    },
+                ));
+            }
+        }
+
+        match last_incompatible {
+            Some(e) if plugins.is_empty() => Err(e.detach()),
+            _ => Ok((plugins, values)),
+        }
+    }
+
     ///
-    pub fn set_registration_fn_name(&mut self, name: &[u8]) {
-        self.registration_fn_name = name.to_vec()
+    /// Return the name of the registration function `fn_name` for the highest registration
+    /// protocol version exported by the library, falling back to `fn_name` itself.
+    ///
+    #[allow(unsafe_code)]
+    fn registration_symbol(
+        &self,
+        from_library: &Arc<LoadedLibrary>,
+        fn_name: &CStr,
+    ) -> Result<CString> {
+        let base_name = fn_name.to_string_lossy();
+        for version in (1..=REGISTRATION_PROTOCOL_VERSION).rev() {
+            let versioned = CString::new(format!("{}_v{}", base_name, version))
+                .map_err(|e| Error::symbol_not_found(&from_library.file_name, &base_name, e))?;
+            let exported = unsafe { from_library.get::<*const ()>(&versioned).is_ok() };
+            if exported {
+                trace!(
+                    "PluginManager::registration_symbol() > found protocol version {}",
+                    version
+                );
+                return Ok(versioned);
+            }
+        }
+        let exported = unsafe { from_library.get::<*const ()>(fn_name).is_ok() };
+        if !exported {
+            let mut versions: Vec<u32> = exported_symbols(&from_library.file_name)
+                .unwrap_or_default()
+                .iter()
+                .map(|name| split_protocol_version(name))
+                .filter(|(name, version)| *name == base_name && *version > 1)
+                .map(|(_, version)| version)
+                .collect();
+            if !versions.is_empty() {
+                versions.sort_unstable();
+                return Err(ErrorKind::UnsupportedRegistrationProtocol {
+                    registration_fn: base_name.to_string(),
+                    versions,
+                }
+                .into());
+            }
+        }
+        Ok(fn_name.to_owned())
+    }
+
+    #[allow(unsafe_code)]
+    fn call_registration_fn(
+        &self,
+        from_library: &Arc<LoadedLibrary>,
+        fn_name: &CStr,
+    ) -> Result<PluginRegistrar<T>> {
+        let load_fn = unsafe {
+            let loader_fn: Symbol<'_, PluginRegistrationFn<T>> = from_library.get(fn_name)?;
+            loader_fn
+        };
+
+        trace!(
+            "PluginManager::call_registration_fn() > calling `{}`",
+            fn_name.to_string_lossy()
+        );
+        let mut registrar = PluginRegistrar::new(
+            self.host_info.clone(),
+            self.host_api.for_registration(),
+            self.event_sink.clone(),
+            self.blackboard.for_library(from_library),
+            plugin_type_hash::<T>(),
+            self.max_plugins_per_library,
+        );
+        load_fn(&mut registrar);
+        if registrar.is_limit_exceeded() {
+            return Err(plugin_limit_exceeded(
+                from_library,
+                PluginLimit::PluginsPerLibrary,
+                self.max_plugins_per_library.unwrap_or_default(),
+            ));
+        }
+        Ok(registrar)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(library = ?from_library.file_name))
+    )]
+    fn load_registered_plugins(
+        &self,
+        from_library: Arc<LoadedLibrary>,
+        library_specifier: &Path,
+        entry_point: &EntryPoint,
+        plugins: Vec<RegisteredPlugin<T>>,
+        values: Vec<ValueRegistration>,
+    ) -> Result<()> {
+        if let Err(e) = self.check_plugin_limits(&from_library, plugins.len()) {
+            drop(values);
+            drop(plugins);
+            if let Err(e) = release_library(from_library) {
+                warn!("Error closing library after exceeding a limit; {}", e);
+            }
+            return Err(e);
+        }
+
+        let mut loaded: Vec<RegisteredPlugin<T>> = Vec::with_capacity(plugins.len());
+        for registered in plugins {
+            let plugin = &registered.plugin;
+            info!(
+                "PluginManager::load_registered_plugins() > calling plugin `on_load` for {:?} ({})",
+                plugin.plugin_id(),
+                registered.instance_id
+            );
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("on_load", plugin_id = %plugin.plugin_id(), instance_id = %registered.instance_id).entered();
+            let start = Instant::now();
+            let from = self.previous_state(plugin.plugin_id());
+            if let Err(e) = self.interceptors.call(plugin, Hook::Load) {
+                let e = e.detach();
+                error!(
+                    "Plugin failed to load, unloading other plugins from the library; {}",
+                    e
+                );
+                self.metrics.write().unwrap().record_plugin_load(
+                    plugin.plugin_id(),
+                    &from_library.file_name,
+                    start.elapsed(),
+                    false,
+                );
+                self.record_state(
+                    plugin.plugin_id(),
+                    registered.instance_id,
+                    from,
+                    PluginState::Failed,
+                );
+                self.roll_back(&loaded);
+                drop(values);
+                drop(registered);
+                drop(loaded);
+                if let Err(e) = release_library(from_library) {
+                    warn!("Error closing library during roll back; {}", e);
+                }
+                return Err(e);
+            }
+            self.metrics.write().unwrap().record_plugin_load(
+                plugin.plugin_id(),
+                &from_library.file_name,
+                start.elapsed(),
+                true,
+            );
+            self.record_state(
+                plugin.plugin_id(),
+                registered.instance_id,
+                from,
+                PluginState::Loaded,
+            );
+            loaded.push(registered);
+        }
+
+        let entry_point = Arc::new(entry_point.clone());
+        let library_specifier: Arc<Path> = Arc::from(library_specifier);
+        let plugin_ids = self.update_registry(|registry| {
+            let mut plugin_ids = Vec::with_capacity(loaded.len());
+            for registered in loaded {
+                let plugin = self.new_loaded_plugin(
+                    registered,
+                    &from_library,
+                    &library_specifier,
+                    &entry_point,
+                );
+                plugin_ids.push((plugin.plugin_id.clone(), plugin.instance_id));
+                if registry.insert(plugin).is_some() {
+                    warn!("New plugin replaced a plugin with the same ID and version");
+                }
+            }
+            plugin_ids
+        });
+        self.store_values(&from_library, values);
+        for (plugin_id, instance_id) in plugin_ids {
+            self.record_state(
+                &plugin_id,
+                instance_id,
+                PluginState::Loaded,
+                PluginState::Active,
+            );
+        }
+
+        Ok(())
     }
 
     ///
-    /// Returns `true` if the plugin manager has no plugins registered, else `false`.
+    /// Store the values registered by a library whose plugins have been loaded; the values of a
+    /// library that registered no plugins are discarded, as nothing would remove them.
     ///
-    pub fn is_empty(&self) -> bool {
-        self.plugins.read().unwrap().is_empty()
+    fn store_values(&self, from_library: &Arc<LoadedLibrary>, values: Vec<ValueRegistration>) {
+        if values.is_empty() {
+            return;
+        }
+        if !self
+            .plugins
+            .load()
+            .contains_library(&from_library.canonical_name)
+        {
+            warn!(
+                "Library {:?} registered values but no plugins, discarding them",
+                from_library.file_name
+            );
+            return;
+        }
+        let mut stored = self.values.write().unwrap();
+        for (type_id, name, value) in values {
+            trace!("PluginManager::store_values() > storing value {:?}", name);
+            let _ = stored.insert(
+                (type_id, name),
+                RegisteredValue {
+                    value,
+                    in_library: from_library.clone(),
+                },
+            );
+        }
     }
 
     ///
-    /// Return the number of plugins registered in this plugin manager.
+    /// Remove the values registered by the library once none of its plugins remain registered,
+    /// so that the values do not keep the library open.
     ///
-    pub fn len(&self) -> usize {
-        self.plugins.read().unwrap().len()
-    }
-
     ///
-    /// Returns `true` if this plugin manager has a registered plugin with the provided plugin
-    /// identifier, else `false`.
-    pub fn contains(&self, plugin_id: &str) -> bool {
-        let plugins = self.plugins.read().unwrap();
-        plugins.contains_key(plugin_id)
+    /// Capture the state of each plugin registered from the library, see `Plugin::save_state`,
+    /// keyed by plugin identifier and version so that each version's state is restored to the
+    /// same version.
+    ///
+    fn save_library_state(&self, canonical_name: &Path) -> HashMap<StateKey, Vec<u8>> {
+        self.plugins
+            .load()
+            .values()
+            .filter(|p| p.in_library.canonical_name == canonical_name)
+            .map(|p| (state_key(p.plugin.as_ref()), p.plugin.save_state()))
+            .collect()
     }
 
-    ///
-    /// Returns the plugin with the provided plugin identifier, if one exists, else `None`.
-    pub fn get(&self, plugin_id: &str) -> Option<Arc<T>> {
-        let plugins = self.plugins.read().unwrap();
-        plugins.get(plugin_id).map(|p| p.plugin.clone())
+    fn release_values(&self, canonical_name: &Path) {
+        if self.plugins.load().contains_library(canonical_name) {
+            return;
+        }
+        let mut values = self.values.write().unwrap();
+        if !values.is_empty() {
+            values.retain(|_, value| value.in_library.canonical_name != canonical_name);
+        }
     }
 
     ///
-    /// Return all the plugins registered in this plugin manager as a vector.
+    /// Unload, in reverse order, plugins whose `on_load` method was called before a later plugin
+    /// from the same library failed to load.
     ///
-    pub fn plugins(&self) -> Vec<Arc<T>> {
-        let plugins = self.plugins.read().unwrap();
-        plugins.values().map(|p| p.plugin.clone()).collect()
+    fn roll_back(&self, loaded: &[RegisteredPlugin<T>]) {
+        for loaded in loaded.iter().rev() {
+            if let Err(e) = self.interceptors.call(&loaded.plugin, Hook::Unload) {
+                warn!("Error unloading plugin during roll back; {}", e);
+            }
+            self.record_state(
+                loaded.plugin.plugin_id(),
+                loaded.instance_id,
+                PluginState::Loaded,
+                PluginState::Unloaded,
+            );
+        }
     }
 
-    ///
-    /// Unload all plugins, and associated libraries, that are currently registered in this
-    /// plugin manager.
-    ///
-    pub fn unload_all(&mut self) -> Result<()> {
-        info!("PluginManager::unload_all()");
-        let plugin_names: Vec<String> = {
-            let plugins = self.plugins.write().unwrap();
-            plugins.iter().map(|(n, _)| n).cloned().collect()
-        };
-        for name in plugin_names {
-            self.unload_plugin(&name)?;
+    fn check_plugin_limits(&self, from_library: &Arc<LoadedLibrary>, count: usize) -> Result<()> {
+        if let Some(max_plugins_per_library) = self.max_plugins_per_library {
+            if count > max_plugins_per_library {
+                return Err(plugin_limit_exceeded(
+                    from_library,
+                    PluginLimit::PluginsPerLibrary,
+                    max_plugins_per_library,
+                ));
+            }
+        }
+        let registry = self.plugins.load();
+        if let Some(max_plugins) = self.max_plugins {
+            if registry.len() + count > max_plugins {
+                return Err(plugin_limit_exceeded(
+                    from_library,
+                    PluginLimit::Plugins,
+                    max_plugins,
+                ));
+            }
+        }
+        if let Some(max_libraries) = self.max_libraries {
+            if !registry.contains_library(&from_library.canonical_name)
+                && registry.library_count() >= max_libraries
+            {
+                return Err(plugin_limit_exceeded(
+                    from_library,
+                    PluginLimit::Libraries,
+                    max_libraries,
+                ));
+            }
         }
         Ok(())
     }
 
-    ///
-    /// Unload the plugin identified by the provided plugin identifier, if one exists. Note that
-    /// this method will also close the plugin library if no other plugins are using it.
-    ///
-    pub fn unload_plugin(&mut self, plugin_name: &str) -> Result<()> {
-        info!("PluginManager::unload_plugin({:?})", plugin_name);
-        let mut plugins = self.plugins.write().unwrap();
-        if let Some(plugin) = plugins.remove(plugin_name) {
-            trace!("PluginManager::unload_plugin() > calling plugin `on_unload`");
-            plugin.plugin.on_unload()?;
-            if Arc::strong_count(&plugin.in_library) == 1 {
-                trace!("PluginManager::unload_plugin() > closing library");
-                let in_library = Arc::try_unwrap(plugin.in_library).unwrap();
-                if let Err(e) = in_library.library.close() {
-                    error!(
-                        "Error closing library {:?}; {}",
-                        in_library.file_name.to_string_lossy().to_string(),
+    fn new_loaded_plugin(
+        &self,
+        registered: RegisteredPlugin<T>,
+        in_library: &Arc<LoadedLibrary>,
+        library_specifier: &Arc<Path>,
+        entry_point: &Arc<EntryPoint>,
+    ) -> LoadedPlugin<T> {
+        let RegisteredPlugin {
+            plugin,
+            instance_id,
+            factory,
+            registration_fn,
+            priority: _,
+        } = registered;
+        let version = plugin
+            .plugin_version()
+            .and_then(|version| match Version::parse(version) {
+                Ok(version) => Some(version),
+                Err(e) => {
+                    warn!(
+                        "Plugin {:?} reported an invalid version {:?}, ignoring; {}",
+                        plugin.plugin_id(),
+                        version,
                         e
                     );
-                    return Err(ErrorKind::LibraryCloseFailed(
-                        in_library.file_name.to_string_lossy().to_string(),
-                        Box::new(e),
-                    )
-                    .into());
+                    None
                 }
-            }
+            });
+        LoadedPlugin {
+            plugin_id: Arc::from(plugin.plugin_id()),
+            plugin,
+            instance_id,
+            instancing: if matches!(factory, Some(PluginFactory::PerUse(_))) {
+                Instancing::PerUse
+            } else {
+                Instancing::Shared
+            },
+            factory,
+            instances: Default::default(),
+            version,
+            in_library: in_library.clone(),
+            library_specifier: library_specifier.clone(),
+            entry_point: entry_point.clone(),
+            registration_fn,
+            loaded_at: SystemTime::now(),
+            sequence: self.next_sequence.fetch_add(1, Ordering::Relaxed),
+            enabled: true,
+            paused: false,
+            failed: false,
         }
-        Ok(())
     }
+}
 
-    // --------------------------------------------------------------------------------------------
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
 
-    fn find_library(&self, file_name: &Path) -> PathBuf {
-        trace!("PluginManager::find_library() > checking search path for library");
-        self.search_path
-            .find_file(file_name)
-            .unwrap_or(file_name.to_path_buf())
+fn shard_index(plugin_id: &str) -> usize {
+    (stable_hash(&[plugin_id]) % REGISTRY_SHARDS as u64) as usize
+}
+
+///
+/// Returns `true` if `text` matches the glob `pattern`, in which `*` matches any sequence of
+/// characters and `?` any single character.
+///
+fn glob_match(pattern: &[char], text: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, t));
+                }
+                None => return false,
+            },
+        }
     }
+    pattern[p..].iter().all(|c| *c == '*')
+}
 
-    #[allow(unsafe_code)]
-    fn check_compatibility(&self, library: &LoadedLibrary) -> Result<()> {
-        let compatibility_fn = unsafe {
-            let loader_fn: Symbol<'_, CompatibilityFn> =
-                library.library.get(COMPATIBILITY_FN_NAME).map_err(|e| {
-                    Error::from(ErrorKind::SymbolNotFound(
-                        String::from_utf8(COMPATIBILITY_FN_NAME.to_vec()).expect(UTF8_STRING_PANIC),
-                        Box::new(e),
-                    ))
-                })?;
-            loader_fn
-        };
-        trace!("PluginManager::check_compatibility() > fetching library compatibility hash");
-        let lib_compatibility_hash: u64 = compatibility_fn();
-        trace!("PluginManager::check_compatibility() > fetching local compatibility hash");
-        let local_compatibility_hash: u64 = compatibility_hash();
-        if lib_compatibility_hash != local_compatibility_hash {
-            error!(
-                "Version incompatibility {:?} != {:?}",
-                lib_compatibility_hash, local_compatibility_hash
-            );
-            return Err(ErrorKind::IncompatibleLibraryVersion(
-                library.file_name.to_string_lossy().to_string(),
-            )
-            .into());
+///
+/// The files in `directory` named according to the platform's conventions, and the libraries
+/// within any framework bundles on macOS, in file name order; other subdirectories are not
+/// searched.
+///
+fn dylib_files_in(directory: &Path) -> Result<Vec<PathBuf>> {
+    let read_failed = |e: std::io::Error| Error::directory_read_failed(directory, e);
+    let mut file_names: Vec<PathBuf> = Default::default();
+    for entry in std::fs::read_dir(directory).map_err(read_failed)? {
+        let file_name = entry.map_err(read_failed)?.path();
+        if file_name.is_file() && parse_dylib_name(&file_name).is_some() {
+            file_names.push(file_name);
+        } else if let Some(library) = Platform::current().framework_library(&file_name) {
+            file_names.push(library);
         }
-        trace!("PluginManager::check_compatibility() > compatibility version check passed");
-        Ok(())
     }
+    file_names.sort();
+    Ok(file_names)
+}
 
-    #[allow(unsafe_code)]
-    fn register_plugins(&mut self, from_library: LoadedLibrary) -> Result<()> {
-        trace!(
-            "PluginManager::register_plugins(_, {:?})",
-            &from_library.file_name
-        );
-        let load_fn = unsafe {
-            let loader_fn: Symbol<'_, PluginRegistrationFn<T>> = from_library
-                .library
-                .get(self.registration_fn_name.as_slice())
-                .map_err(|e| {
-                    Error::from(ErrorKind::SymbolNotFound(
-                        String::from_utf8(self.registration_fn_name.clone())
-                            .expect(UTF8_STRING_PANIC),
-                        Box::new(e),
-                    ))
-                })?;
-            loader_fn
-        };
+///
+/// The directory of the running executable, which is the loader of all plugin libraries.
+///
+fn executable_directory() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_default()
+}
 
-        trace!(
-            "PluginManager::register_plugins() > calling `{}`",
-            String::from_utf8(self.registration_fn_name.clone()).expect(UTF8_STRING_PANIC)
-        );
-        let mut registrar = PluginRegistrar::default();
-        load_fn(&mut registrar);
+///
+/// Split a registration function name into the name without any `_v<VERSION>` suffix, and the
+/// registration protocol version, which is `1` if there is no suffix.
+///
+fn split_protocol_version(name: &str) -> (&str, u32) {
+    name.rsplit_once("_v")
+        .and_then(|(base_name, version)| {
+            if version.bytes().all(|b| b.is_ascii_digit()) {
+                version
+                    .parse()
+                    .ok()
+                    .filter(|version| *version > 0)
+                    .map(|version| (base_name, version))
+            } else {
+                None
+            }
+        })
+        .unwrap_or((name, 1))
+}
 
-        let mut registry = self.plugins.write().unwrap();
+fn display_name(library: &Path) -> Cow<'_, str> {
+    library
+        .file_name()
+        .unwrap_or(library.as_os_str())
+        .to_string_lossy()
+}
 
-        let from_library = Arc::new(from_library);
+fn plugin_limit_exceeded(
+    from_library: &LoadedLibrary,
+    limit: PluginLimit,
+    max_value: usize,
+) -> Error {
+    error!(
+        "Library {:?} exceeds the limit of {} {}",
+        from_library.file_name, max_value, limit
+    );
+    Error::from(ErrorKind::PluginLimitExceeded {
+        library: from_library.file_name.to_string_lossy().to_string(),
+        limit,
+        value: max_value,
+    })
+}
 
-        for plugin in registrar
-            .plugins()
-            .map_err(|e| Error::from(ErrorKind::PluginRegistration(e)))?
-        {
-            info!("PluginManager::register_plugins() > calling plugin `on_load`");
-            plugin.on_load()?;
-            if let Some(_) = registry.insert(
-                plugin.plugin_id().to_string(),
-                LoadedPlugin {
-                    plugin,
-                    in_library: from_library.clone(),
-                },
-            ) {
-                warn!("New plugin replaced a plugin with the same ID");
-            }
+///
+/// Return the compatibility inputs reported by the library, or `None` if it does not export
+/// `compatibility_info`.
+///
+#[allow(unsafe_code)]
+fn library_compatibility(library: &LoadedLibrary) -> Option<Compatibility> {
+    trace!("library_compatibility({:?})", library.file_name);
+    unsafe {
+        let info_fn = library
+            .get::<CompatibilityInfoFn>(COMPATIBILITY_INFO_FN_NAME)
+            .ok()?;
+        let mut compatibility = Compatibility::from_info(&info_fn());
+        if let Ok(inputs_fn) = library.get::<CompatibilityInputsFn>(COMPATIBILITY_INPUTS_FN_NAME) {
+            compatibility.add_inputs_from(inputs_fn());
         }
+        Some(compatibility)
+    }
+}
 
-        Ok(())
+fn incompatible_library(
+    file_name: &Path,
+    host: &Compatibility,
+    found: Option<Compatibility>,
+) -> Error {
+    Error::from(ErrorKind::IncompatibleLibraryVersion {
+        library: file_name.to_string_lossy().to_string(),
+        host: Box::new(host.clone()),
+        found: found.map(Box::new),
+    })
+}
+
+fn state_key<T>(plugin: &T) -> StateKey
+where
+    T: Plugin,
+{
+    (
+        plugin.plugin_id().to_string(),
+        plugin.plugin_version().map(str::to_string),
+    )
+}
+
+fn release_library(library: Arc<LoadedLibrary>) -> Result<()> {
+    match Arc::into_inner(library) {
+        Some(library) => close_library(library),
+        None => Ok(()),
+    }
+}
+
+fn close_library(library: LoadedLibrary) -> Result<()> {
+    trace!("PluginManager::close_library() > closing library");
+    let file_name = library.file_name.clone();
+    library.close().inspect_err(|e| {
+        error!(
+            "Error closing library {:?}; {}",
+            file_name.to_string_lossy().to_string(),
+            e
+        )
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use windows_sys::Win32::System::LibraryLoader::{AddDllDirectory, RemoveDllDirectory};
+
+    pub(super) type DllDirectoryCookie = *mut c_void;
+
+    #[allow(unsafe_code)]
+    pub(super) fn add_dll_directory(directory: &Path) -> Option<DllDirectoryCookie> {
+        let wide: Vec<u16> = directory
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        let cookie = unsafe { AddDllDirectory(wide.as_ptr()) };
+        if cookie.is_null() {
+            warn!("AddDllDirectory failed for {:?}", directory);
+            None
+        } else {
+            Some(cookie)
+        }
+    }
+
+    #[allow(unsafe_code)]
+    pub(super) fn remove_dll_directory(cookie: DllDirectoryCookie) {
+        if unsafe { RemoveDllDirectory(cookie) } == 0 {
+            warn!("RemoveDllDirectory failed");
+        }
     }
 }
 
@@ -529,4 +6150,443 @@ mod tests {
         let file_name = make_platform_dylib_name("my_lib.foo".as_ref());
         assert_eq!(file_name.to_str().unwrap(), EXPECTED_FILE);
     }
+
+    #[test]
+    fn test_auto_platform_names() {
+        let mut manager: PluginManager<NoPlugin> = PluginManager::default();
+        assert_eq!(
+            manager.resolve_library("my_lib".as_ref()),
+            PathBuf::from("my_lib")
+        );
+        manager.set_auto_platform_names(true);
+        assert_eq!(
+            manager.resolve_library("my_lib".as_ref()),
+            PathBuf::from(EXPECTED_FILE)
+        );
+        assert_eq!(
+            manager.resolve_library("plugins/my_lib".as_ref()),
+            Path::new("plugins").join(EXPECTED_FILE)
+        );
+        assert_eq!(
+            manager.resolve_library("my_lib.foo".as_ref()),
+            PathBuf::from("my_lib.foo")
+        );
+        assert_ne!(
+            manager.resolve_library("my_lib.foo".as_ref()),
+            make_platform_dylib_name("my_lib.foo".as_ref())
+        );
+    }
+
+    #[test]
+    fn test_probe_platform_names() {
+        let directory = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        std::fs::write(directory.path().join("sound.dll"), b"").unwrap();
+        std::fs::write(other.path().join("libecho.so"), b"").unwrap();
+        let mut manager: PluginManager<NoPlugin> = PluginManager::default();
+        assert_eq!(
+            manager.resolve_library(&directory.path().join("sound")),
+            directory.path().join("sound.dll")
+        );
+
+        manager.add_search_path(directory.path());
+        manager.add_search_path(other.path());
+        assert_eq!(
+            manager.resolve_library("sound".as_ref()),
+            directory.path().join("sound.dll")
+        );
+        assert_eq!(
+            manager.resolve_library("echo".as_ref()),
+            other.path().join("libecho.so")
+        );
+        assert_eq!(
+            manager.resolve_library("delay".as_ref()),
+            PathBuf::from("delay")
+        );
+    }
+
+    #[test]
+    fn test_platform_plugin_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        assert_eq!(
+            platform_plugin_directory(directory.path()),
+            directory.path()
+        );
+
+        let platform_directory = directory.path().join(Platform::current().to_string());
+        std::fs::create_dir(&platform_directory).unwrap();
+        assert_eq!(
+            platform_plugin_directory(directory.path()),
+            platform_directory
+        );
+
+        if let Some(target) = Platform::current_target_triple() {
+            let target_directory = directory.path().join(target);
+            std::fs::create_dir(&target_directory).unwrap();
+            assert_eq!(
+                platform_plugin_directory(directory.path()),
+                target_directory
+            );
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        let glob =
+            |pattern: &str, text: &str| glob_match(&pattern.chars().collect::<Vec<char>>(), text);
+        assert!(glob(
+            "sound_*::*::Delay*",
+            "sound_plugin::sound_plugin::DelayEffect"
+        ));
+        assert!(glob("*", ""));
+        assert!(glob("*Effect", "sound_plugin::sound_plugin::ReverbEffect"));
+        assert!(glob(
+            "sound_plugin::?ound_plugin::*",
+            "sound_plugin::sound_plugin::Delay"
+        ));
+        assert!(glob("a*b*c", "aXbYbZc"));
+        assert!(!glob(
+            "sound_*::*::Delay*",
+            "sound_plugin::sound_plugin::ReverbEffect"
+        ));
+        assert!(!glob("Delay", "DelayEffect"));
+        assert!(!glob("?", ""));
+        assert!(!glob("a*b*c", "aXbYcZ"));
+    }
+
+    #[test]
+    fn test_split_protocol_version() {
+        assert_eq!(
+            split_protocol_version("register_plugins"),
+            ("register_plugins", 1)
+        );
+        assert_eq!(
+            split_protocol_version("register_plugins_v2"),
+            ("register_plugins", 2)
+        );
+        assert_eq!(
+            split_protocol_version("register_plugins_v0"),
+            ("register_plugins_v0", 1)
+        );
+        assert_eq!(
+            split_protocol_version("register_video_plugins"),
+            ("register_video_plugins", 1)
+        );
+    }
+
+    #[derive(Debug)]
+    struct NoPlugin(String);
+
+    impl Plugin for NoPlugin {
+        fn plugin_id(&self) -> &str {
+            &self.0
+        }
+        fn on_load(&self) -> Result<()> {
+            Ok(())
+        }
+        fn on_unload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_id_normalization() {
+        let mut manager = crate::testing::mock_manager(vec![
+            NoPlugin("sound::Delay".to_string()),
+            NoPlugin("sound::Reverb".to_string()),
+        ])
+        .unwrap();
+        assert!(!manager.contains("SOUND::delay"));
+
+        let mut id_normalization = IdNormalization::default();
+        id_normalization.set_case_insensitive(true);
+        id_normalization.set_trim_whitespace(true);
+        id_normalization.add_alias("legacy::Echo", "sound::Delay");
+        manager.set_id_normalization(id_normalization);
+
+        assert!(manager.contains("sound::Delay"));
+        assert!(manager.contains(" SOUND::delay\t"));
+        assert!(manager.contains("Legacy::Echo "));
+        assert_eq!(manager.get("sound::reverb").unwrap().0, "sound::Reverb");
+        assert!(manager.plugin_info("LEGACY::ECHO").is_some());
+        assert!(!manager.contains("sound::Chorus"));
+
+        manager.unload_plugin("legacy::echo").unwrap();
+        assert!(!manager.contains("sound::Delay"));
+        assert_eq!(manager.len(), 1);
+    }
+
+    ///
+    /// Check the registry's plugin count, and per-library plugin counts, against its contents.
+    ///
+    fn library_counts(manager: &PluginManager<NoPlugin>) -> Vec<usize> {
+        let registry = manager.plugins.load();
+        let mut counts: BTreeMap<PathBuf, usize> = Default::default();
+        for plugin in registry.values() {
+            *counts
+                .entry(plugin.in_library.canonical_name.clone())
+                .or_default() += 1;
+        }
+        assert_eq!(registry.len(), registry.values().count());
+        assert_eq!(registry.library_count(), counts.len());
+        for (library, count) in &counts {
+            assert_eq!(registry.libraries.get(library.as_path()), Some(count));
+        }
+        counts.into_values().collect()
+    }
+
+    #[test]
+    fn test_registry_counts_across_shards() {
+        let memory_source = crate::testing::MemorySource::default();
+        for i in 0..40 {
+            let location = if i % 2 == 0 { "delays" } else { "reverbs" };
+            memory_source.add_plugin(location, NoPlugin(format!("sound::Effect{}", i)));
+        }
+        let source: Arc<dyn PluginSource<NoPlugin>> = Arc::new(memory_source);
+        let manager = PluginManager::default();
+        manager.load_all_plugins_from_source(&source).unwrap();
+        assert!(
+            manager
+                .plugins
+                .load()
+                .shards
+                .iter()
+                .filter(|shard| !shard.is_empty())
+                .count()
+                > 1
+        );
+        assert_eq!(manager.len(), 40);
+        assert_eq!(library_counts(&manager), vec![20, 20]);
+
+        for i in (0..40).step_by(4) {
+            manager
+                .unload_plugin(&format!("sound::Effect{}", i))
+                .unwrap();
+        }
+        assert_eq!(manager.len(), 30);
+        assert_eq!(library_counts(&manager), vec![10, 20]);
+
+        manager.unload_all().unwrap();
+        assert!(manager.is_empty());
+        assert!(library_counts(&manager).is_empty());
+    }
+
+    #[derive(Debug)]
+    struct RenamedPlugin(String, Vec<&'static str>);
+
+    impl Plugin for RenamedPlugin {
+        fn plugin_id(&self) -> &str {
+            &self.0
+        }
+        fn plugin_aliases(&self) -> &[&str] {
+            &self.1
+        }
+        fn on_load(&self) -> Result<()> {
+            Ok(())
+        }
+        fn on_unload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_plugin_aliases() {
+        let mut manager = crate::testing::mock_manager(vec![
+            RenamedPlugin("sound::Delay".to_string(), vec!["old_sound::Delay"]),
+            RenamedPlugin("sound::Reverb".to_string(), vec![]),
+        ])
+        .unwrap();
+        assert!(manager.contains("old_sound::Delay"));
+        assert_eq!(manager.get("old_sound::Delay").unwrap().0, "sound::Delay");
+        assert!(!manager.contains("old_sound::Reverb"));
+
+        manager.add_alias("old_sound::Reverb", "sound::Reverb");
+        assert_eq!(manager.get("old_sound::Reverb").unwrap().0, "sound::Reverb");
+
+        manager.unload_plugin("old_sound::Delay").unwrap();
+        assert!(!manager.contains("sound::Delay"));
+        assert!(!manager.contains("old_sound::Delay"));
+        assert_eq!(manager.len(), 1);
+    }
+
+    #[derive(Debug)]
+    struct GroupedPlugin(String, Vec<&'static str>);
+
+    impl Plugin for GroupedPlugin {
+        fn plugin_id(&self) -> &str {
+            &self.0
+        }
+        fn plugin_groups(&self) -> &[&str] {
+            &self.1
+        }
+        fn on_load(&self) -> Result<()> {
+            Ok(())
+        }
+        fn on_unload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_plugin_groups() {
+        let manager = crate::testing::mock_manager(vec![
+            GroupedPlugin("video::Scope".to_string(), vec!["visualizers"]),
+            GroupedPlugin("video::Spectrum".to_string(), vec![]),
+            GroupedPlugin("sound::Delay".to_string(), vec!["effects"]),
+        ])
+        .unwrap();
+        manager.add_to_group("visualizers", "video::Spectrum");
+        let visualizers = manager.plugins_in_group("visualizers");
+        assert_eq!(visualizers.len(), 2);
+        assert_eq!(visualizers[0].0, "video::Scope");
+        assert!(manager.plugins_in_group("analyzers").is_empty());
+
+        manager.disable_group("visualizers").unwrap();
+        assert!(!manager.is_enabled("video::Scope"));
+        assert!(!manager.is_enabled("video::Spectrum"));
+        assert!(manager.is_enabled("sound::Delay"));
+        manager.enable_group("visualizers").unwrap();
+        assert!(manager.is_enabled("video::Spectrum"));
+        manager.enable_group("analyzers").unwrap();
+
+        manager.unload_group("visualizers").unwrap();
+        assert_eq!(manager.len(), 1);
+        assert!(manager.contains("sound::Delay"));
+
+        assert!(manager.remove_from_group("visualizers", "video::Spectrum"));
+        assert!(!manager.remove_from_group("visualizers", "video::Spectrum"));
+    }
+
+    #[derive(Debug)]
+    struct VersionedPlugin(String, &'static str);
+
+    impl Plugin for VersionedPlugin {
+        fn plugin_id(&self) -> &str {
+            &self.0
+        }
+        fn plugin_version(&self) -> Option<&str> {
+            Some(self.1)
+        }
+        fn on_load(&self) -> Result<()> {
+            Ok(())
+        }
+        fn on_unload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_interned_plugin_ids() {
+        let manager = crate::testing::mock_manager(vec![
+            VersionedPlugin("sound::Delay".to_string(), "1.0.0"),
+            VersionedPlugin("sound::Delay".to_string(), "2.0.0"),
+        ])
+        .unwrap();
+        assert_eq!(manager.len(), 2);
+        let infos = manager.plugin_infos();
+        assert_eq!(infos[0].plugin_id(), "sound::Delay");
+        assert!(Arc::ptr_eq(&infos[0].plugin_id, &infos[1].plugin_id));
+
+        let plugins = manager.plugins.load();
+        let (registered, _) = plugins.entries().next().unwrap();
+        assert!(Arc::ptr_eq(registered, &infos[0].plugin_id));
+    }
+
+    #[test]
+    fn test_read_guard() {
+        let manager = crate::testing::mock_manager(vec![
+            NoPlugin("sound::Delay".to_string()),
+            NoPlugin("sound::Reverb".to_string()),
+        ])
+        .unwrap();
+        let plugins = manager.read();
+        assert_eq!(plugins.len(), 2);
+        assert!(plugins.contains("sound::Reverb"));
+        assert_eq!(plugins.get("sound::Delay").unwrap().0, "sound::Delay");
+        assert!(plugins.get("sound::Chorus").is_none());
+
+        manager.unload_plugin("sound::Delay").unwrap();
+        assert_eq!(manager.len(), 1);
+        assert_eq!(plugins.iter().count(), 2);
+        drop(plugins);
+        assert_eq!(manager.read().len(), 1);
+    }
+
+    #[test]
+    fn test_child_manager() {
+        let parent = Arc::new(
+            crate::testing::mock_manager(vec![NoPlugin("core::Delay".to_string())]).unwrap(),
+        );
+        let child = PluginManager::new_child(&parent);
+        assert!(Arc::ptr_eq(child.parent().unwrap(), &parent));
+        assert!(child.contains("core::Delay"));
+        assert!(child.is_empty());
+
+        let source = Arc::new(crate::testing::MemorySource::default());
+        source.add_plugin("session", NoPlugin("session::Reverb".to_string()));
+        let source: Arc<dyn PluginSource<NoPlugin>> = source;
+        child.load_plugins_from_source(&source, "session").unwrap();
+        assert_eq!(child.len(), 1);
+        assert_eq!(child.plugins().len(), 2);
+        assert!(child.get("session::Reverb").is_some());
+        assert!(!parent.contains("session::Reverb"));
+
+        child.unload_plugin("core::Delay").unwrap();
+        assert!(parent.contains("core::Delay"));
+
+        drop(child);
+        assert_eq!(parent.len(), 1);
+        assert_eq!(Arc::strong_count(&parent), 1);
+    }
+
+    #[test]
+    fn test_add_dll_directory() {
+        let mut manager: PluginManager<NoPlugin> = PluginManager::default();
+        manager.add_dll_directory("helpers".as_ref());
+        manager.add_dll_directory("helpers".as_ref());
+
+        let directories: Vec<&PathBuf> = manager.dll_directories().collect();
+        assert_eq!(directories.len(), 1);
+        assert!(directories[0].is_absolute());
+        assert!(directories[0].ends_with("helpers"));
+    }
+
+    #[test]
+    fn test_search_path_mutation() {
+        let mut manager: PluginManager<NoPlugin> = PluginManager::default();
+        assert!(manager.search_path().is_empty());
+
+        manager.add_search_path("/opt/one".as_ref());
+        manager.add_search_path("/opt/two".as_ref());
+        manager.add_search_path("/opt/one".as_ref());
+        manager.prepend_search_path("/opt/two".as_ref());
+        assert_eq!(
+            manager.search_path().iter().collect::<Vec<&PathBuf>>(),
+            vec![&PathBuf::from("/opt/two"), &PathBuf::from("/opt/one")]
+        );
+
+        assert!(manager.remove_search_path("/opt/two".as_ref()));
+        assert!(!manager.remove_search_path("/opt/two".as_ref()));
+        assert_eq!(manager.search_path().len(), 1);
+    }
+
+    #[test]
+    fn test_search_path_from_env() {
+        let value = env::join_paths(["/opt/one", "", "/opt/two", "/opt/one"]).unwrap();
+        env::set_var("DYGPI_TEST_SEARCH_PATH_FROM_ENV", value);
+
+        let manager: PluginManager<NoPlugin> = PluginManager::new_with_search_path_from_env(
+            &["DYGPI_TEST_SEARCH_PATH_FROM_ENV", "DYGPI_TEST_NOT_SET"],
+            &["/opt/three".as_ref(), "/opt/two".as_ref()],
+        );
+        let paths: Vec<&PathBuf> = manager.search_path.iter().collect();
+        assert_eq!(
+            paths,
+            vec![
+                &PathBuf::from("/opt/one"),
+                &PathBuf::from("/opt/two"),
+                &PathBuf::from("/opt/three")
+            ]
+        );
+    }
 }
@@ -0,0 +1,293 @@
+/*!
+Provides the [`PluginManifest`](struct.PluginManifest.html) type, a static description of the
+plugins a library provides, and the [`plugin_manifest!`](../macro.plugin_manifest.html) macro that
+a plugin provider uses to embed the manifest into its library.
+
+The manifest is exported as a `PLUGIN_MANIFEST` static containing a NUL-terminated text
+descriptor; it is read directly from the library file by
+[`inspect::read_manifest`](../inspect/fn.read_manifest.html) and
+[`inspect::validate_library`](../inspect/fn.validate_library.html) without opening the library, and
+so without running any code in it.
+
+# Example
+
+In the plugin provider:
+
+```rust
+dygpi::plugin_manifest! {
+    host_api: 2..=3,
+    plugins: [
+        ("sound_one::DelayEffect", "0.1.0"),
+        ("sound_one::ReverbEffect", env!("CARGO_PKG_VERSION")),
+    ]
+}
+```
+
+The text descriptor has a header line followed by one line per entry; the `host_api` line is
+omitted if the provider supports any host API version.
+
+```text
+dygpi-manifest 1
+host_api 2 3
+plugin sound_one::DelayEffect 0.1.0
+plugin sound_one::ReverbEffect 0.1.0
+```
+
+*/
+
+use crate::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The description of a plugin library read from its `PLUGIN_MANIFEST` symbol.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginManifest {
+    host_api_version: Option<RangeInclusive<u32>>,
+    plugins: Vec<PluginDescriptor>,
+}
+
+///
+/// The description of a single plugin within a [`PluginManifest`](struct.PluginManifest.html).
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PluginDescriptor {
+    plugin_id: String,
+    version: String,
+}
+
+///
+/// The name of the static symbol containing the plugin manifest.
+///
+pub const PLUGIN_MANIFEST_SYMBOL_NAME: &str = "PLUGIN_MANIFEST";
+
+///
+/// The first line of every plugin manifest, this includes the manifest format version.
+///
+pub const PLUGIN_MANIFEST_HEADER: &str = "dygpi-manifest 1";
+
+// ------------------------------------------------------------------------------------------------
+// Public Macros
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Export a `PLUGIN_MANIFEST` static from a plugin provider, see the
+/// [`manifest`](manifest/index.html) module. Plugin identifiers and versions must be string
+/// literals, or macros such as `concat!` and `env!` that expand to string literals.
+///
+#[macro_export]
+macro_rules! plugin_manifest {
+    (host_api: $min:literal ..= $max:literal, plugins: [ $( ($id:expr, $version:expr) ),* $(,)? ] $(,)?) => {
+        $crate::plugin_manifest!(@static concat!(
+            "host_api ", $min, " ", $max, "\n",
+            $( "plugin ", $id, " ", $version, "\n", )*
+        ));
+    };
+    (plugins: [ $( ($id:expr, $version:expr) ),* $(,)? ] $(,)?) => {
+        $crate::plugin_manifest!(@static concat!(
+            $( "plugin ", $id, " ", $version, "\n", )*
+        ));
+    };
+    (@static $body:expr) => {
+        #[doc(hidden)]
+        const __DYGPI_PLUGIN_MANIFEST_TEXT: &str = concat!("dygpi-manifest 1\n", $body);
+
+        /// The plugin manifest for this library.
+        #[allow(unsafe_code)]
+        #[no_mangle]
+        pub static PLUGIN_MANIFEST: [u8; __DYGPI_PLUGIN_MANIFEST_TEXT.len() + 1] =
+            $crate::manifest::manifest_bytes(__DYGPI_PLUGIN_MANIFEST_TEXT);
+    };
+}
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Copy the text into a NUL-terminated byte array; used by the `plugin_manifest!` macro.
+///
+#[doc(hidden)]
+pub const fn manifest_bytes<const N: usize>(text: &str) -> [u8; N] {
+    let bytes = text.as_bytes();
+    let mut result = [0u8; N];
+    let mut i = 0;
+    while i < bytes.len() {
+        result[i] = bytes[i];
+        i += 1;
+    }
+    result
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for PluginManifest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", PLUGIN_MANIFEST_HEADER)?;
+        if let Some(range) = &self.host_api_version {
+            writeln!(f, "host_api {} {}", range.start(), range.end())?;
+        }
+        for plugin in &self.plugins {
+            writeln!(f, "plugin {} {}", plugin.plugin_id, plugin.version)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PluginManifest {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |message: String| Error::invalid_plugin_manifest(&message);
+        let mut lines = s.lines().map(str::trim).filter(|line| !line.is_empty());
+        match lines.next() {
+            Some(PLUGIN_MANIFEST_HEADER) => {}
+            other => {
+                return Err(invalid(format!("unexpected manifest header {:?}", other)));
+            }
+        }
+
+        let mut manifest = PluginManifest::default();
+        for line in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["host_api", min, max] => {
+                    let parse = |v: &str| {
+                        v.parse::<u32>()
+                            .map_err(|_| invalid(format!("invalid host API version {:?}", v)))
+                    };
+                    manifest.host_api_version = Some(parse(min)?..=parse(max)?);
+                }
+                ["plugin", plugin_id, version] => manifest.plugins.push(PluginDescriptor {
+                    plugin_id: plugin_id.to_string(),
+                    version: version.to_string(),
+                }),
+                _ => return Err(invalid(format!("unexpected manifest line {:?}", line))),
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+impl PluginManifest {
+    ///
+    /// Construct a new manifest.
+    ///
+    pub fn new(
+        host_api_version: Option<RangeInclusive<u32>>,
+        plugins: Vec<PluginDescriptor>,
+    ) -> Self {
+        Self {
+            host_api_version,
+            plugins,
+        }
+    }
+
+    ///
+    /// The range of host API versions supported by the provider, or `None` if the provider
+    /// supports any host API version.
+    ///
+    pub fn host_api_version(&self) -> Option<&RangeInclusive<u32>> {
+        self.host_api_version.as_ref()
+    }
+
+    ///
+    /// Returns `true` if the provider supports the host API version.
+    ///
+    pub fn supports_host_api_version(&self, api_version: u32) -> bool {
+        self.host_api_version
+            .as_ref()
+            .is_none_or(|range| range.contains(&api_version))
+    }
+
+    ///
+    /// The plugins described by this manifest.
+    ///
+    pub fn plugins(&self) -> impl Iterator<Item = &PluginDescriptor> {
+        self.plugins.iter()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl PluginDescriptor {
+    ///
+    /// Construct a new plugin descriptor.
+    ///
+    pub fn new(plugin_id: &str, version: &str) -> Self {
+        Self {
+            plugin_id: plugin_id.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    ///
+    /// The plugin identifier.
+    ///
+    pub fn plugin_id(&self) -> &String {
+        &self.plugin_id
+    }
+
+    ///
+    /// The version of the plugin.
+    ///
+    pub fn version(&self) -> &String {
+        &self.version
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(unreachable_pub)]
+    mod provider {
+        plugin_manifest! {
+            host_api: 2..=3,
+            plugins: [
+                ("sound::DelayEffect", "0.1.0"),
+                (concat!("sound::", "ReverbEffect"), "0.2.0"),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_manifest_macro_round_trip() {
+        let text = std::str::from_utf8(&provider::PLUGIN_MANIFEST).unwrap();
+        let text = text.strip_suffix('\0').unwrap();
+        let manifest: PluginManifest = text.parse().unwrap();
+        assert_eq!(
+            manifest,
+            PluginManifest::new(
+                Some(2..=3),
+                vec![
+                    PluginDescriptor::new("sound::DelayEffect", "0.1.0"),
+                    PluginDescriptor::new("sound::ReverbEffect", "0.2.0"),
+                ]
+            )
+        );
+        assert_eq!(manifest.to_string(), text);
+        assert!(manifest.supports_host_api_version(3));
+        assert!(!manifest.supports_host_api_version(4));
+    }
+
+    #[test]
+    fn test_invalid_manifest() {
+        assert!("plugin one 0.1.0".parse::<PluginManifest>().is_err());
+        assert!("dygpi-manifest 1\nhost_api two 3"
+            .parse::<PluginManifest>()
+            .is_err());
+    }
+}
@@ -0,0 +1,250 @@
+/*!
+Provides the [`LoadMetrics`](struct.LoadMetrics.html) type, timing and failure information
+recorded by a plugin manager as it loads libraries and plugins.
+
+Metrics are always recorded by the plugin manager and may be retrieved with its
+[`metrics`](../manager/struct.PluginManager.html#method.metrics) method. If the feature `metrics`
+is enabled the same values are also reported through the [metrics](https://docs.rs/metrics)
+crate facade using the following names.
+
+| Name                               | Kind      | Labels    |
+|------------------------------------|-----------|-----------|
+| `dygpi.library.load_duration`      | histogram | `library` |
+| `dygpi.library.load_failures`      | counter   | `library` |
+| `dygpi.plugin.on_load_duration`    | histogram | `plugin`  |
+
+# Example
+
+```rust,no_run
+use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str {
+#         unimplemented!()
+#     }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+
+let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.load_plugins_from("libsound_one.dylib").unwrap();
+
+let metrics = plugin_manager.metrics();
+for (library, library_metrics) in metrics.libraries() {
+    println!("{:?} took {:?}", library, library_metrics.last_load_duration());
+}
+```
+
+*/
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A snapshot of the metrics recorded by a plugin manager.
+///
+#[derive(Clone, Debug, Default)]
+pub struct LoadMetrics {
+    libraries: HashMap<PathBuf, LibraryMetrics>,
+    plugins: HashMap<String, PluginMetrics>,
+}
+
+///
+/// Metrics recorded for a single library, identified by its resolved path.
+///
+#[derive(Clone, Debug, Default)]
+pub struct LibraryMetrics {
+    load_count: u64,
+    failure_count: u64,
+    last_load_duration: Duration,
+    total_load_duration: Duration,
+}
+
+///
+/// Metrics recorded for a single plugin, identified by its plugin identifier.
+///
+#[derive(Clone, Debug)]
+pub struct PluginMetrics {
+    library: PathBuf,
+    load_count: u64,
+    failure_count: u64,
+    last_on_load_duration: Duration,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl LoadMetrics {
+    /// Return an iterator over the metrics for every library the plugin manager attempted to load.
+    pub fn libraries(&self) -> impl Iterator<Item = (&PathBuf, &LibraryMetrics)> {
+        self.libraries.iter()
+    }
+
+    /// Return the metrics for the library with the provided resolved path, if any.
+    pub fn library(&self, file_name: &Path) -> Option<&LibraryMetrics> {
+        self.libraries.get(file_name)
+    }
+
+    /// Return an iterator over the metrics for every plugin the plugin manager loaded.
+    pub fn plugins(&self) -> impl Iterator<Item = (&String, &PluginMetrics)> {
+        self.plugins.iter()
+    }
+
+    /// Return the metrics for the plugin with the provided plugin identifier, if any.
+    pub fn plugin(&self, plugin_id: &str) -> Option<&PluginMetrics> {
+        self.plugins.get(plugin_id)
+    }
+
+    /// Return the total time spent loading libraries, including failed attempts.
+    pub fn total_load_duration(&self) -> Duration {
+        self.libraries.values().map(|l| l.total_load_duration).sum()
+    }
+
+    /// Return the total number of failed library loads.
+    pub fn failure_count(&self) -> u64 {
+        self.libraries.values().map(|l| l.failure_count).sum()
+    }
+
+    pub(crate) fn record_library_load(&mut self, file_name: &Path, duration: Duration, ok: bool) {
+        let library = self.libraries.entry(file_name.to_path_buf()).or_default();
+        library.load_count += 1;
+        library.last_load_duration = duration;
+        library.total_load_duration += duration;
+        if !ok {
+            library.failure_count += 1;
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            let label = file_name.to_string_lossy().to_string();
+            metrics::histogram!("dygpi.library.load_duration", "library" => label.clone())
+                .record(duration.as_secs_f64());
+            if !ok {
+                metrics::counter!("dygpi.library.load_failures", "library" => label).increment(1);
+            }
+        }
+    }
+
+    pub(crate) fn record_plugin_load(
+        &mut self,
+        plugin_id: &str,
+        file_name: &Path,
+        duration: Duration,
+        ok: bool,
+    ) {
+        let plugin = self
+            .plugins
+            .entry(plugin_id.to_string())
+            .or_insert_with(|| PluginMetrics {
+                library: file_name.to_path_buf(),
+                load_count: 0,
+                failure_count: 0,
+                last_on_load_duration: Duration::default(),
+            });
+        plugin.library = file_name.to_path_buf();
+        plugin.load_count += 1;
+        plugin.last_on_load_duration = duration;
+        if !ok {
+            plugin.failure_count += 1;
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            let label = plugin_id.to_string();
+            metrics::histogram!("dygpi.plugin.on_load_duration", "plugin" => label.clone())
+                .record(duration.as_secs_f64());
+            if !ok {
+                metrics::counter!("dygpi.plugin.on_load_failures", "plugin" => label).increment(1);
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl LibraryMetrics {
+    /// The number of times the library was loaded, including failed attempts.
+    pub fn load_count(&self) -> u64 {
+        self.load_count
+    }
+
+    /// The number of times loading the library failed.
+    pub fn failure_count(&self) -> u64 {
+        self.failure_count
+    }
+
+    /// The time taken by the most recent load of the library; this includes opening the library,
+    /// the compatibility check, registration, and all plugin `on_load` calls.
+    pub fn last_load_duration(&self) -> Duration {
+        self.last_load_duration
+    }
+
+    /// The total time taken by all loads of the library.
+    pub fn total_load_duration(&self) -> Duration {
+        self.total_load_duration
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl PluginMetrics {
+    /// The resolved path of the library that provided the plugin.
+    pub fn library(&self) -> &PathBuf {
+        &self.library
+    }
+
+    /// The number of times the plugin was loaded, including failed `on_load` calls.
+    pub fn load_count(&self) -> u64 {
+        self.load_count
+    }
+
+    /// The number of times the plugin's `on_load` method failed.
+    pub fn failure_count(&self) -> u64 {
+        self.failure_count
+    }
+
+    /// The time taken by the most recent call to the plugin's `on_load` method.
+    pub fn last_on_load_duration(&self) -> Duration {
+        self.last_on_load_duration
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_loads() {
+        let mut metrics = LoadMetrics::default();
+        let library: &Path = "libone.so".as_ref();
+        metrics.record_library_load(library, Duration::from_millis(5), true);
+        metrics.record_library_load(library, Duration::from_millis(7), false);
+        metrics.record_plugin_load("one", library, Duration::from_millis(1), true);
+        metrics.record_plugin_load("two", library, Duration::from_millis(1), false);
+
+        let library_metrics = metrics.library(library).unwrap();
+        assert_eq!(library_metrics.load_count(), 2);
+        assert_eq!(library_metrics.failure_count(), 1);
+        assert_eq!(
+            library_metrics.last_load_duration(),
+            Duration::from_millis(7)
+        );
+        assert_eq!(metrics.total_load_duration(), Duration::from_millis(12));
+        assert_eq!(metrics.failure_count(), 1);
+        assert_eq!(metrics.plugin("one").unwrap().library(), library);
+        assert_eq!(metrics.plugin("one").unwrap().failure_count(), 0);
+        assert_eq!(metrics.plugin("two").unwrap().failure_count(), 1);
+    }
+}
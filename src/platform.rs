@@ -0,0 +1,554 @@
+/*!
+Provides the [`Platform`](enum.Platform.html) type that describes the dynamic library naming
+conventions of a target platform. This allows build tools, and deployment code, to compute library
+file names for a platform other than the one they are running on.
+
+# Example
+
+```rust
+use dygpi::platform::Platform;
+use std::path::PathBuf;
+
+let platform = Platform::from_target_triple("x86_64-pc-windows-msvc").unwrap();
+assert_eq!(platform, Platform::Windows);
+assert_eq!(
+    platform.make_dylib_name("plugins/sound".as_ref()),
+    PathBuf::from("plugins/sound.dll")
+);
+assert_eq!(
+    Platform::Linux.parse_dylib_name("plugins/libsound.so".as_ref()),
+    Some("sound".to_string())
+);
+```
+
+On macOS, libraries may also be named relative to the host executable, or the run-path search
+list, using the `@executable_path`, `@loader_path`, and `@rpath` prefixes, and may be packaged as
+framework bundles; see [`expand_install_name`](enum.Platform.html#method.expand_install_name) and
+[`framework_library`](enum.Platform.html#method.framework_library).
+
+*/
+
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The set of platforms, distinguished by their conventions for naming dynamic libraries.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// Linux, including Android; libraries are named `lib*.so`.
+    Linux,
+    /// macOS and iOS; libraries are named `lib*.dylib`.
+    MacOs,
+    /// Windows; libraries are named `*.dll`.
+    Windows,
+    /// Other Unix-like platforms (the BSDs, Solaris, etc.); libraries are named `lib*.so`.
+    Unix,
+}
+
+///
+/// The file name extension of a macOS framework bundle.
+///
+pub const FRAMEWORK_EXTENSION: &str = "framework";
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Platform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Platform::Linux => "linux",
+                Platform::MacOs => "macos",
+                Platform::Windows => "windows",
+                Platform::Unix => "unix",
+            }
+        )
+    }
+}
+
+impl Platform {
+    ///
+    /// Return the platform this crate was compiled for.
+    ///
+    pub fn current() -> Self {
+        if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+            Platform::MacOs
+        } else if cfg!(any(target_os = "linux", target_os = "android")) {
+            Platform::Linux
+        } else {
+            Platform::Unix
+        }
+    }
+
+    ///
+    /// Return the target triple this crate was compiled for, if it was recorded by the build.
+    ///
+    pub fn current_target_triple() -> Option<&'static str> {
+        option_env!("DYGPI_BUILD_TARGET")
+    }
+
+    ///
+    /// Determine the platform from a target triple such as `"aarch64-apple-darwin"` or
+    /// `"x86_64-unknown-linux-gnu"`. This method returns `None` if the operating system component
+    /// of the triple is not recognized.
+    ///
+    pub fn from_target_triple(target_triple: &str) -> Option<Self> {
+        let components: Vec<&str> = target_triple.split('-').collect();
+        let has = |name: &str| components.iter().skip(1).any(|c| c.starts_with(name));
+        if has("windows") {
+            Some(Platform::Windows)
+        } else if has("darwin") || has("macos") || has("ios") {
+            Some(Platform::MacOs)
+        } else if has("linux") || has("android") {
+            Some(Platform::Linux)
+        } else if has("freebsd")
+            || has("netbsd")
+            || has("openbsd")
+            || has("dragonfly")
+            || has("solaris")
+            || has("illumos")
+        {
+            Some(Platform::Unix)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// File name extension used for a dynamic library on this platform.
+    ///
+    pub fn dylib_extension(&self) -> &'static str {
+        match self {
+            Platform::Linux | Platform::Unix => "so",
+            Platform::MacOs => "dylib",
+            Platform::Windows => "dll",
+        }
+    }
+
+    ///
+    /// Prefix for dynamic libraries on this platform, if any.
+    ///
+    pub fn dylib_prefix(&self) -> &'static str {
+        match self {
+            Platform::Windows => "",
+            _ => "lib",
+        }
+    }
+
+    ///
+    /// Given a file name, or path with a file name, return a new path that formats the file name
+    /// according to the conventions of this platform. If the file name appears to have an
+    /// extension it will be overwritten by the platform extension.
+    ///
+    pub fn make_dylib_name(&self, file_path: &Path) -> PathBuf {
+        if let Some(file_stem) = file_path.file_stem() {
+            let mut file_name = OsString::from(self.dylib_prefix());
+            file_name.push(file_stem);
+            let mut file_path = file_path.to_path_buf();
+            file_path.set_file_name(file_name);
+            let _ = file_path.set_extension(self.dylib_extension());
+            file_path
+        } else {
+            file_path.to_path_buf()
+        }
+    }
+
+    ///
+    /// Given a file name without an extension, or path with such a file name, return the library
+    /// file names that may be used for it, in priority order; the names following the conventions
+    /// of this platform come first, followed by those of the other platforms, from `lib*.so`,
+    /// `*.so`, `lib*.dylib`, and `*.dll`. So, for `"plugins/sound"` on macOS this returns
+    /// `plugins/libsound.dylib`, `plugins/libsound.so`, `plugins/sound.so`, and
+    /// `plugins/sound.dll`. This allows libraries named for the wrong platform, as is common for
+    /// cross-compiled plugins, to be found. If the file name has an extension it is returned
+    /// unchanged as the only candidate.
+    ///
+    pub fn dylib_name_candidates(&self, file_path: &Path) -> Vec<PathBuf> {
+        let file_stem = match file_path.file_name() {
+            Some(file_stem) if file_path.extension().is_none() => file_stem,
+            _ => return vec![file_path.to_path_buf()],
+        };
+        let conventions = [("lib", "so"), ("", "so"), ("lib", "dylib"), ("", "dll")];
+        let is_native = |(prefix, extension): &(&str, &str)| {
+            *prefix == self.dylib_prefix() && *extension == self.dylib_extension()
+        };
+        conventions
+            .iter()
+            .filter(|convention| is_native(convention))
+            .chain(
+                conventions
+                    .iter()
+                    .filter(|convention| !is_native(convention)),
+            )
+            .map(|(prefix, extension)| {
+                let mut file_name = OsString::from(prefix);
+                file_name.push(file_stem);
+                file_name.push(".");
+                file_name.push(extension);
+                file_path.with_file_name(file_name)
+            })
+            .collect()
+    }
+
+    ///
+    /// The inverse of [`make_dylib_name`](#method.make_dylib_name), return the library name
+    /// without any platform prefix, extension, or version. This method returns `None` if the file
+    /// name does not follow the conventions of this platform.
+    ///
+    pub fn parse_dylib_name(&self, file_path: &Path) -> Option<String> {
+        self.parse_versioned_dylib_name(file_path)
+            .map(|(name, _)| name)
+    }
+
+    ///
+    /// Return the library name, as [`parse_dylib_name`](#method.parse_dylib_name), as well as any
+    /// version numbers in the file name. Versioned library names follow the SONAME conventions of
+    /// each platform, `libfoo.so.1.2.3` on Linux and other Unix platforms and `libfoo.1.2.3.dylib`
+    /// on macOS; Windows has no such convention. The version is empty for an unversioned name.
+    ///
+    pub fn parse_versioned_dylib_name(&self, file_path: &Path) -> Option<(String, Vec<u64>)> {
+        let file_name = file_path.file_name()?.to_str()?;
+        let file_name = file_name.strip_prefix(self.dylib_prefix())?;
+        let (name, version) = match self {
+            Platform::Linux | Platform::Unix => {
+                let extension = format!(".{}", self.dylib_extension());
+                if let Some(name) = file_name.strip_suffix(&extension) {
+                    (name, Vec::default())
+                } else {
+                    let index = file_name.find(&format!("{}.", extension))?;
+                    let version = parse_version(&file_name[index + extension.len() + 1..])?;
+                    (&file_name[..index], version)
+                }
+            }
+            Platform::MacOs | Platform::Windows => {
+                let (stem, extension) = file_name.rsplit_once('.')?;
+                if !extension.eq_ignore_ascii_case(self.dylib_extension()) {
+                    return None;
+                }
+                if *self == Platform::MacOs {
+                    split_version(stem)
+                } else {
+                    (stem, Vec::default())
+                }
+            }
+        };
+        if name.is_empty() {
+            None
+        } else {
+            Some((name.to_string(), version))
+        }
+    }
+
+    ///
+    /// Given the path of an unversioned library, for example `plugins/libfoo.so`, return the path
+    /// of the library with the highest version in the same directory, for example
+    /// `plugins/libfoo.so.1.10.0` in preference to `plugins/libfoo.so.1.2.3`. This method returns
+    /// `None` if no versioned library with the same name exists.
+    ///
+    pub fn find_versioned_dylib(&self, file_path: &Path) -> Option<PathBuf> {
+        let (name, version) = self.parse_versioned_dylib_name(file_path)?;
+        if !version.is_empty() {
+            return None;
+        }
+        let directory = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        std::fs::read_dir(directory)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .filter_map(|path| match self.parse_versioned_dylib_name(&path) {
+                Some((found, version)) if found == name && !version.is_empty() => {
+                    Some((version, path))
+                }
+                _ => None,
+            })
+            .max_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs))
+            .map(|(_, path)| path)
+    }
+
+    ///
+    /// Given the path of a framework bundle, for example `plugins/Sound.framework`, return the
+    /// path of the library within it, `plugins/Sound.framework/Sound`, or if the bundle has no
+    /// top-level link to its library, `plugins/Sound.framework/Versions/Current/Sound`. This
+    /// method returns `None` on platforms other than macOS, or if the path is not a framework
+    /// bundle holding its library.
+    ///
+    pub fn framework_library(&self, bundle_path: &Path) -> Option<PathBuf> {
+        if *self != Platform::MacOs
+            || bundle_path.extension() != Some(FRAMEWORK_EXTENSION.as_ref())
+            || !bundle_path.is_dir()
+        {
+            return None;
+        }
+        let file_stem = bundle_path.file_stem()?;
+        vec![
+            bundle_path.join(file_stem),
+            bundle_path.join("Versions").join("Current").join(file_stem),
+        ]
+        .into_iter()
+        .find(|library| library.is_file())
+    }
+
+    ///
+    /// Given a macOS install name style path, one starting with `@executable_path`,
+    /// `@loader_path`, or `@rpath`, return the paths it may refer to, in priority order. The
+    /// first two prefixes are replaced by `loader_directory`; plugin libraries are loaded by the
+    /// host, so this is usually the directory of the running executable. The `@rpath` prefix is
+    /// replaced by each of the `run_paths` in turn. This method returns `None` on platforms other
+    /// than macOS, or if the path does not start with one of these prefixes.
+    ///
+    pub fn expand_install_name(
+        &self,
+        file_path: &Path,
+        loader_directory: &Path,
+        run_paths: &[PathBuf],
+    ) -> Option<Vec<PathBuf>> {
+        if *self != Platform::MacOs {
+            return None;
+        }
+        if let Ok(file_path) = file_path.strip_prefix("@rpath") {
+            Some(
+                run_paths
+                    .iter()
+                    .map(|run_path| run_path.join(file_path))
+                    .collect(),
+            )
+        } else {
+            ["@executable_path", "@loader_path"]
+                .iter()
+                .find_map(|prefix| file_path.strip_prefix(prefix).ok())
+                .map(|file_path| vec![loader_directory.join(file_path)])
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .split('.')
+        .map(|component| component.parse::<u64>().ok())
+        .collect()
+}
+
+fn split_version(stem: &str) -> (&str, Vec<u64>) {
+    let mut name = stem;
+    let mut version = Vec::default();
+    while let Some((rest, component)) = name.rsplit_once('.') {
+        match component.parse::<u64>() {
+            Ok(component) => {
+                version.insert(0, component);
+                name = rest;
+            }
+            Err(_) => break,
+        }
+    }
+    (name, version)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_target_triple() {
+        assert_eq!(
+            Platform::from_target_triple("x86_64-unknown-linux-gnu"),
+            Some(Platform::Linux)
+        );
+        assert_eq!(
+            Platform::from_target_triple("aarch64-linux-android"),
+            Some(Platform::Linux)
+        );
+        assert_eq!(
+            Platform::from_target_triple("aarch64-apple-darwin"),
+            Some(Platform::MacOs)
+        );
+        assert_eq!(
+            Platform::from_target_triple("x86_64-pc-windows-gnu"),
+            Some(Platform::Windows)
+        );
+        assert_eq!(
+            Platform::from_target_triple("x86_64-unknown-freebsd"),
+            Some(Platform::Unix)
+        );
+        assert_eq!(Platform::from_target_triple("wasm32-unknown-unknown"), None);
+    }
+
+    #[test]
+    fn test_make_and_parse_dylib_name() {
+        for platform in &[
+            Platform::Linux,
+            Platform::MacOs,
+            Platform::Windows,
+            Platform::Unix,
+        ] {
+            let file_name = platform.make_dylib_name("plugins/my_lib.foo".as_ref());
+            assert_eq!(
+                platform.parse_dylib_name(&file_name),
+                Some("my_lib".to_string())
+            );
+        }
+        assert_eq!(
+            Platform::Windows.parse_dylib_name("libmy_lib.so".as_ref()),
+            None
+        );
+        assert_eq!(Platform::Linux.parse_dylib_name("my_lib.so".as_ref()), None);
+    }
+
+    #[test]
+    fn test_parse_versioned_dylib_name() {
+        assert_eq!(
+            Platform::Linux.parse_versioned_dylib_name("libfoo.so.1.2.3".as_ref()),
+            Some(("foo".to_string(), vec![1, 2, 3]))
+        );
+        assert_eq!(
+            Platform::MacOs.parse_versioned_dylib_name("libfoo.1.2.dylib".as_ref()),
+            Some(("foo".to_string(), vec![1, 2]))
+        );
+        assert_eq!(
+            Platform::Linux.parse_versioned_dylib_name("libfoo.so".as_ref()),
+            Some(("foo".to_string(), vec![]))
+        );
+        assert_eq!(
+            Platform::Linux.parse_versioned_dylib_name("libfoo.so.1.x".as_ref()),
+            None
+        );
+        assert_eq!(
+            Platform::Linux.parse_dylib_name("libfoo.so.1".as_ref()),
+            Some("foo".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dylib_name_candidates() {
+        let names = |platform: Platform| {
+            platform
+                .dylib_name_candidates("plugins/sound".as_ref())
+                .into_iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            names(Platform::Linux),
+            vec![
+                "plugins/libsound.so",
+                "plugins/sound.so",
+                "plugins/libsound.dylib",
+                "plugins/sound.dll"
+            ]
+        );
+        assert_eq!(
+            names(Platform::MacOs),
+            vec![
+                "plugins/libsound.dylib",
+                "plugins/libsound.so",
+                "plugins/sound.so",
+                "plugins/sound.dll"
+            ]
+        );
+        assert_eq!(
+            names(Platform::Windows),
+            vec![
+                "plugins/sound.dll",
+                "plugins/libsound.so",
+                "plugins/sound.so",
+                "plugins/libsound.dylib"
+            ]
+        );
+        assert_eq!(
+            Platform::Linux.dylib_name_candidates("plugins/libsound.so".as_ref()),
+            vec![PathBuf::from("plugins/libsound.so")]
+        );
+    }
+
+    #[test]
+    fn test_find_versioned_dylib() {
+        let directory = tempfile::tempdir().unwrap();
+        for file_name in &["libfoo.so.1.2.3", "libfoo.so.1.10.0", "libfoobar.so.9"] {
+            std::fs::write(directory.path().join(file_name), b"").unwrap();
+        }
+        assert_eq!(
+            Platform::Linux.find_versioned_dylib(&directory.path().join("libfoo.so")),
+            Some(directory.path().join("libfoo.so.1.10.0"))
+        );
+        assert_eq!(
+            Platform::Linux.find_versioned_dylib(&directory.path().join("libbar.so")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_framework_library() {
+        let directory = tempfile::tempdir().unwrap();
+        let flat = directory.path().join("Sound.framework");
+        std::fs::create_dir_all(&flat).unwrap();
+        std::fs::write(flat.join("Sound"), b"").unwrap();
+        let versioned = directory.path().join("Light.framework");
+        std::fs::create_dir_all(versioned.join("Versions/Current")).unwrap();
+        std::fs::write(versioned.join("Versions/Current/Light"), b"").unwrap();
+
+        assert_eq!(
+            Platform::MacOs.framework_library(&flat),
+            Some(flat.join("Sound"))
+        );
+        assert_eq!(
+            Platform::MacOs.framework_library(&versioned),
+            Some(versioned.join("Versions/Current/Light"))
+        );
+        assert_eq!(Platform::Linux.framework_library(&flat), None);
+        assert_eq!(
+            Platform::MacOs.framework_library(&directory.path().join("Other.framework")),
+            None
+        );
+        assert_eq!(Platform::MacOs.framework_library(directory.path()), None);
+    }
+
+    #[test]
+    fn test_expand_install_name() {
+        let loader_directory = Path::new("/Applications/Host.app/Contents/MacOS");
+        let run_paths = vec![
+            PathBuf::from("/plugins"),
+            PathBuf::from("/Library/Frameworks"),
+        ];
+        let expand = |platform: Platform, file_path: &str| {
+            platform.expand_install_name(file_path.as_ref(), loader_directory, &run_paths)
+        };
+        assert_eq!(
+            expand(Platform::MacOs, "@rpath/Sound.framework/Sound"),
+            Some(vec![
+                PathBuf::from("/plugins/Sound.framework/Sound"),
+                PathBuf::from("/Library/Frameworks/Sound.framework/Sound")
+            ])
+        );
+        assert_eq!(
+            expand(Platform::MacOs, "@loader_path/../PlugIns/libsound.dylib"),
+            Some(vec![loader_directory.join("../PlugIns/libsound.dylib")])
+        );
+        assert_eq!(
+            expand(Platform::MacOs, "@executable_path/libsound.dylib"),
+            Some(vec![loader_directory.join("libsound.dylib")])
+        );
+        assert_eq!(expand(Platform::MacOs, "plugins/libsound.dylib"), None);
+        assert_eq!(expand(Platform::Linux, "@rpath/libsound.so"), None);
+    }
+}
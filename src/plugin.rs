@@ -18,7 +18,7 @@ struct SoundEffectPlugin {
 };
 
 impl Plugin for SoundEffectPlugin {
-    fn plugin_id(&self) -> &String {
+    fn plugin_id(&self) -> &str {
         &self.id
     }
 
@@ -55,7 +55,7 @@ use dygpi::plugin::PluginRegistrar;
 #     media: MediaStream,
 # };
 # impl Plugin for SoundEffectPlugin {
-#     fn plugin_id(&self) -> &String {
+#     fn plugin_id(&self) -> &str {
 #         &self.id
 #     }
 #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
@@ -76,14 +76,30 @@ pub extern "C" fn register_plugins<MyPlugin>(
 }
 ```
 
+# Sharing Values
+
+Values passed between the plugin host and plugin providers, other than plugins, are matched to
+their type by `TypeId`, with `Any::downcast_ref`; this includes the event sink, see
+[`PluginRegistrar::event_sink`](struct.PluginRegistrar.html#method.event_sink), registered values,
+the parameters passed to instantiable plugins, and the values on the
+[`Blackboard`](../state/struct.Blackboard.html). The `TypeId` of a type defined outside the
+standard library depends on how the crate defining it was built, so a value is only found if the
+host and the provider were built together, with the same compiler and the same build of the crate
+defining its type, for example from the same cargo workspace. Otherwise the value is not found,
+it is never reinterpreted as another type.
+
 */
 
-use crate::error::Result;
-use std::any::Any;
-use std::collections::hash_map::DefaultHasher;
-use std::fmt::Debug;
-use std::hash::{Hash, Hasher};
-use std::sync::Arc;
+use crate::error::{Error, ErrorKind, Result};
+use crate::event::EventSink;
+use crate::state::Blackboard;
+use std::any::{type_name, Any, TypeId};
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::RangeInclusive;
+use std::os::raw::c_char;
+use std::sync::{Arc, RwLock};
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -100,7 +116,88 @@ pub trait Plugin: Any + Debug + Sync + Send {
     /// ```rust
     /// const PLUGIN_ID: &str = concat!(env!("CARGO_PKG_NAME"), "::", module_path!(), "::MyPlugin");
     /// ```
-    fn plugin_id(&self) -> &String;
+    fn plugin_id(&self) -> &str;
+
+    ///
+    /// Return an identifier for the version of the plugin API that defines this plugin type; this
+    /// is combined with the type's name in [`plugin_type_hash`](fn.plugin_type_hash.html) so that a
+    /// provider built against a different plugin type, or a different version of the plugin API
+    /// crate, is rejected at registration time. The default implementation returns an empty
+    /// string, plugin API crates should override it as shown below.
+    ///
+    /// ```rust
+    /// # use dygpi::plugin::Plugin;
+    /// # #[derive(Debug)] struct SoundEffectPlugin;
+    /// impl Plugin for SoundEffectPlugin {
+    ///     fn plugin_api_version() -> &'static str {
+    ///         concat!(env!("CARGO_PKG_NAME"), "@", env!("CARGO_PKG_VERSION"))
+    ///     }
+    /// #   fn plugin_id(&self) -> &str { unimplemented!() }
+    /// #   fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// #   fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+    /// }
+    /// ```
+    ///
+    fn plugin_api_version() -> &'static str
+    where
+        Self: Sized,
+    {
+        ""
+    }
+
+    ///
+    /// Return the version of this plugin, as a semantic version string such as `"1.2.0"`. A
+    /// plugin manager may hold several versions of a plugin with the same identifier, for example
+    /// while rolling out an upgrade, and select between them with
+    /// [`PluginManager::get_version`](../manager/struct.PluginManager.html#method.get_version).
+    /// The default implementation returns `None`, an unversioned plugin.
+    ///
+    fn plugin_version(&self) -> Option<&str> {
+        None
+    }
+
+    ///
+    /// Return a short, human-readable, description of this plugin, reported alongside its
+    /// identifier and version in [`PluginInfo`](../manager/struct.PluginInfo.html) and the
+    /// manager's status report. The default implementation returns an empty string.
+    ///
+    fn plugin_description(&self) -> &str {
+        ""
+    }
+
+    ///
+    /// Return the capabilities this plugin declares, such as `"realtime-safe"` or
+    /// `"offline-render"`. A plugin manager may be configured with a set of capabilities that every
+    /// plugin it loads must declare, see
+    /// [`PluginManager::set_required_capabilities`](../manager/struct.PluginManager.html#method.set_required_capabilities).
+    /// The meaning of each capability is agreed between the host and plugin providers, usually in
+    /// the plugin API crate. The default implementation declares no capabilities.
+    ///
+    fn plugin_capabilities(&self) -> &[&str] {
+        &[]
+    }
+
+    ///
+    /// Return alternate identifiers by which this plugin may be looked up, for example the
+    /// identifiers it was registered with before being renamed, so that references saved by the
+    /// host continue to resolve. Aliases are consulted by the plugin manager's lookups only when
+    /// no plugin is registered with the identifier itself. The default implementation declares no
+    /// aliases.
+    ///
+    fn plugin_aliases(&self) -> &[&str] {
+        &[]
+    }
+
+    ///
+    /// Return the named groups this plugin belongs to, such as `"visualizers"`, so that the host
+    /// may operate on all the plugins in a group as one, see
+    /// [`PluginManager::plugins_in_group`](../manager/struct.PluginManager.html#method.plugins_in_group).
+    /// The host may also add plugins to groups once they are loaded. The default implementation
+    /// declares no groups.
+    ///
+    fn plugin_groups(&self) -> &[&str] {
+        &[]
+    }
 
     ///
     /// Called by the plugin manager after the registration process is complete.
@@ -112,6 +209,108 @@ pub trait Plugin: Any + Debug + Sync + Send {
     /// is closed.
     ///
     fn on_unload(&self) -> Result<()>;
+
+    ///
+    /// Called by the plugin manager when a previously disabled plugin is enabled again. Plugins
+    /// are enabled when loaded, so this is not called after `on_load`. The default
+    /// implementation does nothing.
+    ///
+    fn on_enable(&self) -> Result<()> {
+        Ok(())
+    }
+
+    ///
+    /// Called by the plugin manager when the plugin is disabled, the plugin remains registered
+    /// and its library remains loaded. The default implementation does nothing.
+    ///
+    fn on_disable(&self) -> Result<()> {
+        Ok(())
+    }
+
+    ///
+    /// Called by the plugin manager to temporarily suspend the plugin, for example while a
+    /// resource it depends upon is reconfigured. The default implementation does nothing.
+    ///
+    fn on_pause(&self) -> Result<()> {
+        Ok(())
+    }
+
+    ///
+    /// Called by the plugin manager to resume a plugin previously suspended with `on_pause`.
+    /// The default implementation does nothing.
+    ///
+    fn on_resume(&self) -> Result<()> {
+        Ok(())
+    }
+
+    ///
+    /// Called by the plugin manager when a new configuration is applied, with
+    /// [`PluginManager::apply_configuration`](../manager/struct.PluginManager.html#method.apply_configuration),
+    /// and the library providing the plugin remains loaded. The default implementation does
+    /// nothing.
+    ///
+    fn on_config_changed(&self) -> Result<()> {
+        Ok(())
+    }
+
+    ///
+    /// Called by the plugin manager before the plugin is unloaded as part of a library reload,
+    /// the returned bytes are handed to the new instance with the same identifier via
+    /// `restore_state`. The default implementation returns no state.
+    ///
+    fn save_state(&self) -> Vec<u8> {
+        Vec::default()
+    }
+
+    ///
+    /// Called by the plugin manager, after `on_load`, on the new instance of a plugin that was
+    /// reloaded with any state returned by the previous instance's `save_state`. This is not
+    /// called if the previous instance returned no state. The default implementation does
+    /// nothing.
+    ///
+    fn restore_state(&self, state: &[u8]) -> Result<()> {
+        let _ = state;
+        Ok(())
+    }
+}
+
+///
+/// A marker for a kind of plugin, pairing the plugin type identifier used in configuration with
+/// the plugin type itself. A plugin API crate may export a marker for each of its plugin types,
+/// usually with the [`plugin_type!`](../macro.plugin_type.html) macro, so that hosts refer to
+/// the marker rather than a string identifier and a misspelled name is caught by the compiler.
+/// Markers are accepted by
+/// [`PluginManagerConfiguration::make_manager`](../config/struct.PluginManagerConfiguration.html#method.make_manager)
+/// and [`PluginManager::new_for_type`](../manager/struct.PluginManager.html#method.new_for_type).
+///
+/// ```rust
+/// use dygpi::plugin::{Plugin, PluginType};
+///
+/// #[derive(Debug)]
+/// pub struct SoundEffectPlugin;
+/// # impl Plugin for SoundEffectPlugin {
+/// #     fn plugin_id(&self) -> &str { unimplemented!() }
+/// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// # }
+///
+/// pub struct SoundEffects;
+///
+/// impl PluginType for SoundEffects {
+///     const ID: &'static str = "sound_effects";
+///     type Plugin = SoundEffectPlugin;
+/// }
+/// ```
+///
+pub trait PluginType {
+    ///
+    /// The plugin type identifier, which must be valid according to
+    /// [`is_valid_plugin_type`](../config/fn.is_valid_plugin_type.html).
+    ///
+    const ID: &'static str;
+
+    /// The type of the plugins of this kind.
+    type Plugin: Plugin;
 }
 
 ///
@@ -119,6 +318,13 @@ pub trait Plugin: Any + Debug + Sync + Send {
 /// library. This function constructs plugin instances and uses the registrar as a callback
 /// into the plugin manager.
 ///
+/// The function uses the C calling convention, matching the `extern "C"` functions exported by
+/// providers. The registrar is passed as an opaque pointer, its layout is only shared between
+/// the host and providers built with the same version of this crate and compiler, as verified by
+/// the plugin manager's compatibility check before the function is called. A panic in the
+/// registration function cannot unwind across the C ABI and aborts the process; errors should be
+/// reported with [`PluginRegistrar::error`](struct.PluginRegistrar.html#method.error).
+///
 /// ```rust
 /// use dygpi::plugin::PluginRegistrar;
 /// # use dygpi::plugin::Plugin;
@@ -132,7 +338,7 @@ pub trait Plugin: Any + Debug + Sync + Send {
 /// #     media: MediaStream,
 /// # };
 /// # impl Plugin for SoundEffectPlugin {
-/// #     fn plugin_id(&self) -> &String {
+/// #     fn plugin_id(&self) -> &str {
 /// #         &self.id
 /// #     }
 /// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
@@ -149,13 +355,29 @@ pub trait Plugin: Any + Debug + Sync + Send {
 /// }
 /// ```
 ///
-pub type PluginRegistrationFn<T> = fn(registrar: &mut PluginRegistrar<T>);
+pub type PluginRegistrationFn<T> = extern "C" fn(registrar: &mut PluginRegistrar<T>);
 
 ///
 /// The required name of the registration function (see the
 /// [`PluginRegistrationFn`](type.PluginRegistrationFn.html) type).
 ///
-pub const PLUGIN_REGISTRATION_FN_NAME: &[u8] = b"register_plugins\0";
+pub const PLUGIN_REGISTRATION_FN_NAME: &CStr = crate::symbol!("register_plugins");
+
+///
+/// The highest version of the registration protocol, the form of the
+/// [`PluginRegistrar`](struct.PluginRegistrar.html) passed to registration functions, supported
+/// by this crate. A provider may export a registration function for a specific protocol version
+/// by appending `_v<VERSION>` to its name, for example `register_plugins_v1`; the plugin manager
+/// calls the function for the highest version it supports, and a name without a version suffix
+/// is protocol version `1`.
+///
+pub const REGISTRATION_PROTOCOL_VERSION: u32 = 1;
+
+///
+/// The default prefix used to discover registration functions when registration function
+/// discovery is enabled on the plugin manager.
+///
+pub const DEFAULT_REGISTRATION_FN_PREFIX: &str = "register_";
 
 ///
 /// A registrar is created by a plugin manager and provided to the library's registration
@@ -166,17 +388,342 @@ pub struct PluginRegistrar<T>
 where
     T: Plugin,
 {
-    plugins: Vec<Arc<T>>,
-    error: Option<Box<dyn std::error::Error>>,
+    host_info: HostInfo,
+    host_api: HostApi,
+    event_sink: Option<Arc<dyn Any + Send + Sync>>,
+    blackboard: Blackboard,
+    plugin_type_hash: u64,
+    plugins: Vec<Registration<T>>,
+    values: Vec<ValueRegistration>,
+    max_plugins: Option<usize>,
+    priority: i32,
+    error: Option<(Option<String>, Box<dyn std::error::Error>)>,
+    incompatible: bool,
+    limit_exceeded: bool,
+}
+
+///
+/// Information about the plugin host, provided by the plugin manager to the registration function
+/// via the registrar. This allows a plugin provider to refuse to register plugins, or to adapt
+/// the plugins it registers, based upon the version of the host's plugin API.
+///
+/// The API version is defined by the host, it is not the version of this crate, and is
+/// independent of the compatibility check performed by the plugin manager.
+///
+/// # Example
+///
+/// ```rust
+/// use dygpi::plugin::{HostInfo, PluginRegistrar};
+/// # use dygpi::plugin::Plugin;
+/// # #[derive(Debug)]
+/// # struct SoundEffectPlugin;
+/// # impl Plugin for SoundEffectPlugin {
+/// #     fn plugin_id(&self) -> &str { unimplemented!() }
+/// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// # }
+/// # impl SoundEffectPlugin {
+/// #     pub fn new(id: &str) -> Self { unimplemented!() }
+/// #     pub fn new_with_automation(id: &str) -> Self { unimplemented!() }
+/// # }
+///
+/// #[no_mangle]
+/// pub extern "C" fn register_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+///     if registrar.require_api_version(2..=3) {
+///         if registrar.host_info().api_version() >= 3 {
+///             registrar.register(SoundEffectPlugin::new_with_automation("delay"));
+///         } else {
+///             registrar.register(SoundEffectPlugin::new("delay"));
+///         }
+///     }
+/// }
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostInfo {
+    api_version: u32,
+    host_name: String,
+    host_version: String,
+}
+
+///
+/// Services provided by the plugin host to plugin providers, as a table of function pointers
+/// into the host, provided by the plugin manager to the registration function via the registrar.
+/// The table may be copied and kept by plugins, the functions remain valid for the life of the
+/// host process.
+///
+/// By default log messages are passed to the host's `log` facade, configuration lookups return
+/// `None`, and events are discarded.
+///
+/// # Example
+///
+/// ```rust
+/// use dygpi::plugin::{HostApi, PluginRegistrar};
+/// # use dygpi::plugin::Plugin;
+/// # #[derive(Debug)] struct SoundEffectPlugin;
+/// # impl Plugin for SoundEffectPlugin {
+/// #     fn plugin_id(&self) -> &str { unimplemented!() }
+/// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// # }
+/// # impl SoundEffectPlugin {
+/// #     pub fn new(id: &str, host_api: HostApi) -> Self { unimplemented!() }
+/// # }
+///
+/// #[no_mangle]
+/// pub extern "C" fn register_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+///     let host_api = *registrar.host_api();
+///     if host_api.config("effects.delay.enabled").as_deref() != Some("false") {
+///         host_api.log(log::Level::Info, module_path!(), "registering delay effect");
+///         registrar.register(SoundEffectPlugin::new("delay", host_api));
+///     }
+/// }
+/// ```
+///
+#[derive(Clone, Copy, Debug)]
+pub struct HostApi {
+    log_fn: HostLogFn,
+    config_fn: HostConfigFn,
+    event_fn: HostEventFn,
+    max_level: Option<log::LevelFilter>,
+    host_logger: usize,
+}
+
+///
+/// The host function called by [`HostApi::log`](struct.HostApi.html#method.log), with the level,
+/// target, and message.
+///
+pub type HostLogFn = fn(log::Level, &str, &str);
+
+///
+/// The host function called by [`HostApi::config`](struct.HostApi.html#method.config), with the
+/// configuration key.
+///
+pub type HostConfigFn = fn(&str) -> Option<String>;
+
+///
+/// The host function called by [`HostApi::emit`](struct.HostApi.html#method.emit), with the event
+/// name and payload.
+///
+pub type HostEventFn = fn(&str, &str);
+
+///
+/// The inputs to the compatibility check, captured when `dygpi` was built into the plugin host or
+/// a plugin provider. A plugin library is only loaded if its inputs are the same as the host's,
+/// otherwise an
+/// [`IncompatibleLibraryVersion`](../error/enum.ErrorKind.html#variant.IncompatibleLibraryVersion)
+/// error, containing both, is returned.
+///
+/// A host may add its own inputs, such as the host application's version, see
+/// [`PluginManager::add_compatibility_input`](../manager/struct.PluginManager.html#method.add_compatibility_input);
+/// a library must then declare the same input, with the same value, using the
+/// [`compatibility_inputs`](../macro.compatibility_inputs.html) macro.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Compatibility {
+    dygpi_version: String,
+    rustc_version: String,
+    inputs: BTreeMap<String, String>,
+}
+
+///
+/// The value returned by [`compatibility_info`](fn.compatibility_info.html); each field is a
+/// NUL-terminated string with static lifetime in the library that returned it.
+///
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct CompatibilityInfo {
+    dygpi_version: *const c_char,
+    rustc_version: *const c_char,
+}
+
+///
+/// The parameters passed by the plugin host to
+/// [`PluginManager::instantiate`](../manager/struct.PluginManager.html#method.instantiate), and
+/// on to the factory registered with
+/// [`PluginRegistrar::register_instantiable`](struct.PluginRegistrar.html#method.register_instantiable).
+///
+/// The parameters are matched to their type as described in
+/// [sharing values](index.html#sharing-values).
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Parameters<'a> {
+    type_name: &'a str,
+    value: &'a dyn Any,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Forwards log records in a plugin provider to the host, see
+/// `PluginRegistrar::install_host_logger`. The host services are replaced on each install, so
+/// records go to the most recent host to install the logger.
+///
+#[derive(Debug)]
+struct HostLogger(RwLock<Option<HostApi>>);
+
+static HOST_LOGGER: HostLogger = HostLogger(RwLock::new(None));
+
+///
+/// A function, provided by a plugin provider, which creates new instances of a plugin.
+///
+pub(crate) enum PluginFactory<T>
+where
+    T: Plugin,
+{
+    /// Creates an instance for each use, see `PluginRegistrar::register_factory`.
+    PerUse(Arc<dyn Fn() -> T + Send + Sync>),
+    /// Creates an instance from the host's parameters, see
+    /// `PluginRegistrar::register_instantiable`.
+    Parameterized(Arc<InstantiateFn<T>>),
+}
+
+///
+/// A factory creating a plugin instance from the host's parameters.
+///
+pub(crate) type InstantiateFn<T> = dyn Fn(&Parameters<'_>) -> Result<T> + Send + Sync;
+
+///
+/// A plugin registered with a registrar, and the factory it was registered with, if any.
+///
+pub(crate) type Registration<T> = (Arc<T>, Option<PluginFactory<T>>);
+
+///
+/// A value registered with a registrar, keyed by its type and name.
+///
+pub(crate) type ValueRegistration = (TypeId, String, Arc<dyn Any + Send + Sync>);
+
+// ------------------------------------------------------------------------------------------------
+// Public Macros
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Construct a `&'static CStr` symbol name from a string literal, adding the terminating NUL
+/// character, for example for
+/// [`PluginManager::set_registration_fn_name`](manager/struct.PluginManager.html#method.set_registration_fn_name).
+/// A name containing a NUL character is rejected at compile time when used in a constant, and
+/// panics otherwise.
+///
+/// ```rust
+/// use dygpi::symbol;
+/// use std::ffi::CStr;
+///
+/// const REGISTER_SOURCES: &CStr = symbol!("register_sources");
+/// assert_eq!(REGISTER_SOURCES.to_bytes_with_nul(), b"register_sources\0");
+/// ```
+///
+#[macro_export]
+macro_rules! symbol {
+    ($name:expr) => {
+        match ::std::ffi::CStr::from_bytes_with_nul(concat!($name, "\0").as_bytes()) {
+            Ok(symbol) => symbol,
+            Err(_) => panic!("symbol names must not contain a NUL character"),
+        }
+    };
+}
+
+///
+/// Declare the compatibility inputs of a plugin provider, which must include each input added by
+/// the plugin host with
+/// [`PluginManager::add_compatibility_input`](manager/struct.PluginManager.html#method.add_compatibility_input),
+/// with the same value, for the provider's libraries to be loaded. Names and values must be
+/// string literals, or macros such as `env!` that expand to one, and must not contain a newline;
+/// names must also not contain `=`. This exports the function `compatibility_inputs` from the
+/// provider, and so may only be used once in a provider.
+///
+/// ```rust
+/// dygpi::compatibility_inputs! {
+///     "sound_host" => "2",
+///     "sound_api" => env!("CARGO_PKG_VERSION"),
+/// }
+/// ```
+///
+#[macro_export]
+macro_rules! compatibility_inputs {
+    ($($name:expr => $value:expr),* $(,)?) => {
+        #[allow(unsafe_code)]
+        #[no_mangle]
+        pub extern "C" fn compatibility_inputs() -> *const ::std::os::raw::c_char {
+            concat!($($name, "=", $value, "\n",)* "\0").as_ptr().cast()
+        }
+    };
+}
+
+///
+/// Declare a [`PluginType`](plugin/trait.PluginType.html) marker, a unit struct named for the kind
+/// of plugin, with its plugin type identifier and plugin type. An identifier that is not valid,
+/// see [`is_valid_plugin_type`](config/fn.is_valid_plugin_type.html), is rejected at compile
+/// time.
+///
+/// ```rust
+/// use dygpi::plugin::{Plugin, PluginType};
+///
+/// #[derive(Debug)]
+/// pub struct SoundEffectPlugin;
+/// # impl Plugin for SoundEffectPlugin {
+/// #     fn plugin_id(&self) -> &str { unimplemented!() }
+/// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// # }
+///
+/// dygpi::plugin_type! {
+///     /// Sound effects, configured as `sound_effects`.
+///     pub SoundEffects = "sound_effects" for SoundEffectPlugin
+/// }
+///
+/// assert_eq!(SoundEffects::ID, "sound_effects");
+/// ```
+///
+/// The following does not compile, as the identifier contains a space.
+///
+/// ```rust,compile_fail
+/// # use dygpi::plugin::Plugin;
+/// # #[derive(Debug)]
+/// # pub struct SoundEffectPlugin;
+/// # impl Plugin for SoundEffectPlugin {
+/// #     fn plugin_id(&self) -> &str { unimplemented!() }
+/// #     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// #     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+/// # }
+/// dygpi::plugin_type!(pub SoundEffects = "sound effects" for SoundEffectPlugin);
+/// ```
+///
+#[macro_export]
+macro_rules! plugin_type {
+    ($(#[$meta:meta])* $vis:vis $name:ident = $id:literal for $plugin:ty) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        $vis struct $name;
+
+        impl $crate::plugin::PluginType for $name {
+            const ID: &'static str = $id;
+            type Plugin = $plugin;
+        }
+
+        const _: () = assert!(
+            $crate::config::is_valid_plugin_type($id),
+            "invalid plugin type identifier"
+        );
+    };
 }
 
 // ------------------------------------------------------------------------------------------------
 // Public Functions
 // ------------------------------------------------------------------------------------------------
 
-pub(crate) type CompatibilityFn = fn() -> u64;
+pub(crate) type CompatibilityFn = extern "C" fn() -> u64;
+
+pub(crate) const COMPATIBILITY_FN_NAME: &CStr = crate::symbol!("compatibility_hash");
 
-pub(crate) const COMPATIBILITY_FN_NAME: &[u8] = b"compatibility_hash\0";
+pub(crate) type CompatibilityInfoFn = extern "C" fn() -> CompatibilityInfo;
+
+pub(crate) const COMPATIBILITY_INFO_FN_NAME: &CStr = crate::symbol!("compatibility_info");
+
+pub(crate) type CompatibilityInputsFn = extern "C" fn() -> *const c_char;
+
+pub(crate) const COMPATIBILITY_INPUTS_FN_NAME: &CStr = crate::symbol!("compatibility_inputs");
 
 ///
 /// This function is exposed so that the version linked into a plugin provider may be compared to
@@ -193,36 +740,547 @@ pub extern "C" fn compatibility_hash() -> u64 {
         CARGO_PKG_VERSION, RUSTC_VERSION
     );
 
-    let mut s = DefaultHasher::new();
-    CARGO_PKG_VERSION.hash(&mut s);
-    RUSTC_VERSION.hash(&mut s);
-    s.finish()
+    stable_hash(&[CARGO_PKG_VERSION, RUSTC_VERSION])
+}
+
+///
+/// This function is exposed so that, when the compatibility hashes differ, the plugin host can
+/// report which of the inputs to the hash differ.
+///
+#[allow(unsafe_code)]
+#[no_mangle]
+pub extern "C" fn compatibility_info() -> CompatibilityInfo {
+    CompatibilityInfo {
+        dygpi_version: concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast(),
+        rustc_version: concat!(env!("RUSTC_VERSION"), "\0").as_ptr().cast(),
+    }
+}
+
+///
+/// Return a hash identifying the plugin type `T`, computed from the type's name and its
+/// [`plugin_api_version`](trait.Plugin.html#method.plugin_api_version). The plugin manager
+/// provides the hash for its plugin type to the registrar, and the registrar compares it to the
+/// hash for the plugin type the provider was built against; any mismatch is reported as an
+/// [`IncompatiblePluginType`](../error/enum.ErrorKind.html#variant.IncompatiblePluginType)
+/// error and no plugins are registered.
+///
+pub fn plugin_type_hash<T>() -> u64
+where
+    T: Plugin,
+{
+    stable_hash(&[type_name::<T>(), T::plugin_api_version()])
+}
+
+///
+/// Return a hash of `inputs` that is the same in every build of every release of `dygpi`, and so
+/// may be compared between a plugin host and a plugin provider built separately. This is the
+/// 64-bit FNV-1a hash of the UTF-8 bytes of each input in turn, each followed by the byte `0xFF`,
+/// which cannot occur in UTF-8, so that `["ab", "c"]` and `["a", "bc"]` hash differently.
+///
+pub fn stable_hash(inputs: &[&str]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    inputs
+        .iter()
+        .flat_map(|input| input.bytes().chain(std::iter::once(0xFF)))
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        })
 }
 
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
+impl HostInfo {
+    ///
+    /// Construct a new host information value.
+    ///
+    pub fn new(api_version: u32, host_name: &str, host_version: &str) -> Self {
+        Self {
+            api_version,
+            host_name: host_name.to_string(),
+            host_version: host_version.to_string(),
+        }
+    }
+
+    ///
+    /// The version of the host's plugin API.
+    ///
+    pub fn api_version(&self) -> u32 {
+        self.api_version
+    }
+
+    ///
+    /// The name of the host application.
+    ///
+    pub fn host_name(&self) -> &String {
+        &self.host_name
+    }
+
+    ///
+    /// The version of the host application.
+    ///
+    pub fn host_version(&self) -> &String {
+        &self.host_version
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Display for Compatibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "dygpi {} built with rustc {}",
+            self.dygpi_version, self.rustc_version
+        )?;
+        if !self.inputs.is_empty() {
+            write!(
+                f,
+                " ({})",
+                self.inputs
+                    .iter()
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Compatibility {
+    ///
+    /// Construct a new compatibility value.
+    ///
+    pub fn new(dygpi_version: &str, rustc_version: &str) -> Self {
+        Self {
+            dygpi_version: dygpi_version.to_string(),
+            rustc_version: rustc_version.to_string(),
+            inputs: Default::default(),
+        }
+    }
+
+    ///
+    /// The compatibility inputs of the plugin host, that is those of this build of `dygpi`.
+    ///
+    pub fn host() -> Self {
+        Self::new(env!("CARGO_PKG_VERSION"), env!("RUSTC_VERSION"))
+    }
+
+    ///
+    /// Copy the values returned by a library's `compatibility_info` function.
+    ///
+    /// # Safety
+    ///
+    /// The library that returned `info` must still be loaded.
+    ///
+    #[allow(unsafe_code)]
+    pub(crate) unsafe fn from_info(info: &CompatibilityInfo) -> Self {
+        Self {
+            dygpi_version: CStr::from_ptr(info.dygpi_version)
+                .to_string_lossy()
+                .to_string(),
+            rustc_version: CStr::from_ptr(info.rustc_version)
+                .to_string_lossy()
+                .to_string(),
+            inputs: Default::default(),
+        }
+    }
+
+    ///
+    /// Add the inputs returned by a library's `compatibility_inputs` function, one `name=value`
+    /// per line.
+    ///
+    /// # Safety
+    ///
+    /// The library that returned `inputs` must still be loaded.
+    ///
+    #[allow(unsafe_code)]
+    pub(crate) unsafe fn add_inputs_from(&mut self, inputs: *const c_char) {
+        for line in CStr::from_ptr(inputs).to_string_lossy().lines() {
+            if let Some((name, value)) = line.split_once('=') {
+                self.add_input(name, value);
+            }
+        }
+    }
+
+    /// The version of `dygpi`.
+    pub fn dygpi_version(&self) -> &String {
+        &self.dygpi_version
+    }
+
+    /// The version of the compiler used to build `dygpi`.
+    pub fn rustc_version(&self) -> &String {
+        &self.rustc_version
+    }
+
+    ///
+    /// Add an input, replacing any existing input with the same name.
+    ///
+    pub fn add_input(&mut self, name: &str, value: &str) {
+        let _ = self.inputs.insert(name.to_string(), value.to_string());
+    }
+
+    /// The value of the named input, if present.
+    pub fn input(&self, name: &str) -> Option<&String> {
+        self.inputs.get(name)
+    }
+
+    /// Return an iterator over the added inputs, as name and value pairs, ordered by name.
+    pub fn inputs(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.inputs.iter()
+    }
+
+    ///
+    /// Return the name, and the values in `self` and `expected`, of each input that differs; the
+    /// added inputs compared are those in `expected`, any others in `self` are ignored. An input
+    /// missing from `self` has the value `"unset"`.
+    ///
+    pub fn differences<'a>(&'a self, expected: &'a Self) -> Vec<(&'a str, &'a str, &'a str)> {
+        [
+            ("dygpi", &self.dygpi_version, &expected.dygpi_version),
+            ("rustc", &self.rustc_version, &expected.rustc_version),
+        ]
+        .iter()
+        .filter(|(_, ours, theirs)| ours != theirs)
+        .map(|(name, ours, theirs)| (*name, ours.as_str(), theirs.as_str()))
+        .chain(
+            expected
+                .inputs
+                .iter()
+                .filter_map(|(name, theirs)| match self.inputs.get(name) {
+                    Some(ours) if ours == theirs => None,
+                    ours => Some((
+                        name.as_str(),
+                        ours.map(String::as_str).unwrap_or("unset"),
+                        theirs.as_str(),
+                    )),
+                }),
+        )
+        .collect()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Default for HostApi {
+    fn default() -> Self {
+        Self {
+            log_fn: host_log,
+            config_fn: |_| None,
+            event_fn: |_, _| {},
+            max_level: None,
+            host_logger: 0,
+        }
+    }
+}
+
+impl HostApi {
+    ///
+    /// Set the function called to log a message from a plugin.
+    ///
+    pub fn set_log_fn(&mut self, log_fn: HostLogFn) {
+        self.log_fn = log_fn;
+    }
+
+    ///
+    /// Set the function called to look up a configuration value for a plugin.
+    ///
+    pub fn set_config_fn(&mut self, config_fn: HostConfigFn) {
+        self.config_fn = config_fn;
+    }
+
+    ///
+    /// Set the function called when a plugin emits an event.
+    ///
+    pub fn set_event_fn(&mut self, event_fn: HostEventFn) {
+        self.event_fn = event_fn;
+    }
+
+    ///
+    /// Set the most verbose level of messages forwarded by a provider's host logger, see
+    /// [`PluginRegistrar::install_host_logger`](struct.PluginRegistrar.html#method.install_host_logger).
+    /// By default this is the host's `log::max_level()` at the time of registration.
+    ///
+    pub fn set_max_level(&mut self, max_level: log::LevelFilter) {
+        self.max_level = Some(max_level);
+    }
+
+    ///
+    /// The most verbose level of messages forwarded by a provider's host logger.
+    ///
+    pub fn max_level(&self) -> log::LevelFilter {
+        self.max_level.unwrap_or_else(log::max_level)
+    }
+
+    ///
+    /// Log a message in the host, the target is usually the plugin provider's module path.
+    ///
+    pub fn log(&self, level: log::Level, target: &str, message: &str) {
+        (self.log_fn)(level, target, message)
+    }
+
+    ///
+    /// Look up a configuration value in the host.
+    ///
+    pub fn config(&self, key: &str) -> Option<String> {
+        (self.config_fn)(key)
+    }
+
+    ///
+    /// Emit an event, with a payload, to the host.
+    ///
+    pub fn emit(&self, event: &str, payload: &str) {
+        (self.event_fn)(event, payload)
+    }
+
+    ///
+    /// Complete the table in the host before it is passed to a registration function.
+    ///
+    pub(crate) fn for_registration(mut self) -> Self {
+        self.max_level = Some(self.max_level());
+        self.host_logger = logger_address();
+        self
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
 impl<T> PluginRegistrar<T>
 where
     T: Plugin,
 {
-    pub(crate) fn default() -> Self {
+    pub(crate) fn new(
+        host_info: HostInfo,
+        host_api: HostApi,
+        event_sink: Option<Arc<dyn Any + Send + Sync>>,
+        blackboard: Blackboard,
+        plugin_type_hash: u64,
+        max_plugins: Option<usize>,
+    ) -> Self {
         Self {
+            host_info,
+            host_api,
+            event_sink,
+            blackboard,
+            plugin_type_hash,
             plugins: Default::default(),
+            values: Default::default(),
+            max_plugins,
+            priority: 0,
             error: None,
+            incompatible: false,
+            limit_exceeded: false,
+        }
+    }
+
+    ///
+    /// Return information about the plugin host performing the registration.
+    ///
+    pub fn host_info(&self) -> &HostInfo {
+        &self.host_info
+    }
+
+    ///
+    /// Return the services provided by the plugin host performing the registration.
+    ///
+    pub fn host_api(&self) -> &HostApi {
+        &self.host_api
+    }
+
+    ///
+    /// Return the sink for events of type `E` set on the plugin manager performing the
+    /// registration, see the [`event`](../event/index.html) module, or `None` if no sink was set
+    /// or the sink is for events of a different type, see
+    /// [sharing values](index.html#sharing-values).
+    ///
+    pub fn event_sink<E>(&self) -> Option<EventSink<E>>
+    where
+        E: Send + 'static,
+    {
+        self.event_sink
+            .as_ref()
+            .and_then(|sink| sink.downcast_ref::<EventSink<E>>())
+            .cloned()
+    }
+
+    ///
+    /// Return the blackboard of the plugin manager performing the registration, see the
+    /// [`state`](../state/index.html) module. Plugins may keep the returned handle to share
+    /// data with other plugins, and with the host, after registration.
+    ///
+    pub fn blackboard(&self) -> Blackboard {
+        self.blackboard.clone()
+    }
+
+    ///
+    /// Install a logger in the plugin provider that forwards all `log` output from the provider
+    /// to the host's logger, via [`HostApi::log`](struct.HostApi.html#method.log). A dynamic
+    /// library has its own copy of the `log` crate, and so of its global logger, and without
+    /// this the provider's log output is discarded. Crates using `tracing` may forward their
+    /// events to the installed logger by enabling `tracing`'s `log` feature.
+    ///
+    /// Returns `true` if the logger was installed, or `false` if the provider already has a
+    /// logger, or shares its `log` crate with the host. If the logger was installed by an earlier
+    /// registration, it forwards to the host services of this registrar from now on.
+    ///
+    pub fn install_host_logger(&self) -> bool {
+        if self.host_api.host_logger == logger_address() {
+            return false;
+        }
+        let installed = log::set_logger(&HOST_LOGGER).is_ok()
+            || logger_address() == std::ptr::from_ref(&HOST_LOGGER).cast::<()>() as usize;
+        if installed {
+            *HOST_LOGGER.0.write().unwrap() = Some(self.host_api);
+            log::set_max_level(self.host_api.max_level());
+        }
+        installed
+    }
+
+    ///
+    /// Returns `true` if the host's API version is within the provided range, else records an
+    /// [`IncompatibleHostApiVersion`](../error/enum.ErrorKind.html#variant.IncompatibleHostApiVersion)
+    /// error with the registrar and returns `false`; in this case the registration function should
+    /// not register any plugins.
+    ///
+    pub fn require_api_version(&mut self, supported: RangeInclusive<u32>) -> bool {
+        let api_version = self.host_info.api_version();
+        if supported.contains(&api_version) {
+            true
+        } else {
+            self.error(Box::new(Error::from(
+                ErrorKind::IncompatibleHostApiVersion { api_version },
+            )));
+            self.incompatible = true;
+            false
         }
     }
 
     ///
     /// Register a plugin, this will store the plugin in the registrar until the registration is
     /// completed. After the registration function completes, the plugin manager will add all
-    /// plugins, if no errors were reported.
+    /// plugins, if no errors were reported, calling each plugin's `on_load` method in the order
+    /// the plugins were registered.
+    ///
+    /// If the plugin type the provider was built against does not match the plugin manager's
+    /// plugin type the plugin is discarded and an error is recorded. If the plugin manager limits
+    /// the number of plugins per library, plugins registered beyond the limit are discarded and
+    /// the library fails to load.
     ///
     pub fn register(&mut self, plugin: T) {
-        if self.error.is_none() {
-            self.plugins.push(Arc::new(plugin));
-        }
+        self.register_plugin(plugin, None)
+    }
+
+    ///
+    /// Register a plugin created by `factory`, which the plugin manager also calls to create a
+    /// new instance of the plugin each time it is requested, rather than sharing the registered
+    /// instance; see [`Instancing`](../manager/enum.Instancing.html). Plugins with state that
+    /// cannot be shared, such as an effect with per-channel buffers, should be registered this
+    /// way. Only the registered instance receives lifecycle calls such as `on_load`, instances
+    /// created by the factory must be ready for use.
+    ///
+    pub fn register_factory<F>(&mut self, factory: F)
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        let plugin = factory();
+        self.register_plugin(plugin, Some(PluginFactory::PerUse(Arc::new(factory))))
+    }
+
+    ///
+    /// Register a plugin, and a factory which the plugin manager calls to create configured
+    /// instances of the plugin with the parameters passed by the host to
+    /// [`PluginManager::instantiate`](../manager/struct.PluginManager.html#method.instantiate),
+    /// for example a sample rate and channel count. The parameters are of a type agreed between
+    /// the host and plugin providers, usually defined in the plugin API crate, and are retrieved
+    /// with [`Parameters::get`](struct.Parameters.html#method.get), which returns a
+    /// [`WrongParameterType`](../error/enum.ErrorKind.html#variant.WrongParameterType) error for
+    /// parameters of another type. Lookups return the registered instance, and only it receives
+    /// lifecycle calls such as `on_load`.
+    ///
+    /// ```rust
+    /// use dygpi::error::Result;
+    /// use dygpi::plugin::{Parameters, PluginRegistrar};
+    /// # use dygpi::plugin::Plugin;
+    /// # #[derive(Debug)] struct SoundEffectPlugin;
+    /// # impl Plugin for SoundEffectPlugin {
+    /// #     fn plugin_id(&self) -> &str { unimplemented!() }
+    /// #     fn on_load(&self) -> Result<()> { Ok(()) }
+    /// #     fn on_unload(&self) -> Result<()> { Ok(()) }
+    /// # }
+    /// # impl SoundEffectPlugin {
+    /// #     pub fn new(id: &str, sample_rate: u32, channels: u16) -> Self { unimplemented!() }
+    /// # }
+    /// # const PLUGIN_ID: &str = "sound_plugin::DelayEffect";
+    /// pub struct EffectParameters {
+    ///     pub sample_rate: u32,
+    ///     pub channels: u16,
+    /// }
+    ///
+    /// fn new_delay(params: &Parameters<'_>) -> Result<SoundEffectPlugin> {
+    ///     let params = params.get::<EffectParameters>(PLUGIN_ID)?;
+    ///     Ok(SoundEffectPlugin::new(
+    ///         PLUGIN_ID,
+    ///         params.sample_rate,
+    ///         params.channels,
+    ///     ))
+    /// }
+    ///
+    /// #[no_mangle]
+    /// pub extern "C" fn register_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    ///     registrar.register_instantiable(SoundEffectPlugin::new(PLUGIN_ID, 48_000, 2), new_delay);
+    /// }
+    /// ```
+    ///
+    pub fn register_instantiable<F>(&mut self, plugin: T, factory: F)
+    where
+        F: Fn(&Parameters<'_>) -> Result<T> + Send + Sync + 'static,
+    {
+        self.register_plugin(
+            plugin,
+            Some(PluginFactory::Parameterized(Arc::new(factory))),
+        )
+    }
+
+    ///
+    /// Set the priority of this library's plugins when the library is loaded together with
+    /// others, for example from a directory or a configuration. All the libraries are opened, and
+    /// their plugins registered, before any plugin's `on_load` method is called; the plugins of
+    /// libraries with a higher priority are then loaded first, and libraries with the same
+    /// priority are loaded in the order provided. Within a library plugins are always loaded in
+    /// the order they were registered. The default priority is `0`.
+    ///
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority
+    }
+
+    /// The priority of this library's plugins, see [`set_priority`](#method.set_priority).
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    ///
+    /// Register a value that is not a plugin, such as a codec table or a schema descriptor, to
+    /// be handed to the host alongside the library's plugins. The plugin manager stores the value
+    /// keyed by its type and `name`, where it is retrieved with
+    /// [`PluginManager::registered_value`](../manager/struct.PluginManager.html#method.registered_value),
+    /// replacing any value of the same type and name registered previously.
+    ///
+    /// Values are only stored if the library's plugins load, and are removed when the library's
+    /// plugins are unloaded; a library that registers values but no plugins is not loaded, and
+    /// its values are discarded. As with the plugin type, the types of values are usually
+    /// defined in the plugin API crate, and are matched as described in
+    /// [sharing values](index.html#sharing-values).
+    ///
+    pub fn register_value<V>(&mut self, name: &str, value: V)
+    where
+        V: Any + Send + Sync,
+    {
+        self.values
+            .push((TypeId::of::<V>(), name.to_string(), Arc::new(value)));
     }
 
     ///
@@ -230,13 +1288,295 @@ where
     /// propagate out of the plugin manager.
     ///
     pub fn error(&mut self, error: Box<dyn std::error::Error>) {
-        self.error = Some(error);
+        self.error = Some((None, error));
+    }
+
+    ///
+    /// Inform the registrar of an error creating, or registering, the plugin with the identifier
+    /// `plugin_id`; the identifier is reported by the
+    /// [`PluginRegistration`](../error/enum.ErrorKind.html#variant.PluginRegistration) error
+    /// returned by the plugin manager, see
+    /// [`Error::failed_plugin_id`](../error/struct.Error.html#method.failed_plugin_id). As with
+    /// [`error`](#method.error), only the last error recorded will propagate.
+    ///
+    pub fn error_for(&mut self, plugin_id: &str, error: Box<dyn std::error::Error>) {
+        self.error = Some((Some(plugin_id.to_string()), error));
+    }
+
+    fn register_plugin(&mut self, plugin: T, factory: Option<PluginFactory<T>>) {
+        if self.plugin_type_hash != plugin_type_hash::<T>() {
+            error!(
+                "Provider plugin type {:?} does not match the plugin manager",
+                type_name::<T>()
+            );
+            self.error(Box::new(Error::from(ErrorKind::IncompatiblePluginType {
+                type_name: type_name::<T>().to_string(),
+            })));
+            self.incompatible = true;
+        } else if self
+            .max_plugins
+            .is_some_and(|max_plugins| self.plugins.len() >= max_plugins)
+        {
+            if !self.limit_exceeded {
+                error!("Provider registered more plugins than the limit, discarding");
+            }
+            self.limit_exceeded = true;
+        } else if self.error.is_none() {
+            self.plugins.push((Arc::new(plugin), factory));
+        }
+    }
+
+    pub(crate) fn is_incompatible(&self) -> bool {
+        self.incompatible
     }
 
-    pub(crate) fn plugins(self) -> std::result::Result<Vec<Arc<T>>, Box<dyn std::error::Error>> {
+    pub(crate) fn is_limit_exceeded(&self) -> bool {
+        self.limit_exceeded
+    }
+
+    ///
+    /// Take the values registered with [`register_value`](#method.register_value).
+    ///
+    pub(crate) fn take_values(&mut self) -> Vec<ValueRegistration> {
+        std::mem::take(&mut self.values)
+    }
+
+    ///
+    /// Return the registered plugins, or a
+    /// [`PluginRegistration`](../error/enum.ErrorKind.html#variant.PluginRegistration) error if
+    /// the provider reported one; the error must be detached before the library is closed.
+    ///
+    pub(crate) fn plugins(self) -> Result<Vec<Registration<T>>> {
         match self.error {
             None => Ok(self.plugins),
-            Some(error) => Err(error),
+            Some((plugin_id, source)) => {
+                Err(ErrorKind::PluginRegistration { plugin_id, source }.into())
+            }
         }
     }
 }
+
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> Parameters<'a> {
+    ///
+    /// Wrap the parameters `value`, for example to call a factory directly in a provider's tests.
+    ///
+    pub fn new<P>(value: &'a P) -> Self
+    where
+        P: Any,
+    {
+        Self {
+            type_name: type_name::<P>(),
+            value,
+        }
+    }
+
+    /// The name of the type of the parameters, see `std::any::type_name`.
+    pub fn type_name(&self) -> &str {
+        self.type_name
+    }
+
+    ///
+    /// Return the parameters if they are of type `P`, else `None`.
+    ///
+    pub fn downcast_ref<P>(&self) -> Option<&'a P>
+    where
+        P: Any,
+    {
+        self.value.downcast_ref()
+    }
+
+    ///
+    /// Return the parameters if they are of type `P`, else a
+    /// [`WrongParameterType`](../error/enum.ErrorKind.html#variant.WrongParameterType) error
+    /// naming both types for the plugin `plugin_id`.
+    ///
+    pub fn get<P>(&self, plugin_id: &str) -> Result<&'a P>
+    where
+        P: Any,
+    {
+        self.downcast_ref()
+            .ok_or_else(|| Error::wrong_parameter_type(plugin_id, type_name::<P>(), self.type_name))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Clone for PluginFactory<T>
+where
+    T: Plugin,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::PerUse(factory) => Self::PerUse(factory.clone()),
+            Self::Parameterized(factory) => Self::Parameterized(factory.clone()),
+        }
+    }
+}
+
+impl<T> Debug for PluginFactory<T>
+where
+    T: Plugin,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PerUse(_) => write!(f, "PerUse<{}>", type_name::<T>()),
+            Self::Parameterized(_) => write!(f, "Parameterized<{}>", type_name::<T>()),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl log::Log for HostLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        match *self.0.read().unwrap() {
+            Some(host_api) => metadata.level() <= host_api.max_level(),
+            None => false,
+        }
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if let Some(host_api) = *self.0.read().unwrap() {
+            if record.level() <= host_api.max_level() {
+                host_api.log(record.level(), record.target(), &record.args().to_string())
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The address of the current global logger, this identifies the copy of the `log` crate in use.
+///
+fn logger_address() -> usize {
+    std::ptr::from_ref(log::logger()).cast::<()>() as usize
+}
+
+fn host_log(level: log::Level, target: &str, message: &str) {
+    log!(target: target, level, "{}", message);
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_hash() {
+        assert_eq!(stable_hash(&[]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(&["a"]), 0x089b_c907_b544_c769);
+        assert_eq!(stable_hash(&["0.1.0", "1.95.0"]), 0x6976_b114_d613_136b);
+        assert_ne!(stable_hash(&["ab", "c"]), stable_hash(&["a", "bc"]));
+    }
+
+    #[test]
+    fn test_compatibility_differences() {
+        let mut host = Compatibility::new("0.1.0", "1.95.0");
+        host.add_input("sound_host", "2");
+        let mut library = Compatibility::new("0.1.0", "1.94.0");
+        library.add_input("other", "1");
+        assert_eq!(
+            library.differences(&host),
+            vec![("rustc", "1.94.0", "1.95.0"), ("sound_host", "unset", "2")]
+        );
+
+        library.add_input("sound_host", "2");
+        assert_eq!(
+            library.differences(&host),
+            vec![("rustc", "1.94.0", "1.95.0")]
+        );
+        assert!(host.differences(&host).is_empty());
+        assert_eq!(
+            host.to_string(),
+            "dygpi 0.1.0 built with rustc 1.95.0 (sound_host=2)"
+        );
+    }
+
+    #[derive(Debug)]
+    struct TestPlugin;
+
+    impl Plugin for TestPlugin {
+        fn plugin_id(&self) -> &str {
+            "test::TestPlugin"
+        }
+        fn on_load(&self) -> Result<()> {
+            Ok(())
+        }
+        fn on_unload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_event_sink_matched_by_type() {
+        let (sink, _events) = EventSink::<u32>::channel();
+        let registrar: PluginRegistrar<TestPlugin> = PluginRegistrar::new(
+            HostInfo::default(),
+            HostApi::default(),
+            Some(Arc::new(sink)),
+            Blackboard::default(),
+            plugin_type_hash::<TestPlugin>(),
+            None,
+        );
+        assert!(registrar.event_sink::<u32>().is_some());
+        assert!(registrar.event_sink::<i32>().is_none());
+        assert!(registrar.event_sink::<String>().is_none());
+    }
+
+    #[test]
+    fn test_parameters_matched_by_type() {
+        let params = Parameters::new(&5u32);
+        assert_eq!(params.downcast_ref::<u32>(), Some(&5));
+        assert!(params.downcast_ref::<i32>().is_none());
+        assert_eq!(
+            params
+                .get::<i32>("test::TestPlugin")
+                .unwrap_err()
+                .to_string(),
+            "Plugin 'test::TestPlugin' expects parameters of type 'i32', but was given 'u32'"
+        );
+    }
+
+    fn make_registrar() -> PluginRegistrar<TestPlugin> {
+        PluginRegistrar::new(
+            HostInfo::default(),
+            HostApi::default(),
+            None,
+            Blackboard::default(),
+            plugin_type_hash::<TestPlugin>(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_error_for() {
+        let mut registrar = make_registrar();
+        registrar.register(TestPlugin);
+        registrar.error_for("test::TestPlugin", Box::from("no audio device"));
+        let error = registrar.plugins().err().unwrap();
+        assert_eq!(error.failed_plugin_id(), Some("test::TestPlugin"));
+        assert_eq!(
+            error.to_string(),
+            "Plugin 'test::TestPlugin' failed to register; error: 'no audio device'"
+        );
+
+        let mut registrar = make_registrar();
+        registrar.error(Box::from("no audio device"));
+        let error = registrar.plugins().err().unwrap();
+        assert_eq!(error.failed_plugin_id(), None);
+        assert_eq!(
+            error.to_string(),
+            "Plugin(s) failed to register; error: 'no audio device'"
+        );
+    }
+}
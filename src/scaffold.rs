@@ -0,0 +1,275 @@
+/*!
+Provides the [`ProviderTemplate`](struct.ProviderTemplate.html) type, which generates the skeleton
+of a new plugin provider crate. The generated crate is built as a `dylib`, so that the
+compatibility function linked in from `dygpi` is exported alongside the registration function,
+and has a build script that emits the compatibility inputs, see the `dygpi-build` crate. This
+module is only available if the feature `clap` is enabled.
+
+# Example
+
+```rust,no_run
+use dygpi::scaffold::ProviderTemplate;
+use std::path::Path;
+
+let mut template = ProviderTemplate::new("sound_one", "sound_api", "SoundEffectPlugin").unwrap();
+template.set_plugin_api_path("../sound_api");
+let crate_directory = template.generate(Path::new(".")).unwrap();
+println!("Created provider crate in {:?}", crate_directory);
+```
+
+Creates the following files:
+
+```text
+sound_one/Cargo.toml
+sound_one/build.rs
+sound_one/src/lib.rs
+```
+
+*/
+
+use crate::error::{Error, Result};
+use crate::plugin::PLUGIN_REGISTRATION_FN_NAME;
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A template for a new plugin provider crate, see the [module documentation](index.html).
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProviderTemplate {
+    crate_name: String,
+    plugin_api_crate: String,
+    plugin_api_dependency: PluginApiDependency,
+    plugin_type: String,
+    registration_fn_name: String,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PluginApiDependency {
+    Version(String),
+    Path(PathBuf),
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl ProviderTemplate {
+    ///
+    /// Create a template for the provider crate `crate_name`, providing plugins of the type
+    /// `plugin_type` defined in the plugin API crate `plugin_api_crate`. An error is returned if
+    /// either crate name is not a valid package name, or `plugin_type` is not a valid identifier.
+    ///
+    pub fn new(crate_name: &str, plugin_api_crate: &str, plugin_type: &str) -> Result<Self> {
+        for (name, allow_hyphen) in [
+            (crate_name, true),
+            (plugin_api_crate, true),
+            (plugin_type, false),
+        ] {
+            if !is_identifier(name, allow_hyphen) {
+                return Err(Error::provider_generation_failed(
+                    crate_name,
+                    format!("'{}' is not a valid name", name),
+                ));
+            }
+        }
+        Ok(Self {
+            crate_name: crate_name.to_string(),
+            plugin_api_crate: plugin_api_crate.to_string(),
+            plugin_api_dependency: PluginApiDependency::Version("*".to_string()),
+            plugin_type: plugin_type.to_string(),
+            registration_fn_name: PLUGIN_REGISTRATION_FN_NAME.to_string_lossy().to_string(),
+        })
+    }
+
+    ///
+    /// Depend on the published plugin API crate with this version requirement; the default is
+    /// `"*"`.
+    ///
+    pub fn set_plugin_api_version(&mut self, version: &str) {
+        self.plugin_api_dependency = PluginApiDependency::Version(version.to_string())
+    }
+
+    ///
+    /// Depend on the plugin API crate at this path, relative to the generated crate.
+    ///
+    pub fn set_plugin_api_path<P>(&mut self, path: P)
+    where
+        P: AsRef<Path>,
+    {
+        self.plugin_api_dependency = PluginApiDependency::Path(path.as_ref().to_path_buf())
+    }
+
+    ///
+    /// Override the default registration function name
+    /// [`PLUGIN_REGISTRATION_FN_NAME`](../plugin/constant.PLUGIN_REGISTRATION_FN_NAME.html), for
+    /// hosts that call
+    /// [`PluginManager::set_registration_fn_name`](../manager/struct.PluginManager.html#method.set_registration_fn_name).
+    ///
+    pub fn set_registration_fn_name(&mut self, name: &CStr) {
+        self.registration_fn_name = name.to_string_lossy().to_string()
+    }
+
+    /// The name of the provider crate.
+    pub fn crate_name(&self) -> &String {
+        &self.crate_name
+    }
+
+    ///
+    /// Return the contents of the generated `Cargo.toml` file.
+    ///
+    pub fn cargo_toml(&self) -> String {
+        let plugin_api_dependency = match &self.plugin_api_dependency {
+            PluginApiDependency::Version(version) => format!("{:?}", version),
+            PluginApiDependency::Path(path) => {
+                format!("{{ path = {:?} }}", path.to_string_lossy())
+            }
+        };
+        format!(
+            r#"[package]
+name = "{}"
+version = "0.1.0"
+edition = "2018"
+
+[lib]
+# Built as a Rust dynamic library so that dygpi's compatibility function is exported.
+crate-type = ["dylib"]
+
+[dependencies]
+dygpi = "{}"
+{} = {}
+
+[build-dependencies]
+dygpi-build = "0.1"
+"#,
+            self.crate_name,
+            env!("CARGO_PKG_VERSION"),
+            self.plugin_api_crate,
+            plugin_api_dependency
+        )
+    }
+
+    ///
+    /// Return the contents of the generated `build.rs` file.
+    ///
+    pub fn build_rs(&self) -> String {
+        "fn main() {\n    dygpi_build::emit_compatibility_env();\n}\n".to_string()
+    }
+
+    ///
+    /// Return the contents of the generated `src/lib.rs` file.
+    ///
+    pub fn lib_rs(&self) -> String {
+        format!(
+            r#"use dygpi::plugin::PluginRegistrar;
+use {api}::{plugin_type};
+
+#[no_mangle]
+pub extern "C" fn {registration_fn}(registrar: &mut PluginRegistrar<{plugin_type}>) {{
+    // Create, and register, each plugin provided by this library, for example:
+    //
+    // registrar.register({plugin_type}::new(concat!(
+    //     env!("CARGO_PKG_NAME"),
+    //     "::",
+    //     module_path!(),
+    //     "::MyPlugin"
+    // )));
+    let _ = registrar;
+}}
+"#,
+            api = self.plugin_api_crate.replace('-', "_"),
+            plugin_type = self.plugin_type,
+            registration_fn = self.registration_fn_name
+        )
+    }
+
+    ///
+    /// Create the provider crate in a new directory, named for the crate, within `directory`,
+    /// returning the path of the new directory. An error is returned if the crate's directory
+    /// already exists.
+    ///
+    pub fn generate(&self, directory: &Path) -> Result<PathBuf> {
+        let crate_directory = directory.join(&self.crate_name);
+        info!("ProviderTemplate::generate({:?})", crate_directory);
+        if crate_directory.exists() {
+            return Err(Error::provider_generation_failed(
+                &self.crate_name,
+                format!("{:?} already exists", crate_directory),
+            ));
+        }
+        let write_failed =
+            |e: std::io::Error| Error::provider_generation_failed(&self.crate_name, e);
+        std::fs::create_dir_all(crate_directory.join("src")).map_err(write_failed)?;
+        for (file_name, contents) in [
+            ("Cargo.toml", self.cargo_toml()),
+            ("build.rs", self.build_rs()),
+            ("src/lib.rs", self.lib_rs()),
+        ] {
+            trace!("ProviderTemplate::generate() > writing {:?}", file_name);
+            std::fs::write(crate_directory.join(file_name), contents).map_err(write_failed)?;
+        }
+        Ok(crate_directory)
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn is_identifier(name: &str, allow_hyphen: bool) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || (allow_hyphen && c == '-'))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_names() {
+        assert!(ProviderTemplate::new("sound-one", "sound_api", "SoundEffectPlugin").is_ok());
+        assert!(ProviderTemplate::new("", "sound_api", "SoundEffectPlugin").is_err());
+        assert!(ProviderTemplate::new("1sound", "sound_api", "SoundEffectPlugin").is_err());
+        assert!(ProviderTemplate::new("sound_one", "sound api", "SoundEffectPlugin").is_err());
+        assert!(ProviderTemplate::new("sound_one", "sound_api", "Sound-Effect").is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut template =
+            ProviderTemplate::new("sound_one", "sound-api", "SoundEffectPlugin").unwrap();
+        template.set_plugin_api_path("../sound_api");
+        template.set_registration_fn_name(crate::symbol!("register_effects"));
+
+        let crate_directory = template.generate(directory.path()).unwrap();
+        assert_eq!(crate_directory, directory.path().join("sound_one"));
+        let cargo_toml = std::fs::read_to_string(crate_directory.join("Cargo.toml")).unwrap();
+        assert!(cargo_toml.contains("crate-type = [\"dylib\"]"));
+        assert!(cargo_toml.contains("sound-api = { path = \"../sound_api\" }"));
+        let lib_rs = std::fs::read_to_string(crate_directory.join("src/lib.rs")).unwrap();
+        assert!(lib_rs.contains("use sound_api::SoundEffectPlugin;"));
+        assert!(lib_rs.contains(
+            "pub extern \"C\" fn register_effects(registrar: &mut PluginRegistrar<SoundEffectPlugin>)"
+        ));
+        assert!(crate_directory.join("build.rs").is_file());
+
+        assert!(template.generate(directory.path()).is_err());
+    }
+}
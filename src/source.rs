@@ -0,0 +1,168 @@
+/*!
+Provides the [`PluginSource`](trait.PluginSource.html) trait, implemented by alternate backends
+that provide plugins to a plugin manager without loading a dynamic library; for example plugins
+linked statically into the host, compiled to WASM, fetched from a remote service, or test doubles.
+
+A plugin manager loads plugins from dynamic libraries by default, a source is used by passing it
+to [`PluginManager::load_plugins_from_source`](../manager/struct.PluginManager.html#method.load_plugins_from_source)
+or [`PluginManager::load_all_plugins_from_source`](../manager/struct.PluginManager.html#method.load_all_plugins_from_source).
+Each _location_ loaded from a source is treated as a library by the plugin manager; its plugins
+are loaded (`on_load`), looked up, and unloaded (`on_unload`), just as those from a dynamic
+library, and once the last of them is unloaded the source is told to release the location with
+[`PluginSource::unload`](trait.PluginSource.html#method.unload). The operations that require a
+dynamic library, such as
+[`PluginManager::with_symbol`](../manager/struct.PluginManager.html#method.with_symbol) and
+[`PluginManager::reload_library`](../manager/struct.PluginManager.html#method.reload_library), do
+not apply to locations loaded from a source.
+
+# Example
+
+```rust
+use dygpi::error::Result;
+use dygpi::manager::PluginManager;
+use dygpi::plugin::Plugin;
+use dygpi::source::PluginSource;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct SoundEffectPlugin {
+    id: String,
+}
+impl Plugin for SoundEffectPlugin {
+    fn plugin_id(&self) -> &str { &self.id }
+    fn on_load(&self) -> Result<()> { Ok(()) }
+    fn on_unload(&self) -> Result<()> { Ok(()) }
+}
+
+#[derive(Debug)]
+struct BuiltinEffects;
+
+impl PluginSource<SoundEffectPlugin> for BuiltinEffects {
+    fn list(&self) -> Result<Vec<PathBuf>> {
+        Ok(vec![PathBuf::from("builtin")])
+    }
+
+    fn load(&self, _: &Path) -> Result<Vec<Arc<SoundEffectPlugin>>> {
+        Ok(vec![Arc::new(SoundEffectPlugin { id: "builtin::Delay".to_string() })])
+    }
+}
+
+let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+let source: Arc<dyn PluginSource<SoundEffectPlugin>> = Arc::new(BuiltinEffects);
+plugin_manager.load_all_plugins_from_source(&source).unwrap();
+assert!(plugin_manager.contains("builtin::Delay"));
+
+plugin_manager.unload_plugin("builtin::Delay").unwrap();
+assert!(plugin_manager.is_empty());
+```
+
+*/
+
+use crate::error::Result;
+use crate::plugin::Plugin;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Implemented by types that provide plugins of type `T` to a plugin manager, see the
+/// [`source`](index.html) module.
+///
+pub trait PluginSource<T>: Debug + Send + Sync
+where
+    T: Plugin,
+{
+    ///
+    /// Return the locations from which this source can load plugins, in the order they should be
+    /// loaded.
+    ///
+    fn list(&self) -> Result<Vec<PathBuf>>;
+
+    ///
+    /// Return the plugins provided at `location`. The plugin manager calls each plugin's
+    /// `on_load` method, the source should not.
+    ///
+    fn load(&self, location: &Path) -> Result<Vec<Arc<T>>>;
+
+    ///
+    /// Release any resources held for `location`; called once every plugin loaded from it has
+    /// been unloaded. The default implementation does nothing.
+    ///
+    fn unload(&self, location: &Path) -> Result<()> {
+        let _ = location;
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A location loaded from a source, which calls the source's `unload` method when closed, or
+/// dropped, so that it may be held by the non-generic library type.
+///
+#[derive(Debug)]
+pub(crate) struct SourceLocation {
+    location: PathBuf,
+    source: Option<Box<dyn UnloadSource>>,
+}
+
+trait UnloadSource: Debug + Send + Sync {
+    fn unload(&self, location: &Path) -> Result<()>;
+}
+
+#[derive(Debug)]
+struct BoundSource<T>(Arc<dyn PluginSource<T>>)
+where
+    T: Plugin;
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl SourceLocation {
+    pub(crate) fn new<T>(source: &Arc<dyn PluginSource<T>>, location: &Path) -> Self
+    where
+        T: Plugin,
+    {
+        Self {
+            location: location.to_path_buf(),
+            source: Some(Box::new(BoundSource(source.clone()))),
+        }
+    }
+
+    pub(crate) fn close(mut self) -> Result<()> {
+        match self.source.take() {
+            Some(source) => source.unload(&self.location),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for SourceLocation {
+    fn drop(&mut self) {
+        if let Some(source) = self.source.take() {
+            if let Err(e) = source.unload(&self.location) {
+                warn!("Error unloading source location {:?}; {}", self.location, e);
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> UnloadSource for BoundSource<T>
+where
+    T: Plugin,
+{
+    fn unload(&self, location: &Path) -> Result<()> {
+        trace!("PluginSource::unload({:?})", location);
+        self.0.unload(location)
+    }
+}
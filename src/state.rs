@@ -0,0 +1,206 @@
+/*!
+Provides the [`Blackboard`](struct.Blackboard.html) type, a thread-safe store of values of any
+type, keyed by name, for loosely coupled sharing of data between plugins and the plugin host.
+
+Each plugin manager owns a blackboard, returned by
+[`PluginManager::blackboard`](../manager/struct.PluginManager.html#method.blackboard), and hands
+it to each library's registration function where it is retrieved with
+[`PluginRegistrar::blackboard`](../plugin/struct.PluginRegistrar.html#method.blackboard). A
+`Blackboard` is a handle, all clones share the same values, and so a blackboard may be shared
+between plugin managers with
+[`PluginManager::set_blackboard`](../manager/struct.PluginManager.html#method.set_blackboard).
+
+Values are stored behind an `Arc` and retrieved by type; a value is only returned if it is
+requested with the type it was stored as. As with the plugin type itself, the types of shared
+values are usually defined in the plugin API crate so that the host and all plugin providers agree
+on them.
+
+A value stored by a plugin may refer to code in the library that provides the plugin, and so the
+handle given to a registration function keeps that library open for as long as any value stored
+through it remains on the blackboard, even after its plugins have been unloaded.
+
+# Example
+
+```rust
+use dygpi::state::Blackboard;
+
+let blackboard = Blackboard::default();
+let _ = blackboard.set("sample_rate", 48_000u32);
+
+assert_eq!(blackboard.get::<u32>("sample_rate").as_deref(), Some(&48_000));
+assert!(blackboard.get::<u64>("sample_rate").is_none());
+```
+
+*/
+
+use crate::cache::LoadedLibrary;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A thread-safe store of values of any type, keyed by name.
+///
+#[derive(Clone, Default)]
+pub struct Blackboard {
+    values: Arc<RwLock<HashMap<String, Entry>>>,
+    library: Option<Arc<LoadedLibrary>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A stored value, and the library that stored it. The value is declared, and so dropped, first
+/// as its drop code may be in the library.
+///
+struct Entry {
+    value: Arc<dyn Any + Send + Sync>,
+    _library: Option<Arc<LoadedLibrary>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for Blackboard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut keys = self.keys();
+        keys.sort();
+        f.debug_struct("Blackboard").field("keys", &keys).finish()
+    }
+}
+
+impl Blackboard {
+    ///
+    /// Return a handle to the same values which keeps `library` open while values stored through
+    /// it remain on the blackboard.
+    ///
+    pub(crate) fn for_library(&self, library: &Arc<LoadedLibrary>) -> Self {
+        Self {
+            values: self.values.clone(),
+            library: Some(library.clone()),
+        }
+    }
+
+    ///
+    /// Store `value` under `key`, returning `true` if this replaced an existing value.
+    ///
+    pub fn set<V>(&self, key: &str, value: V) -> bool
+    where
+        V: Any + Send + Sync,
+    {
+        trace!("Blackboard::set({:?})", key);
+        self.values
+            .write()
+            .unwrap()
+            .insert(
+                key.to_string(),
+                Entry {
+                    value: Arc::new(value),
+                    _library: self.library.clone(),
+                },
+            )
+            .is_some()
+    }
+
+    ///
+    /// Return the value stored under `key`, or `None` if there is no value or the value is not of
+    /// type `V`.
+    ///
+    pub fn get<V>(&self, key: &str) -> Option<Arc<V>>
+    where
+        V: Any + Send + Sync,
+    {
+        self.values
+            .read()
+            .unwrap()
+            .get(key)
+            .and_then(|entry| entry.value.clone().downcast::<V>().ok())
+    }
+
+    ///
+    /// Remove the value stored under `key`, returning `true` if there was a value.
+    ///
+    pub fn remove(&self, key: &str) -> bool {
+        trace!("Blackboard::remove({:?})", key);
+        self.values.write().unwrap().remove(key).is_some()
+    }
+
+    /// Returns `true` if a value, of any type, is stored under `key`, else `false`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.values.read().unwrap().contains_key(key)
+    }
+
+    ///
+    /// Return the keys of all stored values, in no particular order.
+    ///
+    pub fn keys(&self) -> Vec<String> {
+        self.values.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Returns `true` if no values are stored, else `false`.
+    pub fn is_empty(&self) -> bool {
+        self.values.read().unwrap().is_empty()
+    }
+
+    /// The number of stored values.
+    pub fn len(&self) -> usize {
+        self.values.read().unwrap().len()
+    }
+
+    ///
+    /// Remove all stored values.
+    ///
+    pub fn clear(&self) {
+        self.values.write().unwrap().clear()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_get_remove() {
+        let blackboard = Blackboard::default();
+        assert!(blackboard.is_empty());
+        assert!(!blackboard.set("level", 11u32));
+        assert!(blackboard.set("level", 7u32));
+        assert!(!blackboard.set("name", "delay".to_string()));
+        assert_eq!(blackboard.len(), 2);
+
+        assert_eq!(blackboard.get::<u32>("level").as_deref(), Some(&7));
+        assert!(blackboard.get::<String>("level").is_none());
+        assert_eq!(
+            blackboard.get::<String>("name").as_deref(),
+            Some(&"delay".to_string())
+        );
+
+        assert!(blackboard.remove("level"));
+        assert!(!blackboard.remove("level"));
+        assert!(!blackboard.contains_key("level"));
+        assert_eq!(blackboard.keys(), vec!["name".to_string()]);
+
+        blackboard.clear();
+        assert!(blackboard.is_empty());
+    }
+
+    #[test]
+    fn test_clones_share_values() {
+        let blackboard = Blackboard::default();
+        let other = blackboard.clone();
+        let _ = other.set("level", 11u32);
+        assert_eq!(blackboard.get::<u32>("level").as_deref(), Some(&11));
+    }
+}
@@ -0,0 +1,244 @@
+/*!
+Provides the [`StatusReport`](struct.StatusReport.html) type, a snapshot of the state of a plugin
+manager returned by [`PluginManager::status`](../manager/struct.PluginManager.html#method.status);
+it lists the loaded libraries, the plugins and plugin versions each provides, the health of each
+plugin, and any libraries in quarantine.
+
+If the feature `config_serde` is enabled the report implements Serde's `Serialize` trait, and so
+may be returned directly as JSON, for example from an administrative endpoint.
+
+# Example
+
+```rust,no_run
+use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+
+let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.load_plugins_from("libsound_one.dylib").unwrap();
+
+let status = plugin_manager.status();
+if !status.is_healthy() {
+    println!("{}", serde_json::to_string_pretty(&status).unwrap());
+}
+```
+
+*/
+
+use crate::manager::{InstanceId, PluginInfo, PluginState, QuarantinedLibrary};
+use semver::Version;
+#[cfg(feature = "config_serde")]
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A snapshot of the state of a plugin manager. The manager is healthy if every plugin is
+/// [`Active`](enum.PluginHealth.html#variant.Active) and no library is in quarantine.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+pub struct StatusReport {
+    healthy: bool,
+    libraries: Vec<LibraryStatus>,
+    quarantined: Vec<QuarantinedLibrary>,
+}
+
+///
+/// A library within a [`StatusReport`](struct.StatusReport.html), and the plugins it provides in
+/// the order in which they were registered.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+pub struct LibraryStatus {
+    library: PathBuf,
+    references: usize,
+    plugins: Vec<PluginStatus>,
+}
+
+///
+/// A plugin within a [`StatusReport`](struct.StatusReport.html).
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+pub struct PluginStatus {
+    plugin_id: String,
+    instance_id: InstanceId,
+    version: Option<Version>,
+    description: String,
+    registration_fn: String,
+    loaded_at: SystemTime,
+    health: PluginHealth,
+}
+
+///
+/// The health of a single plugin.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "config_serde",
+    derive(Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum PluginHealth {
+    /// The plugin is enabled and not paused.
+    Active,
+    /// The plugin is enabled but paused.
+    Paused,
+    /// The plugin is disabled.
+    Disabled,
+    /// A lifecycle method of the plugin reported an error, see
+    /// [`PluginState::Failed`](../manager/enum.PluginState.html#variant.Failed).
+    Failed,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl StatusReport {
+    pub(crate) fn new(plugins: Vec<PluginInfo>, quarantined: Vec<QuarantinedLibrary>) -> Self {
+        let mut report = Self {
+            quarantined,
+            ..Default::default()
+        };
+        for plugin in plugins {
+            report.add(plugin);
+        }
+        report.healthy = report.quarantined.is_empty()
+            && report
+                .plugins()
+                .all(|plugin| plugin.health == PluginHealth::Active);
+        report
+    }
+
+    ///
+    /// Returns `true` if every plugin is active and no library is in quarantine, else `false`.
+    ///
+    pub fn is_healthy(&self) -> bool {
+        self.healthy
+    }
+
+    ///
+    /// Return an iterator over the loaded libraries, in the order in which they were first loaded.
+    ///
+    pub fn libraries(&self) -> impl Iterator<Item = &LibraryStatus> {
+        self.libraries.iter()
+    }
+
+    ///
+    /// Return an iterator over all plugins, including all versions of each plugin.
+    ///
+    pub fn plugins(&self) -> impl Iterator<Item = &PluginStatus> {
+        self.libraries
+            .iter()
+            .flat_map(|library| library.plugins.iter())
+    }
+
+    ///
+    /// Return an iterator over the libraries in quarantine.
+    ///
+    pub fn quarantined(&self) -> impl Iterator<Item = &QuarantinedLibrary> {
+        self.quarantined.iter()
+    }
+
+    fn add(&mut self, plugin: PluginInfo) {
+        let status = PluginStatus {
+            plugin_id: plugin.plugin_id().to_string(),
+            instance_id: plugin.instance_id(),
+            version: plugin.version().cloned(),
+            description: plugin.description().clone(),
+            registration_fn: plugin.registration_fn().clone(),
+            loaded_at: plugin.loaded_at(),
+            health: if plugin.state() == PluginState::Failed {
+                PluginHealth::Failed
+            } else if !plugin.is_enabled() {
+                PluginHealth::Disabled
+            } else if plugin.is_paused() {
+                PluginHealth::Paused
+            } else {
+                PluginHealth::Active
+            },
+        };
+        match self
+            .libraries
+            .iter_mut()
+            .find(|library| library.library == *plugin.library())
+        {
+            Some(library) => library.plugins.push(status),
+            None => self.libraries.push(LibraryStatus {
+                library: plugin.library().clone(),
+                references: plugin.library_references(),
+                plugins: vec![status],
+            }),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl LibraryStatus {
+    /// The path of the library.
+    pub fn library(&self) -> &PathBuf {
+        &self.library
+    }
+
+    /// The number of references to the open library, across all plugin managers.
+    pub fn references(&self) -> usize {
+        self.references
+    }
+
+    /// Return an iterator over the plugins provided by the library.
+    pub fn plugins(&self) -> impl Iterator<Item = &PluginStatus> {
+        self.plugins.iter()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl PluginStatus {
+    /// The plugin identifier.
+    pub fn plugin_id(&self) -> &String {
+        &self.plugin_id
+    }
+
+    /// The runtime identifier of this instance of the plugin.
+    pub fn instance_id(&self) -> InstanceId {
+        self.instance_id
+    }
+
+    /// The version of the plugin, if it reports one.
+    pub fn version(&self) -> Option<&Version> {
+        self.version.as_ref()
+    }
+
+    /// The description of the plugin, empty if it does not provide one.
+    pub fn description(&self) -> &String {
+        &self.description
+    }
+
+    /// The name of the registration function that registered the plugin.
+    pub fn registration_fn(&self) -> &String {
+        &self.registration_fn
+    }
+
+    /// The time at which the plugin was registered.
+    pub fn loaded_at(&self) -> SystemTime {
+        self.loaded_at
+    }
+
+    /// The health of the plugin.
+    pub fn health(&self) -> PluginHealth {
+        self.health
+    }
+}
@@ -0,0 +1,471 @@
+/*!
+Provides support for testing host code that depends on plugins without building, or touching,
+any dynamic library. [`MemorySource`](struct.MemorySource.html) is a
+[`PluginSource`](../source/trait.PluginSource.html) holding plugin instances in memory, keyed by
+location, and [`mock_manager`](fn.mock_manager.html) returns a plugin manager with a set of
+plugins already loaded from one.
+
+Provider crates may check a built provider library in their integration tests with
+[`assert_valid_provider`](fn.assert_valid_provider.html), which loads the library as a host would
+and panics with a description of the first problem found.
+
+# Example
+
+```rust
+use dygpi::error::Result;
+use dygpi::plugin::Plugin;
+use dygpi::testing::mock_manager;
+
+#[derive(Debug)]
+struct SoundEffectPlugin {
+    id: String,
+}
+impl Plugin for SoundEffectPlugin {
+    fn plugin_id(&self) -> &str { &self.id }
+    fn on_load(&self) -> Result<()> { Ok(()) }
+    fn on_unload(&self) -> Result<()> { Ok(()) }
+}
+
+let plugin_manager = mock_manager(vec![
+    SoundEffectPlugin { id: "test::Delay".to_string() },
+    SoundEffectPlugin { id: "test::Reverb".to_string() },
+])
+.unwrap();
+
+assert_eq!(plugin_manager.len(), 2);
+assert!(plugin_manager.contains("test::Delay"));
+```
+
+In a provider crate's integration tests:
+
+```rust,no_run
+use dygpi::testing::assert_valid_provider;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+
+#[test]
+fn test_provider() {
+    let plugin_ids = assert_valid_provider::<SoundEffectPlugin, _>("target/debug/libsound_one.so");
+    assert!(plugin_ids.contains(&"sound_one::sound_one::DelayEffect".to_string()));
+}
+```
+
+*/
+
+use crate::cache::LibraryCache;
+use crate::error::{Error, OpenFailureReason, Result};
+use crate::inspect::exported_symbols;
+use crate::intercept::{Hook, Interceptor};
+use crate::manager::PluginManager;
+use crate::plugin::{
+    Plugin, COMPATIBILITY_FN_NAME, COMPATIBILITY_INFO_FN_NAME, PLUGIN_REGISTRATION_FN_NAME,
+    REGISTRATION_PROTOCOL_VERSION,
+};
+use crate::source::PluginSource;
+use std::collections::HashSet;
+use std::ffi::CStr;
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The location used by [`mock_manager`](fn.mock_manager.html) for all of its plugins.
+///
+pub const MOCK_LOCATION: &str = "memory";
+
+///
+/// A plugin source holding plugin instances in memory, keyed by location. Loading a location
+/// returns the same instances each time, and the locations unloaded are recorded so that tests
+/// may check them.
+///
+pub struct MemorySource<T>
+where
+    T: Plugin,
+{
+    locations: Mutex<Vec<(PathBuf, Vec<Arc<T>>)>>,
+    unloaded: Mutex<Vec<PathBuf>>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Records the identifier and version of each plugin as it is loaded.
+///
+#[derive(Debug, Default)]
+struct LoadRecorder(Mutex<Vec<(String, Option<String>)>>);
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Load the provider library at `file_name` as a host would, using the default registration
+/// function name, and panic if it is not a valid provider of plugins of type `T`. Returns the
+/// identifiers of the plugins registered, in registration order. See
+/// [`assert_valid_provider_with`](fn.assert_valid_provider_with.html) for the checks made.
+///
+pub fn assert_valid_provider<T, P>(file_name: P) -> Vec<String>
+where
+    T: Plugin,
+    P: AsRef<Path>,
+{
+    assert_valid_provider_with::<T, P>(file_name, PLUGIN_REGISTRATION_FN_NAME)
+}
+
+///
+/// Load the provider library at `file_name` as a host would, using the registration function
+/// `registration_fn_name`, and panic if it is not a valid provider of plugins of type `T`.
+/// Returns the identifiers of the plugins registered, in registration order.
+///
+/// The checks made are that:
+///
+/// 1. the library exports the compatibility functions and the registration function, either
+///    unversioned or for a supported registration protocol version,
+/// 1. the library opens, and passes the compatibility check,
+/// 1. the registration function succeeds, registering at least one plugin, and every plugin's
+///    `on_load` method succeeds,
+/// 1. no two plugins have the same identifier and version, and
+/// 1. every plugin's `on_unload` method succeeds.
+///
+pub fn assert_valid_provider_with<T, P>(file_name: P, registration_fn_name: &CStr) -> Vec<String>
+where
+    T: Plugin,
+    P: AsRef<Path>,
+{
+    let file_name = file_name.as_ref();
+    info!("assert_valid_provider_with({:?})", file_name);
+
+    let symbols = exported_symbols(file_name).unwrap_or_else(|e| {
+        panic!(
+            "Provider library {:?} could not be inspected; {}",
+            file_name, e
+        )
+    });
+    for compatibility_fn_name in [COMPATIBILITY_FN_NAME, COMPATIBILITY_INFO_FN_NAME] {
+        let compatibility_fn_name = compatibility_fn_name.to_string_lossy();
+        assert!(
+            symbols
+                .iter()
+                .any(|symbol| *symbol == compatibility_fn_name),
+            "Provider library {:?} does not export the compatibility function `{}`",
+            file_name,
+            compatibility_fn_name
+        );
+    }
+    let fn_name = registration_fn_name.to_string_lossy();
+    assert!(
+        symbols
+            .iter()
+            .any(|symbol| is_registration_symbol(symbol, &fn_name)),
+        "Provider library {:?} does not export the registration function `{}`",
+        file_name,
+        fn_name
+    );
+
+    let recorder = Arc::new(LoadRecorder::default());
+    let mut plugin_manager: PluginManager<T> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_registration_fn_name(registration_fn_name);
+    plugin_manager.add_interceptor(recorder.clone());
+    if let Err(e) = plugin_manager.load_plugins_from(file_name) {
+        panic!("Provider library {:?} failed to load; {}", file_name, e)
+    }
+
+    let loaded = recorder.0.lock().unwrap().clone();
+    assert!(
+        !loaded.is_empty(),
+        "Provider library {:?} did not register any plugins",
+        file_name
+    );
+    let mut unique: HashSet<&(String, Option<String>)> = Default::default();
+    for plugin in &loaded {
+        assert!(
+            unique.insert(plugin),
+            "Provider library {:?} registered the plugin {:?} more than once",
+            file_name,
+            plugin.0
+        );
+    }
+
+    if let Err(e) = plugin_manager.unload_all() {
+        panic!("Provider library {:?} failed to unload; {}", file_name, e)
+    }
+    loaded.into_iter().map(|(plugin_id, _)| plugin_id).collect()
+}
+
+///
+/// Return a plugin manager with `plugins` loaded from a [`MemorySource`](struct.MemorySource.html)
+/// at the location [`MOCK_LOCATION`](constant.MOCK_LOCATION.html). Each plugin's `on_load` method
+/// is called, as it would be for plugins loaded from a dynamic library.
+///
+pub fn mock_manager<T, I>(plugins: I) -> Result<PluginManager<T>>
+where
+    T: Plugin,
+    I: IntoIterator<Item = T>,
+{
+    let memory_source = MemorySource::default();
+    for plugin in plugins {
+        memory_source.add_plugin(MOCK_LOCATION, plugin);
+    }
+    let source: Arc<dyn PluginSource<T>> = Arc::new(memory_source);
+
+    let plugin_manager = PluginManager::default();
+    plugin_manager.load_all_plugins_from_source(&source)?;
+    Ok(plugin_manager)
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Default for MemorySource<T>
+where
+    T: Plugin,
+{
+    fn default() -> Self {
+        Self {
+            locations: Default::default(),
+            unloaded: Default::default(),
+        }
+    }
+}
+
+impl<T> Debug for MemorySource<T>
+where
+    T: Plugin,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemorySource")
+            .field("locations", &self.locations())
+            .finish()
+    }
+}
+
+impl<T> PluginSource<T> for MemorySource<T>
+where
+    T: Plugin,
+{
+    fn list(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.locations())
+    }
+
+    fn load(&self, location: &Path) -> Result<Vec<Arc<T>>> {
+        trace!("MemorySource::load({:?})", location);
+        self.locations
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(found, _)| found == location)
+            .map(|(_, plugins)| plugins.clone())
+            .ok_or_else(|| {
+                Error::library_open_failed(location, "no such location in the memory source")
+                    .with_open_failure_reason(OpenFailureReason::FileNotFound)
+            })
+    }
+
+    fn unload(&self, location: &Path) -> Result<()> {
+        trace!("MemorySource::unload({:?})", location);
+        self.unloaded.lock().unwrap().push(location.to_path_buf());
+        Ok(())
+    }
+}
+
+impl<T> MemorySource<T>
+where
+    T: Plugin,
+{
+    ///
+    /// Add `plugin` to the plugins provided at `location`, adding the location if necessary.
+    /// Locations are listed in the order they were added.
+    ///
+    pub fn add_plugin<P>(&self, location: P, plugin: T)
+    where
+        P: AsRef<Path>,
+    {
+        let location = location.as_ref();
+        let plugin = Arc::new(plugin);
+        let mut locations = self.locations.lock().unwrap();
+        match locations.iter_mut().find(|(found, _)| found == location) {
+            Some((_, plugins)) => plugins.push(plugin),
+            None => locations.push((location.to_path_buf(), vec![plugin])),
+        }
+    }
+
+    ///
+    /// Remove `location`, and its plugins, from the source; plugins already loaded from it are
+    /// not affected. Returns `true` if the location was present.
+    ///
+    pub fn remove_location<P>(&self, location: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        let location = location.as_ref();
+        let mut locations = self.locations.lock().unwrap();
+        let count = locations.len();
+        locations.retain(|(found, _)| found != location);
+        locations.len() != count
+    }
+
+    ///
+    /// Return the locations in the source, in the order they were added.
+    ///
+    pub fn locations(&self) -> Vec<PathBuf> {
+        self.locations
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(location, _)| location.clone())
+            .collect()
+    }
+
+    ///
+    /// Return the locations the plugin manager has unloaded, in the order they were unloaded.
+    ///
+    pub fn unloaded(&self) -> Vec<PathBuf> {
+        self.unloaded.lock().unwrap().clone()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<T> Interceptor<T> for LoadRecorder
+where
+    T: Plugin,
+{
+    fn intercept(
+        &self,
+        plugin: &T,
+        hook: Hook,
+        next: &mut dyn FnMut() -> Result<()>,
+    ) -> Result<()> {
+        next()?;
+        if hook == Hook::Load {
+            self.0.lock().unwrap().push((
+                plugin.plugin_id().to_string(),
+                plugin.plugin_version().map(str::to_string),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn is_registration_symbol(symbol: &str, registration_fn_name: &str) -> bool {
+    match symbol.strip_prefix(registration_fn_name) {
+        Some("") => true,
+        Some(suffix) => suffix
+            .strip_prefix("_v")
+            .and_then(|version| version.parse::<u32>().ok())
+            .is_some_and(|version| (1..=REGISTRATION_PROTOCOL_VERSION).contains(&version)),
+        None => false,
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestPlugin(String);
+
+    impl Plugin for TestPlugin {
+        fn plugin_id(&self) -> &str {
+            &self.0
+        }
+        fn on_load(&self) -> Result<()> {
+            Ok(())
+        }
+        fn on_unload(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_mock_manager() {
+        let plugin_manager = mock_manager(vec![
+            TestPlugin("test::one".to_string()),
+            TestPlugin("test::two".to_string()),
+        ])
+        .unwrap();
+        assert_eq!(plugin_manager.len(), 2);
+        assert_eq!(
+            plugin_manager.plugin_info("test::one").unwrap().library(),
+            &PathBuf::from(MOCK_LOCATION)
+        );
+        plugin_manager.unload_all().unwrap();
+        assert!(plugin_manager.is_empty());
+    }
+
+    #[test]
+    fn test_memory_source() {
+        let memory_source = Arc::new(MemorySource::default());
+        memory_source.add_plugin("one", TestPlugin("test::one".to_string()));
+        memory_source.add_plugin("two", TestPlugin("test::two".to_string()));
+        memory_source.add_plugin("one", TestPlugin("test::three".to_string()));
+        assert_eq!(
+            memory_source.locations(),
+            vec![PathBuf::from("one"), PathBuf::from("two")]
+        );
+
+        let source: Arc<dyn PluginSource<TestPlugin>> = memory_source.clone();
+        let plugin_manager = PluginManager::default();
+        plugin_manager
+            .load_plugins_from_source(&source, "one")
+            .unwrap();
+        assert_eq!(plugin_manager.len(), 2);
+        assert!(plugin_manager
+            .load_plugins_from_source(&source, "missing")
+            .is_err());
+
+        plugin_manager.unload_plugin("test::one").unwrap();
+        assert!(memory_source.unloaded().is_empty());
+        plugin_manager.unload_plugin("test::three").unwrap();
+        assert_eq!(memory_source.unloaded(), vec![PathBuf::from("one")]);
+
+        assert!(memory_source.remove_location("two"));
+        assert!(!memory_source.remove_location("two"));
+    }
+
+    #[test]
+    fn test_is_registration_symbol() {
+        assert!(is_registration_symbol(
+            "register_plugins",
+            "register_plugins"
+        ));
+        assert!(is_registration_symbol(
+            "register_plugins_v1",
+            "register_plugins"
+        ));
+        assert!(!is_registration_symbol(
+            "register_plugins_v99",
+            "register_plugins"
+        ));
+        assert!(!is_registration_symbol(
+            "register_plugins_x",
+            "register_plugins"
+        ));
+        assert!(!is_registration_symbol(
+            "register_other",
+            "register_plugins"
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+/*!
+Provides the [`Topology`](struct.Topology.html) type, a graph of the libraries loaded by a plugin
+manager and the plugins each provides, returned by
+[`PluginManager::topology`](../manager/struct.PluginManager.html#method.topology).
+
+The graph may be exported in the [DOT](https://graphviz.org/doc/info/lang.html) language with
+[`to_dot`](struct.Topology.html#method.to_dot) for visualization with Graphviz. If the feature
+`config_serde` is enabled the graph also implements Serde's `Serialize` trait, and so may be
+exported as JSON with `serde_json`.
+
+# Example
+
+```rust,no_run
+use dygpi::manager::PluginManager;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+
+let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.load_plugins_from("libsound_one.dylib").unwrap();
+
+let topology = plugin_manager.topology();
+std::fs::write("plugins.dot", topology.to_dot()).unwrap();
+```
+
+*/
+
+#[cfg(feature = "config_serde")]
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The libraries loaded by a plugin manager, in the order in which they were first loaded.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+pub struct Topology {
+    libraries: Vec<LibraryNode>,
+}
+
+///
+/// A library within a [`Topology`](struct.Topology.html), and the identifiers of the plugins it
+/// provides in the order in which they were registered.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "config_serde", derive(Serialize))]
+pub struct LibraryNode {
+    library: PathBuf,
+    plugins: Vec<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Topology {
+    ///
+    /// Return an iterator over the libraries in the graph.
+    ///
+    pub fn libraries(&self) -> impl Iterator<Item = &LibraryNode> {
+        self.libraries.iter()
+    }
+
+    ///
+    /// Return the library providing the plugin with the provided plugin identifier, if any.
+    ///
+    pub fn library_for(&self, plugin_id: &str) -> Option<&LibraryNode> {
+        self.libraries
+            .iter()
+            .find(|library| library.plugins.iter().any(|id| id == plugin_id))
+    }
+
+    ///
+    /// Return the graph in the DOT language; libraries are drawn as boxes with an edge to each
+    /// plugin they provide.
+    ///
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph plugins {\n");
+        for library in &self.libraries {
+            let library_name = dot_id(&library.library.to_string_lossy());
+            let _ = writeln!(dot, "    {} [shape=box];", library_name);
+            for plugin_id in &library.plugins {
+                let _ = writeln!(dot, "    {} -> {};", library_name, dot_id(plugin_id));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub(crate) fn add(&mut self, library: &Path, plugin_id: &str) {
+        match self.libraries.iter_mut().find(|l| l.library == library) {
+            Some(node) => node.plugins.push(plugin_id.to_string()),
+            None => self.libraries.push(LibraryNode {
+                library: library.to_path_buf(),
+                plugins: vec![plugin_id.to_string()],
+            }),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl LibraryNode {
+    /// The path of the library.
+    pub fn library(&self) -> &PathBuf {
+        &self.library
+    }
+
+    /// Return an iterator over the identifiers of the plugins provided by the library.
+    pub fn plugins(&self) -> impl Iterator<Item = &String> {
+        self.plugins.iter()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn dot_id(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_topology() -> Topology {
+        let mut topology = Topology::default();
+        topology.add(Path::new("/lib/libeffects.so"), "effects::Delay");
+        topology.add(Path::new("/lib/libsources.so"), "sources::Lfo");
+        topology.add(Path::new("/lib/libeffects.so"), "effects::Reverb");
+        topology
+    }
+
+    #[test]
+    fn test_topology_groups_plugins_by_library() {
+        let topology = make_topology();
+        assert_eq!(topology.libraries().count(), 2);
+        assert_eq!(
+            topology.library_for("effects::Reverb").unwrap().library(),
+            Path::new("/lib/libeffects.so")
+        );
+        assert!(topology.library_for("unknown").is_none());
+    }
+
+    #[test]
+    fn test_topology_to_dot() {
+        assert_eq!(
+            make_topology().to_dot(),
+            r##"digraph plugins {
+    "/lib/libeffects.so" [shape=box];
+    "/lib/libeffects.so" -> "effects::Delay";
+    "/lib/libeffects.so" -> "effects::Reverb";
+    "/lib/libsources.so" [shape=box];
+    "/lib/libsources.so" -> "sources::Lfo";
+}
+"##
+        );
+    }
+
+    #[cfg(feature = "config_serde")]
+    #[test]
+    fn test_topology_to_json() {
+        assert_eq!(
+            serde_json::to_string(&make_topology()).unwrap(),
+            r##"{"libraries":[{"library":"/lib/libeffects.so","plugins":["effects::Delay","effects::Reverb"]},{"library":"/lib/libsources.so","plugins":["sources::Lfo"]}]}"##
+        );
+    }
+}
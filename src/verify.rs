@@ -0,0 +1,328 @@
+/*!
+Provides the [`SignatureVerifier`](trait.SignatureVerifier.html) trait, used by the plugin manager
+to check the signature of a library before it is opened, see
+[`PluginManager::set_signature_verifier`](../manager/struct.PluginManager.html#method.set_signature_verifier).
+A library that fails verification is not opened, and so none of its code, including static
+initializers, is run. This module is only available if the feature `verify` is enabled.
+
+On Windows and macOS [`PlatformVerifier`](struct.PlatformVerifier.html) checks the platform's code
+signature of the library, Authenticode on Windows and `codesign` on macOS, and optionally that it
+was signed by one of a set of allowed identities. Hosts may provide their own verifier, for
+example to check a detached signature on other platforms.
+
+# Example
+
+```rust,no_run
+# #[cfg(any(windows, target_os = "macos"))]
+# fn main() {
+use dygpi::manager::PluginManager;
+use dygpi::verify::PlatformVerifier;
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+use std::sync::Arc;
+
+let mut verifier = PlatformVerifier::default();
+verifier.add_allowed_identity("Developer ID Application: Sound Co (ABCDE12345)");
+
+let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.set_signature_verifier(Arc::new(verifier));
+plugin_manager.load_plugins_from("libsound_one.dylib").unwrap();
+# }
+# #[cfg(not(any(windows, target_os = "macos")))]
+# fn main() {}
+```
+
+# Example - Custom Verifier
+
+```rust
+use dygpi::error::{Error, Result};
+use dygpi::verify::SignatureVerifier;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+struct TrustedDirectory(PathBuf);
+
+impl SignatureVerifier for TrustedDirectory {
+    fn verify(&self, library: &Path) -> Result<()> {
+        if library.starts_with(&self.0) {
+            Ok(())
+        } else {
+            Err(Error::signature_verification_failed(
+                library,
+                "library is not in the trusted directory",
+            ))
+        }
+    }
+}
+```
+
+*/
+
+#[cfg(any(windows, target_os = "macos"))]
+use crate::error::Error;
+use crate::error::Result;
+use std::fmt::Debug;
+use std::path::Path;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Verifies the signature of a library before the plugin manager opens it. Verifiers are shared
+/// between threads, and so must be `Send` and `Sync`.
+///
+pub trait SignatureVerifier: Debug + Send + Sync {
+    ///
+    /// Verify the library at `library`, returning a
+    /// [`SignatureVerificationFailed`](../error/enum.ErrorKind.html#variant.SignatureVerificationFailed)
+    /// error if it is not signed, its signature is not valid, or it was not signed by an allowed
+    /// identity.
+    ///
+    fn verify(&self, library: &Path) -> Result<()>;
+}
+
+///
+/// Verifies libraries using the platform's code signing; on Windows the library must have a valid
+/// Authenticode signature trusted by the system, and on macOS it must pass
+/// `codesign --verify --strict`. This is only available on Windows and macOS.
+///
+/// If any identities are allowed only libraries signed by one of them pass verification. On
+/// Windows the identity is the simple display name of the signing certificate's subject, for
+/// example `"Sound Co Ltd"`; on macOS it is either a signing authority, for example
+/// `"Developer ID Application: Sound Co (ABCDE12345)"`, or a team identifier, `"ABCDE12345"`.
+///
+#[cfg(any(windows, target_os = "macos"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlatformVerifier {
+    allowed_identities: Vec<String>,
+    require_notarization: bool,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(any(windows, target_os = "macos"))]
+impl SignatureVerifier for PlatformVerifier {
+    fn verify(&self, library: &Path) -> Result<()> {
+        trace!("PlatformVerifier::verify({:?})", library);
+        let identities = platform::verify(library, self.require_notarization)?;
+        if self.allowed_identities.is_empty()
+            || identities
+                .iter()
+                .any(|identity| self.allowed_identities.contains(identity))
+        {
+            Ok(())
+        } else {
+            Err(Error::signature_verification_failed(
+                library,
+                format!(
+                    "signed by {:?}, which is not an allowed identity",
+                    identities
+                ),
+            ))
+        }
+    }
+}
+
+#[cfg(any(windows, target_os = "macos"))]
+impl PlatformVerifier {
+    ///
+    /// Allow libraries signed by `identity`; once any identity is allowed libraries signed by
+    /// other identities fail verification.
+    ///
+    pub fn add_allowed_identity(&mut self, identity: &str) {
+        self.allowed_identities.push(identity.to_string())
+    }
+
+    /// Return an iterator over the allowed signing identities.
+    pub fn allowed_identities(&self) -> impl Iterator<Item = &String> {
+        self.allowed_identities.iter()
+    }
+
+    ///
+    /// Set whether libraries must also be notarized, as assessed by `spctl`; this is only
+    /// checked on macOS. The default is `false`.
+    ///
+    pub fn set_require_notarization(&mut self, require_notarization: bool) {
+        self.require_notarization = require_notarization
+    }
+
+    /// Returns `true` if libraries must also be notarized, else `false`.
+    pub fn is_require_notarization(&self) -> bool {
+        self.require_notarization
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Modules
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use crate::error::{Error, Result};
+    use std::path::Path;
+    use std::process::{Command, Output};
+
+    ///
+    /// Verify the library's code signature, returning its signing authorities and team
+    /// identifier.
+    ///
+    pub(super) fn verify(library: &Path, require_notarization: bool) -> Result<Vec<String>> {
+        let _ = run(library, "/usr/bin/codesign", &["--verify", "--strict"])?;
+        if require_notarization {
+            let _ = run(
+                library,
+                "/usr/sbin/spctl",
+                &[
+                    "--assess",
+                    "--type",
+                    "open",
+                    "--context",
+                    "context:primary-signature",
+                ],
+            )?;
+        }
+        // codesign writes the details of the signature to stderr.
+        let output = run(library, "/usr/bin/codesign", &["--display", "--verbose=2"])?;
+        Ok(String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .filter_map(|line| {
+                line.strip_prefix("Authority=")
+                    .or_else(|| line.strip_prefix("TeamIdentifier="))
+            })
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn run(library: &Path, program: &str, args: &[&str]) -> Result<Output> {
+        let output = Command::new(program)
+            .args(args)
+            .arg(library)
+            .output()
+            .map_err(|e| Error::signature_verification_failed(library, e))?;
+        if output.status.success() {
+            Ok(output)
+        } else {
+            Err(Error::signature_verification_failed(
+                library,
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use crate::error::{Error, Result};
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::Security::Cryptography::{
+        CertGetNameStringW, CERT_NAME_SIMPLE_DISPLAY_TYPE,
+    };
+    use windows_sys::Win32::Security::WinTrust::{
+        WTHelperGetProvSignerFromChain, WTHelperProvDataFromStateData, WinVerifyTrust,
+        WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_FILE_INFO, WTD_CHOICE_FILE,
+        WTD_REVOKE_NONE, WTD_STATEACTION_CLOSE, WTD_STATEACTION_VERIFY, WTD_UI_NONE,
+    };
+
+    ///
+    /// Verify the library's Authenticode signature, returning the name of the signer.
+    ///
+    #[allow(unsafe_code)]
+    pub(super) fn verify(library: &Path, _require_notarization: bool) -> Result<Vec<String>> {
+        let wide: Vec<u16> = library
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        let mut file_info = WINTRUST_FILE_INFO {
+            cbStruct: size_of::<WINTRUST_FILE_INFO>() as u32,
+            pcwszFilePath: wide.as_ptr(),
+            hFile: std::ptr::null_mut(),
+            pgKnownSubject: std::ptr::null_mut(),
+        };
+        let mut data: WINTRUST_DATA = unsafe { std::mem::zeroed() };
+        data.cbStruct = size_of::<WINTRUST_DATA>() as u32;
+        data.dwUIChoice = WTD_UI_NONE;
+        data.fdwRevocationChecks = WTD_REVOKE_NONE;
+        data.dwUnionChoice = WTD_CHOICE_FILE;
+        data.Anonymous.pFile = &mut file_info;
+        data.dwStateAction = WTD_STATEACTION_VERIFY;
+        let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+
+        let status = unsafe {
+            WinVerifyTrust(
+                std::ptr::null_mut(),
+                &mut action,
+                std::ptr::addr_of_mut!(data).cast(),
+            )
+        };
+        let signers = if status == 0 {
+            signer_names(data.hWVTStateData)
+        } else {
+            Vec::default()
+        };
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        let _ = unsafe {
+            WinVerifyTrust(
+                std::ptr::null_mut(),
+                &mut action,
+                std::ptr::addr_of_mut!(data).cast(),
+            )
+        };
+
+        if status == 0 {
+            Ok(signers)
+        } else {
+            Err(Error::signature_verification_failed(
+                library,
+                std::io::Error::from_raw_os_error(status),
+            ))
+        }
+    }
+
+    #[allow(unsafe_code)]
+    fn signer_names(state: HANDLE) -> Vec<String> {
+        unsafe {
+            let provider_data = WTHelperProvDataFromStateData(state);
+            if provider_data.is_null() {
+                return Vec::default();
+            }
+            let signer = WTHelperGetProvSignerFromChain(provider_data, 0, 0, 0);
+            if signer.is_null() || (*signer).csCertChain == 0 || (*signer).pasCertChain.is_null() {
+                return Vec::default();
+            }
+            let certificate = (*(*signer).pasCertChain).pCert;
+            let length = CertGetNameStringW(
+                certificate,
+                CERT_NAME_SIMPLE_DISPLAY_TYPE,
+                0,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                0,
+            );
+            if length <= 1 {
+                return Vec::default();
+            }
+            let mut name = vec![0u16; length as usize];
+            let _ = CertGetNameStringW(
+                certificate,
+                CERT_NAME_SIMPLE_DISPLAY_TYPE,
+                0,
+                std::ptr::null(),
+                name.as_mut_ptr(),
+                length,
+            );
+            vec![String::from_utf16_lossy(&name[..length as usize - 1])]
+        }
+    }
+}
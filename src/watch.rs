@@ -0,0 +1,277 @@
+/*!
+Provides the [`PluginWatcher`](struct.PluginWatcher.html) type, returned by
+[`PluginManager::watch`](../manager/struct.PluginManager.html#method.watch), which watches plugin
+libraries, and directories of plugin libraries, for changes and reloads, loads, or unloads them
+as they are rebuilt, added, or removed. This module is only available if the feature `watch` is
+enabled.
+
+Changes are handled according to the watcher's
+[`ReloadPolicy`](../manager/struct.ReloadPolicy.html). Changes are debounced; once a change is seen
+the watcher waits until no further change has been seen for the policy's debounce period before
+acting, so that a library is not reloaded while it is still being written. The changed libraries
+that are loaded and still exist are then reloaded, see
+[`PluginManager::reload_libraries`](../manager/struct.PluginManager.html#method.reload_libraries),
+as a single batch if the policy batches reloads and between calls to the policy's quiesce and
+resume handlers. If the policy synchronizes, changed libraries that are not loaded are loaded, and
+those that are loaded but no longer exist are unloaded. Errors are logged, the watcher continues
+with the next change.
+
+A library that is loaded cannot be replaced on Windows, and on other platforms overwriting a
+loaded library in place may crash the host; enable shadow copy loading with
+[`PluginManager::enable_shadow_copy`](../manager/struct.PluginManager.html#method.enable_shadow_copy)
+so that the library may be rebuilt while its plugins are loaded.
+
+# Example
+
+```rust,no_run
+use dygpi::manager::{PluginManager, ReloadPolicy};
+# use dygpi::plugin::Plugin;
+# #[derive(Debug)]
+# struct SoundEffectPlugin;
+# impl Plugin for SoundEffectPlugin {
+#     fn plugin_id(&self) -> &str { unimplemented!() }
+#     fn on_load(&self) -> dygpi::error::Result<()> { Ok(()) }
+#     fn on_unload(&self) -> dygpi::error::Result<()> { Ok(()) }
+# }
+use std::sync::Arc;
+use std::time::Duration;
+
+let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+plugin_manager.enable_shadow_copy();
+let plugin_manager = Arc::new(plugin_manager);
+plugin_manager.load_plugins_from_dir("target/debug").unwrap();
+
+let mut policy = ReloadPolicy::default();
+policy.set_debounce(Duration::from_secs(1));
+policy.set_batch(true);
+let watcher = plugin_manager.watch(&["target/debug"], policy).unwrap();
+
+// ... plugins are reloaded as they are rebuilt, until the watcher is dropped ...
+
+drop(watcher);
+```
+
+*/
+
+use crate::error::{Error, Result};
+use crate::manager::{parse_dylib_name, platform_plugin_directory, PluginManager, ReloadPolicy};
+use crate::plugin::Plugin;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::Weak;
+use std::thread::JoinHandle;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Watches plugin libraries on behalf of a plugin manager, see the
+/// [module documentation](index.html). Watching stops when the watcher is dropped, or the plugin
+/// manager is dropped.
+///
+pub struct PluginWatcher {
+    watcher: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+    paths: Vec<PathBuf>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// A watched directory and, if a single library was watched, the library within it. Libraries
+/// are watched through their directory as a rebuild usually replaces, rather than rewrites, the
+/// library file.
+///
+#[derive(Clone, Debug)]
+struct WatchTarget {
+    directory: PathBuf,
+    library: Option<PathBuf>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Debug for PluginWatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginWatcher")
+            .field("paths", &self.paths)
+            .finish()
+    }
+}
+
+impl Drop for PluginWatcher {
+    fn drop(&mut self) {
+        // Dropping the watcher closes the event channel, which ends the thread.
+        drop(self.watcher.take());
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("Plugin watcher thread panicked");
+            }
+        }
+    }
+}
+
+impl PluginWatcher {
+    pub(crate) fn new<T>(
+        plugin_manager: Weak<PluginManager<T>>,
+        paths: &[&Path],
+        policy: ReloadPolicy,
+    ) -> Result<Self>
+    where
+        T: Plugin,
+    {
+        let targets: Vec<WatchTarget> = paths.iter().map(|path| WatchTarget::new(path)).collect();
+        let (sender, events) = channel();
+        let mut watcher = notify::recommended_watcher(sender)
+            .map_err(|e| Error::watch_failed(paths.first().copied().unwrap_or(Path::new("")), e))?;
+        for target in &targets {
+            trace!("PluginWatcher::new() > watching {:?}", target.directory);
+            watcher
+                .watch(&target.directory, RecursiveMode::NonRecursive)
+                .map_err(|e| Error::watch_failed(&target.directory, e))?;
+        }
+        let thread = std::thread::Builder::new()
+            .name("dygpi-watcher".to_string())
+            .spawn(move || watch_events(plugin_manager, events, targets, policy))
+            .map_err(|e| Error::watch_failed(paths.first().copied().unwrap_or(Path::new("")), e))?;
+        Ok(Self {
+            watcher: Some(watcher),
+            thread: Some(thread),
+            paths: paths.iter().map(|path| path.to_path_buf()).collect(),
+        })
+    }
+
+    ///
+    /// Return an iterator over the libraries and directories being watched.
+    ///
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.paths.iter()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl WatchTarget {
+    fn new(path: &Path) -> Self {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if path.is_dir() {
+            Self {
+                directory: platform_plugin_directory(&path),
+                library: None,
+            }
+        } else {
+            Self {
+                directory: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+                library: Some(path),
+            }
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        match &self.library {
+            Some(library) => library == path,
+            None => {
+                path.parent() == Some(self.directory.as_path()) && parse_dylib_name(path).is_some()
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn watch_events<T>(
+    plugin_manager: Weak<PluginManager<T>>,
+    events: Receiver<notify::Result<Event>>,
+    targets: Vec<WatchTarget>,
+    policy: ReloadPolicy,
+) where
+    T: Plugin,
+{
+    while let Ok(event) = events.recv() {
+        let mut changed: BTreeSet<PathBuf> = Default::default();
+        add_changed(&targets, event, &mut changed);
+        loop {
+            match events.recv_timeout(policy.debounce()) {
+                Ok(event) => add_changed(&targets, event, &mut changed),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        let plugin_manager = match plugin_manager.upgrade() {
+            Some(plugin_manager) => plugin_manager,
+            None => return,
+        };
+        apply_changes(&plugin_manager, changed, &policy);
+    }
+}
+
+fn add_changed(
+    targets: &[WatchTarget],
+    event: notify::Result<Event>,
+    changed: &mut BTreeSet<PathBuf>,
+) {
+    match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+            changed.extend(
+                event
+                    .paths
+                    .into_iter()
+                    .filter(|path| targets.iter().any(|target| target.matches(path))),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Plugin watcher reported an error; {}", e),
+    }
+}
+
+fn apply_changes<T>(
+    plugin_manager: &PluginManager<T>,
+    changed: BTreeSet<PathBuf>,
+    policy: &ReloadPolicy,
+) where
+    T: Plugin,
+{
+    let (changed, other): (Vec<PathBuf>, Vec<PathBuf>) = changed
+        .into_iter()
+        .partition(|library| plugin_manager.is_library_loaded(library) && library.is_file());
+    if !changed.is_empty() {
+        info!("Plugin watcher reloading changed libraries {:?}", changed);
+        if let Err(e) = plugin_manager.reload_libraries_with(&changed, policy) {
+            error!(
+                "Plugin watcher failed to reload libraries {:?}; {}",
+                changed, e
+            );
+        }
+    }
+    if !policy.is_synchronize() {
+        trace!("apply_changes() > ignoring changes to {:?}", other);
+        return;
+    }
+    for library in other {
+        let result = if library.is_file() {
+            info!("Plugin watcher loading added library {:?}", library);
+            plugin_manager.load_plugins_from(&library).map(|_| ())
+        } else if plugin_manager.is_library_loaded(&library) {
+            info!("Plugin watcher unloading removed library {:?}", library);
+            plugin_manager.unload_library(&library)
+        } else {
+            trace!("apply_changes() > ignoring change to {:?}", library);
+            Ok(())
+        };
+        if let Err(e) = result {
+            error!(
+                "Plugin watcher failed to update library {:?}; {}",
+                library, e
+            );
+        }
+    }
+}
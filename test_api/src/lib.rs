@@ -1,4 +1,7 @@
+use dygpi::error::Error;
+use dygpi::event::EventSink;
 use dygpi::plugin::Plugin;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -16,20 +19,103 @@ pub struct SoundEffectPlugin {
     id: String,
     engine: SoundEngine,
     media: MediaStream,
+    enabled: AtomicBool,
+    paused: AtomicBool,
+    level: AtomicU32,
+    config_changes: AtomicU32,
+    fail_on_load: bool,
+    fail_on_unload: bool,
+    version: Option<String>,
+    capabilities: Vec<&'static str>,
+    events: Option<EventSink<SoundEvent>>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SoundEvent {
+    LevelChanged(String, u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EffectParameters {
+    pub level: u32,
+}
+
+#[derive(Debug)]
+pub struct LightEffectPlugin {
+    id: String,
+}
+
+dygpi::plugin_type!(pub SoundEffects = "sound_effects" for SoundEffectPlugin);
+
+dygpi::plugin_type!(pub LightEffects = "light_effects" for LightEffectPlugin);
+
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
 impl Plugin for SoundEffectPlugin {
-    fn plugin_id(&self) -> &String {
+    fn plugin_id(&self) -> &str {
         &self.id
     }
+    fn plugin_api_version() -> &'static str {
+        concat!(env!("CARGO_PKG_NAME"), "@", env!("CARGO_PKG_VERSION"))
+    }
+    fn plugin_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+    fn plugin_description(&self) -> &str {
+        "A sound effect used to test plugin loading"
+    }
+    fn plugin_capabilities(&self) -> &[&str] {
+        &self.capabilities
+    }
     fn on_load(&self) -> dygpi::error::Result<()> {
-        Ok(())
+        if self.fail_on_load {
+            Err(Error::unknown_plugin(&self.id))
+        } else {
+            Ok(())
+        }
     }
     fn on_unload(&self) -> dygpi::error::Result<()> {
+        if self.fail_on_unload {
+            Err(Error::unknown_plugin(&self.id))
+        } else {
+            Ok(())
+        }
+    }
+    fn on_enable(&self) -> dygpi::error::Result<()> {
+        self.enabled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+    fn on_disable(&self) -> dygpi::error::Result<()> {
+        self.enabled.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+    fn on_pause(&self) -> dygpi::error::Result<()> {
+        self.paused.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+    fn on_resume(&self) -> dygpi::error::Result<()> {
+        self.paused.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+    fn on_config_changed(&self) -> dygpi::error::Result<()> {
+        let _ = self.config_changes.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+    fn save_state(&self) -> Vec<u8> {
+        self.level().to_le_bytes().to_vec()
+    }
+    fn restore_state(&self, state: &[u8]) -> dygpi::error::Result<()> {
+        if state.len() < 4 {
+            return Err(Error::plugin_registration_for(
+                &self.id,
+                "saved state is shorter than 4 bytes",
+            ));
+        }
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&state[..4]);
+        self.set_level(u32::from_le_bytes(bytes));
         Ok(())
     }
 }
@@ -40,7 +126,87 @@ impl SoundEffectPlugin {
             id: id.to_string(),
             engine: Default::default(),
             media: Default::default(),
+            enabled: AtomicBool::new(true),
+            paused: AtomicBool::new(false),
+            level: AtomicU32::new(0),
+            config_changes: AtomicU32::new(0),
+            fail_on_load: false,
+            fail_on_unload: false,
+            version: None,
+            capabilities: Vec::new(),
+            events: None,
+        }
+    }
+    pub fn new_with_events(id: &str, events: EventSink<SoundEvent>) -> Self {
+        Self {
+            events: Some(events),
+            ..Self::new(id)
+        }
+    }
+    pub fn new_with_version(id: &str, version: &str) -> Self {
+        Self {
+            version: Some(version.to_string()),
+            ..Self::new(id)
+        }
+    }
+    pub fn new_with_capabilities(id: &str, capabilities: &[&'static str]) -> Self {
+        Self {
+            capabilities: capabilities.to_vec(),
+            ..Self::new(id)
+        }
+    }
+    pub fn new_failing(id: &str) -> Self {
+        Self {
+            fail_on_load: true,
+            ..Self::new(id)
+        }
+    }
+    pub fn new_failing_unload(id: &str) -> Self {
+        Self {
+            fail_on_unload: true,
+            ..Self::new(id)
         }
     }
     pub fn play(&self) {}
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+    pub fn level(&self) -> u32 {
+        self.level.load(Ordering::SeqCst)
+    }
+    pub fn config_changes(&self) -> u32 {
+        self.config_changes.load(Ordering::SeqCst)
+    }
+    pub fn set_level(&self, level: u32) {
+        self.level.store(level, Ordering::SeqCst);
+        if let Some(events) = &self.events {
+            let _ = events.emit(SoundEvent::LevelChanged(self.id.clone(), level));
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Plugin for LightEffectPlugin {
+    fn plugin_id(&self) -> &str {
+        &self.id
+    }
+    fn plugin_api_version() -> &'static str {
+        concat!(env!("CARGO_PKG_NAME"), "@", env!("CARGO_PKG_VERSION"))
+    }
+    fn on_load(&self) -> dygpi::error::Result<()> {
+        Ok(())
+    }
+    fn on_unload(&self) -> dygpi::error::Result<()> {
+        Ok(())
+    }
+}
+
+impl LightEffectPlugin {
+    pub fn new(id: &str) -> Self {
+        Self { id: id.to_string() }
+    }
 }
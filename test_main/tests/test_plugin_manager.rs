@@ -1,7 +1,33 @@
-use dygpi::manager::{PluginManager, PLATFORM_DYLIB_EXTENSION, PLATFORM_DYLIB_PREFIX};
-use sound_api::SoundEffectPlugin;
-use std::path::PathBuf;
-use std::sync::Arc;
+use dygpi::cache::LibraryCache;
+use dygpi::check_cache::CheckCache;
+use dygpi::config::PluginManagerConfiguration;
+use dygpi::error::{Error, OpenFailureReason};
+use dygpi::event::EventSink;
+use dygpi::index::{scan_and_index, PluginIndex};
+use dygpi::inspect::{explain_load_failure, validate_library};
+use dygpi::intercept::{Hook, Interceptor};
+use dygpi::manager::{
+    CancellationToken, CapabilityPolicy, DropPolicy, DuplicateLoadPolicy, EnvLoadPolicy,
+    Instancing, PluginInfo, PluginManager, PluginState, ProgressEvent, ReloadPolicy,
+    ShutdownReport, StateTransition, PLATFORM_DYLIB_EXTENSION, PLATFORM_DYLIB_PREFIX,
+};
+use dygpi::platform::Platform;
+use dygpi::plugin::{HostApi, HostInfo, Plugin, PluginType};
+use dygpi::source::PluginSource;
+use dygpi::state::Blackboard;
+use dygpi::status::PluginHealth;
+use dygpi::symbol;
+use dygpi::testing::{assert_valid_provider, assert_valid_provider_with, MemorySource};
+use dygpi::topology::LibraryNode;
+use dygpi::verify::SignatureVerifier;
+use semver::{Version, VersionReq};
+use sound_api::{
+    EffectParameters, LightEffectPlugin, LightEffects, SoundEffectPlugin, SoundEffects, SoundEvent,
+};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 fn make_dylib_name(base_name: &str) -> PathBuf {
     PathBuf::from(format!(
@@ -10,13 +36,21 @@ fn make_dylib_name(base_name: &str) -> PathBuf {
     ))
 }
 
+fn dylib_directory() -> PathBuf {
+    std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
 #[test]
 fn test_library_not_found() {
     let _ = pretty_env_logger::try_init();
 
-    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
 
-    let result = plugin_manager.load_plugins_from(&make_dylib_name("unknown"));
+    let result = plugin_manager.load_plugins_from(make_dylib_name("unknown"));
     assert!(result.is_err());
     let err_message = format!("{:?}", result.err().unwrap());
     println!("{}", err_message);
@@ -24,26 +58,133 @@ fn test_library_not_found() {
 }
 
 #[test]
-fn test_library_with_no_plugins() {
+fn test_library_open_failure_reason() {
+    let _ = pretty_env_logger::try_init();
+
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+
+    let result = plugin_manager.load_plugins_from(make_dylib_name("unknown"));
+    assert_eq!(
+        result.err().unwrap().open_failure_reason(),
+        Some(OpenFailureReason::FileNotFound)
+    );
+
+    let directory = tempfile::tempdir().unwrap();
+    let not_a_library = directory.path().join(make_dylib_name("text"));
+    std::fs::write(&not_a_library, "not a library").unwrap();
+    let error = plugin_manager
+        .load_plugins_from(&not_a_library)
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.open_failure_reason(),
+        Some(OpenFailureReason::NotALibrary)
+    );
+    assert!(error
+        .to_string()
+        .contains("failed to open, the file is not a dynamic library"));
+}
+
+#[test]
+fn test_wrong_architecture() {
+    let _ = pretty_env_logger::try_init();
+
+    // An ELF header, 64-bit little endian, for a machine other than the host's.
+    let (machine, found) = if std::env::consts::ARCH == "aarch64" {
+        (62u16, "x86_64")
+    } else {
+        (183u16, "aarch64")
+    };
+    let mut header = vec![0u8; 64];
+    header[..7].copy_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1]);
+    header[16..18].copy_from_slice(&3u16.to_le_bytes());
+    header[18..20].copy_from_slice(&machine.to_le_bytes());
+
+    let directory = tempfile::tempdir().unwrap();
+    let file_name = directory.path().join(make_dylib_name("foreign"));
+    std::fs::write(&file_name, &header).unwrap();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    assert!(plugin_manager.check_architecture());
+    let error = plugin_manager.load_plugins_from(&file_name).err().unwrap();
+    assert!(format!("{:?}", error).starts_with("Error(WrongArchitecture"));
+    assert!(error.to_string().ends_with(&format!(
+        "was built for the {} architecture, expected {}",
+        found,
+        std::env::consts::ARCH
+    )));
+
+    plugin_manager.set_check_architecture(false);
+    let error = plugin_manager.load_plugins_from(&file_name).err().unwrap();
+    assert!(format!("{:?}", error).starts_with("Error(LibraryOpenFailed"));
+}
+
+#[test]
+fn test_plugin_limits() {
     let _ = pretty_env_logger::try_init();
 
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
     let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_registration_fn_name(symbol!("register_realtime_plugins"));
+
+    plugin_manager.set_max_plugins_per_library(Some(1));
+    let error = plugin_manager.load_plugins_from(&file_name).err().unwrap();
+    assert!(error
+        .to_string()
+        .ends_with("exceeds the limit of 1 plugins per library"));
+    assert!(plugin_manager.is_empty());
+
+    plugin_manager.set_max_plugins_per_library(None);
+    plugin_manager.set_max_plugins(Some(1));
+    let error = plugin_manager.load_plugins_from(&file_name).err().unwrap();
+    assert!(error
+        .to_string()
+        .ends_with("exceeds the limit of 1 plugins"));
+    assert!(plugin_manager.is_empty());
+
+    plugin_manager.set_max_plugins(None);
+    plugin_manager.set_max_libraries(Some(1));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(plugin_manager.len(), 2);
+
+    let source = Arc::new(MemorySource::default());
+    source.add_plugin("builtin", SoundEffectPlugin::new("builtin::Delay"));
+    let source: Arc<dyn PluginSource<SoundEffectPlugin>> = source;
+    let error = plugin_manager
+        .load_plugins_from_source(&source, "builtin")
+        .err()
+        .unwrap();
+    assert!(error
+        .to_string()
+        .ends_with("exceeds the limit of 1 libraries"));
+    assert_eq!(plugin_manager.len(), 2);
+}
+
+#[test]
+fn test_library_with_no_plugins() {
+    let _ = pretty_env_logger::try_init();
+
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
 
-    let result = plugin_manager.load_plugins_from(&make_dylib_name("sound_api"));
+    let result = plugin_manager.load_plugins_from(make_dylib_name("sound_api"));
     assert!(result.is_err());
-    let err_message = format!("{:?}", result.err().unwrap());
+    let error = result.err().unwrap();
+    let err_message = format!("{:?}", error);
     println!("err_message: {}", err_message);
-    assert!(err_message.starts_with(r##"Error(SymbolNotFound("register_plugins"##));
+    assert!(err_message.starts_with(r##"Error(SymbolNotFound { symbol: "register_plugins""##));
+    let display = error.to_string();
+    assert!(display.starts_with("Could not find symbol 'register_plugins' in library '"));
+    assert!(display.contains(&*make_dylib_name("sound_api").to_string_lossy()));
 }
 
 #[test]
 fn test_my_plugin() {
     let _ = pretty_env_logger::try_init();
 
-    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
 
     plugin_manager
-        .load_plugins_from(&make_dylib_name("sound_plugin"))
+        .load_plugins_from(make_dylib_name("sound_plugin"))
         .unwrap();
 
     assert!(!plugin_manager.is_empty());
@@ -61,10 +202,10 @@ fn test_my_other_plugin() {
     let _ = pretty_env_logger::try_init();
 
     let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
-    plugin_manager.set_registration_fn_name(b"register_other_plugins\0");
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
 
     plugin_manager
-        .load_plugins_from(&make_dylib_name("sound_plugin"))
+        .load_plugins_from(make_dylib_name("sound_plugin"))
         .unwrap();
 
     assert!(!plugin_manager.is_empty());
@@ -76,3 +217,2547 @@ fn test_my_other_plugin() {
 
     plugin.play();
 }
+
+#[test]
+fn test_my_plugin_via_search_path() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_search_path(&dylib_directory());
+
+    plugin_manager
+        .load_plugins_from_all(&[make_dylib_name("sound_plugin")])
+        .unwrap();
+
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+
+    let metrics = plugin_manager.metrics();
+    assert_eq!(metrics.libraries().count(), 1);
+    assert_eq!(metrics.failure_count(), 0);
+    assert!(metrics
+        .plugin("sound_plugin::sound_plugin::DelayEffect")
+        .is_some());
+}
+
+#[test]
+fn test_library_handle() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_search_path(&dylib_directory());
+
+    let handle = plugin_manager
+        .load_plugins_from(make_dylib_name("sound_plugin"))
+        .unwrap();
+    assert!(handle.path().is_absolute());
+    assert_eq!(
+        handle.path().file_name(),
+        Some(make_dylib_name("sound_plugin").as_os_str())
+    );
+    assert!(plugin_manager.is_library_loaded(&handle));
+
+    let info = plugin_manager
+        .plugin_info("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert_eq!(info.library(), handle.path());
+    assert_eq!(info.library_specifier(), &make_dylib_name("sound_plugin"));
+
+    plugin_manager.reload_library(&handle).unwrap();
+    let info = plugin_manager
+        .plugin_info("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert_eq!(info.library_specifier(), &make_dylib_name("sound_plugin"));
+
+    plugin_manager.unload_library(&handle).unwrap();
+    assert!(plugin_manager.is_empty());
+    assert!(!plugin_manager.is_library_loaded(&handle));
+}
+
+#[test]
+fn test_plugin_lifecycle_hooks() {
+    let _ = pretty_env_logger::try_init();
+
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager
+        .load_plugins_from(make_dylib_name("sound_plugin"))
+        .unwrap();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let plugin = plugin_manager.get(PLUGIN_ID).unwrap();
+    assert!(plugin_manager.is_enabled(PLUGIN_ID));
+    assert!(!plugin_manager.is_paused(PLUGIN_ID));
+
+    plugin_manager.disable_plugin(PLUGIN_ID).unwrap();
+    assert!(!plugin_manager.is_enabled(PLUGIN_ID));
+    assert!(!plugin.is_enabled());
+    plugin_manager.enable_plugin(PLUGIN_ID).unwrap();
+    assert!(plugin.is_enabled());
+
+    plugin_manager.pause_all().unwrap();
+    assert!(plugin_manager.is_paused(PLUGIN_ID));
+    assert!(plugin.is_paused());
+    plugin_manager.resume_plugin(PLUGIN_ID).unwrap();
+    assert!(!plugin.is_paused());
+
+    let result = plugin_manager.pause_plugin("unknown");
+    assert!(format!("{:?}", result.err().unwrap()).starts_with("Error(UnknownPlugin"));
+}
+
+#[test]
+fn test_reload_library_restores_state() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_search_path(&dylib_directory());
+    plugin_manager
+        .load_plugins_from(make_dylib_name("sound_plugin"))
+        .unwrap();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let before = plugin_manager.get(PLUGIN_ID).unwrap();
+    before.set_level(7);
+
+    plugin_manager
+        .reload_library(make_dylib_name("sound_plugin"))
+        .unwrap();
+
+    let after = plugin_manager.get(PLUGIN_ID).unwrap();
+    assert!(!Arc::ptr_eq(&before, &after));
+    assert_eq!(after.level(), 7);
+}
+
+#[test]
+fn test_reload_libraries_with_policy() {
+    let _ = pretty_env_logger::try_init();
+
+    static QUIESCED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+    static RESUMED: AtomicUsize = AtomicUsize::new(0);
+    fn quiesce(libraries: &[PathBuf]) {
+        QUIESCED.lock().unwrap().push(libraries.len());
+    }
+    fn resume(_: &[PathBuf]) {
+        let _ = RESUMED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    let mut policy = ReloadPolicy::default();
+    policy.set_batch(true);
+    policy.set_quiesce_handler(quiesce);
+    policy.set_resume_handler(resume);
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_search_path(&dylib_directory());
+    plugin_manager.set_reload_policy(policy);
+    assert!(plugin_manager.reload_policy().is_batch());
+    plugin_manager
+        .load_plugins_from(make_dylib_name("sound_plugin"))
+        .unwrap();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let before = plugin_manager.get(PLUGIN_ID).unwrap();
+    plugin_manager
+        .reload_libraries(&[make_dylib_name("sound_plugin")])
+        .unwrap();
+    assert!(!Arc::ptr_eq(
+        &before,
+        &plugin_manager.get(PLUGIN_ID).unwrap()
+    ));
+    assert_eq!(*QUIESCED.lock().unwrap(), vec![1]);
+    assert_eq!(RESUMED.load(Ordering::SeqCst), 1);
+
+    // The resume handler is called even when a library fails to reload, and the batch is not
+    // rolled back, so the library before the failure remains reloaded.
+    let before = plugin_manager.get(PLUGIN_ID).unwrap();
+    assert!(plugin_manager
+        .reload_libraries(&[
+            make_dylib_name("sound_plugin"),
+            make_dylib_name("missing_plugin")
+        ])
+        .is_err());
+    assert_eq!(*QUIESCED.lock().unwrap(), vec![1, 2]);
+    assert_eq!(RESUMED.load(Ordering::SeqCst), 2);
+    assert!(!Arc::ptr_eq(
+        &before,
+        &plugin_manager.get(PLUGIN_ID).unwrap()
+    ));
+}
+
+#[test]
+fn test_swap_library() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_search_path(&dylib_directory());
+    plugin_manager
+        .load_plugins_from(make_dylib_name("sound_plugin"))
+        .unwrap();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let before = plugin_manager.get(PLUGIN_ID).unwrap();
+    before.set_level(3);
+
+    plugin_manager
+        .swap_library(
+            make_dylib_name("sound_plugin"),
+            make_dylib_name("sound_plugin"),
+        )
+        .unwrap();
+
+    let after = plugin_manager.get(PLUGIN_ID).unwrap();
+    assert!(!Arc::ptr_eq(&before, &after));
+    assert_eq!(after.level(), 3);
+    assert_eq!(plugin_manager.len(), 1);
+}
+
+#[test]
+fn test_swap_library_rolls_back() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_search_path(&dylib_directory());
+    plugin_manager
+        .load_plugins_from(make_dylib_name("sound_plugin"))
+        .unwrap();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let before = plugin_manager.get(PLUGIN_ID).unwrap();
+
+    plugin_manager.set_registration_fn_name(symbol!("register_failing_plugins"));
+    let result = plugin_manager.swap_library(
+        make_dylib_name("sound_plugin"),
+        make_dylib_name("sound_plugin"),
+    );
+    assert!(result.is_err());
+
+    let after = plugin_manager.get(PLUGIN_ID).unwrap();
+    assert!(Arc::ptr_eq(&before, &after));
+    assert_eq!(plugin_manager.len(), 1);
+}
+
+#[test]
+fn test_host_api_version_negotiation() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_registration_fn_name(symbol!("register_versioned_plugins"));
+
+    let result = plugin_manager.load_plugins_from(make_dylib_name("sound_plugin"));
+    let err_message = format!("{:?}", result.err().unwrap());
+    assert!(err_message.starts_with(
+        "Error(PluginRegistration { plugin_id: None, source: Error(IncompatibleHostApiVersion { api_version: 0 })"
+    ));
+    assert!(plugin_manager.is_empty());
+
+    plugin_manager.set_host_info(HostInfo::new(2, "sound_host", "0.1.0"));
+    plugin_manager
+        .load_plugins_from(make_dylib_name("sound_plugin"))
+        .unwrap();
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+
+    plugin_manager.set_duplicate_load_policy(DuplicateLoadPolicy::Replace);
+    plugin_manager.set_host_info(HostInfo::new(3, "sound_host", "0.1.0"));
+    plugin_manager
+        .load_plugins_from(make_dylib_name("sound_plugin"))
+        .unwrap();
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::ReverbEffect"));
+}
+
+#[test]
+fn test_incompatible_plugin_type() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_registration_fn_name(symbol!("register_light_plugins"));
+
+    let result = plugin_manager.load_plugins_from(make_dylib_name("sound_plugin"));
+    let err_message = format!("{:?}", result.err().unwrap());
+    println!("err_message: {}", err_message);
+    assert!(err_message.starts_with(
+        r##"Error(PluginRegistration { plugin_id: None, source: Error(IncompatiblePluginType { type_name: "sound_api::LightEffectPlugin" }) })"##
+    ));
+    assert!(plugin_manager.is_empty());
+}
+
+#[test]
+fn test_registration_fn_discovery() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<LightEffectPlugin> = PluginManager::default();
+    unsafe {
+        plugin_manager.enable_registration_fn_discovery();
+    }
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::MoodLighting"));
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    unsafe {
+        plugin_manager.set_registration_fn_prefix("register_other");
+    }
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::ReverbEffect"));
+}
+
+#[test]
+fn test_explain_load_failure() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let explanation = explain_load_failure(&file_name).unwrap();
+    println!("{}", explanation);
+    assert_eq!(explanation.file_name(), &file_name);
+    assert!(!explanation.dependencies().is_empty());
+    assert!(explanation.is_resolved());
+
+    let directory = tempfile::tempdir().unwrap();
+    let not_a_library = directory.path().join(make_dylib_name("text"));
+    std::fs::write(&not_a_library, "not a library").unwrap();
+    assert!(explain_load_failure(&not_a_library).is_err());
+}
+
+#[test]
+fn test_validate_library_manifest() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let manifest = validate_library(&file_name, &HostInfo::default()).unwrap();
+    let plugin_ids: Vec<&String> = manifest.plugins().map(|p| p.plugin_id()).collect();
+    assert_eq!(
+        plugin_ids,
+        vec![
+            "sound_plugin::sound_plugin::DelayEffect",
+            "sound_plugin::sound_plugin::ReverbEffect",
+            "sound_plugin::sound_plugin::MoodLighting",
+        ]
+    );
+    assert!(manifest.plugins().all(|p| p.version() == "0.1.0"));
+}
+
+#[test]
+fn test_load_bundle() {
+    let _ = pretty_env_logger::try_init();
+
+    let bundle = tempfile::tempdir().unwrap();
+    let _ = std::fs::copy(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        bundle.path().join(make_dylib_name("sound_plugin")),
+    )
+    .unwrap();
+    std::fs::write(
+        bundle.path().join("plugin.toml"),
+        r##"
+[[plugin]]
+id = "sound_plugin::sound_plugin::DelayEffect"
+type = "sound_api::SoundEffectPlugin"
+library = "sound_plugin"
+
+[[plugin]]
+id = "sound_plugin::sound_plugin::ReverbEffect"
+type = "sound_api::SoundEffectPlugin"
+library = "sound_plugin"
+entry = "register_other_plugins"
+platforms = ["no_such_platform"]
+
+[[plugin]]
+id = "sound_plugin::sound_plugin::MoodLighting"
+type = "sound_api::LightEffectPlugin"
+library = "sound_plugin"
+entry = "register_light_plugins"
+"##,
+    )
+    .unwrap();
+
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.load_bundle(bundle.path()).unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+
+    let plugin_manager: PluginManager<LightEffectPlugin> = PluginManager::default();
+    plugin_manager.load_bundle(bundle.path()).unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::MoodLighting"));
+}
+
+static LOAD_ORDER: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn record_load_order(transition: StateTransition) {
+    if transition.to() == PluginState::Loaded {
+        LOAD_ORDER
+            .lock()
+            .unwrap()
+            .push(transition.plugin_id().clone());
+    }
+}
+
+#[test]
+fn test_load_bundle_in_priority_order() {
+    let _ = pretty_env_logger::try_init();
+
+    let bundle = tempfile::tempdir().unwrap();
+    let _ = std::fs::copy(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        bundle.path().join(make_dylib_name("sound_plugin")),
+    )
+    .unwrap();
+    std::fs::write(
+        bundle.path().join("plugin.toml"),
+        r##"
+[[plugin]]
+id = "sound_plugin::sound_plugin::DelayEffect"
+type = "sound_api::SoundEffectPlugin"
+library = "sound_plugin"
+
+[[plugin]]
+id = "sound_plugin::sound_plugin::ReverbEffect"
+type = "sound_api::SoundEffectPlugin"
+library = "sound_plugin"
+entry = "register_priority_plugins"
+"##,
+    )
+    .unwrap();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_transition_handler(record_load_order);
+    plugin_manager.load_bundle(bundle.path()).unwrap();
+    assert_eq!(
+        *LOAD_ORDER.lock().unwrap(),
+        vec![
+            "sound_plugin::sound_plugin::ReverbEffect".to_string(),
+            "sound_plugin::sound_plugin::DelayEffect".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_library_cache_shared_between_managers() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+
+    let mut effects: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    effects.set_library_cache(cache.clone());
+    effects.load_plugins_from(&file_name).unwrap();
+
+    let mut lights: PluginManager<LightEffectPlugin> = PluginManager::default();
+    lights.set_library_cache(cache.clone());
+    lights.set_registration_fn_name(symbol!("register_light_plugins"));
+    lights.load_plugins_from(&file_name).unwrap();
+
+    assert_eq!(cache.len(), 1);
+    assert!(cache.contains(&file_name));
+
+    effects.unload_all().unwrap();
+    assert!(cache.contains(&file_name));
+    assert!(lights.contains("sound_plugin::sound_plugin::MoodLighting"));
+
+    lights.unload_all().unwrap();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_deferred_library_close() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    let plugin = plugin_manager
+        .get("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    plugin_manager
+        .unload_plugin("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert!(plugin_manager.is_empty());
+    assert!(cache.contains(&file_name));
+    plugin.play();
+
+    drop(plugin);
+    assert!(cache.contains(&file_name));
+    plugin_manager.unload_all().unwrap();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_library_closed_after_manager_dropped() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    let plugin = plugin_manager
+        .get("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    drop(plugin_manager);
+    assert!(cache.contains(&file_name));
+    plugin.play();
+
+    drop(plugin);
+    let start = Instant::now();
+    while cache.contains(&file_name) && start.elapsed() < Duration::from_secs(5) {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_two_phase_unload() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    plugin_manager
+        .deactivate_plugin("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert!(plugin_manager.is_empty());
+    assert!(plugin_manager
+        .deactivate_plugin("sound_plugin::sound_plugin::DelayEffect")
+        .is_err());
+
+    plugin_manager.unload_all().unwrap();
+    assert!(cache.contains(&file_name));
+
+    plugin_manager.close_idle_libraries().unwrap();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_deactivate_plugin_versions_after_failure() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+    let recorder = Arc::new(HookRecorder::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.add_interceptor(recorder.clone());
+    plugin_manager.add_interceptor(Arc::new(FailingHook {
+        hook: Hook::Unload,
+        plugin_id: PLUGIN_ID,
+        plugin_version: Some("2.0.0"),
+    }));
+    plugin_manager.set_registration_fn_name(symbol!("register_legacy_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_upgraded_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    assert!(plugin_manager.deactivate_plugin(PLUGIN_ID).is_err());
+    assert!(plugin_manager.is_empty());
+    assert_eq!(
+        recorder
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|hook| hook.starts_with("on_unload"))
+            .count(),
+        2
+    );
+
+    plugin_manager.close_idle_libraries().unwrap();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_unload_library_after_failure() {
+    let _ = pretty_env_logger::try_init();
+
+    const STUCK_PLUGIN_ID: &str = "sound_plugin::sound_plugin::StuckEffect";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+    let recorder = Arc::new(HookRecorder::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.add_interceptor(recorder.clone());
+    plugin_manager.set_registration_fn_name(symbol!("register_stuck_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(plugin_manager.len(), 2);
+
+    let plugin = plugin_manager.get(STUCK_PLUGIN_ID).unwrap();
+    assert!(plugin_manager.unload_library(&file_name).is_err());
+    assert!(plugin_manager.is_empty());
+    assert_eq!(
+        recorder
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|hook| hook.starts_with("on_unload"))
+            .count(),
+        2
+    );
+    assert!(cache.contains(&file_name));
+    plugin.play();
+
+    drop(plugin);
+    plugin_manager.close_idle_libraries().unwrap();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_reload_library_restores_state_per_version() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_registration_fn_name(symbol!("register_all_versions_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    let legacy = VersionReq::parse("=1.4.0").unwrap();
+    let upgraded = VersionReq::parse("=2.0.0").unwrap();
+    plugin_manager
+        .get_version(PLUGIN_ID, &legacy)
+        .unwrap()
+        .set_level(3);
+    plugin_manager
+        .get_version(PLUGIN_ID, &upgraded)
+        .unwrap()
+        .set_level(7);
+
+    plugin_manager.reload_library(&file_name).unwrap();
+    assert_eq!(
+        plugin_manager
+            .get_version(PLUGIN_ID, &legacy)
+            .unwrap()
+            .level(),
+        3
+    );
+    assert_eq!(
+        plugin_manager
+            .get_version(PLUGIN_ID, &upgraded)
+            .unwrap()
+            .level(),
+        7
+    );
+}
+
+#[test]
+fn test_reload_library_after_failure() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_registration_fn_name(symbol!("register_stuck_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    let before = plugin_manager.get(PLUGIN_ID).unwrap();
+
+    assert!(plugin_manager.reload_library(&file_name).is_err());
+    assert_eq!(plugin_manager.len(), 2);
+    assert!(!Arc::ptr_eq(
+        &before,
+        &plugin_manager.get(PLUGIN_ID).unwrap()
+    ));
+}
+
+#[test]
+fn test_plugin_instancing() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.set_registration_fn_name(symbol!("register_instanced_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    assert_eq!(
+        plugin_manager.instancing("sound_plugin::sound_plugin::DelayEffect"),
+        Some(Instancing::PerUse)
+    );
+    let first = plugin_manager
+        .get("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    let second = plugin_manager
+        .get("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert!(!Arc::ptr_eq(&first, &second));
+    first.set_level(7);
+    assert_eq!(second.level(), 0);
+
+    assert_eq!(
+        plugin_manager.instancing("sound_plugin::sound_plugin::ReverbEffect"),
+        Some(Instancing::Shared)
+    );
+    assert!(plugin_manager
+        .set_instancing(
+            "sound_plugin::sound_plugin::ReverbEffect",
+            Instancing::PerUse
+        )
+        .is_err());
+    assert!(Arc::ptr_eq(
+        &plugin_manager
+            .get("sound_plugin::sound_plugin::ReverbEffect")
+            .unwrap(),
+        &plugin_manager
+            .get("sound_plugin::sound_plugin::ReverbEffect")
+            .unwrap()
+    ));
+
+    plugin_manager
+        .set_instancing(
+            "sound_plugin::sound_plugin::DelayEffect",
+            Instancing::Shared,
+        )
+        .unwrap();
+    assert!(Arc::ptr_eq(
+        &plugin_manager
+            .get("sound_plugin::sound_plugin::DelayEffect")
+            .unwrap(),
+        &plugin_manager
+            .get("sound_plugin::sound_plugin::DelayEffect")
+            .unwrap()
+    ));
+
+    drop(second);
+    plugin_manager.unload_all().unwrap();
+    assert!(cache.contains(&file_name));
+    first.play();
+    drop(first);
+    plugin_manager.unload_all().unwrap();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_instantiate_plugin() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_registration_fn_name(symbol!("register_instantiable_plugins"));
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+
+    let plugin = plugin_manager
+        .instantiate(
+            "sound_plugin::sound_plugin::DelayEffect",
+            &EffectParameters { level: 11 },
+        )
+        .unwrap();
+    assert_eq!(plugin.level(), 11);
+    assert_eq!(
+        plugin_manager
+            .get("sound_plugin::sound_plugin::DelayEffect")
+            .unwrap()
+            .level(),
+        0
+    );
+
+    let result = plugin_manager.instantiate("sound_plugin::sound_plugin::DelayEffect", &11u32);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Plugin 'sound_plugin::sound_plugin::DelayEffect' expects parameters of type 'sound_api::EffectParameters', but was given 'u32'"
+    );
+    assert!(plugin_manager
+        .instantiate("sound_plugin::sound_plugin::ReverbEffect", &())
+        .is_err());
+}
+
+#[test]
+fn test_duplicate_load_policy() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager
+        .load_plugins_from_all(&[
+            &file_name,
+            &dylib_directory()
+                .join(".")
+                .join(make_dylib_name("sound_plugin")),
+        ])
+        .unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    assert_eq!(
+        plugin_manager
+            .metrics()
+            .library(&file_name)
+            .unwrap()
+            .load_count(),
+        1
+    );
+
+    plugin_manager.set_duplicate_load_policy(DuplicateLoadPolicy::Error);
+    let result = plugin_manager.load_plugins_from(&file_name);
+    let err_message = format!("{:?}", result.err().unwrap());
+    assert!(err_message.starts_with("Error(LibraryAlreadyLoaded"));
+
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(plugin_manager.len(), 2);
+}
+
+#[test]
+fn test_manager_shared_across_threads() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let plugin_manager: Arc<PluginManager<SoundEffectPlugin>> = Arc::new(PluginManager::default());
+
+    let handles: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let plugin_manager = plugin_manager.clone();
+            let file_name = file_name.clone();
+            std::thread::spawn(move || {
+                plugin_manager.load_plugins_from(&file_name).unwrap();
+                assert!(plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(plugin_manager.len(), 1);
+
+    plugin_manager.unload_all().unwrap();
+    assert!(plugin_manager.is_empty());
+}
+
+#[test]
+fn test_lookups_during_load_and_unload() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let plugin_id = "sound_plugin::sound_plugin::DelayEffect";
+    let plugin_manager: Arc<PluginManager<SoundEffectPlugin>> = Arc::new(PluginManager::default());
+    let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+    let reader = {
+        let plugin_manager = plugin_manager.clone();
+        let done = done.clone();
+        std::thread::spawn(move || {
+            let mut lookups = 0_usize;
+            while !done.load(std::sync::atomic::Ordering::Acquire) {
+                if let Some(plugin) = plugin_manager.get(plugin_id) {
+                    assert_eq!(plugin.plugin_id(), plugin_id);
+                }
+                let _ = plugin_manager.plugins();
+                lookups += 1;
+            }
+            lookups
+        })
+    };
+
+    for _ in 0..10 {
+        plugin_manager.load_plugins_from(&file_name).unwrap();
+        assert!(plugin_manager.contains(plugin_id));
+        plugin_manager.unload_all().unwrap();
+        assert!(!plugin_manager.contains(plugin_id));
+    }
+    done.store(true, std::sync::atomic::Ordering::Release);
+    assert!(reader.join().unwrap() > 0);
+}
+
+#[test]
+fn test_shutdown_in_reverse_registration_order() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(plugin_manager.len(), 2);
+
+    let report = plugin_manager.shutdown(Duration::from_secs(10));
+    assert!(report.is_clean());
+    assert_eq!(
+        report.unloaded().collect::<Vec<&String>>(),
+        vec![
+            "sound_plugin::sound_plugin::ReverbEffect",
+            "sound_plugin::sound_plugin::DelayEffect"
+        ]
+    );
+    assert!(plugin_manager.is_empty());
+
+    plugin_manager.set_registration_fn_name(symbol!("register_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    let report = plugin_manager.shutdown(Duration::ZERO);
+    assert!(!report.is_clean());
+    assert_eq!(report.timed_out().count(), 1);
+    assert!(plugin_manager.is_empty());
+}
+
+static DROP_FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+fn count_drop_failures(report: &ShutdownReport) {
+    let _ = DROP_FAILURES.fetch_add(report.failed().count(), Ordering::SeqCst);
+}
+
+#[test]
+fn test_unload_failures_do_not_panic_on_drop() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_registration_fn_name(symbol!("register_stuck_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    let report = plugin_manager.shutdown(Duration::from_secs(10));
+    assert!(!report.is_clean());
+    assert_eq!(report.unloaded().count(), 1);
+    assert_eq!(report.failed().count(), 1);
+    assert!(plugin_manager.is_empty());
+
+    assert_eq!(plugin_manager.drop_policy(), DropPolicy::Log);
+    plugin_manager.set_drop_error_handler(count_drop_failures);
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    drop(plugin_manager);
+    assert_eq!(DROP_FAILURES.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_plugin_info() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    assert!(plugin_manager.plugin_info("unknown").is_none());
+    let info = plugin_manager
+        .plugin_info("sound_plugin::sound_plugin::ReverbEffect")
+        .unwrap();
+    assert_eq!(info.library(), &file_name.canonicalize().unwrap());
+    assert_eq!(info.registration_fn(), "register_other_plugins");
+    assert_eq!(info.library_references(), 2);
+    assert!(info.is_enabled());
+    assert!(info
+        .to_string()
+        .starts_with("sound_plugin::sound_plugin::ReverbEffect library="));
+
+    let infos = plugin_manager.plugin_infos();
+    assert_eq!(
+        infos
+            .iter()
+            .map(PluginInfo::registration_fn)
+            .collect::<Vec<&String>>(),
+        vec!["register_plugins", "register_other_plugins"]
+    );
+    assert!(infos[0].loaded_at() <= infos[1].loaded_at());
+}
+
+#[test]
+fn test_topology() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    let topology = plugin_manager.topology();
+    let libraries: Vec<&LibraryNode> = topology.libraries().collect();
+    assert_eq!(libraries.len(), 1);
+    assert_eq!(
+        libraries[0].plugins().collect::<Vec<&String>>(),
+        vec![
+            "sound_plugin::sound_plugin::DelayEffect",
+            "sound_plugin::sound_plugin::ReverbEffect"
+        ]
+    );
+    assert!(topology
+        .to_dot()
+        .contains("-> \"sound_plugin::sound_plugin::ReverbEffect\";"));
+}
+
+static PROGRESS: Mutex<Vec<ProgressEvent>> = Mutex::new(Vec::new());
+
+fn record_progress(event: ProgressEvent) {
+    PROGRESS.lock().unwrap().push(event);
+}
+
+#[test]
+fn test_load_progress_events() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let missing_file_name = dylib_directory().join(make_dylib_name("missing_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_progress_handler(record_progress);
+    assert!(plugin_manager
+        .load_plugins_from_all(&[&file_name, &missing_file_name])
+        .is_err());
+
+    let events = PROGRESS.lock().unwrap();
+    assert_eq!(events.len(), 4);
+    assert_eq!(
+        events[0].to_string(),
+        format!(
+            "Loading {} (1/2)",
+            make_dylib_name("sound_plugin").display()
+        )
+    );
+    assert!(matches!(
+        events[1],
+        ProgressEvent::LibraryFinished {
+            index: 1,
+            succeeded: true,
+            ..
+        }
+    ));
+    assert!(matches!(
+        events[3],
+        ProgressEvent::LibraryFinished {
+            index: 2,
+            count: 2,
+            succeeded: false,
+            ..
+        }
+    ));
+    assert!(events[3].to_string().starts_with("Failed loading "));
+}
+
+static CANCEL_TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+
+fn cancel_after_first_library(event: ProgressEvent) {
+    if let ProgressEvent::LibraryFinished { index: 1, .. } = event {
+        CANCEL_TOKEN.get().unwrap().cancel();
+    }
+}
+
+#[test]
+fn test_cancel_bulk_load() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let token = CANCEL_TOKEN.get_or_init(CancellationToken::default);
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_cancellation_token(token.clone());
+    plugin_manager.set_progress_handler(cancel_after_first_library);
+
+    let result = plugin_manager.load_plugins_from_all(&[&file_name, &file_name]);
+    assert!(token.is_cancelled());
+    assert_eq!(
+        format!("{:?}", result.err().unwrap()),
+        r#"Error(LoadCancelled { unloaded: ["sound_plugin::sound_plugin::DelayEffect"] })"#
+    );
+    assert!(plugin_manager.is_empty());
+
+    token.reset();
+    plugin_manager.load_plugins_from_all(&[&file_name]).unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+}
+
+#[test]
+fn test_load_plugins_from_first() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let missing_file_name = dylib_directory().join(make_dylib_name("sound_plugin_avx2"));
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+
+    assert!(plugin_manager
+        .load_plugins_from_first(&[&missing_file_name])
+        .is_err());
+    assert_eq!(
+        plugin_manager
+            .load_plugins_from_first::<&Path>(&[])
+            .unwrap_err()
+            .to_string(),
+        "No alternative libraries were provided to load plugins from"
+    );
+
+    let loaded = plugin_manager
+        .load_plugins_from_first(&[&missing_file_name, &file_name])
+        .unwrap();
+    assert_eq!(loaded, file_name);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+}
+
+#[test]
+fn test_load_plugins_from_dir() {
+    let _ = pretty_env_logger::try_init();
+
+    let plugins = tempfile::tempdir().unwrap();
+    let platform_directory = plugins.path().join(Platform::current().to_string());
+    std::fs::create_dir(&platform_directory).unwrap();
+    let _ = std::fs::copy(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        platform_directory.join(make_dylib_name("sound_plugin")),
+    )
+    .unwrap();
+    std::fs::write(platform_directory.join("README.txt"), "not a library").unwrap();
+
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager
+        .load_plugins_from_dir(plugins.path())
+        .unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+
+    assert!(plugin_manager
+        .load_plugins_from_dir(plugins.path().join("missing"))
+        .is_err());
+}
+
+#[test]
+fn test_load_all_plugins_from_env() {
+    let _ = pretty_env_logger::try_init();
+
+    let plugins = tempfile::tempdir().unwrap();
+    let _ = std::fs::copy(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        plugins.path().join(make_dylib_name("sound_plugin")),
+    )
+    .unwrap();
+
+    let env_value = std::env::join_paths([
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        PathBuf::new(),
+    ])
+    .unwrap();
+    std::env::set_var("DYGPI_TEST_ENV_LIBRARIES", env_value);
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    assert_eq!(
+        plugin_manager
+            .load_all_plugins_from_env("DYGPI_TEST_ENV_LIBRARIES")
+            .unwrap(),
+        1
+    );
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+    assert_eq!(
+        plugin_manager
+            .load_all_plugins_from_env("DYGPI_TEST_ENV_LIBRARIES")
+            .unwrap(),
+        0
+    );
+
+    std::env::set_var("DYGPI_TEST_ENV_DIRECTORIES", plugins.path());
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    assert!(plugin_manager
+        .load_all_plugins_from_env("DYGPI_TEST_ENV_DIRECTORIES")
+        .is_err());
+    let mut policy = EnvLoadPolicy::default();
+    policy.set_scan_directories(true);
+    assert_eq!(
+        plugin_manager
+            .load_all_plugins_from_env_with("DYGPI_TEST_ENV_DIRECTORIES", &policy)
+            .unwrap(),
+        1
+    );
+    assert_eq!(plugin_manager.len(), 1);
+
+    assert_eq!(
+        plugin_manager
+            .load_all_plugins_from_env("DYGPI_TEST_ENV_UNSET")
+            .unwrap(),
+        0
+    );
+    policy.set_required(true);
+    let result = plugin_manager.load_all_plugins_from_env_with("DYGPI_TEST_ENV_UNSET", &policy);
+    let err_message = format!("{:?}", result.err().unwrap());
+    assert_eq!(
+        err_message,
+        r#"Error(EnvVarNotSet { name: "DYGPI_TEST_ENV_UNSET" })"#
+    );
+}
+
+#[test]
+fn test_quarantine_failing_library() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.enable_quarantine();
+    plugin_manager.set_registration_fn_name(symbol!("register_failing_plugins"));
+
+    assert!(plugin_manager.load_plugins_from(&file_name).is_err());
+    let quarantined = plugin_manager.quarantined();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].library(), &file_name.canonicalize().unwrap());
+
+    let result = plugin_manager.load_plugins_from(&file_name);
+    assert!(format!("{:?}", result.err().unwrap()).starts_with("Error(LibraryQuarantined"));
+    plugin_manager.load_plugins_from_all(&[&file_name]).unwrap();
+
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    assert_eq!(plugin_manager.retry_quarantined().len(), 1);
+    assert!(plugin_manager.quarantined().is_empty());
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::ReverbEffect"));
+}
+
+#[test]
+fn test_failed_registration_loads_nothing() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.set_registration_fn_name(symbol!("register_failing_plugins"));
+
+    assert!(plugin_manager.load_plugins_from(&file_name).is_err());
+    assert!(plugin_manager.is_empty());
+    assert!(!plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+    assert!(cache.is_empty());
+
+    let metrics = plugin_manager.metrics();
+    let failed = metrics
+        .plugin("sound_plugin::sound_plugin::BrokenEffect")
+        .unwrap();
+    assert_eq!(failed.load_count(), 1);
+    assert_eq!(failed.failure_count(), 1);
+
+    plugin_manager.set_registration_fn_name(symbol!("register_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+}
+
+#[derive(Debug, Default)]
+struct HookRecorder(Mutex<Vec<String>>);
+
+impl Interceptor<SoundEffectPlugin> for HookRecorder {
+    fn intercept(
+        &self,
+        plugin: &SoundEffectPlugin,
+        hook: Hook,
+        next: &mut dyn FnMut() -> dygpi::error::Result<()>,
+    ) -> dygpi::error::Result<()> {
+        let result = next();
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", hook, plugin.plugin_id()));
+        result
+    }
+}
+
+#[test]
+fn test_lifecycle_interceptors() {
+    let _ = pretty_env_logger::try_init();
+
+    let recorder = Arc::new(HookRecorder::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_interceptor(recorder.clone());
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+    plugin_manager.pause_all().unwrap();
+    plugin_manager.unload_all().unwrap();
+
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        vec![
+            "on_load sound_plugin::sound_plugin::DelayEffect",
+            "on_pause sound_plugin::sound_plugin::DelayEffect",
+            "on_unload sound_plugin::sound_plugin::DelayEffect",
+        ]
+    );
+}
+
+#[derive(Debug)]
+struct FailingDisable;
+
+impl Interceptor<SoundEffectPlugin> for FailingDisable {
+    fn intercept(
+        &self,
+        plugin: &SoundEffectPlugin,
+        hook: Hook,
+        next: &mut dyn FnMut() -> dygpi::error::Result<()>,
+    ) -> dygpi::error::Result<()> {
+        if hook == Hook::Disable {
+            Err(Error::unknown_plugin(plugin.plugin_id()))
+        } else {
+            next()
+        }
+    }
+}
+
+static TRANSITIONS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn record_transition(transition: StateTransition) {
+    TRANSITIONS
+        .lock()
+        .unwrap()
+        .push(format!("{} -> {}", transition.from(), transition.to()));
+}
+
+#[test]
+fn test_plugin_lifecycle_states() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_interceptor(Arc::new(FailingDisable));
+    plugin_manager.set_transition_handler(record_transition);
+    assert_eq!(plugin_manager.state_of(PLUGIN_ID), None);
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+    assert_eq!(
+        plugin_manager.state_of(PLUGIN_ID),
+        Some(PluginState::Active)
+    );
+
+    plugin_manager.pause_plugin(PLUGIN_ID).unwrap();
+    assert_eq!(
+        plugin_manager.state_of(PLUGIN_ID),
+        Some(PluginState::Loaded)
+    );
+    plugin_manager.resume_plugin(PLUGIN_ID).unwrap();
+
+    assert!(plugin_manager.disable_plugin(PLUGIN_ID).is_err());
+    assert_eq!(
+        plugin_manager.state_of(PLUGIN_ID),
+        Some(PluginState::Failed)
+    );
+    assert_eq!(
+        plugin_manager.status().plugins().next().unwrap().health(),
+        PluginHealth::Failed
+    );
+    assert_eq!(
+        plugin_manager
+            .enable_plugin(PLUGIN_ID)
+            .unwrap_err()
+            .to_string(),
+        format!(
+            "Plugin '{}' cannot change from the Failed state to Active",
+            PLUGIN_ID
+        )
+    );
+    plugin_manager.pause_all().unwrap();
+
+    plugin_manager.unload_plugin(PLUGIN_ID).unwrap();
+    assert_eq!(
+        plugin_manager.state_of(PLUGIN_ID),
+        Some(PluginState::Unloaded)
+    );
+
+    assert_eq!(
+        *TRANSITIONS.lock().unwrap(),
+        vec![
+            "Discovered -> Loaded",
+            "Loaded -> Active",
+            "Active -> Loaded",
+            "Loaded -> Active",
+            "Active -> Failed",
+            "Failed -> Unloaded",
+        ]
+    );
+}
+
+#[derive(Debug)]
+struct FailingHook {
+    hook: Hook,
+    plugin_id: &'static str,
+    plugin_version: Option<&'static str>,
+}
+
+impl Interceptor<SoundEffectPlugin> for FailingHook {
+    fn intercept(
+        &self,
+        plugin: &SoundEffectPlugin,
+        hook: Hook,
+        next: &mut dyn FnMut() -> dygpi::error::Result<()>,
+    ) -> dygpi::error::Result<()> {
+        if hook == self.hook
+            && plugin.plugin_id() == self.plugin_id
+            && (self.plugin_version.is_none() || plugin.plugin_version() == self.plugin_version)
+        {
+            Err(Error::unknown_plugin(plugin.plugin_id()))
+        } else {
+            next()
+        }
+    }
+}
+
+#[test]
+fn test_failed_update_is_not_published() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    const OTHER_PLUGIN_ID: &str = "sound_plugin::sound_plugin::ReverbEffect";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_interceptor(Arc::new(FailingHook {
+        hook: Hook::Pause,
+        plugin_id: OTHER_PLUGIN_ID,
+        plugin_version: None,
+    }));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    assert!(plugin_manager.pause_all().is_err());
+    assert_eq!(
+        plugin_manager.state_of(PLUGIN_ID),
+        Some(PluginState::Active)
+    );
+    assert_eq!(
+        plugin_manager.state_of(OTHER_PLUGIN_ID),
+        Some(PluginState::Failed)
+    );
+
+    plugin_manager.unload_all().unwrap();
+}
+
+#[test]
+fn test_update_plugin_versions_is_all_or_nothing() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_interceptor(Arc::new(FailingHook {
+        hook: Hook::Disable,
+        plugin_id: PLUGIN_ID,
+        plugin_version: Some("2.0.0"),
+    }));
+    plugin_manager.set_registration_fn_name(symbol!("register_legacy_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_upgraded_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    let legacy = plugin_manager
+        .get_version(PLUGIN_ID, &VersionReq::parse("^1").unwrap())
+        .unwrap();
+    let latest = plugin_manager.get(PLUGIN_ID).unwrap();
+
+    assert!(plugin_manager.disable_plugin(PLUGIN_ID).is_err());
+    assert!(legacy.is_enabled());
+    assert!(latest.is_enabled());
+    let health = |version: Version| {
+        plugin_manager
+            .status()
+            .plugins()
+            .find(|plugin| plugin.version() == Some(&version))
+            .unwrap()
+            .health()
+    };
+    assert_eq!(health(Version::new(1, 4, 0)), PluginHealth::Active);
+    assert_eq!(health(Version::new(2, 0, 0)), PluginHealth::Failed);
+
+    drop(legacy);
+    drop(latest);
+    plugin_manager.unload_plugin(PLUGIN_ID).unwrap();
+}
+
+#[test]
+fn test_plugin_instance_ids() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    let other_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    other_manager.load_plugins_from(&file_name).unwrap();
+
+    let instance_id = plugin_manager.instance_id(PLUGIN_ID).unwrap();
+    assert_ne!(other_manager.instance_id(PLUGIN_ID), Some(instance_id));
+    assert_eq!(
+        plugin_manager.plugin_info(PLUGIN_ID).unwrap().instance_id(),
+        instance_id
+    );
+    assert!(plugin_manager
+        .plugin_info(PLUGIN_ID)
+        .unwrap()
+        .to_string()
+        .contains(&format!("instance={}", instance_id)));
+    assert_eq!(
+        plugin_manager
+            .get_instance(instance_id)
+            .unwrap()
+            .plugin_id(),
+        PLUGIN_ID
+    );
+    assert!(other_manager.get_instance(instance_id).is_none());
+
+    plugin_manager.unload_plugin(PLUGIN_ID).unwrap();
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    let reloaded_id = plugin_manager.instance_id(PLUGIN_ID).unwrap();
+    assert!(reloaded_id > instance_id);
+    assert!(plugin_manager.get_instance(instance_id).is_none());
+}
+
+#[test]
+fn test_get_matching_plugins() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    let plugin_ids = |pattern: &str| {
+        plugin_manager
+            .get_matching(pattern)
+            .iter()
+            .map(|plugin| plugin.plugin_id().to_string())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(
+        plugin_ids("sound_*::*::Delay*"),
+        vec!["sound_plugin::sound_plugin::DelayEffect".to_string()]
+    );
+    assert_eq!(
+        plugin_ids("*Effect"),
+        vec![
+            "sound_plugin::sound_plugin::DelayEffect".to_string(),
+            "sound_plugin::sound_plugin::ReverbEffect".to_string(),
+        ]
+    );
+    assert!(plugin_ids("video_*").is_empty());
+}
+
+#[test]
+fn test_multiple_plugin_versions() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_registration_fn_name(symbol!("register_legacy_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_upgraded_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(plugin_manager.len(), 2);
+
+    let latest = plugin_manager.get(PLUGIN_ID).unwrap();
+    assert_eq!(latest.plugin_version(), Some("2.0.0"));
+    assert_eq!(
+        plugin_manager.plugin_info(PLUGIN_ID).unwrap().version(),
+        Some(&Version::new(2, 0, 0))
+    );
+
+    let legacy = plugin_manager
+        .get_version(PLUGIN_ID, &VersionReq::parse("^1.2").unwrap())
+        .unwrap();
+    assert_eq!(legacy.plugin_version(), Some("1.4.0"));
+    assert!(plugin_manager
+        .get_version(PLUGIN_ID, &VersionReq::parse("^3").unwrap())
+        .is_none());
+
+    plugin_manager.unload_plugin(PLUGIN_ID).unwrap();
+    assert!(plugin_manager.is_empty());
+}
+
+#[test]
+fn test_required_capabilities() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_registration_fn_name(symbol!("register_realtime_plugins"));
+    plugin_manager.set_required_capabilities(["realtime-safe"]);
+    assert_eq!(plugin_manager.capability_policy(), CapabilityPolicy::Reject);
+
+    let result = plugin_manager.load_plugins_from(&file_name);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Plugin 'sound_plugin::sound_plugin::ReverbEffect' does not declare the required capabilities [\"realtime-safe\"]"
+    );
+    assert!(plugin_manager.is_empty());
+
+    plugin_manager.set_capability_policy(CapabilityPolicy::Skip);
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    let plugin = plugin_manager
+        .get("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert_eq!(plugin.plugin_capabilities(), &["realtime-safe"]);
+}
+
+#[test]
+fn test_status_report() {
+    let _ = pretty_env_logger::try_init();
+
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+    assert!(plugin_manager.status().is_healthy());
+
+    plugin_manager
+        .pause_plugin("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    let status = plugin_manager.status();
+    assert!(!status.is_healthy());
+    assert_eq!(status.libraries().count(), 1);
+    let plugin = status.plugins().next().unwrap();
+    assert_eq!(
+        plugin.plugin_id(),
+        "sound_plugin::sound_plugin::DelayEffect"
+    );
+    assert_eq!(plugin.health(), PluginHealth::Paused);
+    assert_eq!(
+        plugin.description(),
+        "A sound effect used to test plugin loading"
+    );
+
+    let json = serde_json::to_value(&status).unwrap();
+    assert_eq!(json["healthy"], false);
+    assert_eq!(json["libraries"][0]["plugins"][0]["health"], "paused");
+    assert_eq!(
+        json["libraries"][0]["plugins"][0]["description"],
+        "A sound effect used to test plugin loading"
+    );
+}
+
+#[test]
+fn test_check_cache() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let check_cache = Arc::new(CheckCache::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_check_cache(check_cache.clone());
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(check_cache.len(), 1);
+
+    let manifest = check_cache
+        .validate_library(&file_name, &HostInfo::new(2, "sound_host", "0.1.0"))
+        .unwrap();
+    assert_eq!(manifest.plugins().count(), 3);
+
+    let directory = tempfile::tempdir().unwrap();
+    let cache_file = directory.path().join("plugins.cache");
+    check_cache.save(&cache_file).unwrap();
+    let loaded = CheckCache::load(&cache_file).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded.read_manifest(&file_name).unwrap(), Some(manifest));
+}
+
+#[test]
+fn test_get_or_load_from_index() {
+    let _ = pretty_env_logger::try_init();
+
+    let plugins = tempfile::tempdir().unwrap();
+    let _ = std::fs::copy(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        plugins.path().join(make_dylib_name("sound_plugin")),
+    )
+    .unwrap();
+    std::fs::write(plugins.path().join(make_dylib_name("not_a_plugin")), "").unwrap();
+
+    let index_file = plugins.path().join("plugins.index");
+    scan_and_index(plugins.path())
+        .unwrap()
+        .save(&index_file)
+        .unwrap();
+    let index = PluginIndex::load(&index_file).unwrap();
+    assert_eq!(index.libraries().count(), 1);
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_plugin_index(index);
+    assert!(plugin_manager.is_empty());
+
+    let plugin = plugin_manager
+        .get_or_load("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert_eq!(
+        plugin.plugin_id(),
+        "sound_plugin::sound_plugin::DelayEffect"
+    );
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.get_or_load("unknown::Plugin").is_err());
+}
+
+#[test]
+fn test_get_or_load_from_search_path_and_mapping() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.add_search_path(&dylib_directory());
+    let plugin = plugin_manager
+        .get_or_load("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert_eq!(
+        plugin.plugin_id(),
+        "sound_plugin::sound_plugin::DelayEffect"
+    );
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager.set_plugin_library(
+        "sound_plugin::sound_plugin::ReverbEffect",
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+    );
+    assert!(plugin_manager
+        .get_or_load("sound_plugin::sound_plugin::ReverbEffect")
+        .is_ok());
+    assert!(plugin_manager.get_or_load("unknown::Plugin").is_err());
+}
+
+#[test]
+fn test_with_symbol() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    let result = unsafe {
+        plugin_manager.with_symbol(
+            &file_name,
+            symbol!("plugin_capabilities"),
+            |f: &extern "C" fn() -> u32| f(),
+        )
+    };
+    assert!(result.is_err());
+
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    let capabilities = unsafe {
+        plugin_manager.with_symbol(
+            &file_name,
+            symbol!("plugin_capabilities"),
+            |f: &extern "C" fn() -> u32| f(),
+        )
+    };
+    assert_eq!(capabilities.unwrap(), 0b101);
+
+    let missing = unsafe {
+        plugin_manager.with_symbol(
+            &file_name,
+            symbol!("no_such_symbol"),
+            |f: &extern "C" fn() -> u32| f(),
+        )
+    };
+    assert!(missing.is_err());
+}
+
+static HOST_EVENTS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+fn record_host_event(event: &str, payload: &str) {
+    HOST_EVENTS
+        .lock()
+        .unwrap()
+        .push((event.to_string(), payload.to_string()));
+}
+
+fn host_config(key: &str) -> Option<String> {
+    (key == "sound_plugin.level").then(|| "7".to_string())
+}
+
+#[test]
+fn test_host_api_callbacks() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut host_api = HostApi::default();
+    host_api.set_config_fn(host_config);
+    host_api.set_event_fn(record_host_event);
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_host_api(host_api);
+    plugin_manager.set_registration_fn_name(symbol!("register_configured_plugins"));
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+
+    let plugin = plugin_manager
+        .get("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert_eq!(plugin.level(), 7);
+    assert_eq!(
+        *HOST_EVENTS.lock().unwrap(),
+        vec![(
+            "registered".to_string(),
+            "sound_plugin::sound_plugin::DelayEffect".to_string()
+        )]
+    );
+}
+
+static HOST_LOG: Mutex<Vec<(log::Level, String, String)>> = Mutex::new(Vec::new());
+
+fn record_host_log(level: log::Level, target: &str, message: &str) {
+    HOST_LOG
+        .lock()
+        .unwrap()
+        .push((level, target.to_string(), message.to_string()));
+}
+
+#[test]
+fn test_plugin_log_forwarded_to_host() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut host_api = HostApi::default();
+    host_api.set_log_fn(record_host_log);
+    host_api.set_max_level(log::LevelFilter::Trace);
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_host_api(host_api);
+    plugin_manager.set_registration_fn_name(symbol!("register_logging_plugins"));
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+
+    assert!(HOST_LOG.lock().unwrap().contains(&(
+        log::Level::Warn,
+        "sound_plugin".to_string(),
+        "registering logging plugin".to_string()
+    )));
+}
+
+#[test]
+fn test_plugin_events() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_registration_fn_name(symbol!("register_eventful_plugins"));
+
+    let (sink, events) = EventSink::<SoundEvent>::channel();
+    plugin_manager.set_event_sink(sink);
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    plugin_manager
+        .get("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap()
+        .set_level(11);
+    assert_eq!(
+        events.try_iter().collect::<Vec<SoundEvent>>(),
+        vec![SoundEvent::LevelChanged(
+            "sound_plugin::sound_plugin::DelayEffect".to_string(),
+            11
+        )]
+    );
+}
+
+#[test]
+fn test_plugin_blackboard() {
+    let _ = pretty_env_logger::try_init();
+
+    let blackboard = Blackboard::default();
+    let _ = blackboard.set("sound_plugin.level", 7u32);
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_blackboard(blackboard.clone());
+    plugin_manager.set_registration_fn_name(symbol!("register_sharing_plugins"));
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+
+    let plugin_id = "sound_plugin::sound_plugin::DelayEffect";
+    assert_eq!(plugin_manager.get(plugin_id).unwrap().level(), 7);
+    assert_eq!(
+        blackboard
+            .get::<String>("sound_plugin.registered")
+            .as_deref(),
+        Some(&plugin_id.to_string())
+    );
+    assert!(plugin_manager
+        .blackboard()
+        .contains_key("sound_plugin.registered"));
+}
+
+#[test]
+fn test_registered_values() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_registration_fn_name(symbol!("register_valued_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    assert_eq!(
+        plugin_manager
+            .registered_value::<EffectParameters>("sound_plugin.defaults")
+            .as_deref(),
+        Some(&EffectParameters { level: 5 })
+    );
+    assert!(plugin_manager
+        .registered_value::<u32>("sound_plugin.defaults")
+        .is_none());
+    assert_eq!(
+        plugin_manager.registered_value_names::<EffectParameters>(),
+        vec!["sound_plugin.defaults".to_string()]
+    );
+
+    plugin_manager.unload_library(&file_name).unwrap();
+    assert!(plugin_manager
+        .registered_value::<EffectParameters>("sound_plugin.defaults")
+        .is_none());
+    assert!(plugin_manager
+        .registered_value_names::<EffectParameters>()
+        .is_empty());
+}
+
+#[test]
+fn test_deactivate_releases_registered_values() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let cache = Arc::new(LibraryCache::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.set_registration_fn_name(symbol!("register_valued_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(
+        plugin_manager.registered_value_names::<EffectParameters>(),
+        vec!["sound_plugin.defaults".to_string()]
+    );
+
+    plugin_manager
+        .deactivate_plugin("sound_plugin::sound_plugin::DelayEffect")
+        .unwrap();
+    assert!(plugin_manager
+        .registered_value::<EffectParameters>("sound_plugin.defaults")
+        .is_none());
+    assert!(cache.contains(&file_name));
+
+    plugin_manager.close_idle_libraries().unwrap();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_apply_configuration() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_TYPE: &str = "sound_effects";
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let original = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let copies = tempfile::tempdir().unwrap();
+    let copy = copies.path().join(make_dylib_name("sound_plugin"));
+    let _ = std::fs::copy(&original, &copy).unwrap();
+
+    let mut config = PluginManagerConfiguration::default();
+    let _ = config.insert(PLUGIN_TYPE, &[original.as_path()]).unwrap();
+    let plugin_manager: PluginManager<SoundEffectPlugin> =
+        config.make_manager_for_type(PLUGIN_TYPE).unwrap();
+    assert_eq!(plugin_manager.plugin_type(), Some(&PLUGIN_TYPE.to_string()));
+    let before = plugin_manager.get(PLUGIN_ID).unwrap();
+
+    plugin_manager.apply_configuration(&config).unwrap();
+    assert!(Arc::ptr_eq(
+        &before,
+        &plugin_manager.get(PLUGIN_ID).unwrap()
+    ));
+    assert_eq!(before.config_changes(), 1);
+
+    let _ = config.insert(PLUGIN_TYPE, &[copy.as_path()]).unwrap();
+    plugin_manager.apply_configuration(&config).unwrap();
+    let after = plugin_manager.get(PLUGIN_ID).unwrap();
+    assert!(!Arc::ptr_eq(&before, &after));
+    assert_eq!(after.config_changes(), 0);
+    assert_eq!(
+        plugin_manager.plugin_info(PLUGIN_ID).unwrap().library(),
+        &std::fs::canonicalize(&copy).unwrap()
+    );
+
+    let _ = config.remove(PLUGIN_TYPE);
+    assert!(plugin_manager.apply_configuration(&config).is_err());
+    assert!(plugin_manager.contains(PLUGIN_ID));
+}
+
+#[test]
+fn test_configured_disabled_plugins() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_TYPE: &str = "sound_effects";
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    const OTHER_TYPE_PLUGIN_ID: &str = "light_plugin::light_plugin::Strobe";
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+
+    let mut config = PluginManagerConfiguration::default();
+    let _ = config.insert(PLUGIN_TYPE, &[file_name.as_path()]).unwrap();
+    config.set_plugin_disabled(PLUGIN_ID, true);
+    config.set_plugin_disabled(OTHER_TYPE_PLUGIN_ID, true);
+    let plugin_manager: PluginManager<SoundEffectPlugin> =
+        config.make_manager_for_type(PLUGIN_TYPE).unwrap();
+    assert!(!plugin_manager.is_enabled(PLUGIN_ID));
+
+    plugin_manager.enable_plugin(PLUGIN_ID).unwrap();
+    config.record_disabled_plugins(&plugin_manager);
+    assert_eq!(
+        config.disabled_plugins().collect::<Vec<&String>>(),
+        vec![OTHER_TYPE_PLUGIN_ID]
+    );
+
+    config.set_plugin_disabled(PLUGIN_ID, true);
+    plugin_manager.apply_configuration(&config).unwrap();
+    assert!(!plugin_manager.is_enabled(PLUGIN_ID));
+}
+
+#[test]
+fn test_configured_plugin_type_options() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_TYPE: &str = "sound_effects";
+    let mut config = PluginManagerConfiguration::default();
+    let _ = config
+        .insert(PLUGIN_TYPE, &[make_dylib_name("sound_plugin").as_path()])
+        .unwrap();
+    let options = config.plugin_type_configuration_mut(PLUGIN_TYPE).unwrap();
+    options.add_search_path(&dylib_directory());
+    options.set_register_fn("register_other_plugins");
+    options.set_required(true);
+
+    let plugin_manager: PluginManager<SoundEffectPlugin> =
+        config.make_manager_for_type(PLUGIN_TYPE).unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::ReverbEffect"));
+
+    config
+        .plugin_type_configuration_mut(PLUGIN_TYPE)
+        .unwrap()
+        .set_register_fn("register\0plugins");
+    assert!(config
+        .make_manager_for_type::<SoundEffectPlugin>(PLUGIN_TYPE)
+        .is_err());
+}
+
+#[test]
+fn test_make_manager_for_type_with() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_TYPE: &str = "sound_effects";
+    let mut config = PluginManagerConfiguration::default();
+    let _ = config
+        .insert(PLUGIN_TYPE, &[make_dylib_name("sound_plugin").as_path()])
+        .unwrap();
+    config
+        .plugin_type_configuration_mut(PLUGIN_TYPE)
+        .unwrap()
+        .set_register_fn("register_failing_plugins");
+
+    let plugin_manager: PluginManager<SoundEffectPlugin> = config
+        .make_manager_for_type_with(PLUGIN_TYPE, |manager| {
+            assert_eq!(manager.plugin_type(), Some(&PLUGIN_TYPE.to_string()));
+            manager.add_search_path(&dylib_directory());
+            manager.set_registration_fn_name(symbol!("register_other_plugins"));
+        })
+        .unwrap();
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::ReverbEffect"));
+}
+
+#[test]
+fn test_make_all_managers() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = make_dylib_name("sound_plugin");
+    let mut config = PluginManagerConfiguration::default();
+    config.add_search_path(&dylib_directory());
+    let _ = config
+        .insert("sound_effects", &[file_name.as_path()])
+        .unwrap();
+    let _ = config
+        .insert("light_effects", &[file_name.as_path()])
+        .unwrap();
+    config
+        .plugin_type_configuration_mut("light_effects")
+        .unwrap()
+        .set_register_fn("register_light_plugins");
+
+    let mut plugin_types: Vec<String> = Vec::new();
+    let mut sounds: Option<PluginManager<SoundEffectPlugin>> = None;
+    let mut lights: Option<PluginManager<LightEffectPlugin>> = None;
+    config
+        .make_all(|configured| {
+            plugin_types.push(configured.plugin_type().to_string());
+            match configured.plugin_type() {
+                "sound_effects" => sounds = Some(configured.make_manager()?),
+                "light_effects" => lights = Some(configured.make_manager()?),
+                other => panic!("unexpected plugin type {}", other),
+            }
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(plugin_types, vec!["light_effects", "sound_effects"]);
+
+    let sounds = sounds.unwrap();
+    let lights = lights.unwrap();
+    assert!(sounds.contains("sound_plugin::sound_plugin::DelayEffect"));
+    assert!(lights.contains("sound_plugin::sound_plugin::MoodLighting"));
+    assert!(Arc::ptr_eq(sounds.library_cache(), lights.library_cache()));
+
+    let result = config.make_all(|configured| {
+        configured.make_manager::<SoundEffectPlugin>()?;
+        Ok(())
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_plugin_type_markers() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut config = PluginManagerConfiguration::default();
+    let _ = config
+        .insert(SoundEffects::ID, &[file_name.as_path()])
+        .unwrap();
+    let _ = config
+        .insert(LightEffects::ID, &[file_name.as_path()])
+        .unwrap();
+
+    let sounds = config.make_manager::<SoundEffects>().unwrap();
+    assert_eq!(sounds.plugin_type(), Some(&"sound_effects".to_string()));
+    assert!(sounds.contains("sound_plugin::sound_plugin::DelayEffect"));
+
+    let mut lights = None;
+    config
+        .make_all(|configured| {
+            if configured.is::<LightEffects>() {
+                lights = Some(configured.make_manager_with(|manager| {
+                    manager.set_registration_fn_name(symbol!("register_light_plugins"))
+                })?);
+            }
+            Ok(())
+        })
+        .unwrap();
+    let lights: PluginManager<LightEffectPlugin> = lights.unwrap();
+    assert!(lights.contains("sound_plugin::sound_plugin::MoodLighting"));
+
+    let plugin_manager = PluginManager::new_for_type::<LightEffects>();
+    assert_eq!(
+        plugin_manager.plugin_type(),
+        Some(&"light_effects".to_string())
+    );
+    assert!(plugin_manager.is_empty());
+}
+
+#[derive(Debug, Default)]
+struct RecordingVerifier {
+    verified: Mutex<Vec<PathBuf>>,
+    reject: bool,
+}
+
+impl SignatureVerifier for RecordingVerifier {
+    fn verify(&self, library: &Path) -> dygpi::error::Result<()> {
+        self.verified.lock().unwrap().push(library.to_path_buf());
+        if self.reject {
+            Err(Error::signature_verification_failed(library, "not signed"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn test_load_bundle_opens_library_once() {
+    let _ = pretty_env_logger::try_init();
+
+    let bundle = tempfile::tempdir().unwrap();
+    let file_name = bundle.path().join(make_dylib_name("sound_plugin"));
+    let _ = std::fs::copy(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        &file_name,
+    )
+    .unwrap();
+    std::fs::write(
+        bundle.path().join("plugin.toml"),
+        r##"
+[[plugin]]
+id = "sound_plugin::sound_plugin::DelayEffect"
+type = "sound_api::SoundEffectPlugin"
+library = "sound_plugin"
+
+[[plugin]]
+id = "sound_plugin::sound_plugin::ReverbEffect"
+type = "sound_api::SoundEffectPlugin"
+library = "sound_plugin"
+entry = "register_other_plugins"
+"##,
+    )
+    .unwrap();
+
+    let verifier = Arc::new(RecordingVerifier::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_signature_verifier(verifier.clone());
+    plugin_manager.load_bundle(bundle.path()).unwrap();
+    assert_eq!(plugin_manager.len(), 2);
+    assert_eq!(verifier.verified.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_signature_verifier() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let verifier = Arc::new(RecordingVerifier::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_signature_verifier(verifier.clone());
+    let _ = plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert_eq!(*verifier.verified.lock().unwrap(), vec![file_name.clone()]);
+    plugin_manager.unload_all().unwrap();
+
+    let verifier = Arc::new(RecordingVerifier {
+        reject: true,
+        ..Default::default()
+    });
+    let cache = Arc::new(LibraryCache::default());
+    plugin_manager.set_library_cache(cache.clone());
+    plugin_manager.set_signature_verifier(verifier.clone());
+    let result = plugin_manager.load_plugins_from(&file_name);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("failed signature verification"));
+    assert_eq!(verifier.verified.lock().unwrap().len(), 1);
+    assert!(cache.is_empty());
+    assert!(plugin_manager.is_empty());
+}
+
+#[test]
+fn test_signature_verifier_rejects_unresolved_library() {
+    let _ = pretty_env_logger::try_init();
+
+    let verifier = Arc::new(RecordingVerifier::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_signature_verifier(verifier.clone());
+    let result = plugin_manager.load_plugins_from(make_dylib_name("sound_plugin"));
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("failed signature verification"));
+    assert!(error.contains("could not be resolved to a file"));
+    assert!(verifier.verified.lock().unwrap().is_empty());
+    assert!(plugin_manager.is_empty());
+}
+
+#[test]
+fn test_signature_verifier_checks_shadow_copy() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let shadow_directory = tempfile::tempdir().unwrap();
+    let verifier = Arc::new(RecordingVerifier::default());
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_shadow_copy_directory(shadow_directory.path());
+    plugin_manager.set_signature_verifier(verifier.clone());
+    let _ = plugin_manager.load_plugins_from(&file_name).unwrap();
+    let verified = verifier.verified.lock().unwrap().clone();
+    assert_eq!(verified.len(), 1);
+    assert!(verified[0].starts_with(shadow_directory.path()));
+    assert_ne!(verified[0], file_name);
+    plugin_manager.unload_all().unwrap();
+}
+
+#[test]
+fn test_shadow_copy_loading() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let original = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let copies = tempfile::tempdir().unwrap();
+    let copy = copies.path().join(make_dylib_name("sound_plugin"));
+    let _ = std::fs::copy(&original, &copy).unwrap();
+    let shadow_directory = tempfile::tempdir().unwrap();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_shadow_copy_directory(shadow_directory.path());
+    plugin_manager.load_plugins_from(&copy).unwrap();
+    let shadow_copy = plugin_manager.shadow_copy_of(&copy).unwrap();
+    assert!(shadow_copy.starts_with(shadow_directory.path()));
+    assert!(shadow_copy.is_file());
+    assert_eq!(
+        plugin_manager.plugin_info(PLUGIN_ID).unwrap().library(),
+        &copy.canonicalize().unwrap()
+    );
+
+    let _ = std::fs::copy(&original, &copy).unwrap();
+    plugin_manager.reload_library(&copy).unwrap();
+    let reloaded_copy = plugin_manager.shadow_copy_of(&copy).unwrap();
+    assert_ne!(reloaded_copy, shadow_copy);
+    assert!(!shadow_copy.exists());
+
+    plugin_manager.unload_plugin(PLUGIN_ID).unwrap();
+    assert!(plugin_manager.shadow_copy_of(&copy).is_none());
+    assert!(!reloaded_copy.exists());
+}
+
+#[test]
+fn test_reload_while_referenced() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let copies = tempfile::tempdir().unwrap();
+    let copy = copies.path().join(make_dylib_name("sound_plugin"));
+    let _ = std::fs::copy(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        &copy,
+    )
+    .unwrap();
+    let shadow_directory = tempfile::tempdir().unwrap();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_shadow_copy_directory(shadow_directory.path());
+    plugin_manager.load_plugins_from(&copy).unwrap();
+    let shadow_copy = plugin_manager.shadow_copy_of(&copy).unwrap();
+    let before = plugin_manager.get(PLUGIN_ID).unwrap();
+
+    plugin_manager.reload_library(&copy).unwrap();
+    let reloaded_copy = plugin_manager.shadow_copy_of(&copy).unwrap();
+    assert_ne!(reloaded_copy, shadow_copy);
+    assert!(shadow_copy.exists());
+    let after = plugin_manager.get(PLUGIN_ID).unwrap();
+    assert!(!Arc::ptr_eq(&before, &after));
+    before.play();
+}
+
+#[test]
+fn test_compatibility_inputs() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_compatibility_input("sound_host", "1");
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert!(!plugin_manager.is_empty());
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.add_compatibility_input("sound_host", "2");
+    plugin_manager.add_compatibility_input("sound_engine", "1");
+    assert_eq!(
+        plugin_manager.host_compatibility().input("sound_host"),
+        Some(&"2".to_string())
+    );
+    let error = plugin_manager.load_plugins_from(&file_name).unwrap_err();
+    assert!(error.to_string().ends_with(
+        "sound_engine version is unset, expected 1, sound_host version is 1, expected 2"
+    ));
+    assert!(plugin_manager.is_empty());
+}
+
+#[test]
+fn test_unload_library() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+    assert!(plugin_manager.is_library_loaded(&file_name));
+
+    plugin_manager.unload_library(&file_name).unwrap();
+    assert!(plugin_manager.is_empty());
+    assert!(!plugin_manager.is_library_loaded(&file_name));
+    assert!(plugin_manager.unload_library(&file_name).is_err());
+}
+
+#[test]
+fn test_watch_plugin_directory() {
+    let _ = pretty_env_logger::try_init();
+
+    const PLUGIN_ID: &str = "sound_plugin::sound_plugin::DelayEffect";
+    let original = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let watched = tempfile::tempdir().unwrap();
+    let copy = watched.path().join(make_dylib_name("sound_plugin"));
+    let shadow_directory = tempfile::tempdir().unwrap();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_shadow_copy_directory(shadow_directory.path());
+    let plugin_manager = Arc::new(plugin_manager);
+    let mut policy = ReloadPolicy::default();
+    policy.set_debounce(Duration::from_millis(100));
+    let watcher = plugin_manager.watch(&[watched.path()], policy).unwrap();
+    let wait_for = |condition: &dyn Fn() -> bool| {
+        let start = Instant::now();
+        while !condition() {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    };
+
+    let _ = std::fs::copy(&original, &copy).unwrap();
+    wait_for(&|| plugin_manager.contains(PLUGIN_ID));
+    let instance_id = plugin_manager.instance_id(PLUGIN_ID).unwrap();
+
+    let _ = std::fs::copy(&original, &copy).unwrap();
+    wait_for(&|| {
+        plugin_manager
+            .instance_id(PLUGIN_ID)
+            .is_some_and(|reloaded| reloaded != instance_id)
+    });
+
+    std::fs::remove_file(&copy).unwrap();
+    wait_for(&|| !plugin_manager.contains(PLUGIN_ID));
+    drop(watcher);
+}
+
+#[test]
+fn test_retain_and_clear() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+    plugin_manager.set_registration_fn_name(symbol!("register_other_plugins"));
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+    assert_eq!(plugin_manager.len(), 2);
+
+    let mut seen = Vec::new();
+    plugin_manager
+        .retain(|plugin_id, _| {
+            seen.push(plugin_id.to_string());
+            plugin_id.ends_with("DelayEffect")
+        })
+        .unwrap();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(plugin_manager.len(), 1);
+    assert!(plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+
+    plugin_manager.clear().unwrap();
+    assert!(plugin_manager.is_empty());
+}
+
+#[test]
+fn test_versioned_registration_fn() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let mut plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager.set_library_cache(Arc::new(LibraryCache::default()));
+    plugin_manager.set_registration_fn_name(symbol!("register_modern_plugins"));
+    plugin_manager.load_plugins_from(&file_name).unwrap();
+
+    let plugin_id = "sound_plugin::sound_plugin::ReverbEffect";
+    assert!(plugin_manager.contains(plugin_id));
+    assert!(!plugin_manager.contains("sound_plugin::sound_plugin::DelayEffect"));
+    assert_eq!(
+        plugin_manager
+            .plugin_info(plugin_id)
+            .unwrap()
+            .registration_fn(),
+        "register_modern_plugins_v1"
+    );
+
+    plugin_manager.set_registration_fn_name(symbol!("register_future_plugins"));
+    let result = plugin_manager.load_plugins_from(&file_name);
+    assert_eq!(
+        result.err().unwrap().to_string(),
+        "Registration function 'register_future_plugins' is only exported for unsupported protocol versions [99]"
+    );
+}
+
+#[derive(Debug, Default)]
+struct StaticEffects {
+    unloaded: Mutex<Vec<PathBuf>>,
+}
+
+impl PluginSource<SoundEffectPlugin> for StaticEffects {
+    fn list(&self) -> dygpi::error::Result<Vec<PathBuf>> {
+        Ok(vec![
+            PathBuf::from("static/delay"),
+            PathBuf::from("static/reverb"),
+        ])
+    }
+
+    fn load(&self, location: &Path) -> dygpi::error::Result<Vec<Arc<SoundEffectPlugin>>> {
+        let name = location.file_name().unwrap().to_string_lossy();
+        Ok(vec![Arc::new(SoundEffectPlugin::new(&format!(
+            "static::{}",
+            name
+        )))])
+    }
+
+    fn unload(&self, location: &Path) -> dygpi::error::Result<()> {
+        self.unloaded.lock().unwrap().push(location.to_path_buf());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_plugin_source() {
+    let _ = pretty_env_logger::try_init();
+
+    let static_effects = Arc::new(StaticEffects::default());
+    let source: Arc<dyn PluginSource<SoundEffectPlugin>> = static_effects.clone();
+    let plugin_manager: PluginManager<SoundEffectPlugin> = PluginManager::default();
+    plugin_manager
+        .load_plugins_from(dylib_directory().join(make_dylib_name("sound_plugin")))
+        .unwrap();
+    plugin_manager
+        .load_all_plugins_from_source(&source)
+        .unwrap();
+    plugin_manager
+        .load_plugins_from_source(&source, "static/delay")
+        .unwrap();
+    assert_eq!(plugin_manager.len(), 3);
+
+    let info = plugin_manager.plugin_info("static::reverb").unwrap();
+    assert_eq!(info.library(), &PathBuf::from("static/reverb"));
+    assert_eq!(info.registration_fn(), "source");
+
+    plugin_manager.unload_plugin("static::reverb").unwrap();
+    assert_eq!(
+        *static_effects.unloaded.lock().unwrap(),
+        vec![PathBuf::from("static/reverb")]
+    );
+
+    plugin_manager.unload_all().unwrap();
+    assert!(plugin_manager.is_empty());
+    assert_eq!(static_effects.unloaded.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn test_assert_valid_provider() {
+    let _ = pretty_env_logger::try_init();
+
+    let file_name = dylib_directory().join(make_dylib_name("sound_plugin"));
+    let plugin_ids = assert_valid_provider::<SoundEffectPlugin, _>(&file_name);
+    assert_eq!(
+        plugin_ids,
+        vec!["sound_plugin::sound_plugin::DelayEffect".to_string()]
+    );
+    let plugin_ids = assert_valid_provider_with::<SoundEffectPlugin, _>(
+        &file_name,
+        symbol!("register_modern_plugins"),
+    );
+    assert_eq!(
+        plugin_ids,
+        vec!["sound_plugin::sound_plugin::ReverbEffect".to_string()]
+    );
+}
+
+#[test]
+#[should_panic(expected = "failed to load")]
+fn test_assert_valid_provider_wrong_plugin_type() {
+    let _ = pretty_env_logger::try_init();
+
+    let _ = assert_valid_provider::<LightEffectPlugin, _>(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+    );
+}
+
+#[test]
+#[should_panic(expected = "does not export the registration function `register_nothing`")]
+fn test_assert_valid_provider_missing_registration_fn() {
+    let _ = pretty_env_logger::try_init();
+
+    let _ = assert_valid_provider_with::<SoundEffectPlugin, _>(
+        dylib_directory().join(make_dylib_name("sound_plugin")),
+        symbol!("register_nothing"),
+    );
+}
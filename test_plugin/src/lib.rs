@@ -7,8 +7,9 @@ More detailed description, with
 
  */
 
-use dygpi::plugin::PluginRegistrar;
-use sound_api::SoundEffectPlugin;
+use dygpi::plugin::{Parameters, PluginRegistrar};
+use log::Level;
+use sound_api::{EffectParameters, LightEffectPlugin, SoundEffectPlugin, SoundEvent};
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -22,6 +23,10 @@ use sound_api::SoundEffectPlugin;
 // Public Functions
 // ------------------------------------------------------------------------------------------------
 
+dygpi::compatibility_inputs! {
+    "sound_host" => "1",
+}
+
 #[no_mangle]
 pub extern "C" fn register_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
     registrar.register(SoundEffectPlugin::new(PLUGIN_NAME));
@@ -32,10 +37,178 @@ pub extern "C" fn register_other_plugins(registrar: &mut PluginRegistrar<SoundEf
     registrar.register(SoundEffectPlugin::new(OTHER_PLUGIN_NAME));
 }
 
+#[no_mangle]
+pub extern "C" fn register_priority_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.set_priority(10);
+    registrar.register(SoundEffectPlugin::new(OTHER_PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_failing_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register(SoundEffectPlugin::new(PLUGIN_NAME));
+    registrar.register(SoundEffectPlugin::new_failing(FAILING_PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_stuck_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register(SoundEffectPlugin::new(PLUGIN_NAME));
+    registrar.register(SoundEffectPlugin::new_failing_unload(STUCK_PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_versioned_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    if registrar.require_api_version(2..=3) {
+        if registrar.host_info().api_version() >= 3 {
+            registrar.register(SoundEffectPlugin::new(OTHER_PLUGIN_NAME));
+        } else {
+            registrar.register(SoundEffectPlugin::new(PLUGIN_NAME));
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn register_legacy_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register(SoundEffectPlugin::new_with_version(PLUGIN_NAME, "1.4.0"));
+}
+
+#[no_mangle]
+pub extern "C" fn register_upgraded_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register(SoundEffectPlugin::new_with_version(PLUGIN_NAME, "2.0.0"));
+}
+
+#[no_mangle]
+pub extern "C" fn register_all_versions_plugins(
+    registrar: &mut PluginRegistrar<SoundEffectPlugin>,
+) {
+    registrar.register(SoundEffectPlugin::new_with_version(PLUGIN_NAME, "1.4.0"));
+    registrar.register(SoundEffectPlugin::new_with_version(PLUGIN_NAME, "2.0.0"));
+}
+
+#[no_mangle]
+pub extern "C" fn register_configured_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    let host_api = *registrar.host_api();
+    let plugin = SoundEffectPlugin::new(PLUGIN_NAME);
+    if let Some(level) = host_api
+        .config("sound_plugin.level")
+        .and_then(|level| level.parse().ok())
+    {
+        plugin.set_level(level);
+    }
+    host_api.log(Level::Info, module_path!(), "registering configured plugin");
+    host_api.emit("registered", PLUGIN_NAME);
+    registrar.register(plugin);
+}
+
+#[no_mangle]
+pub extern "C" fn register_logging_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    let _ = registrar.install_host_logger();
+    log::warn!("registering logging plugin");
+    registrar.register(SoundEffectPlugin::new(PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_eventful_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    match registrar.event_sink::<SoundEvent>() {
+        Some(events) => registrar.register(SoundEffectPlugin::new_with_events(PLUGIN_NAME, events)),
+        None => registrar.register(SoundEffectPlugin::new(PLUGIN_NAME)),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn register_sharing_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    let blackboard = registrar.blackboard();
+    let plugin = SoundEffectPlugin::new(PLUGIN_NAME);
+    if let Some(level) = blackboard.get::<u32>("sound_plugin.level") {
+        plugin.set_level(*level);
+    }
+    let _ = blackboard.set("sound_plugin.registered", PLUGIN_NAME.to_string());
+    registrar.register(plugin);
+}
+
+#[no_mangle]
+pub extern "C" fn register_valued_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register_value("sound_plugin.defaults", EffectParameters { level: 5 });
+    registrar.register(SoundEffectPlugin::new(PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_modern_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register(SoundEffectPlugin::new(PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_modern_plugins_v1(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register(SoundEffectPlugin::new(OTHER_PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_future_plugins_v99(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register(SoundEffectPlugin::new(PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_realtime_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register(SoundEffectPlugin::new_with_capabilities(
+        PLUGIN_NAME,
+        &["realtime-safe"],
+    ));
+    registrar.register(SoundEffectPlugin::new_with_capabilities(
+        OTHER_PLUGIN_NAME,
+        &["offline-render"],
+    ));
+}
+
+#[no_mangle]
+pub extern "C" fn register_instanced_plugins(registrar: &mut PluginRegistrar<SoundEffectPlugin>) {
+    registrar.register_factory(|| SoundEffectPlugin::new(PLUGIN_NAME));
+    registrar.register(SoundEffectPlugin::new(OTHER_PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn register_instantiable_plugins(
+    registrar: &mut PluginRegistrar<SoundEffectPlugin>,
+) {
+    registrar.register_instantiable(SoundEffectPlugin::new(PLUGIN_NAME), new_delay_effect);
+}
+
+#[no_mangle]
+pub extern "C" fn register_light_plugins(registrar: &mut PluginRegistrar<LightEffectPlugin>) {
+    registrar.register(LightEffectPlugin::new(LIGHT_PLUGIN_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn plugin_capabilities() -> u32 {
+    PLUGIN_CAPABILITIES
+}
+
+dygpi::plugin_manifest! {
+    plugins: [
+        (
+            concat!(env!("CARGO_PKG_NAME"), "::", module_path!(), "::", "DelayEffect"),
+            env!("CARGO_PKG_VERSION")
+        ),
+        (
+            concat!(env!("CARGO_PKG_NAME"), "::", module_path!(), "::", "ReverbEffect"),
+            env!("CARGO_PKG_VERSION")
+        ),
+        (
+            concat!(env!("CARGO_PKG_NAME"), "::", module_path!(), "::", "MoodLighting"),
+            env!("CARGO_PKG_VERSION")
+        ),
+    ]
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
 
+fn new_delay_effect(params: &Parameters<'_>) -> dygpi::error::Result<SoundEffectPlugin> {
+    let params = params.get::<EffectParameters>(PLUGIN_NAME)?;
+    let plugin = SoundEffectPlugin::new(PLUGIN_NAME);
+    plugin.set_level(params.level);
+    Ok(plugin)
+}
+
 const PLUGIN_NAME: &str = concat!(
     env!("CARGO_PKG_NAME"),
     "::",
@@ -52,6 +225,32 @@ const OTHER_PLUGIN_NAME: &str = concat!(
     "ReverbEffect"
 );
 
+const LIGHT_PLUGIN_NAME: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "::",
+    module_path!(),
+    "::",
+    "MoodLighting"
+);
+
+const FAILING_PLUGIN_NAME: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "::",
+    module_path!(),
+    "::",
+    "BrokenEffect"
+);
+
+const PLUGIN_CAPABILITIES: u32 = 0b101;
+
+const STUCK_PLUGIN_NAME: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "::",
+    module_path!(),
+    "::",
+    "StuckEffect"
+);
+
 // ------------------------------------------------------------------------------------------------
 // Private Functions
 // ------------------------------------------------------------------------------------------------